@@ -1,25 +1,54 @@
 #[cfg(feature = "db")]
-use std::collections::HashSet;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::collections::{BTreeSet, HashSet};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+#[cfg(feature = "db")]
+use std::{future::Future, sync::LazyLock};
 
 use anyhow::{Context, Result};
+#[cfg(feature = "db")]
+use ethers::contract::Multicall;
 use ethers::{
     providers::Middleware,
-    types::{Address, TransactionReceipt, U256},
+    types::{Address, BlockId, BlockNumber, TransactionReceipt, H256, U256},
 };
 use fermah_common::{
     hash::{blake3::Blake3Hash, keccak256::Keccak256Hash},
     operator::OperatorId,
+    supervisor::{RestartPolicy, Supervisor},
+    types::{block::ObservedBlock, quorum::QuorumNumbers},
 };
 #[cfg(feature = "db")]
-use fermah_database::Database;
-use tokio::{
-    sync::{watch, Mutex},
-    task::JoinSet,
+use fermah_common::{
+    operator::offense::Offense,
+    proof::request::ProofRequestId,
+    types::{
+        reconciliation::{VaultDiscrepancy, VaultReconciliation},
+        webhook::WebhookEvent,
+        withdrawal::{Withdrawal, WithdrawalId, WithdrawalStatus},
+    },
 };
+#[cfg(feature = "db")]
+use fermah_database::{avs_transactions::AvsTxStatus, Database};
+#[cfg(feature = "db")]
+use tokio::sync::mpsc;
+use tokio::{sync::Mutex, task::JoinSet};
 use tracing::{debug, info, warn};
 
-use crate::{contract::Contracts, ELOperatorStatus};
+#[cfg(feature = "db")]
+use crate::{
+    block_range::{chunks, BlockRangePoller, MAX_LOG_RANGE},
+    metrics::Metrics,
+    slashing::{SlashingDecision, SlashingPolicy},
+    tx_manager::TxStatus,
+};
+use crate::{contract::Contracts, tx_manager::TxManager, ELOperatorStatus};
+
+#[cfg(feature = "db")]
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::init);
 
 #[derive(Clone)]
 pub struct Avs {
@@ -27,24 +56,47 @@ pub struct Avs {
     #[cfg(feature = "db")]
     pub database: Database,
     pub block_number: Arc<Mutex<u64>>,
+    pub tx_manager: TxManager,
+    /// Recently observed canonical blocks, oldest first. Used to detect reorgs.
+    recent_blocks: Arc<Mutex<VecDeque<ObservedBlock>>>,
 }
 
 impl Avs {
     const MINIMUM_REGISTRATION_BLOCKS: u64 = 500;
+    /// How many recent blocks are checked for reorgs
+    const REORG_TRACKING_DEPTH: usize = 64;
+    /// Quorums the operators register to
+    const QUORUM_NUMBERS: [u8; 1] = [0];
+    #[cfg(feature = "db")]
+    /// Blocks past the end of its registration after which an operator is archived, about a day on Holesky
+    const ARCHIVAL_GRACE_BLOCKS: u64 = 7_200;
 
     pub async fn from_contracts(
         contracts: Contracts,
         #[cfg(feature = "db")] database: Database,
     ) -> Result<Self> {
         let block_number = Arc::new(Mutex::new(0));
+        let tx_manager = TxManager::new(
+            contracts.provider.clone(),
+            contracts.fee_policy.clone(),
+            #[cfg(feature = "db")]
+            database.clone(),
+        );
         Ok(Self {
             contracts,
             #[cfg(feature = "db")]
             database,
             block_number,
+            tx_manager,
+            recent_blocks: Arc::new(Mutex::new(VecDeque::new())),
         })
     }
 
+    /// Latest block seen by the block update thread, if any
+    async fn observed_head(&self) -> Option<ObservedBlock> {
+        self.recent_blocks.lock().await.back().copied()
+    }
+
     pub async fn check_operator(&self, operator_id: &OperatorId) -> Result<Option<Keccak256Hash>> {
         let el_operator_id: Keccak256Hash = self
             .contracts
@@ -67,21 +119,37 @@ impl Avs {
     /// if operator is not registered. This means that, unlike some methods that return an Option<T> where None signals that
     /// operator is not registered, this method doesn't distinguish between operators which are actually registered and not.
     pub async fn get_registered_till_block(&self, operator_id: &OperatorId) -> Result<U256> {
-        let registered_till_block: U256 = self
-            .contracts
-            .avs_contracts
-            .registry_coordinator
-            .registered_till_block(operator_id.0)
-            .call()
-            .await?;
+        let observed = self.observed_head().await;
+        let registered_till_block = self.registered_till_block_at(operator_id, observed).await?;
 
         #[cfg(feature = "db")]
-        self.database
-            .set_operator_registered_till(operator_id, registered_till_block)?;
+        self.database.set_operator_registered_till_at(
+            operator_id,
+            registered_till_block,
+            observed,
+        )?;
 
         Ok(registered_till_block)
     }
 
+    /// Reads registeredTillBlock at the given block, or at the latest one if `None`
+    async fn registered_till_block_at(
+        &self,
+        operator_id: &OperatorId,
+        at: Option<ObservedBlock>,
+    ) -> Result<U256> {
+        let mut call = self
+            .contracts
+            .avs_contracts
+            .registry_coordinator
+            .registered_till_block(operator_id.0);
+        if let Some(at) = at {
+            call = call.block(BlockId::from(at.hash));
+        }
+
+        Ok(call.call().await?)
+    }
+
     pub async fn check_registered_till_block(&self, operator_id: &OperatorId) -> Result<bool> {
         let registered_till_block = self.get_registered_till_block(operator_id).await?;
         let current_block: U256 = { *self.block_number.lock().await }.into();
@@ -250,30 +318,241 @@ impl Avs {
         proof_requester: Address,
         amount: U256,
     ) -> Result<TransactionReceipt> {
-        self.contracts
+        self.withdraw_to(proof_requester, proof_requester, amount)
+            .await
+    }
+
+    /// Withdraws the amount from the requester's deposit to the recipient
+    pub async fn withdraw_to(
+        &self,
+        proof_requester: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<TransactionReceipt> {
+        let call = self.contracts.fermah_contracts.vault.withdraw(amount, to);
+
+        let receipt = self
+            .tx_manager
+            .send_and_wait(call.tx)
+            .await
+            .context("failed to reserve")?;
+
+        #[cfg(feature = "db")]
+        self.notify_withdrawal_settled(proof_requester, amount, receipt.transaction_hash);
+        #[cfg(not(feature = "db"))]
+        let _ = proof_requester;
+
+        Ok(receipt)
+    }
+
+    #[cfg(feature = "db")]
+    fn notify_withdrawal_settled(&self, proof_requester: Address, amount: U256, tx_hash: H256) {
+        let event = WebhookEvent::WithdrawalSettled {
+            requester: proof_requester,
+            amount,
+            tx_hash,
+        };
+        if let Err(err) = self
+            .database
+            .enqueue_webhook_event(&proof_requester, &event)
+        {
+            warn!(
+                ?err,
+                ?proof_requester,
+                "failed to enqueue the withdrawal webhook event"
+            );
+        }
+    }
+
+    /// Sends the pending withdrawal to the vault, and records its transaction once it's mined. The nonce of the
+    /// transaction is recorded before it's broadcast, so a withdrawal whose transaction may have been mined is left
+    /// submitted for [`Self::reconcile_withdrawals`]. Its amount is only released once the transaction is provably
+    /// not mined, or reverted. Returns the hash of the transaction it was settled in.
+    #[cfg(feature = "db")]
+    pub async fn execute_withdrawal(&self, id: &WithdrawalId) -> Result<H256> {
+        let withdrawal = self
+            .database
+            .get_withdrawal(id)?
+            .with_context(|| format!("unknown withdrawal {id:?}"))?;
+        if withdrawal.status != WithdrawalStatus::Pending {
+            anyhow::bail!("withdrawal {id:?} is {}, not pending", withdrawal.status);
+        }
+        let request = &withdrawal.request;
+        if request.deadline <= chrono::Utc::now() {
+            self.database.fail_withdrawal(id, "deadline passed")?;
+            anyhow::bail!("deadline of withdrawal {id:?} passed");
+        }
+
+        let call = self
+            .contracts
             .fermah_contracts
             .vault
-            .withdraw(amount, proof_requester)
-            .send()
-            .await?
+            .withdraw(request.amount, request.to);
+        let sent = self
+            .tx_manager
+            .send_with(call.tx, |nonce| {
+                if !self.database.submit_withdrawal(id, nonce)? {
+                    anyhow::bail!("withdrawal {id:?} is not pending anymore");
+                }
+                Ok(())
+            })
+            .await;
+        let pending = match sent {
+            Ok(pending) => pending,
+            Err(err) => {
+                // Nothing was broadcast unless it was submitted, it's reconciled by its receipt otherwise
+                if self.database.get_withdrawal(id)?.map(|w| w.status)
+                    == Some(WithdrawalStatus::Pending)
+                {
+                    self.database.fail_withdrawal(id, &err.to_string())?;
+                }
+                return Err(err);
+            }
+        };
+
+        let status = match self.tx_manager.wait(&pending).await {
+            Ok(receipt) if receipt.status == Some(1.into()) => TxStatus::Confirmed(receipt),
+            Ok(receipt) => TxStatus::Reverted(receipt),
+            Err(err) if pending.status() == TxStatus::Dropped => {
+                warn!(?err, ?id, "withdrawal transaction dropped");
+                TxStatus::Dropped
+            }
+            Err(err) => {
+                warn!(
+                    ?err,
+                    ?id,
+                    nonce = pending.nonce,
+                    "withdrawal left to be reconciled"
+                );
+                return Err(err);
+            }
+        };
+        self.resolve_withdrawal(&withdrawal, &status)
             .await?
-            .context("failed to reserve")
+            .with_context(|| format!("withdrawal {id:?} was not settled: {status:?}"))
+    }
+
+    /// Settles or fails the submitted withdrawal by the final status of its transaction, returns the hash of the
+    /// transaction it was settled in. Left submitted while the transaction is pending.
+    #[cfg(feature = "db")]
+    async fn resolve_withdrawal(
+        &self,
+        withdrawal: &Withdrawal,
+        status: &TxStatus,
+    ) -> Result<Option<H256>> {
+        let id = &withdrawal.id;
+        let receipt = match status {
+            TxStatus::Pending { .. } => return Ok(None),
+            TxStatus::Confirmed(receipt) => receipt,
+            TxStatus::Reverted(receipt) => {
+                let reason = format!(
+                    "withdrawal transaction reverted in {:?}",
+                    receipt.transaction_hash
+                );
+                self.database.fail_withdrawal(id, &reason)?;
+                warn!(?id, reason, "Withdrawal failed");
+                return Ok(None);
+            }
+            TxStatus::Dropped => {
+                self.database
+                    .fail_withdrawal(id, "withdrawal transaction was dropped")?;
+                warn!(?id, "Withdrawal failed, its transaction was dropped");
+                return Ok(None);
+            }
+        };
+
+        self.database
+            .settle_withdrawal(id, receipt.transaction_hash)?;
+        self.notify_withdrawal_settled(
+            withdrawal.requester,
+            withdrawal.request.amount,
+            receipt.transaction_hash,
+        );
+        info!(?id, tx_hash=?receipt.transaction_hash, amount=%withdrawal.request.amount, "Withdrawal settled");
+
+        // The deposit shrank, it's read again before the requester spends it
+        if let Err(err) = self.get_vault_balance_now(&withdrawal.requester).await {
+            warn!(?err, requester=?withdrawal.requester, "failed to refresh the deposit after the withdrawal");
+        }
+
+        Ok(Some(receipt.transaction_hash))
+    }
+
+    #[cfg(feature = "db")]
+    /// Seconds after which a submitted withdrawal without a recorded transaction is known not to be broadcast, the
+    /// transaction is recorded right after the withdrawal is submitted
+    const WITHDRAWAL_SUBMISSION_GRACE_SECS: i64 = 600;
+
+    /// Settles or fails the withdrawals left submitted by [`Self::execute_withdrawal`] after a failure or a restart,
+    /// by the receipts of their transactions. Returns how many were resolved.
+    #[cfg(feature = "db")]
+    pub async fn reconcile_withdrawals(&self) -> Result<usize> {
+        let sender = self.contracts.provider.address();
+        let mut resolved = 0;
+        for (id, nonce, submitted_at) in self.database.submitted_withdrawals()? {
+            let Some(withdrawal) = self.database.get_withdrawal(&id)? else {
+                continue;
+            };
+            let status = match self.database.get_transaction(&sender, nonce)? {
+                // Recorded before it's broadcast, so it was never sent
+                None if chrono::Utc::now() - submitted_at
+                    > chrono::Duration::seconds(Self::WITHDRAWAL_SUBMISSION_GRACE_SECS) =>
+                {
+                    self.database
+                        .fail_withdrawal(&id, "withdrawal transaction was never sent")?;
+                    warn!(
+                        ?id,
+                        nonce, "Withdrawal failed, its transaction was never sent"
+                    );
+                    resolved += 1;
+                    continue;
+                }
+                None => continue,
+                Some(tx) => {
+                    match tx.status {
+                        AvsTxStatus::Pending => continue,
+                        AvsTxStatus::Dropped => TxStatus::Dropped,
+                        AvsTxStatus::Confirmed | AvsTxStatus::Reverted => {
+                            let mut mined = None;
+                            for hash in tx.tx_hashes.iter().rev() {
+                                if let Some(receipt) = self
+                                    .contracts
+                                    .provider
+                                    .get_transaction_receipt(*hash)
+                                    .await?
+                                {
+                                    mined = Some(receipt);
+                                    break;
+                                }
+                            }
+                            // Not found by any of its hashes, checked again on the next run
+                            let Some(receipt) = mined else {
+                                continue;
+                            };
+                            if receipt.status == Some(1.into()) {
+                                TxStatus::Confirmed(receipt)
+                            } else {
+                                TxStatus::Reverted(receipt)
+                            }
+                        }
+                    }
+                }
+            };
+            self.resolve_withdrawal(&withdrawal, &status).await?;
+            resolved += 1;
+        }
+        Ok(resolved)
     }
 
     // Get balance by querying the chain
     pub async fn get_vault_balance_now(&self, someone: &Address) -> Result<U256> {
-        let deposit: U256 = self
-            .contracts
-            .fermah_contracts
-            .vault
-            .balances(*someone)
-            .call()
-            .await?;
+        let observed = self.observed_head().await;
+        let deposit = self.vault_balance_at(someone, observed).await?;
 
         #[cfg(feature = "db")]
         if !deposit.is_zero() {
             self.database
-                .set_proof_requester_deposit(someone, deposit)?;
+                .set_proof_requester_deposit_at(someone, deposit, observed)?;
         }
 
         debug!(address=?someone, ?deposit, "Checked balance");
@@ -281,6 +560,147 @@ impl Avs {
         Ok(deposit)
     }
 
+    /// Reads the vault balance at the given block, or at the latest one if `None`
+    async fn vault_balance_at(&self, someone: &Address, at: Option<ObservedBlock>) -> Result<U256> {
+        let mut call = self.contracts.fermah_contracts.vault.balances(*someone);
+        if let Some(at) = at {
+            call = call.block(BlockId::from(at.hash));
+        }
+
+        Ok(call.call().await?)
+    }
+
+    #[cfg(feature = "db")]
+    /// Refreshes the cached deposits of the requesters who deposited to the vault in the `[from, to]` block range,
+    /// returns them. The events are queried [`MAX_LOG_RANGE`] blocks at a time.
+    pub async fn refresh_deposits(&self, from: u64, to: u64) -> Result<Vec<Address>> {
+        let mut depositors = HashSet::new();
+        for (chunk_from, chunk_to) in chunks(from, to, MAX_LOG_RANGE) {
+            let deposits = self
+                .contracts
+                .fermah_contracts
+                .vault
+                .deposit_filter()
+                .from_block(chunk_from)
+                .to_block(chunk_to)
+                .query()
+                .await
+                .with_context(|| {
+                    format!("failed to query vault deposits of blocks {chunk_from}..={chunk_to}")
+                })?;
+            depositors.extend(deposits.into_iter().map(|deposit| deposit.user));
+        }
+
+        let depositors: Vec<Address> = depositors.into_iter().collect();
+        for depositor in &depositors {
+            self.get_vault_balance_now(depositor).await?;
+        }
+        METRICS.inc_deposit_refreshes(depositors.len() as u64);
+
+        Ok(depositors)
+    }
+
+    #[cfg(feature = "db")]
+    /// Compares the vault balances at block `to` with the books of the matchmaker, and with the `BalanceUpdated` events
+    /// the vault emitted in the `[from, to]` block range. The requesters with books and the addresses of the events
+    /// are checked, the report is recorded for `getVaultReconciliation`. The events are queried [`MAX_LOG_RANGE`]
+    /// blocks at a time.
+    pub async fn reconcile_vault(&self, from: u64, to: u64) -> Result<VaultReconciliation> {
+        // Oldest first, the last event of an address is its balance at `to`
+        let mut event_balances: HashMap<Address, U256> = HashMap::new();
+        for (chunk_from, chunk_to) in chunks(from, to, MAX_LOG_RANGE) {
+            let updates = self
+                .contracts
+                .fermah_contracts
+                .vault
+                .balance_updated_filter()
+                .from_block(chunk_from)
+                .to_block(chunk_to)
+                .query()
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to query vault balance updates of blocks {chunk_from}..={chunk_to}"
+                    )
+                })?;
+            event_balances.extend(
+                updates
+                    .into_iter()
+                    .map(|update| (update.user, update.balance)),
+            );
+        }
+
+        let booked = self.database.get_booked_balances()?;
+        let addresses: BTreeSet<Address> = booked
+            .keys()
+            .chain(event_balances.keys())
+            .copied()
+            .collect();
+        let addresses: Vec<Address> = addresses.into_iter().collect();
+        let vault_balances = self.vault_balances_at(&addresses, to).await?;
+        let mut discrepancies = vec![];
+        for (address, vault_balance) in addresses.iter().zip(vault_balances) {
+            discrepancies.extend(VaultDiscrepancy::find(
+                *address,
+                vault_balance,
+                &booked.get(address).copied().unwrap_or_default(),
+                event_balances.get(address).copied(),
+            ));
+        }
+        let mut counts: HashMap<&'static str, u64> = HashMap::new();
+        for discrepancy in &discrepancies {
+            *counts.entry(discrepancy.kind.into()).or_default() += 1;
+        }
+        METRICS.set_vault_discrepancies(counts);
+
+        self.database
+            .record_vault_reconciliation(to, addresses.len() as u64, discrepancies)
+    }
+
+    #[cfg(feature = "db")]
+    /// Addresses whose vault balances are read by one multicall
+    const BALANCE_BATCH: usize = 200;
+
+    #[cfg(feature = "db")]
+    /// Reads the vault balances of the addresses at block `at`, in their order. They are batched through Multicall3
+    /// where the chain has it, read one by one otherwise, e.g. on a devnet.
+    async fn vault_balances_at(&self, addresses: &[Address], at: u64) -> Result<Vec<U256>> {
+        let vault = &self.contracts.fermah_contracts.vault;
+        let mut balances = Vec::with_capacity(addresses.len());
+        let mut multicall = match Multicall::new(vault.client(), None).await {
+            Ok(multicall) => multicall.block(at),
+            Err(e) => {
+                debug!(error=?e, "no multicall on the chain, reading the vault balances one by one");
+                for address in addresses {
+                    let balance = vault
+                        .balances(*address)
+                        .block(at)
+                        .call()
+                        .await
+                        .with_context(|| {
+                            format!("failed to read the vault balance of {address:?}")
+                        })?;
+                    balances.push(balance);
+                }
+                return Ok(balances);
+            }
+        };
+        for batch in addresses.chunks(Self::BALANCE_BATCH) {
+            multicall.clear_calls();
+            for address in batch {
+                multicall.add_call(vault.balances(*address), false);
+            }
+            let batch_balances: Vec<U256> = multicall.call_array().await.with_context(|| {
+                format!(
+                    "failed to read the vault balances of {} addresses",
+                    batch.len()
+                )
+            })?;
+            balances.extend(batch_balances);
+        }
+        Ok(balances)
+    }
+
     #[cfg(feature = "db")]
     pub fn get_vault_balance_cached(&self, someone: &Address) -> Result<Option<U256>> {
         self.database.get_seeker_deposit(someone)
@@ -290,12 +710,9 @@ impl Avs {
         &self,
         operator_id: OperatorId,
     ) -> Result<Option<U256>> {
-        let registered_till_block: U256 = self
-            .contracts
-            .avs_contracts
-            .registry_coordinator
-            .registered_till_block(operator_id.0)
-            .call()
+        let observed = self.observed_head().await;
+        let registered_till_block = self
+            .registered_till_block_at(&operator_id, observed)
             .await?;
         debug!(
             ?registered_till_block,
@@ -304,8 +721,11 @@ impl Avs {
         );
 
         #[cfg(feature = "db")]
-        self.database
-            .set_operator_registered_till(&operator_id, registered_till_block)?;
+        self.database.set_operator_registered_till_at(
+            &operator_id,
+            registered_till_block,
+            observed,
+        )?;
 
         if registered_till_block.is_zero() {
             return Ok(None);
@@ -313,9 +733,11 @@ impl Avs {
         Ok(Some(registered_till_block))
     }
 
+    /// Distributes the operator payouts, and the protocol commission of the same requests to the treasury
     pub async fn distribute_payments_for_many(
         &self,
         payments: &HashMap<OperatorId, HashMap<Address, U256>>,
+        protocol_fees: &HashMap<Address, U256>,
     ) -> Result<TransactionReceipt> {
         let mut provers = vec![];
         let mut requesters = vec![];
@@ -332,21 +754,40 @@ impl Avs {
             requesters.push(reqs);
             amounts.push(amts);
         }
-        self.contracts
+        if let Some((treasury, reqs, amts)) = self.treasury_payment(protocol_fees) {
+            provers.push(treasury);
+            requesters.push(reqs);
+            amounts.push(amts);
+        }
+
+        let call = self
+            .contracts
             .fermah_contracts
             .vault
-            .distribute_to_provers(provers, requesters, amounts)
-            .send()
-            .await?
-            .await?
+            .distribute_to_provers(provers, requesters, amounts);
+
+        self.tx_manager
+            .send_and_wait(call.tx)
+            .await
             .context("failed to distribute")
     }
 
+    /// Distributes the payouts of one operator, with the protocol commission of the same requests if there is any
     pub async fn distribute_payments(
         &self,
         prover: &OperatorId,
         payments: &HashMap<Address, U256>,
+        protocol_fees: &HashMap<Address, U256>,
     ) -> Result<TransactionReceipt> {
+        if self.treasury_payment(protocol_fees).is_some() {
+            return self
+                .distribute_payments_for_many(
+                    &HashMap::from([(*prover, payments.clone())]),
+                    protocol_fees,
+                )
+                .await;
+        }
+
         let mut requesters = vec![];
         let mut amounts = vec![];
         for (proof_requester, requester_amount) in payments {
@@ -354,48 +795,566 @@ impl Avs {
             amounts.push(*requester_amount);
         }
 
-        self.contracts
+        let call = self
+            .contracts
             .fermah_contracts
             .vault
-            .distribute_to_prover(prover.0, requesters, amounts)
-            .send()
-            .await?
-            .await?
+            .distribute_to_prover(prover.0, requesters, amounts);
+
+        self.tx_manager
+            .send_and_wait(call.tx)
+            .await
             .context("failed to distribute")
     }
 
+    /// The vault pays the treasury like another prover, out of the requesters' deposits
+    fn treasury_payment(
+        &self,
+        protocol_fees: &HashMap<Address, U256>,
+    ) -> Option<(Address, Vec<Address>, Vec<U256>)> {
+        let (requesters, amounts): (Vec<Address>, Vec<U256>) = protocol_fees
+            .iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(requester, amount)| (*requester, *amount))
+            .unzip();
+        if requesters.is_empty() {
+            return None;
+        }
+
+        // Fees reserved before the fee was turned off are still owed to the treasury
+        let treasury = self.contracts.protocol_fee.treasury;
+        if treasury.is_zero() {
+            warn!(
+                ?requesters,
+                "protocol fees not distributed: no treasury configured"
+            );
+            return None;
+        }
+        Some((treasury, requesters, amounts))
+    }
+
+    /// Freezes the operator through the EL slasher. The operator must have opted into slashing by the service manager.
+    pub async fn freeze_operator(&self, operator_id: &OperatorId) -> Result<TransactionReceipt> {
+        let call = self
+            .contracts
+            .el_contracts
+            .slasher
+            .freeze_operator(operator_id.0);
+
+        self.tx_manager
+            .send_and_wait(call.tx)
+            .await
+            .context("failed to freeze operator")
+    }
+
+    pub async fn is_operator_frozen(&self, operator_id: &OperatorId) -> Result<bool> {
+        let frozen = self
+            .contracts
+            .el_contracts
+            .slasher
+            .is_frozen(operator_id.0)
+            .call()
+            .await?;
+
+        Ok(frozen)
+    }
+
+    /// Deregisters the operator from the quorums of the AVS, the signer has to be the ejector of the registry
+    /// coordinator
+    pub async fn eject_operator(&self, operator_id: &OperatorId) -> Result<TransactionReceipt> {
+        let quorum_numbers = QuorumNumbers::<1>::new(Self::QUORUM_NUMBERS.to_vec());
+        let call = self
+            .contracts
+            .avs_contracts
+            .registry_coordinator
+            .eject_operator(operator_id.0, quorum_numbers.into());
+
+        self.tx_manager
+            .send_and_wait(call.tx)
+            .await
+            .context("failed to eject operator")
+    }
+
+    #[cfg(feature = "db")]
+    /// Settles the operator which deregistered: pays it out for its proven requests, and ejects it from the AVS if
+    /// it's leaving it. Redundant requests are paid with the other operators whose proofs agreed. Returns the
+    /// requests paid for.
+    pub async fn exit_operator(
+        &self,
+        operator_id: &OperatorId,
+        leave_avs: bool,
+    ) -> Result<Vec<ProofRequestId>> {
+        let (payments, unpaid) = self.database.get_ready_to_pay_proof_requests(operator_id)?;
+        if !unpaid.is_empty() {
+            let protocol_fees = self.database.get_protocol_fees(&unpaid)?;
+            let receipt = self
+                .distribute_payments(operator_id, &payments, &protocol_fees)
+                .await?;
+            if receipt.status != Some(1.into()) {
+                anyhow::bail!(
+                    "payout of operator {operator_id:?} reverted in {:?}",
+                    receipt.transaction_hash
+                );
+            }
+            self.database.set_proof_requests_paid(&unpaid)?;
+            info!(?operator_id, tx=?receipt.transaction_hash, paid=unpaid.len(), "exiting operator paid out");
+        }
+
+        if leave_avs {
+            let receipt = self.eject_operator(operator_id).await?;
+            info!(?operator_id, tx=?receipt.transaction_hash, "operator left the AVS");
+        }
+        Ok(unpaid)
+    }
+
+    #[cfg(feature = "db")]
+    /// Records the offense and freezes the operator, if its unsettled offenses within the policy window call for it
+    pub async fn record_offense(
+        &self,
+        policy: &SlashingPolicy,
+        operator_id: &OperatorId,
+        proof_request_id: &ProofRequestId,
+        offense: Offense,
+    ) -> Result<SlashingDecision> {
+        if !self
+            .database
+            .record_operator_offense(operator_id, proof_request_id, offense)?
+        {
+            return Ok(SlashingDecision::Tolerate);
+        }
+
+        let since = chrono::Utc::now()
+            - chrono::Duration::from_std(policy.window()).context("invalid slashing window")?;
+        let offenses = self.database.get_unsettled_offenses(operator_id, since)?;
+
+        let decision = policy.decide(&offenses);
+        info!(
+            ?operator_id,
+            ?offense,
+            ?offenses,
+            ?decision,
+            "operator offense recorded"
+        );
+
+        if decision == SlashingDecision::Freeze {
+            if self.is_operator_frozen(operator_id).await? {
+                warn!(?operator_id, "operator is already frozen");
+                return Ok(decision);
+            }
+
+            let receipt = self.freeze_operator(operator_id).await?;
+            let settled = self
+                .database
+                .settle_operator_offenses(operator_id, receipt.transaction_hash)?;
+            info!(?operator_id, tx=?receipt.transaction_hash, settled, "operator frozen");
+        }
+
+        Ok(decision)
+    }
+
+    /// Reads the latest block, checks recently observed blocks for a reorg and re-queries cached chain values
+    /// observed on the abandoned fork.
+    pub async fn update_head(&self) -> Result<()> {
+        let block = self
+            .contracts
+            .provider
+            .get_block(BlockNumber::Latest)
+            .await?
+            .context("latest block is not available")?;
+        let head = ObservedBlock {
+            number: block.number.context("latest block has no number")?.as_u64(),
+            hash: block.hash.context("latest block has no hash")?,
+        };
+
+        let fork = {
+            let mut recent_blocks = self.recent_blocks.lock().await;
+
+            // After a restart, pick up blocks at which cached values were read, so a reorg during downtime
+            // is still noticed
+            #[cfg(feature = "db")]
+            if recent_blocks.is_empty() {
+                let since = head
+                    .number
+                    .saturating_sub(Self::REORG_TRACKING_DEPTH as u64);
+                recent_blocks.extend(self.database.get_cache_observations_since(since)?);
+            }
+
+            if recent_blocks.back() == Some(&head) {
+                return Ok(());
+            }
+
+            let fork = self.find_fork_point(&recent_blocks).await?;
+            if let Some(fork) = fork {
+                recent_blocks.retain(|b| b.number < fork);
+            }
+            recent_blocks.retain(|b| b.number < head.number);
+            recent_blocks.push_back(head);
+            while recent_blocks.len() > Self::REORG_TRACKING_DEPTH {
+                recent_blocks.pop_front();
+            }
+
+            fork
+        };
+
+        *self.block_number.lock().await = head.number;
+
+        if let Some(fork) = fork {
+            warn!(fork, head = head.number, "chain reorg detected");
+            #[cfg(feature = "db")]
+            self.requery_cache_since(fork, head).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Lowest block number among `recent_blocks` that is not canonical anymore, walking back from the newest block
+    /// until a canonical one is found
+    async fn find_fork_point(
+        &self,
+        recent_blocks: &VecDeque<ObservedBlock>,
+    ) -> Result<Option<u64>> {
+        let mut fork = None;
+        let mut canonical_at = None;
+        let mut canonical_hash: Option<(u64, H256)> = None;
+
+        for observed in recent_blocks.iter().rev() {
+            // Everything below a canonical block is canonical as well
+            if canonical_at.is_some_and(|n| n > observed.number) {
+                break;
+            }
+
+            let hash = match canonical_hash {
+                Some((number, hash)) if number == observed.number => hash,
+                _ => {
+                    let hash = self
+                        .contracts
+                        .provider
+                        .get_block(observed.number)
+                        .await?
+                        .and_then(|b| b.hash)
+                        .unwrap_or_default();
+                    canonical_hash = Some((observed.number, hash));
+                    hash
+                }
+            };
+
+            if hash == observed.hash {
+                canonical_at = Some(observed.number);
+            } else {
+                fork = Some(observed.number);
+            }
+        }
+
+        Ok(fork)
+    }
+
+    #[cfg(feature = "db")]
+    /// Re-reads deposits and registeredTillBlock of entries observed at or after the `fork` block
+    async fn requery_cache_since(&self, fork: u64, head: ObservedBlock) -> Result<()> {
+        let (requesters, operators) = self.database.get_cache_entries_observed_since(fork)?;
+
+        for requester in &requesters {
+            let deposit = self.vault_balance_at(requester, Some(head)).await?;
+            self.database
+                .set_proof_requester_deposit_at(requester, deposit, Some(head))?;
+        }
+        for operator_id in &operators {
+            let registered_till_block = self
+                .registered_till_block_at(operator_id, Some(head))
+                .await?;
+            self.database.set_operator_registered_till_at(
+                operator_id,
+                registered_till_block,
+                Some(head),
+            )?;
+        }
+
+        info!(
+            fork,
+            requesters = requesters.len(),
+            operators = operators.len(),
+            "cache entries re-queried after reorg"
+        );
+        METRICS.inc_cache_invalidations("deposit", requesters.len() as u64);
+        METRICS.inc_cache_invalidations("registered_till_block", operators.len() as u64);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "db")]
+    /// Archives the operators whose registration ended more than [`Self::ARCHIVAL_GRACE_BLOCKS`] ago, and restores
+    /// the ones which extended it since
+    pub async fn archive_expired_operators(&self) -> Result<()> {
+        let current_block = { *self.block_number.lock().await };
+        // Also skipped until the block update thread saw the first block
+        let Some(expired_before) = current_block.checked_sub(Self::ARCHIVAL_GRACE_BLOCKS) else {
+            return Ok(());
+        };
+
+        let archival = self
+            .database
+            .archive_expired_operators(expired_before.into())?;
+        for operator_id in &archival.archived {
+            info!(
+                ?operator_id,
+                expired_before, "operator archived, its registration expired"
+            );
+        }
+        for operator_id in &archival.restored {
+            info!(
+                ?operator_id,
+                "archived operator restored, its registration was extended"
+            );
+        }
+        METRICS.inc_operator_archivals("archived", archival.archived.len() as u64);
+        METRICS.inc_operator_archivals("restored", archival.restored.len() as u64);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "db")]
+    const OPERATOR_ARCHIVAL_PERIOD: Duration = Duration::from_secs(10 * 60);
+    #[cfg(feature = "db")]
+    /// Periodically archives the operators whose registration expired, see [`Self::archive_expired_operators`]
+    pub async fn start_operator_archival_thread(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+    ) -> Result<()> {
+        let avs = self.clone();
+        supervisor.spawn(
+            tasks,
+            "operator_archival",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let avs = avs.clone();
+                async move {
+                    let mut interval = tokio::time::interval(Self::OPERATOR_ARCHIVAL_PERIOD);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Operator archival thread stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => {
+                                if let Err(e) = avs.archive_expired_operators().await {
+                                    warn!(error=?e, "failed to archive expired operators");
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "db")]
+    const WITHDRAWAL_RECONCILIATION_PERIOD: Duration = Duration::from_secs(60);
+    #[cfg(feature = "db")]
+    /// Periodically resolves the withdrawals whose transactions weren't known to be mined when they were executed,
+    /// see [`Self::reconcile_withdrawals`], and fails the ones whose deadline passed awaiting approval
+    pub async fn start_withdrawal_reconciliation_thread(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+    ) -> Result<()> {
+        let avs = self.clone();
+        supervisor.spawn(
+            tasks,
+            "withdrawal_reconciliation",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let avs = avs.clone();
+                async move {
+                    let mut interval =
+                        tokio::time::interval(Self::WITHDRAWAL_RECONCILIATION_PERIOD);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Withdrawal reconciliation thread stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => {
+                                match avs.reconcile_withdrawals().await {
+                                    Ok(0) => {}
+                                    Ok(resolved) => info!(resolved, "submitted withdrawals reconciled"),
+                                    Err(e) => warn!(error=?e, "failed to reconcile submitted withdrawals"),
+                                }
+                                match avs.database.expire_withdrawals_awaiting_approval() {
+                                    Ok(expired) if expired.is_empty() => {}
+                                    Ok(expired) => info!(?expired, "withdrawals awaiting approval expired"),
+                                    Err(e) => warn!(error=?e, "failed to expire withdrawals awaiting approval"),
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "db")]
+    /// Runs `scan` every `period` on the blocks the poller hands out, up to the head seen by the block update thread.
+    /// The poller advances past each range scanned, a failed one is handed out again on the next tick.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_block_range_poller<F, Fut>(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+        name: &'static str,
+        period: Duration,
+        poller: BlockRangePoller,
+        scan: F,
+    ) where
+        F: Fn(Avs, u64, u64) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let avs = self.clone();
+        supervisor.spawn(
+            tasks,
+            name,
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let avs = avs.clone();
+                let poller = poller.clone();
+                let scan = scan.clone();
+                async move {
+                    let mut interval = tokio::time::interval(period);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!(name, "Block range poller stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => {
+                                let head = { *avs.block_number.lock().await };
+                                while let Some((from, to)) = poller.next_range(head) {
+                                    if let Err(e) = scan(avs.clone(), from, to).await {
+                                        warn!(error=?e, name, from, to, "failed to scan blocks");
+                                        break;
+                                    }
+                                    poller.advance(to);
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    #[cfg(feature = "db")]
+    const DEPOSIT_POLL_PERIOD: Duration = Duration::from_secs(60);
+    #[cfg(feature = "db")]
+    /// Blocks scanned for deposits when the monitor starts, about an hour on Holesky
+    const DEPOSIT_LOOKBACK_BLOCKS: u64 = 300;
+    #[cfg(feature = "db")]
+    /// Periodically refreshes the deposits of the requesters who deposited to the vault, so they don't have to call
+    /// `updateBalance`, and sends them to `deposited` for their underfunded requests to be re-evaluated. The blocks
+    /// are scanned [`MAX_LOG_RANGE`] at a time, so the monitor catches up after an outage.
+    pub async fn start_deposit_monitor_thread(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+        deposited: mpsc::Sender<Address>,
+    ) -> Result<()> {
+        let poller = BlockRangePoller::new(Self::DEPOSIT_LOOKBACK_BLOCKS).chunked(MAX_LOG_RANGE);
+        self.spawn_block_range_poller(
+            supervisor,
+            tasks,
+            "deposit_monitor",
+            Self::DEPOSIT_POLL_PERIOD,
+            poller,
+            move |avs, from, to| {
+                let deposited = deposited.clone();
+                async move {
+                    for depositor in avs.refresh_deposits(from, to).await? {
+                        info!(?depositor, "deposit refreshed");
+                        if deposited.send(depositor).await.is_err() {
+                            warn!(?depositor, "deposit receiver is gone");
+                        }
+                    }
+                    Ok(())
+                }
+            },
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "db")]
+    const VAULT_RECONCILIATION_PERIOD: Duration = Duration::from_secs(10 * 60);
+    #[cfg(feature = "db")]
+    /// Periodically compares the books of the matchmaker with the vault, see [`Self::reconcile_vault`]. Each run scans
+    /// the events of the blocks since the previous one, the first one the last [`Self::DEPOSIT_LOOKBACK_BLOCKS`]. The
+    /// balances are compared at the head, so the blocks aren't handed out in chunks, `reconcile_vault` queries their
+    /// events in chunks itself.
+    pub async fn start_vault_reconciliation_thread(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+    ) -> Result<()> {
+        self.spawn_block_range_poller(
+            supervisor,
+            tasks,
+            "vault_reconciliation",
+            Self::VAULT_RECONCILIATION_PERIOD,
+            BlockRangePoller::new(Self::DEPOSIT_LOOKBACK_BLOCKS),
+            |avs, from, to| {
+                async move {
+                    let report = avs.reconcile_vault(from, to).await?;
+                    if !report.discrepancies.is_empty() {
+                        warn!(
+                            n = report.discrepancies.len(),
+                            block = to,
+                            "vault balances disagree with the books"
+                        );
+                    }
+                    Ok(())
+                }
+            },
+        );
+        Ok(())
+    }
+
     const HOLESKY_SLOT_DURATION: Duration = Duration::from_secs(12);
     /// A block is minted every 12 seconds on the Holesky network.
     /// TODO: use websocket for mainnet.
     pub async fn start_holesky_block_update_thread(
         &self,
+        supervisor: &Supervisor,
         tasks: &mut JoinSet<Result<()>>,
-        mut shutdown_rx: watch::Receiver<bool>,
     ) -> Result<()> {
-        let provider = self.contracts.provider.clone();
-        let block_number = self.block_number.clone();
-        let mut interval = tokio::time::interval(Self::HOLESKY_SLOT_DURATION);
-        tasks.spawn({
-            async move {
-                loop {
-                    tokio::select! {
-                        _ = shutdown_rx.changed() => {
-                            info!("Block update thread stopped");
-                            return Ok(())
-                        }
+        let avs = self.clone();
+        supervisor.spawn(
+            tasks,
+            "block_updates",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let avs = avs.clone();
+                async move {
+                    let mut interval = tokio::time::interval(Self::HOLESKY_SLOT_DURATION);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Block update thread stopped");
+                                return Ok(())
+                            }
 
-                        _= interval.tick() => {
-                            if let Ok(current_block_number) = provider.get_block_number().await {
-                                let mut block_lock = block_number.lock().await;
-                                *block_lock = current_block_number.as_u64();
-                            } else {
-                                warn!("failed to read current block number");
+                            _= interval.tick() => {
+                                if let Err(e) = avs.update_head().await {
+                                    warn!(error=?e, "failed to update current block");
+                                }
                             }
                         }
                     }
                 }
-            }
-        });
+            },
+        );
         Ok(())
     }
 }
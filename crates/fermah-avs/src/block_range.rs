@@ -0,0 +1,109 @@
+//! Scanning the logs of the chain in bounded block ranges, as the head moves
+
+use std::sync::{Arc, Mutex};
+
+/// Most blocks queried by one `eth_getLogs` call, providers refuse or time out on larger ranges
+pub const MAX_LOG_RANGE: u64 = 2_000;
+
+/// Splits the `[from, to]` block range into consecutive ranges of at most `size` blocks
+pub fn chunks(from: u64, to: u64, size: u64) -> impl Iterator<Item = (u64, u64)> {
+    let size = size.max(1);
+    let mut next = Some(from).filter(|from| *from <= to);
+    std::iter::from_fn(move || {
+        let start = next?;
+        let end = start.saturating_add(size - 1).min(to);
+        next = end.checked_add(1).filter(|next| *next <= to);
+        Some((start, end))
+    })
+}
+
+/// Blocks scanned so far by a poller following the head. The first range starts `lookback` blocks behind the head,
+/// the next ones right after the last scanned block. Clones share the progress, so a restarted poller doesn't scan
+/// the blocks twice.
+#[derive(Debug, Clone)]
+pub struct BlockRangePoller {
+    scanned_till: Arc<Mutex<Option<u64>>>,
+    lookback: u64,
+    chunk: u64,
+}
+
+impl BlockRangePoller {
+    /// Poller handing out all the blocks up to the head at once
+    pub fn new(lookback: u64) -> Self {
+        Self {
+            scanned_till: Arc::new(Mutex::new(None)),
+            lookback,
+            chunk: u64::MAX,
+        }
+    }
+
+    /// Hands out ranges of at most `chunk` blocks, the poller catches up with the head range by range
+    pub fn chunked(mut self, chunk: u64) -> Self {
+        self.chunk = chunk;
+        self
+    }
+
+    /// Next range of blocks up to `head` to scan, `None` once they all are. A head of 0 is the head not seen yet.
+    pub fn next_range(&self, head: u64) -> Option<(u64, u64)> {
+        let from = match *self.scanned_till.lock().unwrap() {
+            Some(scanned) if scanned >= head => return None,
+            Some(scanned) => scanned + 1,
+            None if head == 0 => return None,
+            None => head.saturating_sub(self.lookback),
+        };
+        chunks(from, head, self.chunk).next()
+    }
+
+    /// Marks the blocks up to `to` scanned
+    pub fn advance(&self, to: u64) {
+        *self.scanned_till.lock().unwrap() = Some(to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks() {
+        assert_eq!(
+            chunks(10, 4_500, MAX_LOG_RANGE).collect::<Vec<_>>(),
+            vec![(10, 2_009), (2_010, 4_009), (4_010, 4_500)]
+        );
+        assert_eq!(chunks(7, 7, 100).collect::<Vec<_>>(), vec![(7, 7)]);
+        assert_eq!(chunks(8, 7, 100).count(), 0);
+        assert_eq!(
+            chunks(u64::MAX - 1, u64::MAX, 1).collect::<Vec<_>>(),
+            vec![(u64::MAX - 1, u64::MAX - 1), (u64::MAX, u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn test_poller_catches_up_by_chunk() {
+        let poller = BlockRangePoller::new(300).chunked(MAX_LOG_RANGE);
+        // Head not seen yet
+        assert_eq!(poller.next_range(0), None);
+
+        assert_eq!(poller.next_range(1_000), Some((700, 1_000)));
+        poller.advance(1_000);
+        assert_eq!(poller.next_range(1_000), None);
+
+        // Far behind after an outage, a failed range is handed out again
+        assert_eq!(poller.next_range(5_500), Some((1_001, 3_000)));
+        assert_eq!(poller.next_range(5_500), Some((1_001, 3_000)));
+        poller.advance(3_000);
+        // Shared by the clones, e.g. the restarted poller
+        let restarted = poller.clone();
+        assert_eq!(restarted.next_range(5_500), Some((3_001, 5_000)));
+        restarted.advance(5_000);
+        assert_eq!(poller.next_range(5_500), Some((5_001, 5_500)));
+    }
+
+    #[test]
+    fn test_poller_unchunked() {
+        let poller = BlockRangePoller::new(300);
+        assert_eq!(poller.next_range(100), Some((0, 100)));
+        poller.advance(100);
+        assert_eq!(poller.next_range(9_000), Some((101, 9_000)));
+    }
+}
@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 
-use ethers::types::Address;
-use fermah_common::manifest::{ElManifestConfig, FermahManifestConfig};
+use ethers::types::{Address, H256};
+use fermah_common::{
+    manifest::{ElManifestConfig, FermahManifestConfig},
+    types::fee::ProtocolFee,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{slashing::SlashingPolicy, tx_manager::FeePolicy};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
@@ -11,9 +16,77 @@ pub struct Config {
     pub avs_contract: AvsContract,
     pub fermah_contract: FermahContract,
     pub el_contract: ElContract,
+    #[serde(default)]
+    pub slashing_policy: SlashingPolicy,
+    #[serde(default)]
+    pub fee_policy: FeePolicy,
+    /// Commission distributed to the protocol treasury out of every payout
+    #[serde(default)]
+    pub protocol_fee: ProtocolFee,
+    /// Keccak256 hashes of the code expected at the contract addresses, by their field, e.g. `fermahContract.vault`,
+    /// of the implementation's code for proxies. The ones known for the network take precedence, the contracts
+    /// without either only have to be deployed.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub codehashes: HashMap<String, H256>,
 }
 
 impl Config {
+    /// Configured contract addresses by the field they are set in, the slasher is left out if it isn't set
+    pub fn contract_addresses(&self) -> Vec<(String, Address)> {
+        let mut addresses = vec![
+            (
+                "avsContract.operatorStateRetriever",
+                self.avs_contract.operator_state_retriever,
+            ),
+            (
+                "avsContract.registryCoordinator",
+                self.avs_contract.registry_coordinator,
+            ),
+            (
+                "fermahContract.disputeManager",
+                self.fermah_contract.dispute_manager,
+            ),
+            (
+                "fermahContract.serviceManager",
+                self.fermah_contract.service_manager,
+            ),
+            ("fermahContract.vault", self.fermah_contract.vault),
+            (
+                "fermahContract.vaultToken",
+                self.fermah_contract.vault_token,
+            ),
+            ("fermahContract.whitelist", self.fermah_contract.whitelist),
+            ("elContract.avsDirectory", self.el_contract.avs_directory),
+            (
+                "elContract.delegationManager",
+                self.el_contract.delegation_manager,
+            ),
+            (
+                "elContract.strategyManager",
+                self.el_contract.strategy_manager,
+            ),
+            (
+                "elContract.rewardsCoordinator",
+                self.el_contract.rewards_coordinator,
+            ),
+        ]
+        .into_iter()
+        .map(|(field, address)| (field.to_string(), address))
+        .collect::<Vec<_>>();
+
+        if !self.el_contract.slasher.is_zero() {
+            addresses.push(("elContract.slasher".to_string(), self.el_contract.slasher));
+        }
+        let mut strategies = self.el_contract.strategies.iter().collect::<Vec<_>>();
+        strategies.sort();
+        addresses.extend(
+            strategies
+                .into_iter()
+                .map(|(name, address)| (format!("elContract.strategies.{name}"), *address)),
+        );
+        addresses
+    }
+
     pub fn merge(&mut self, el_config: &ElManifestConfig, fermah_config: &FermahManifestConfig) {
         self.avs_contract.operator_state_retriever =
             fermah_config.addresses.operator_state_retriever;
@@ -29,6 +102,7 @@ impl Config {
         self.el_contract.delegation_manager = el_config.addresses.delegation_manager;
         self.el_contract.strategy_manager = el_config.addresses.strategy_manager;
         self.el_contract.rewards_coordinator = el_config.addresses.rewards_coordinator;
+        self.el_contract.slasher = el_config.addresses.slasher;
 
         self.el_contract
             .strategies
@@ -62,5 +136,7 @@ pub struct ElContract {
     pub delegation_manager: Address,
     pub strategy_manager: Address,
     pub rewards_coordinator: Address,
+    #[serde(default)]
+    pub slasher: Address,
     pub strategies: HashMap<String, Address>,
 }
@@ -24,6 +24,8 @@ abigen!(
     "contracts/out/IRewardsCoordinator.sol/IRewardsCoordinator.json"
 );
 
+abigen!(ISlasher, "contracts/out/ISlasher.sol/ISlasher.json");
+
 abigen!(
     AVSDirectoryStorage,
     "contracts/out/AVSDirectoryStorage.sol/AVSDirectoryStorage.json"
@@ -36,6 +38,7 @@ pub struct ELContracts {
     pub delegation: DelegationManager<SignerMiddlewareContract>,
     pub strategy_manager: IStrategyManager<SignerMiddlewareContract>,
     pub rewards_coordinator: IRewardsCoordinator<SignerMiddlewareContract>,
+    pub slasher: ISlasher<SignerMiddlewareContract>,
     pub strategies: Strategies,
 }
 
@@ -59,6 +62,7 @@ impl ELContracts {
                 config.el_contract.rewards_coordinator,
                 middleware.clone(),
             ),
+            slasher: ISlasher::new(config.el_contract.slasher, middleware.clone()),
             strategies: Strategies::new(config, middleware),
         }
     }
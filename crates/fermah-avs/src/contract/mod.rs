@@ -3,6 +3,7 @@ pub mod el;
 pub mod erc20;
 pub mod fermah;
 pub mod strategy;
+pub mod verify;
 
 use std::sync::Arc;
 
@@ -13,11 +14,12 @@ use ethers::{
     middleware::MiddlewareBuilder,
     prelude::{Http, Provider, Signer},
 };
-use fermah_common::crypto::signer::ecdsa::EcdsaSigner;
+use fermah_common::types::fee::ProtocolFee;
+use tracing::debug;
 use url::Url;
 
-use self::fermah::FermahContracts;
-use crate::{config::Config, SignerMiddlewareContract};
+use self::{fermah::FermahContracts, verify::verify_contracts};
+use crate::{config::Config, signer::ChainSigner, tx_manager::FeePolicy, SignerMiddlewareContract};
 
 #[derive(Clone)]
 pub struct Contracts {
@@ -26,21 +28,41 @@ pub struct Contracts {
     pub el_contracts: ELContracts,
     // Uh, oh, this is so dirty to have provider here and in the contracts
     pub provider: Arc<SignerMiddlewareContract>,
+    pub fee_policy: FeePolicy,
+    pub protocol_fee: ProtocolFee,
 }
 
 impl Contracts {
-    pub async fn from_config(config: &Config, rpc: &Url, signer: EcdsaSigner) -> Result<Self> {
+    /// Transactions are signed by `signer`, either a local key or a KMS held one. Fails if the configured addresses
+    /// don't hold the expected contracts on the chain, pointing at the fields to fix.
+    pub async fn from_config(
+        config: &Config,
+        rpc: &Url,
+        signer: impl Into<ChainSigner>,
+    ) -> Result<Self> {
         let client = Arc::new(
             Provider::<Http>::try_from(&rpc.to_string()).context("failed to create provider")?,
         );
+        // Commissions without a treasury would be reserved and never paid out
+        config
+            .protocol_fee
+            .validate()
+            .context("invalid protocol fee")?;
+        // Misconfigured addresses would otherwise only fail on the first call of their contract
+        let verified = verify_contracts(config, client.as_ref()).await?;
+        debug!(count = verified.len(), "verified the configured contracts");
+
+        let signer: ChainSigner = signer.into();
         let signer = signer.with_chain_id(config.chain_id);
-        let provider = Arc::new(client.with_signer::<EcdsaSigner>(signer));
+        let provider = Arc::new(client.with_signer(signer));
 
         Ok(Self {
             avs_contracts: AVSContracts::new(config, provider.clone()),
             fermah_contracts: FermahContracts::new(config, provider.clone()),
             el_contracts: ELContracts::new(config, provider.clone()),
             provider,
+            fee_policy: config.fee_policy.clone(),
+            protocol_fee: config.protocol_fee,
         })
     }
 }
@@ -0,0 +1,414 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use ethers::{
+    providers::Middleware,
+    types::{Address, H256},
+    utils::keccak256,
+};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::config::Config;
+
+/// Storage slot of the implementation of an EIP-1967 proxy, `keccak256("eip1967.proxy.implementation") - 1`
+const IMPLEMENTATION_SLOT: H256 = H256([
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+]);
+
+/// Codehashes of the contracts deployed on the networks, by chain id and config field. They take precedence over
+/// the ones of the profiles, which are only trusted on first use.
+static KNOWN_CODEHASHES: LazyLock<HashMap<u64, HashMap<String, H256>>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../config/codehashes.json"))
+        .expect("codehashes.json is valid")
+});
+
+/// Codehashes expected on the chain whatever the profile says, by config field
+pub fn known_codehashes(chain_id: u64) -> Option<&'static HashMap<String, H256>> {
+    KNOWN_CODEHASHES.get(&chain_id)
+}
+
+/// Contract found where the config expects it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedContract {
+    /// Config field the address is set in, e.g. `fermahContract.vault`
+    pub field: String,
+    pub address: Address,
+    /// Implementation the address delegates to, if it's an EIP-1967 proxy
+    pub implementation: Option<Address>,
+    /// Hash of the code at the address, of the implementation's code for a proxy
+    pub codehash: H256,
+}
+
+/// Code found at a configured address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeployedCode {
+    Contract(H256),
+    /// EIP-1967 proxy, with the hash of its implementation's code if there's one
+    Proxy {
+        implementation: Address,
+        codehash: Option<H256>,
+    },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContractProblem {
+    #[error("the address isn't set")]
+    Unset,
+
+    #[error("same address as `{0}`, one of them is likely copied by mistake")]
+    Duplicate(String),
+
+    #[error("no contract is deployed there")]
+    NoCode,
+
+    #[error("the proxy delegates to {0:?}, where no contract is deployed")]
+    NoImplementation(Address),
+
+    #[error("codehash is {actual:?} instead of {expected:?}, it's likely another contract or another version of it")]
+    Codehash { expected: H256, actual: H256 },
+}
+
+/// Configured address which doesn't hold the expected contract, with the config field to fix
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("`{field}` ({address:?}): {problem}")]
+pub struct ContractIssue {
+    pub field: String,
+    pub address: Address,
+    pub problem: ContractProblem,
+}
+
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error("failed to query the chain: {0}")]
+    Provider(String),
+
+    #[error("chain RPC is on chain {actual}, the config is for chain {expected}: check `chainId` or the chain RPC URL")]
+    ChainId { expected: u64, actual: u64 },
+
+    #[error("none of the contracts is deployed on chain {0}: the contracts aren't deployed yet, or the chain RPC is a fresh node of the network")]
+    NothingDeployed(u64),
+
+    #[error("{} misconfigured contracts:\n{}", .0.len(), issues_list(.0))]
+    Contracts(Vec<ContractIssue>),
+}
+
+fn issues_list(issues: &[ContractIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("  {issue}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Checks the chain RPC is on the chain of the config, and every configured address holds a contract, with the
+/// codehash known for the network or the config expects for it if there is one. The code of a proxy is its
+/// implementation's, the proxy itself is the same for any contract.
+pub async fn verify_contracts<M: Middleware>(
+    config: &Config,
+    provider: &M,
+) -> Result<Vec<VerifiedContract>, VerifyError> {
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|err| VerifyError::Provider(err.to_string()))?
+        .as_u64();
+    if chain_id != config.chain_id {
+        return Err(VerifyError::ChainId {
+            expected: config.chain_id,
+            actual: chain_id,
+        });
+    }
+
+    let mut found = HashMap::new();
+    for (_, address) in config.contract_addresses() {
+        if address.is_zero() || found.contains_key(&address) {
+            continue;
+        }
+        found.insert(address, deployed_code(provider, address).await?);
+    }
+    diagnose(config, known_codehashes(config.chain_id), &found)
+}
+
+/// Code at the address, `None` if there is none
+async fn deployed_code<M: Middleware>(
+    provider: &M,
+    address: Address,
+) -> Result<Option<DeployedCode>, VerifyError> {
+    let codehash = |address| {
+        async move {
+            let code = provider
+                .get_code(address, None)
+                .await
+                .map_err(|err| VerifyError::Provider(err.to_string()))?;
+            Ok::<_, VerifyError>((!code.is_empty()).then(|| H256(keccak256(&code))))
+        }
+    };
+
+    let Some(own_codehash) = codehash(address).await? else {
+        return Ok(None);
+    };
+    let slot = provider
+        .get_storage_at(address, IMPLEMENTATION_SLOT, None)
+        .await
+        .map_err(|err| VerifyError::Provider(err.to_string()))?;
+    let implementation = Address::from(slot);
+    if implementation.is_zero() {
+        return Ok(Some(DeployedCode::Contract(own_codehash)));
+    }
+    Ok(Some(DeployedCode::Proxy {
+        implementation,
+        codehash: codehash(implementation).await?,
+    }))
+}
+
+/// Compares the codes found at the configured addresses, `None` where there's no code, with the codehashes known
+/// for the network and the ones of the config
+fn diagnose(
+    config: &Config,
+    known: Option<&HashMap<String, H256>>,
+    found: &HashMap<Address, Option<DeployedCode>>,
+) -> Result<Vec<VerifiedContract>, VerifyError> {
+    let addresses = config.contract_addresses();
+    for (field, codehash) in &config.codehashes {
+        if !addresses.iter().any(|(configured, _)| configured == field) {
+            warn!(
+                field,
+                "codehash of a field which isn't a configured contract"
+            );
+        }
+        if let Some(known) = known.and_then(|known| known.get(field)) {
+            if known != codehash {
+                warn!(field, pinned = ?codehash, ?known, "pinned codehash overridden by the one known for the network");
+            }
+        }
+    }
+
+    let mut verified = vec![];
+    let mut issues = vec![];
+    let mut seen: HashMap<Address, &str> = HashMap::new();
+    for (field, address) in &addresses {
+        let problem = if address.is_zero() {
+            Some(ContractProblem::Unset)
+        } else if let Some(first) = seen.get(address) {
+            Some(ContractProblem::Duplicate(first.to_string()))
+        } else {
+            let expected = known
+                .and_then(|known| known.get(field))
+                .or(config.codehashes.get(field));
+            let deployed = match found.get(address).copied().flatten() {
+                None => Err(ContractProblem::NoCode),
+                Some(DeployedCode::Contract(codehash)) => Ok((None, codehash)),
+                Some(DeployedCode::Proxy {
+                    implementation,
+                    codehash: Some(codehash),
+                }) => Ok((Some(implementation), codehash)),
+                Some(DeployedCode::Proxy {
+                    implementation,
+                    codehash: None,
+                }) => Err(ContractProblem::NoImplementation(implementation)),
+            };
+            match (deployed, expected) {
+                (Err(problem), _) => Some(problem),
+                (Ok((_, actual)), Some(expected)) if actual != *expected => {
+                    Some(ContractProblem::Codehash {
+                        expected: *expected,
+                        actual,
+                    })
+                }
+                (Ok((implementation, codehash)), _) => {
+                    verified.push(VerifiedContract {
+                        field: field.clone(),
+                        address: *address,
+                        implementation,
+                        codehash,
+                    });
+                    None
+                }
+            }
+        };
+        seen.entry(*address).or_insert(field);
+
+        if let Some(problem) = problem {
+            issues.push(ContractIssue {
+                field: field.clone(),
+                address: *address,
+                problem,
+            });
+        }
+    }
+
+    if issues.is_empty() {
+        return Ok(verified);
+    }
+    // Nothing to point at, it's the chain which is off
+    if verified.is_empty()
+        && issues
+            .iter()
+            .all(|issue| issue.problem == ContractProblem::NoCode)
+    {
+        return Err(VerifyError::NothingDeployed(config.chain_id));
+    }
+    Err(VerifyError::Contracts(issues))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AvsContract, ElContract, FermahContract};
+
+    fn config() -> Config {
+        let address = Address::from_low_u64_be;
+        Config {
+            chain_id: 17000,
+            avs_contract: AvsContract {
+                operator_state_retriever: address(1),
+                registry_coordinator: address(2),
+            },
+            fermah_contract: FermahContract {
+                dispute_manager: address(3),
+                service_manager: address(4),
+                vault: address(5),
+                vault_token: address(6),
+                whitelist: address(7),
+            },
+            el_contract: ElContract {
+                avs_directory: address(8),
+                delegation_manager: address(9),
+                strategy_manager: address(10),
+                rewards_coordinator: address(11),
+                slasher: Address::zero(),
+                strategies: HashMap::from([("weth".to_string(), address(12))]),
+            },
+            slashing_policy: Default::default(),
+            fee_policy: Default::default(),
+            protocol_fee: Default::default(),
+            codehashes: HashMap::new(),
+        }
+    }
+
+    fn deployed(config: &Config) -> HashMap<Address, Option<DeployedCode>> {
+        config
+            .contract_addresses()
+            .into_iter()
+            .map(|(_, address)| {
+                (
+                    address,
+                    Some(DeployedCode::Contract(H256::from_low_u64_be(
+                        address.to_low_u64_be(),
+                    ))),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let mut config = config();
+        let mut found = deployed(&config);
+        // The unset slasher isn't checked
+        assert_eq!(diagnose(&config, None, &found).unwrap().len(), 12);
+
+        config
+            .codehashes
+            .insert("fermahContract.vault".to_string(), H256::from_low_u64_be(5));
+        assert!(diagnose(&config, None, &found).is_ok());
+
+        // Vault token set to the vault, and no whitelist deployed
+        config.fermah_contract.vault_token = config.fermah_contract.vault;
+        found.insert(config.fermah_contract.whitelist, None);
+        let Err(VerifyError::Contracts(issues)) = diagnose(&config, None, &found) else {
+            panic!("misconfigured contracts verified");
+        };
+        assert_eq!(
+            issues,
+            vec![
+                ContractIssue {
+                    field: "fermahContract.vaultToken".to_string(),
+                    address: config.fermah_contract.vault,
+                    problem: ContractProblem::Duplicate("fermahContract.vault".to_string()),
+                },
+                ContractIssue {
+                    field: "fermahContract.whitelist".to_string(),
+                    address: config.fermah_contract.whitelist,
+                    problem: ContractProblem::NoCode,
+                },
+            ]
+        );
+
+        // Another contract at the vault address
+        let mut config = self::config();
+        config
+            .codehashes
+            .insert("fermahContract.vault".to_string(), H256::repeat_byte(1));
+        let Err(VerifyError::Contracts(issues)) = diagnose(&config, None, &deployed(&config))
+        else {
+            panic!("unexpected vault code verified");
+        };
+        assert_eq!(issues[0].field, "fermahContract.vault");
+
+        let nothing = found.keys().map(|address| (*address, None)).collect();
+        assert!(matches!(
+            diagnose(&self::config(), None, &nothing),
+            Err(VerifyError::NothingDeployed(17000))
+        ));
+    }
+
+    #[test]
+    fn test_diagnose_proxies() {
+        let config = config();
+        let vault = config.fermah_contract.vault;
+        let implementation = Address::from_low_u64_be(100);
+        let mut found = deployed(&config);
+        found.insert(
+            vault,
+            Some(DeployedCode::Proxy {
+                implementation,
+                codehash: Some(H256::repeat_byte(2)),
+            }),
+        );
+
+        // The implementation's code is checked against the codehash known for the network
+        let known = HashMap::from([("fermahContract.vault".to_string(), H256::repeat_byte(2))]);
+        let verified = diagnose(&config, Some(&known), &found).unwrap();
+        let verified_vault = verified
+            .iter()
+            .find(|contract| contract.address == vault)
+            .unwrap();
+        assert_eq!(verified_vault.implementation, Some(implementation));
+        assert_eq!(verified_vault.codehash, H256::repeat_byte(2));
+
+        // Upgraded to another implementation, whatever the profile pinned
+        let mut pinned = config.clone();
+        pinned
+            .codehashes
+            .insert("fermahContract.vault".to_string(), H256::repeat_byte(3));
+        let known = HashMap::from([("fermahContract.vault".to_string(), H256::repeat_byte(3))]);
+        assert!(diagnose(&config, Some(&known), &found).is_err());
+        let known = HashMap::from([("fermahContract.vault".to_string(), H256::repeat_byte(2))]);
+        assert!(diagnose(&pinned, Some(&known), &found).is_ok());
+
+        found.insert(
+            vault,
+            Some(DeployedCode::Proxy {
+                implementation,
+                codehash: None,
+            }),
+        );
+        let Err(VerifyError::Contracts(issues)) = diagnose(&config, None, &found) else {
+            panic!("proxy without implementation verified");
+        };
+        assert_eq!(
+            issues[0].problem,
+            ContractProblem::NoImplementation(implementation)
+        );
+    }
+
+    #[test]
+    fn test_known_codehashes() {
+        assert!(known_codehashes(31337)
+            .unwrap()
+            .contains_key("fermahContract.vault"));
+        assert!(known_codehashes(5).is_none());
+    }
+}
@@ -1,8 +1,13 @@
 pub mod avs;
+pub mod block_range;
 pub mod config;
 pub mod contract;
 pub mod error;
 pub mod manifest;
+pub mod metrics;
+pub mod signer;
+pub mod slashing;
+pub mod tx_manager;
 
 use std::sync::Arc;
 
@@ -10,9 +15,9 @@ use ethers::{
     middleware::SignerMiddleware,
     providers::{Http, Provider},
 };
-use fermah_common::crypto::signer::ecdsa::EcdsaSigner;
+use signer::ChainSigner;
 
-pub type SignerMiddlewareContract = SignerMiddleware<Arc<Provider<Http>>, EcdsaSigner>;
+pub type SignerMiddlewareContract = SignerMiddleware<Arc<Provider<Http>>, ChainSigner>;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum ELOperatorStatus {
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use opentelemetry::{
+    global::meter,
+    metrics::{Counter, ObservableGauge},
+    KeyValue,
+};
+
+#[derive(Clone)]
+pub struct Metrics {
+    cache_invalidations: Counter<u64>,
+    operator_archivals: Counter<u64>,
+    deposit_refreshes: Counter<u64>,
+    /// Discrepancies by kind found by the last vault reconciliation, observed by the gauge
+    vault_discrepancies: Arc<Mutex<HashMap<&'static str, u64>>>,
+    _vault_discrepancies_gauge: ObservableGauge<u64>,
+}
+
+impl Metrics {
+    pub fn init() -> Self {
+        let m = meter("avs metrics");
+        let cache_invalidations = m.u64_counter("cache_invalidations").init();
+        let operator_archivals = m.u64_counter("operator_archivals").init();
+        let deposit_refreshes = m.u64_counter("deposit_refreshes").init();
+        let vault_discrepancies = Arc::new(Mutex::new(HashMap::<&'static str, u64>::new()));
+        let observed = vault_discrepancies.clone();
+        let vault_discrepancies_gauge = m
+            .u64_observable_gauge("vault_discrepancies")
+            .with_callback(move |gauge| {
+                for (kind, count) in observed.lock().unwrap().iter() {
+                    gauge.observe(*count, &[KeyValue::new("kind", *kind)]);
+                }
+            })
+            .init();
+
+        Self {
+            cache_invalidations,
+            operator_archivals,
+            deposit_refreshes,
+            vault_discrepancies,
+            _vault_discrepancies_gauge: vault_discrepancies_gauge,
+        }
+    }
+
+    pub fn inc_cache_invalidations(&self, kind: &'static str, count: u64) {
+        self.cache_invalidations
+            .add(count, &[KeyValue::new("kind", kind)])
+    }
+
+    pub fn inc_operator_archivals(&self, kind: &'static str, count: u64) {
+        self.operator_archivals
+            .add(count, &[KeyValue::new("kind", kind)])
+    }
+
+    /// Deposits refreshed after the requesters deposited to the vault
+    pub fn inc_deposit_refreshes(&self, count: u64) {
+        self.deposit_refreshes.add(count, &[])
+    }
+
+    /// Vault balances found disagreeing with the books by the last reconciliation, to alert on. The kinds found
+    /// before but not anymore drop to 0.
+    pub fn set_vault_discrepancies(&self, counts: HashMap<&'static str, u64>) {
+        let mut discrepancies = self.vault_discrepancies.lock().unwrap();
+        discrepancies.values_mut().for_each(|count| *count = 0);
+        discrepancies.extend(counts);
+    }
+}
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use ethers::{
+    signers::{Signer, WalletError},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address,
+        Signature,
+    },
+};
+use fermah_common::crypto::signer::ecdsa::EcdsaSigner;
+#[cfg(feature = "kms")]
+use fermah_common::crypto::signer::kms::{KmsError, KmsSigner};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainSignerError {
+    #[error("wallet error: {0}")]
+    Wallet(#[from] WalletError),
+    #[cfg(feature = "kms")]
+    #[error("kms error: {0}")]
+    Kms(#[from] KmsError),
+}
+
+/// Signer of the AVS transactions: a local key, or a key held by a KMS
+#[derive(Clone, Debug)]
+pub enum ChainSigner {
+    Local(EcdsaSigner),
+    #[cfg(feature = "kms")]
+    Kms(KmsSigner),
+}
+
+impl From<EcdsaSigner> for ChainSigner {
+    fn from(value: EcdsaSigner) -> Self {
+        Self::Local(value)
+    }
+}
+
+#[cfg(feature = "kms")]
+impl From<KmsSigner> for ChainSigner {
+    fn from(value: KmsSigner) -> Self {
+        Self::Kms(value)
+    }
+}
+
+#[async_trait]
+impl Signer for ChainSigner {
+    type Error = ChainSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(signer) => Ok(signer.sign_message(message).await?),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => Ok(signer.sign_message(message).await?),
+        }
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(signer) => Ok(signer.sign_transaction(tx).await?),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => Ok(signer.sign_transaction(tx).await?),
+        }
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            Self::Local(signer) => Ok(signer.sign_typed_data(payload).await?),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => Ok(signer.sign_typed_data(payload).await?),
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            Self::Local(signer) => signer.address(),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.address(),
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            Self::Local(signer) => signer.chain_id(),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => signer.chain_id(),
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            Self::Local(signer) => Self::Local(signer.with_chain_id(chain_id)),
+            #[cfg(feature = "kms")]
+            Self::Kms(signer) => Self::Kms(signer.with_chain_id(chain_id)),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{collections::HashMap, time::Duration};
+
+use fermah_common::operator::offense::Offense;
+use serde::{Deserialize, Serialize};
+
+/// Decides when recorded operator offenses should be penalised on-chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashingPolicy {
+    /// If disabled, offenses are still recorded, but no transaction is ever submitted
+    pub enabled: bool,
+    /// Missed deadlines after acknowledgement tolerated within the window
+    pub max_missed_deadlines: u64,
+    /// Invalid proofs tolerated within the window
+    pub max_invalid_proofs: u64,
+    /// Window over which offenses are counted, in seconds
+    pub window_secs: u64,
+}
+
+impl Default for SlashingPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_missed_deadlines: 3,
+            max_invalid_proofs: 0,
+            window_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlashingDecision {
+    /// Offenses are within the tolerated limits
+    Tolerate,
+    /// Operator has to be frozen through the EL slasher
+    Freeze,
+}
+
+impl SlashingPolicy {
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    /// Decides on the penalty given the operator's unsettled offenses within the window
+    pub fn decide(&self, offenses: &HashMap<Offense, u64>) -> SlashingDecision {
+        if !self.enabled {
+            return SlashingDecision::Tolerate;
+        }
+
+        let count = |offense| offenses.get(&offense).copied().unwrap_or_default();
+
+        if count(Offense::MissedDeadlineAfterAck) > self.max_missed_deadlines
+            || count(Offense::InvalidProof) > self.max_invalid_proofs
+        {
+            SlashingDecision::Freeze
+        } else {
+            SlashingDecision::Tolerate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        let policy = SlashingPolicy {
+            enabled: true,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.decide(&HashMap::new()), SlashingDecision::Tolerate);
+        assert_eq!(
+            policy.decide(&HashMap::from([(Offense::MissedDeadlineAfterAck, 3)])),
+            SlashingDecision::Tolerate
+        );
+        assert_eq!(
+            policy.decide(&HashMap::from([(Offense::MissedDeadlineAfterAck, 4)])),
+            SlashingDecision::Freeze
+        );
+        assert_eq!(
+            policy.decide(&HashMap::from([(Offense::InvalidProof, 1)])),
+            SlashingDecision::Freeze
+        );
+    }
+
+    #[test]
+    fn test_disabled_tolerates() {
+        let policy = SlashingPolicy::default();
+
+        assert_eq!(
+            policy.decide(&HashMap::from([
+                (Offense::MissedDeadlineAfterAck, 100),
+                (Offense::InvalidProof, 100)
+            ])),
+            SlashingDecision::Tolerate
+        );
+    }
+}
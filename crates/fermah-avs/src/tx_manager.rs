@@ -0,0 +1,609 @@
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use ethers::{
+    providers::Middleware,
+    signers::Signer,
+    types::{
+        transaction::eip2718::TypedTransaction,
+        BlockNumber,
+        Eip1559TransactionRequest,
+        TransactionReceipt,
+        H256,
+        U256,
+    },
+};
+use fermah_common::supervisor::{RestartPolicy, Supervisor};
+#[cfg(feature = "db")]
+use fermah_database::{avs_transactions::AvsTxStatus, Database};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{watch, Mutex},
+    task::JoinSet,
+};
+use tracing::{info, warn};
+
+use crate::SignerMiddlewareContract;
+
+/// Fee limits and replacement rules for transactions sent by the AVS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeePolicy {
+    /// Upper bound of maxFeePerGas, in gwei. Transactions are never bumped above it
+    pub max_fee_per_gas_gwei: u64,
+    /// Upper bound of maxPriorityFeePerGas, in gwei
+    pub max_priority_fee_per_gas_gwei: u64,
+    /// Fee increase of a replacement transaction, in percent. Nodes usually require at least 10
+    pub bump_percent: u64,
+    /// A transaction not mined within this time is replaced with a bumped one, in seconds
+    pub stuck_after_secs: u64,
+    /// Replacements after which the transaction is left to be mined at its last fees
+    pub max_bumps: u32,
+}
+
+impl Default for FeePolicy {
+    fn default() -> Self {
+        Self {
+            max_fee_per_gas_gwei: 200,
+            max_priority_fee_per_gas_gwei: 10,
+            bump_percent: 15,
+            stuck_after_secs: 60,
+            max_bumps: 5,
+        }
+    }
+}
+
+impl FeePolicy {
+    pub fn stuck_after(&self) -> Duration {
+        Duration::from_secs(self.stuck_after_secs)
+    }
+
+    fn gwei(value: u64) -> U256 {
+        U256::from(value) * U256::exp10(9)
+    }
+
+    pub fn max_fee_per_gas(&self) -> U256 {
+        Self::gwei(self.max_fee_per_gas_gwei)
+    }
+
+    pub fn max_priority_fee_per_gas(&self) -> U256 {
+        Self::gwei(self.max_priority_fee_per_gas_gwei)
+    }
+
+    /// Caps the estimated `(max_fee_per_gas, max_priority_fee_per_gas)`
+    pub fn cap(&self, max_fee: U256, priority_fee: U256) -> (U256, U256) {
+        let max_fee = max_fee.min(self.max_fee_per_gas());
+        let priority_fee = priority_fee
+            .min(self.max_priority_fee_per_gas())
+            .min(max_fee);
+        (max_fee, priority_fee)
+    }
+
+    /// Fees of a replacement transaction, both raised by at least `bump_percent`. `None` once the caps don't leave
+    /// room for that, as nodes refuse a replacement which doesn't raise both fees enough
+    pub fn bump(&self, max_fee: U256, priority_fee: U256) -> Option<(U256, U256)> {
+        let min_bump = |fee: U256| fee * (100 + self.bump_percent) / 100;
+        let (new_max_fee, new_priority_fee) =
+            self.cap(min_bump(max_fee) + 1, min_bump(priority_fee) + 1);
+
+        if new_max_fee < min_bump(max_fee) || new_priority_fee < min_bump(priority_fee) {
+            None
+        } else {
+            Some((new_max_fee, new_priority_fee))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Waiting to be mined, replaced `bumps` times with higher fees
+    Pending {
+        hash: H256,
+        bumps: u32,
+    },
+    Confirmed(TransactionReceipt),
+    Reverted(TransactionReceipt),
+    /// The nonce was used by a transaction not known to the manager
+    Dropped,
+}
+
+impl TxStatus {
+    pub fn is_final(&self) -> bool {
+        !matches!(self, TxStatus::Pending { .. })
+    }
+}
+
+#[cfg(feature = "db")]
+impl From<&TxStatus> for AvsTxStatus {
+    fn from(value: &TxStatus) -> Self {
+        match value {
+            TxStatus::Pending { .. } => AvsTxStatus::Pending,
+            TxStatus::Confirmed(_) => AvsTxStatus::Confirmed,
+            TxStatus::Reverted(_) => AvsTxStatus::Reverted,
+            TxStatus::Dropped => AvsTxStatus::Dropped,
+        }
+    }
+}
+
+/// Handle to a submitted transaction
+pub struct PendingTx {
+    pub nonce: u64,
+    status: watch::Receiver<TxStatus>,
+}
+
+impl PendingTx {
+    pub fn status(&self) -> TxStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Waits for the next status change
+    pub async fn changed(&mut self) -> Result<TxStatus> {
+        self.status
+            .changed()
+            .await
+            .context("transaction is not tracked anymore")?;
+        Ok(self.status())
+    }
+}
+
+struct Tracked {
+    request: Eip1559TransactionRequest,
+    hashes: Vec<H256>,
+    max_fee: U256,
+    priority_fee: U256,
+    bumps: u32,
+    submitted_at: Instant,
+    status: watch::Sender<TxStatus>,
+}
+
+/// Copy of a tracked transaction, checked without holding the lock on the others
+struct Attempt {
+    request: Eip1559TransactionRequest,
+    hashes: Vec<H256>,
+    max_fee: U256,
+    priority_fee: U256,
+    bumps: u32,
+    submitted_at: Instant,
+}
+
+impl From<&Tracked> for Attempt {
+    fn from(tracked: &Tracked) -> Self {
+        Self {
+            request: tracked.request.clone(),
+            hashes: tracked.hashes.clone(),
+            max_fee: tracked.max_fee,
+            priority_fee: tracked.priority_fee,
+            bumps: tracked.bumps,
+            submitted_at: tracked.submitted_at,
+        }
+    }
+}
+
+/// Sends AVS transactions with EIP-1559 fees, replaces stuck ones with bumped fees, and keeps track of their nonces.
+#[derive(Clone)]
+pub struct TxManager {
+    provider: Arc<SignerMiddlewareContract>,
+    policy: FeePolicy,
+    #[cfg(feature = "db")]
+    database: Database,
+    /// Nonce of the next transaction, `None` until it is read from the chain
+    next_nonce: Arc<Mutex<Option<u64>>>,
+    pending: Arc<Mutex<BTreeMap<u64, Tracked>>>,
+}
+
+impl TxManager {
+    const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+    pub fn new(
+        provider: Arc<SignerMiddlewareContract>,
+        policy: FeePolicy,
+        #[cfg(feature = "db")] database: Database,
+    ) -> Self {
+        Self {
+            provider,
+            policy,
+            #[cfg(feature = "db")]
+            database,
+            next_nonce: Arc::new(Mutex::new(None)),
+            pending: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Sends the transaction with the next nonce and EIP-1559 fees within the policy caps
+    pub async fn send(&self, tx: TypedTransaction) -> Result<PendingTx> {
+        self.send_with(tx, |_| Ok(())).await
+    }
+
+    /// Sends the transaction like [`Self::send`], calling `on_nonce` with its nonce before it's broadcast. Nothing is
+    /// broadcast if `on_nonce` fails.
+    pub async fn send_with(
+        &self,
+        tx: TypedTransaction,
+        on_nonce: impl FnOnce(u64) -> Result<()>,
+    ) -> Result<PendingTx> {
+        let mut request = into_eip1559(tx);
+        request.from = Some(self.provider.address());
+        if request.gas.is_none() {
+            let gas = self
+                .provider
+                .estimate_gas(&request.clone().into(), None)
+                .await
+                .context("failed to estimate gas")?;
+            request.gas = Some(gas);
+        }
+
+        let (max_fee, priority_fee) = self
+            .provider
+            .estimate_eip1559_fees(None)
+            .await
+            .context("failed to estimate fees")?;
+        let (max_fee, priority_fee) = self.policy.cap(max_fee, priority_fee);
+
+        let known_nonce = self.next_nonce.lock().await.is_some();
+        let initial_nonce = if known_nonce {
+            None
+        } else {
+            Some(self.initial_nonce().await?)
+        };
+
+        // Only held while the transaction is signed and broadcast, so nonces reach the node in order and a failed
+        // send leaves no gap
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = match (*next_nonce, initial_nonce) {
+            (Some(nonce), _) | (None, Some(nonce)) => nonce,
+            (None, None) => bail!("nonce is not initialized"),
+        };
+        request.nonce = Some(nonce.into());
+
+        on_nonce(nonce)?;
+        let hash = self.submit(nonce, &request, max_fee, priority_fee).await?;
+        *next_nonce = Some(nonce + 1);
+        drop(next_nonce);
+
+        let (status, status_rx) = watch::channel(TxStatus::Pending { hash, bumps: 0 });
+        self.pending.lock().await.insert(
+            nonce,
+            Tracked {
+                request,
+                hashes: vec![hash],
+                max_fee,
+                priority_fee,
+                bumps: 0,
+                submitted_at: Instant::now(),
+                status,
+            },
+        );
+
+        Ok(PendingTx {
+            nonce,
+            status: status_rx,
+        })
+    }
+
+    /// Waits until the transaction is mined, replacing it while it is stuck
+    pub async fn wait(&self, pending: &PendingTx) -> Result<TransactionReceipt> {
+        let mut interval = tokio::time::interval(Self::POLL_INTERVAL);
+        loop {
+            match pending.status() {
+                TxStatus::Pending { .. } => {}
+                TxStatus::Confirmed(receipt) | TxStatus::Reverted(receipt) => return Ok(receipt),
+                TxStatus::Dropped => bail!("transaction with nonce {} was dropped", pending.nonce),
+            }
+
+            interval.tick().await;
+            self.poll(pending.nonce).await?;
+        }
+    }
+
+    pub async fn send_and_wait(&self, tx: TypedTransaction) -> Result<TransactionReceipt> {
+        let pending = self.send(tx).await?;
+        self.wait(&pending).await
+    }
+
+    /// Status of a transaction which is not mined yet
+    pub async fn subscribe(&self, nonce: u64) -> Option<watch::Receiver<TxStatus>> {
+        self.pending
+            .lock()
+            .await
+            .get(&nonce)
+            .map(|tracked| tracked.status.subscribe())
+    }
+
+    /// Checks whether the transaction was mined, and replaces it with bumped fees if it is stuck
+    async fn poll(&self, nonce: u64) -> Result<()> {
+        // Copied, so the pending transactions aren't locked across the provider calls below
+        let Some(attempt) = self.pending.lock().await.get(&nonce).map(Attempt::from) else {
+            return Ok(());
+        };
+
+        // Read the nonce first, so a transaction mined in between is still found by its receipt below
+        let mined_nonce = self
+            .provider
+            .get_transaction_count(self.provider.address(), Some(BlockNumber::Latest.into()))
+            .await?;
+
+        for &hash in attempt.hashes.iter().rev() {
+            if let Some(receipt) = self.provider.get_transaction_receipt(hash).await? {
+                let status = if receipt.status == Some(1.into()) {
+                    TxStatus::Confirmed(receipt)
+                } else {
+                    TxStatus::Reverted(receipt)
+                };
+                return self.finish(nonce, status).await;
+            }
+        }
+
+        if mined_nonce > nonce.into() {
+            warn!(nonce, "nonce was used by an unknown transaction");
+            return self.finish(nonce, TxStatus::Dropped).await;
+        }
+
+        if attempt.submitted_at.elapsed() < self.policy.stuck_after() {
+            return Ok(());
+        }
+        if attempt.bumps >= self.policy.max_bumps {
+            warn!(
+                nonce,
+                bumps = attempt.bumps,
+                "transaction is stuck, bumps exhausted"
+            );
+            return Ok(());
+        }
+        let Some((max_fee, priority_fee)) = self.policy.bump(attempt.max_fee, attempt.priority_fee)
+        else {
+            warn!(
+                nonce,
+                max_fee=?attempt.max_fee,
+                priority_fee=?attempt.priority_fee,
+                "transaction is stuck at the fee caps, it can't be replaced with fees raised enough"
+            );
+            return Ok(());
+        };
+
+        match self
+            .submit(nonce, &attempt.request, max_fee, priority_fee)
+            .await
+        {
+            Ok(hash) => {
+                let mut pending = self.pending.lock().await;
+                // Finished, or replaced by a concurrent poll meanwhile
+                let Some(tracked) = pending
+                    .get_mut(&nonce)
+                    .filter(|tracked| tracked.bumps == attempt.bumps)
+                else {
+                    return Ok(());
+                };
+                tracked.hashes.push(hash);
+                tracked.max_fee = max_fee;
+                tracked.priority_fee = priority_fee;
+                tracked.bumps += 1;
+                tracked.submitted_at = Instant::now();
+                tracked.status.send_replace(TxStatus::Pending {
+                    hash,
+                    bumps: tracked.bumps,
+                });
+                info!(
+                    nonce,
+                    ?hash,
+                    ?max_fee,
+                    ?priority_fee,
+                    "stuck transaction replaced"
+                );
+            }
+            // The previous transaction may have been mined meanwhile, which is checked on the next poll
+            Err(e) => warn!(nonce, error=?e, "failed to replace stuck transaction"),
+        }
+
+        Ok(())
+    }
+
+    async fn finish(&self, nonce: u64, status: TxStatus) -> Result<()> {
+        #[cfg(feature = "db")]
+        self.database
+            .set_transaction_status(&self.provider.address(), nonce, (&status).into())?;
+
+        if let Some(tracked) = self.pending.lock().await.remove(&nonce) {
+            tracked.status.send_replace(status);
+        }
+        Ok(())
+    }
+
+    async fn submit(
+        &self,
+        nonce: u64,
+        request: &Eip1559TransactionRequest,
+        max_fee: U256,
+        priority_fee: U256,
+    ) -> Result<H256> {
+        let mut tx: TypedTransaction = request
+            .clone()
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .into();
+
+        // Signed here, so its hash is recorded before it's broadcast and it's found by its receipt whatever happens
+        // after
+        tx.set_chain_id(self.provider.signer().chain_id());
+        let signature = self
+            .provider
+            .signer()
+            .sign_transaction(&tx)
+            .await
+            .context("failed to sign transaction")?;
+        let hash = tx.hash(&signature);
+
+        #[cfg(feature = "db")]
+        self.database.record_transaction_submission(
+            &self.provider.address(),
+            nonce,
+            &serde_json::to_string(&tx)?,
+            hash,
+            max_fee,
+            priority_fee,
+        )?;
+        #[cfg(not(feature = "db"))]
+        let _ = nonce;
+
+        self.provider
+            .send_raw_transaction(tx.rlp_signed(&signature))
+            .await
+            .context("failed to send transaction")?;
+
+        Ok(hash)
+    }
+
+    async fn initial_nonce(&self) -> Result<u64> {
+        let chain_nonce = self
+            .provider
+            .get_transaction_count(self.provider.address(), Some(BlockNumber::Pending.into()))
+            .await?
+            .as_u64();
+
+        // Transactions sent before a restart may have been evicted from the mempool, they are re-submitted by the
+        // monitor thread under their original nonces
+        #[cfg(feature = "db")]
+        if let Some(db_nonce) = self.database.get_next_nonce(&self.provider.address())? {
+            return Ok(chain_nonce.max(db_nonce));
+        }
+
+        Ok(chain_nonce)
+    }
+
+    #[cfg(feature = "db")]
+    /// Picks up transactions which were pending before a restart
+    async fn restore_pending(&self) -> Result<()> {
+        let restored = self
+            .database
+            .get_pending_transactions(&self.provider.address())?;
+
+        let mut pending = self.pending.lock().await;
+        for tx in restored {
+            let request: TypedTransaction = serde_json::from_str(&tx.request)?;
+            let Some(&hash) = tx.tx_hashes.last() else {
+                continue;
+            };
+            let age = (chrono::Utc::now() - tx.submitted_at)
+                .to_std()
+                .unwrap_or_default();
+
+            let (status, _) = watch::channel(TxStatus::Pending {
+                hash,
+                bumps: tx.bumps,
+            });
+            pending.insert(
+                tx.nonce,
+                Tracked {
+                    request: into_eip1559(request),
+                    hashes: tx.tx_hashes,
+                    max_fee: tx.max_fee_per_gas,
+                    priority_fee: tx.max_priority_fee_per_gas,
+                    bumps: tx.bumps,
+                    submitted_at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+                    status,
+                },
+            );
+        }
+        info!(restored = pending.len(), "pending transactions restored");
+
+        Ok(())
+    }
+
+    /// Periodically checks all pending transactions, so they are replaced and finalized even if nobody waits on them
+    pub async fn start_tx_monitor_thread(
+        &self,
+        supervisor: &Supervisor,
+        tasks: &mut JoinSet<Result<()>>,
+    ) -> Result<()> {
+        #[cfg(feature = "db")]
+        self.restore_pending().await?;
+
+        let manager = self.clone();
+        supervisor.spawn(tasks, "tx_monitor", RestartPolicy::default(), move |mut shutdown_rx| {
+            let manager = manager.clone();
+            async move {
+                let mut interval = tokio::time::interval(Self::POLL_INTERVAL);
+                loop {
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => {
+                            info!("Transaction monitor thread stopped");
+                            return Ok(())
+                        }
+
+                        _ = interval.tick() => {
+                            let nonces: Vec<u64> = manager.pending.lock().await.keys().copied().collect();
+                            for nonce in nonces {
+                                if let Err(e) = manager.poll(nonce).await {
+                                    warn!(nonce, error=?e, "failed to check pending transaction");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn into_eip1559(tx: TypedTransaction) -> Eip1559TransactionRequest {
+    match tx {
+        TypedTransaction::Eip1559(request) => request,
+        other => {
+            let mut request = Eip1559TransactionRequest::new();
+            request.from = other.from().copied();
+            request.to = other.to().cloned();
+            request.gas = other.gas().copied();
+            request.value = other.value().copied();
+            request.data = other.data().cloned();
+            request.chain_id = other.chain_id();
+            if let Some(access_list) = other.access_list() {
+                request.access_list = access_list.clone();
+            }
+            request
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap() {
+        let policy = FeePolicy::default();
+        let gwei = FeePolicy::gwei;
+
+        assert_eq!(policy.cap(gwei(50), gwei(2)), (gwei(50), gwei(2)));
+        assert_eq!(policy.cap(gwei(500), gwei(50)), (gwei(200), gwei(10)));
+        // Priority fee never exceeds the max fee
+        assert_eq!(policy.cap(gwei(1), gwei(5)), (gwei(1), gwei(1)));
+    }
+
+    #[test]
+    fn test_bump() {
+        let policy = FeePolicy::default();
+        let gwei = FeePolicy::gwei;
+
+        let (max_fee, priority_fee) = policy.bump(gwei(100), gwei(2)).unwrap();
+        assert!(max_fee > gwei(115) && max_fee < gwei(116));
+        assert!(priority_fee > gwei(2) * 115 / 100);
+
+        // Up to the caps, as long as both fees are still raised enough
+        let (max_fee, priority_fee) = policy.bump(gwei(170), gwei(2)).unwrap();
+        assert_eq!(max_fee, gwei(1955) / 10 + 1);
+        assert!(priority_fee > gwei(2) * 115 / 100);
+        assert_eq!(
+            policy.bump(gwei(100), gwei(8)),
+            Some((gwei(115) + 1, gwei(92) / 10 + 1))
+        );
+
+        // Stuck once a cap leaves no room for the minimum bump of either fee
+        assert_eq!(policy.bump(gwei(180), gwei(2)), None);
+        assert_eq!(policy.bump(gwei(100), gwei(9)), None);
+        assert_eq!(policy.bump(gwei(200), gwei(10)), None);
+    }
+}
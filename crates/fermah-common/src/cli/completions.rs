@@ -0,0 +1,166 @@
+use std::io::Write;
+
+use clap::{Arg, Command, CommandFactory};
+pub use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
+
+/// Writes the completion script of the CLI for the shell, e.g. `seek completions zsh > _seek`
+pub fn write_completions<C: CommandFactory>(shell: Shell, out: &mut impl Write) {
+    let mut command = C::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
+}
+
+/// Argument of a command, as wrapper tooling needs it to build command lines
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArgumentTree {
+    pub id: String,
+    pub long: Option<String>,
+    pub short: Option<char>,
+    pub help: Option<String>,
+    pub required: bool,
+    pub positional: bool,
+    /// Whether the argument takes values, flags don't
+    pub takes_value: bool,
+    pub value_names: Vec<String>,
+    /// Values the argument is restricted to, any value if empty
+    pub possible_values: Vec<String>,
+    pub default_values: Vec<String>,
+    /// Propagated to the subcommands
+    pub global: bool,
+    pub hidden: bool,
+}
+
+impl From<&Arg> for ArgumentTree {
+    fn from(arg: &Arg) -> Self {
+        Self {
+            id: arg.get_id().to_string(),
+            long: arg.get_long().map(str::to_string),
+            short: arg.get_short(),
+            help: arg.get_help().map(ToString::to_string),
+            required: arg.is_required_set(),
+            positional: arg.is_positional(),
+            takes_value: arg.get_action().takes_values(),
+            value_names: arg
+                .get_value_names()
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            possible_values: arg
+                .get_possible_values()
+                .iter()
+                .filter(|value| !value.is_hide_set())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+            default_values: arg
+                .get_default_values()
+                .iter()
+                .map(|value| value.to_string_lossy().into_owned())
+                .collect(),
+            global: arg.is_global_set(),
+            hidden: arg.is_hide_set(),
+        }
+    }
+}
+
+/// Commands, arguments and subcommands of a CLI, printed by the hidden `--dump-cli-json` flag
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTree {
+    pub name: String,
+    pub about: Option<String>,
+    pub aliases: Vec<String>,
+    pub hidden: bool,
+    pub args: Vec<ArgumentTree>,
+    pub subcommands: Vec<CommandTree>,
+}
+
+impl CommandTree {
+    /// Tree of the CLI, with the arguments clap adds itself such as `--help`
+    pub fn of<C: CommandFactory>() -> Self {
+        let mut command = C::command();
+        command.build();
+        Self::from(&command)
+    }
+}
+
+impl From<&Command> for CommandTree {
+    fn from(command: &Command) -> Self {
+        Self {
+            name: command.get_name().to_string(),
+            about: command.get_about().map(ToString::to_string),
+            aliases: command.get_all_aliases().map(str::to_string).collect(),
+            hidden: command.is_hide_set(),
+            args: command.get_arguments().map(ArgumentTree::from).collect(),
+            subcommands: command.get_subcommands().map(CommandTree::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{Parser, Subcommand};
+
+    use super::*;
+
+    /// Test CLI
+    #[derive(Parser, Debug)]
+    struct Cli {
+        /// Prints more
+        #[arg(short, long, global = true)]
+        verbose: bool,
+        #[command(subcommand)]
+        command: Commands,
+    }
+
+    #[derive(Subcommand, Debug)]
+    enum Commands {
+        /// Sends a request
+        #[command(alias = "s")]
+        Send {
+            /// Request ID
+            #[arg(long, value_name = "ID")]
+            id: String,
+            #[arg(long, value_enum, default_value_t = Shell::Bash)]
+            shell: Shell,
+        },
+    }
+
+    #[test]
+    fn test_command_tree() {
+        let tree = CommandTree::of::<Cli>();
+        assert_eq!(tree.about.as_deref(), Some("Test CLI"));
+
+        let send = tree
+            .subcommands
+            .iter()
+            .find(|command| command.name == "send")
+            .unwrap();
+        assert_eq!(send.aliases, vec!["s"]);
+        assert_eq!(send.about.as_deref(), Some("Sends a request"));
+
+        let id = send.args.iter().find(|arg| arg.id == "id").unwrap();
+        assert!(id.required && id.takes_value);
+        assert_eq!(id.value_names, vec!["ID"]);
+
+        let shell = send.args.iter().find(|arg| arg.id == "shell").unwrap();
+        assert!(shell.possible_values.contains(&"zsh".to_string()));
+        assert_eq!(shell.default_values, vec!["bash"]);
+
+        // Global arguments are propagated to the subcommands
+        let verbose = send.args.iter().find(|arg| arg.id == "verbose").unwrap();
+        assert!(verbose.global && !verbose.takes_value);
+        assert_eq!(verbose.short, Some('v'));
+    }
+
+    #[test]
+    fn test_completions() {
+        let mut script = vec![];
+        write_completions::<Cli>(Shell::Bash, &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("--verbose"));
+        assert!(script.contains("send"));
+    }
+}
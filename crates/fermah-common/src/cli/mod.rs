@@ -1,4 +1,5 @@
 pub mod ascii;
+pub mod completions;
 pub mod prompts;
 pub mod spinner;
 
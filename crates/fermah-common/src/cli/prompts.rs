@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, io, io::Write};
 
 use termion::color;
 
@@ -39,3 +39,81 @@ pub fn print_var<V: Display>(name: &str, value: V) {
         color::Fg(color::Reset)
     );
 }
+
+fn read_answer() -> Result<String, io::Error> {
+    print!("{}", get_prompt());
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    print!("{}", color::Fg(color::Reset));
+    Ok(answer.trim().to_string())
+}
+
+/// Asks for a line of text, `default` is taken if it's left empty
+pub fn prompt_for_input(question: &str, default: &str) -> Result<String, io::Error> {
+    println!("\n❓ {question} [{default}]");
+    let answer = read_answer()?;
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer
+    })
+}
+
+/// Asks for a value which isn't echoed, e.g. a private key
+pub fn prompt_for_secret(question: &str) -> Result<String, io::Error> {
+    println!("\n🔑 {question}");
+    let secret = rpassword::prompt_password(get_prompt())?;
+    println!("{}", color::Fg(color::Reset));
+    Ok(secret.trim().to_string())
+}
+
+/// Asks a yes/no question, `default` is taken if it's left empty
+pub fn prompt_for_confirmation(question: &str, default: bool) -> Result<bool, io::Error> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        println!("\n❓ {question} [{hint}]");
+        match read_answer()?.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => {
+                println!(
+                    "{}Answer y or n{}",
+                    color::Fg(color::Yellow),
+                    color::Fg(color::Reset)
+                )
+            }
+        }
+    }
+}
+
+/// Asks to pick one of the options by its number, the first one is taken if it's left empty. Returns its index.
+pub fn prompt_for_choice<O: Display>(question: &str, options: &[O]) -> Result<usize, io::Error> {
+    loop {
+        println!("\n❓ {question}");
+        for (i, option) in options.iter().enumerate() {
+            println!(
+                "  {}{}){} {option}",
+                color::Fg(color::LightMagenta),
+                i + 1,
+                color::Fg(color::Reset)
+            );
+        }
+        let answer = read_answer()?;
+        if answer.is_empty() {
+            return Ok(0);
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => return Ok(n - 1),
+            _ => {
+                println!(
+                    "{}Pick a number from 1 to {}{}",
+                    color::Fg(color::Yellow),
+                    options.len(),
+                    color::Fg(color::Reset)
+                )
+            }
+        }
+    }
+}
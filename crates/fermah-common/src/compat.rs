@@ -0,0 +1,611 @@
+//! Wire format compatibility suite, run with `cargo test -p fermah-common --features compat`.
+//!
+//! Every type exchanged between the seek CLI, the matchmaker and the operators has golden JSON and bincode vectors
+//! in `compat/`, which the current encoding has to match byte for byte. A failure means nodes running different
+//! versions can't understand each other anymore. If the change is intended, regenerate the vectors by running the
+//! suite with `FERMAH_BLESS_GOLDEN=1` and commit them.
+//!
+//! Bincode is positional, appending a field to a stored type breaks decoding the values stored before. Such a change
+//! needs a legacy layout the old values are decoded with, and the vectors it replaces kept in `compat/legacy/`, see
+//! [`crate::proof::legacy`].
+
+use std::{fmt::Debug, fs, path::PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use const_hex::traits::FromHex;
+use ethers::types::{Address, Signature, U256};
+use proptest::{collection::vec, option, prelude::*};
+use serde::{de::DeserializeOwned, Serialize};
+use url::Url;
+
+use crate::{
+    crypto::{
+        sealed::ResultKey,
+        signer::{ecdsa::EcdsaSigner, SignedData},
+    },
+    executable::{
+        Executable,
+        Image,
+        InMount,
+        Injector,
+        ResultExtractor,
+        Source,
+        WasmLimits,
+        WasmModule,
+    },
+    hash::{
+        blake3::{Blake3Hash, Blake3Hasher},
+        Hashable,
+    },
+    operator::{offense::Offense, OperatorId},
+    proof::{
+        job_array::JobArrayStatus,
+        receipt::ProofReceipt,
+        redundancy::{Redundancy, MAX_COPIES},
+        request::{ProofRequest, ProofRequestId, ProvidedProof},
+        result_validation::ResultValidator,
+        retention::ProofRetention,
+        status::ProofStatus,
+        Proof,
+        ProofId,
+    },
+    resource::{
+        gpu::{GPUModel, GPUVendor, GPUVersion},
+        requirement::ResourceRequirement,
+        tee::Tee,
+    },
+    resources::{ImageDelta, ImageHash, RemoteResource},
+    serialization::hash::SerializableHash,
+    types::{
+        balance::RequesterBalance,
+        region::{PreferredRegions, Region},
+    },
+};
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/compat");
+const BLESS_ENV: &str = "FERMAH_BLESS_GOLDEN";
+
+fn golden_path(file: String) -> PathBuf {
+    PathBuf::from(GOLDEN_DIR).join(file)
+}
+
+fn golden_json<T: DeserializeOwned>(name: &str) -> T {
+    let json = fs::read_to_string(golden_path(format!("{name}.json"))).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+/// Checks the value encodes to the golden vectors of `name`, and is decoded back from them
+fn check_golden<T>(name: &str, value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json_path = golden_path(format!("{name}.json"));
+    let bincode_path = golden_path(format!("{name}.bincode.hex"));
+
+    let json = serde_json::to_string(value).unwrap();
+    let bincode = const_hex::encode(bincode::serialize(value).unwrap());
+
+    if std::env::var_os(BLESS_ENV).is_some() {
+        fs::write(json_path, format!("{json}\n")).unwrap();
+        fs::write(bincode_path, format!("{bincode}\n")).unwrap();
+        return;
+    }
+
+    let golden_json = fs::read_to_string(json_path).unwrap();
+    let golden_bincode = fs::read_to_string(bincode_path).unwrap();
+
+    assert_eq!(
+        json,
+        golden_json.trim_end(),
+        "{name}: JSON encoding changed"
+    );
+    assert_eq!(
+        bincode,
+        golden_bincode.trim_end(),
+        "{name}: bincode encoding changed"
+    );
+
+    let from_json: T = serde_json::from_str(&golden_json).unwrap();
+    assert_eq!(&from_json, value, "{name}: JSON decoding changed");
+
+    let from_bincode: T =
+        bincode::deserialize(&const_hex::decode(golden_bincode.trim_end()).unwrap()).unwrap();
+    assert_eq!(&from_bincode, value, "{name}: bincode decoding changed");
+}
+
+fn operator() -> OperatorId {
+    "3c44cdddb6a900fa2b585dd299e03d12fa4293bc"
+        .try_into()
+        .unwrap()
+}
+
+fn requester() -> Address {
+    "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn golden_signed_proof_request() {
+    // Captured from a client, so it is decoded from the vector itself
+    let signed: SignedData<ProofRequest, EcdsaSigner> = golden_json("signed_proof_request");
+    check_golden("signed_proof_request", &signed);
+    // Signed before executables were hashed with every field
+    assert_eq!(signed.hash, signed.payload.legacy_hash::<Blake3Hasher>());
+    assert_ne!(signed.hash, signed.payload.hash::<Blake3Hasher>());
+}
+
+#[test]
+fn golden_signed_hash() {
+    let signed = SignedData::<SerializableHash<Blake3Hasher>, EcdsaSigner> {
+        hash: Blake3Hash::from([0xab; 32]),
+        payload: SerializableHash::from_hex(
+            "99e6070bde0937991360bdc960ef7f683cd8b3d6514f30ac4f2b04283c76c803",
+        )
+        .unwrap(),
+        public_key: requester(),
+        signature: Signature {
+            r: U256::one(),
+            s: U256::from_str_radix(
+                "7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0",
+                16,
+            )
+            .unwrap(),
+            v: 28,
+        },
+    };
+    check_golden("signed_hash", &signed);
+
+    // Typed ids are signed in place of the bare hash, RPC clients send the same JSON
+    let signed_id: SignedData<ProofRequestId, EcdsaSigner> = golden_json("signed_hash");
+    assert_eq!(
+        serde_json::to_string(&signed_id).unwrap(),
+        serde_json::to_string(&signed).unwrap()
+    );
+    assert_eq!(signed_id.payload, signed.payload.into());
+}
+
+#[test]
+fn golden_resource_requirement() {
+    let requirement = ResourceRequirement {
+        min_vram: Some(12 * 1024 * 1024 * 1024),
+        min_ram: Some(16 * 1024 * 1024 * 1024),
+        min_ssd: None,
+        min_gpu: vec![GPUModel::GeForceRtx3060_12GB],
+        min_cpu_cores: Some(16),
+        tee: Tee::Sgx,
+        gpu_vendor: Some(GPUVendor::Nvidia),
+        min_compute_capability: None,
+        min_gpu_driver: None,
+        min_cuda: Some(GPUVersion::new(12, 2, 0)),
+        class: None,
+    };
+    check_golden("resource_requirement", &requirement);
+}
+
+#[test]
+fn golden_proof_statuses() {
+    let proof = Proof::new(vec![1, 2, 3, 4, 5, 6, 7, 8], operator());
+    let statuses = vec![
+        ProofStatus::Created,
+        ProofStatus::Accepted,
+        ProofStatus::Cancelled,
+        ProofStatus::reject("invalid proof"),
+        ProofStatus::Assigned(operator()),
+        ProofStatus::AcknowledgedAssignment(operator()),
+        ProofStatus::ProofBeingTested(proof.clone()),
+        ProofStatus::Proven(proof),
+    ];
+    check_golden("proof_statuses", &statuses);
+}
+
+#[test]
+fn golden_proof_receipt() {
+    let receipt = ProofReceipt {
+        proof_request_id: ProofRequestId::from_hex(
+            "99e6070bde0937991360bdc960ef7f683cd8b3d6514f30ac4f2b04283c76c803",
+        )
+        .unwrap(),
+        requester: requester(),
+        proof_hash: ProofId::from([7; 32]),
+        operator_id: operator(),
+        proven_at: Utc.with_ymd_and_hms(2024, 10, 1, 12, 0, 0).unwrap(),
+        issued_at: Utc.with_ymd_and_hms(2024, 10, 1, 12, 0, 5).unwrap(),
+    };
+    check_golden("proof_receipt", &receipt);
+}
+
+#[test]
+fn golden_requester_balance() {
+    let balance = RequesterBalance::new(U256::exp10(18), U256::from(100));
+    check_golden("requester_balance", &balance);
+}
+
+#[test]
+fn golden_job_array_status() {
+    let mut status = JobArrayStatus::default();
+    for s in [
+        ProofStatus::Proven(Proof::new(vec![], operator())),
+        ProofStatus::Proven(Proof::new(vec![], operator())),
+        ProofStatus::Proven(Proof::new(vec![], operator())),
+        ProofStatus::Assigned(operator()),
+        ProofStatus::reject("invalid proof"),
+    ] {
+        status.add(&s);
+    }
+    check_golden("job_array_status", &status);
+}
+
+#[test]
+fn golden_offenses() {
+    check_golden(
+        "offenses",
+        &vec![Offense::MissedDeadlineAfterAck, Offense::InvalidProof],
+    );
+}
+
+fn roundtrip<T>(value: &T) -> Result<(), TestCaseError>
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let from_json: T = serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap();
+    prop_assert_eq!(&from_json, value);
+
+    let from_bincode: T = bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap();
+    prop_assert_eq!(&from_bincode, value);
+    Ok(())
+}
+
+fn address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>().prop_map(Address::from)
+}
+
+fn u256() -> impl Strategy<Value = U256> {
+    any::<[u64; 4]>().prop_map(U256)
+}
+
+fn blake3_hash<T: From<[u8; 32]> + Debug>() -> impl Strategy<Value = T> {
+    any::<[u8; 32]>().prop_map(T::from)
+}
+
+fn path() -> impl Strategy<Value = PathBuf> {
+    "(/[a-z0-9_.]{1,8}){1,3}".prop_map(PathBuf::from)
+}
+
+fn url() -> impl Strategy<Value = Url> {
+    "[a-z]{1,10}".prop_map(|host| Url::parse(&format!("https://{host}.example/res")).unwrap())
+}
+
+fn proof() -> impl Strategy<Value = Proof> {
+    (vec(any::<u8>(), 0..64), address())
+        .prop_map(|(proof, prover)| Proof::new(proof, prover.into()))
+}
+
+fn proof_status() -> impl Strategy<Value = ProofStatus> {
+    prop_oneof![
+        Just(ProofStatus::Created),
+        Just(ProofStatus::Accepted),
+        Just(ProofStatus::Cancelled),
+        ".*".prop_map(ProofStatus::Rejected),
+        address().prop_map(|a| ProofStatus::Assigned(a.into())),
+        address().prop_map(|a| ProofStatus::AcknowledgedAssignment(a.into())),
+        proof().prop_map(ProofStatus::ProofBeingTested),
+        proof().prop_map(ProofStatus::Proven),
+    ]
+}
+
+fn resource_requirement() -> impl Strategy<Value = ResourceRequirement> {
+    let gpu = prop::sample::select(vec![
+        GPUModel::GeForceRtx3060_12GB,
+        GPUModel::IntelArcA770,
+        GPUModel::RadeonProW6600x,
+    ]);
+    (
+        any::<Option<u64>>(),
+        any::<Option<u64>>(),
+        any::<Option<u64>>(),
+        vec(gpu, 0..3),
+        any::<Option<u64>>(),
+        prop::sample::select(vec![Tee::None, Tee::Sgx, Tee::SevSnp]),
+        prop::option::of(prop::sample::select(vec![
+            GPUVendor::Nvidia,
+            GPUVendor::Amd,
+            GPUVendor::Intel,
+        ])),
+        prop::option::of(gpu_version()),
+        prop::option::of(gpu_version()),
+        prop::option::of(gpu_version()),
+    )
+        .prop_map(
+            |(
+                min_vram,
+                min_ram,
+                min_ssd,
+                min_gpu,
+                min_cpu_cores,
+                tee,
+                gpu_vendor,
+                min_compute_capability,
+                min_gpu_driver,
+                min_cuda,
+            )| {
+                ResourceRequirement {
+                    min_vram,
+                    min_ram,
+                    min_ssd,
+                    min_gpu,
+                    min_cpu_cores,
+                    tee,
+                    gpu_vendor,
+                    min_compute_capability,
+                    min_gpu_driver,
+                    min_cuda,
+                    class: None,
+                }
+            },
+        )
+}
+
+fn gpu_version() -> impl Strategy<Value = GPUVersion> {
+    any::<(u32, u32, u32)>().prop_map(|(major, minor, patch)| GPUVersion::new(major, minor, patch))
+}
+
+fn remote_resource() -> impl Strategy<Value = RemoteResource> {
+    (
+        url(),
+        blake3_hash::<ImageHash>(),
+        option::of((blake3_hash::<ImageHash>(), url())),
+    )
+        .prop_map(|(url, hash, delta)| {
+            RemoteResource {
+                url,
+                hash,
+                delta: delta.map(|(base, url)| ImageDelta { base, url }),
+            }
+        })
+}
+
+fn executable() -> impl Strategy<Value = Executable> {
+    let image = prop_oneof![
+        "[a-z0-9]{1,12}:[a-z0-9.]{1,8}".prop_map(Image::Docker),
+        (remote_resource(), "[a-z0-9]{1,12}:[a-z0-9.]{1,8}").prop_map(Image::RemoteDocker),
+        (
+            remote_resource(),
+            "[a-z_][a-z0-9_]{0,12}",
+            any::<(u64, u64)>()
+        )
+            .prop_map(|(module, entry, (max_memory, max_fuel))| {
+                Image::Wasm(WasmModule {
+                    module,
+                    entry,
+                    limits: WasmLimits {
+                        max_memory,
+                        max_fuel,
+                    },
+                })
+            }),
+    ];
+    let source = prop_oneof![
+        remote_resource().prop_map(Source::File),
+        remote_resource().prop_map(Source::Manifest),
+    ];
+    let in_mount = (source, path(), any::<bool>()).prop_map(|(source, target, temporary)| {
+        InMount {
+            source,
+            target,
+            temporary,
+        }
+    });
+    let result_extractor = prop_oneof![
+        path().prop_map(ResultExtractor::File),
+        any::<i64>().prop_map(ResultExtractor::NegativeExitCode),
+        ".*".prop_map(ResultExtractor::RegexStdout),
+    ];
+    let injector = prop_oneof![
+        path().prop_map(Injector::File),
+        path().prop_map(Injector::Directory),
+    ];
+
+    (
+        image,
+        option::of("[a-z0-9/]{1,16}"),
+        vec(in_mount, 0..3),
+        option::of(result_extractor),
+        option::of(injector),
+        vec(".*", 0..3),
+        vec(".*", 0..3),
+        option::of(prop::collection::hash_map("[A-Z_]{1,8}", ".*", 0..3)),
+        any::<(bool, bool, bool)>(),
+    )
+        .prop_map(
+            |(
+                image,
+                platform,
+                in_mounts,
+                result_extractor,
+                injector,
+                entrypoint,
+                cmd,
+                env_vars,
+                (network_enabled, privileged, docker_access),
+            )| {
+                Executable {
+                    image,
+                    platform,
+                    in_mounts,
+                    result_extractor,
+                    injector,
+                    entrypoint,
+                    cmd,
+                    env_vars,
+                    network_enabled,
+                    privileged,
+                    docker_access,
+                }
+            },
+        )
+}
+
+fn redundancy() -> impl Strategy<Value = Redundancy> {
+    (2..=MAX_COPIES)
+        .prop_flat_map(|copies| (Just(copies), copies / 2 + 1..=copies))
+        .prop_map(|(copies, quorum)| Redundancy { copies, quorum })
+}
+
+fn preferred_regions() -> impl Strategy<Value = PreferredRegions> {
+    (
+        vec("[a-z][a-z0-9-]{0,12}(/[a-z0-9]{1,4})?", 1..4),
+        any::<bool>(),
+    )
+        .prop_map(|(names, required)| {
+            PreferredRegions {
+                regions: names
+                    .iter()
+                    .map(|name| Region::new(name).unwrap())
+                    .collect(),
+                required,
+            }
+        })
+}
+
+fn provided_proof() -> impl Strategy<Value = ProvidedProof> {
+    prop_oneof![
+        vec(any::<u8>(), 0..64).prop_map(ProvidedProof::Inline),
+        remote_resource().prop_map(ProvidedProof::Remote),
+    ]
+}
+
+fn proof_retention() -> impl Strategy<Value = ProofRetention> {
+    prop_oneof![
+        any::<u16>().prop_map(ProofRetention::Days),
+        Just(ProofRetention::UntilRetrieved),
+    ]
+}
+
+fn result_validator() -> impl Strategy<Value = ResultValidator> {
+    (
+        option::of(any::<u64>()),
+        option::of(any::<u64>()),
+        vec(any::<u8>(), 0..8),
+        option::of(Just(r#"{"type":"object"}"#.to_string())),
+    )
+        .prop_map(|(min_len, max_len, magic, json_schema)| {
+            ResultValidator {
+                min_len,
+                max_len,
+                magic,
+                json_schema,
+            }
+        })
+}
+
+fn proof_request() -> impl Strategy<Value = ProofRequest> {
+    (
+        option::of(address()),
+        executable(),
+        executable(),
+        resource_requirement(),
+        option::of(url()),
+        option::of(0..4_102_444_800_i64),
+        any::<u64>(),
+        vec(blake3_hash::<ProofRequestId>(), 0..3),
+        option::of(redundancy()),
+        option::of(preferred_regions()),
+        option::of(provided_proof()),
+        (
+            option::of(vec(any::<u8>(), 33).prop_map(ResultKey)),
+            option::of(proof_retention()),
+            option::of(result_validator()),
+        ),
+    )
+        .prop_map(
+            |(
+                requester,
+                prover,
+                verifier,
+                resource_requirement,
+                callback_url,
+                deadline,
+                nonce,
+                depends_on,
+                redundancy,
+                preferred_regions,
+                proof,
+                (result_key, retention, result_validator),
+            )| {
+                ProofRequest {
+                    requester,
+                    prover,
+                    verifier,
+                    resource_requirement,
+                    callback_url,
+                    deadline: deadline.and_then(|secs| DateTime::from_timestamp(secs, 0)),
+                    nonce,
+                    depends_on,
+                    redundancy,
+                    preferred_regions,
+                    proof,
+                    result_key,
+                    retention,
+                    result_validator,
+                }
+            },
+        )
+}
+
+fn signature() -> impl Strategy<Value = Signature> {
+    (u256(), u256(), any::<u64>()).prop_map(|(r, s, v)| Signature { r, s, v })
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_proof_status(status in proof_status()) {
+        roundtrip(&status)?;
+    }
+
+    #[test]
+    fn roundtrip_resource_requirement(requirement in resource_requirement()) {
+        roundtrip(&requirement)?;
+    }
+
+    #[test]
+    fn roundtrip_signed_proof_request(
+        hash in blake3_hash::<Blake3Hash>(),
+        payload in proof_request(),
+        public_key in address(),
+        signature in signature(),
+    ) {
+        roundtrip(&SignedData::<ProofRequest, EcdsaSigner> { hash, payload, public_key, signature })?;
+    }
+
+    #[test]
+    fn roundtrip_requester_balance(deposit in u256(), reserved in u256()) {
+        roundtrip(&RequesterBalance::new(deposit, reserved))?;
+    }
+
+    #[test]
+    fn roundtrip_job_array_status(statuses in vec(proof_status(), 0..16)) {
+        let mut status = JobArrayStatus::default();
+        statuses.iter().for_each(|s| status.add(s));
+        roundtrip(&status)?;
+    }
+
+    #[test]
+    fn roundtrip_proof_receipt(
+        proof_request_id in blake3_hash::<ProofRequestId>(),
+        requester in address(),
+        proof_hash in blake3_hash::<ProofId>(),
+        operator_id in address(),
+        proven_at in 0..4_102_444_800_i64,
+        issued_at in 0..4_102_444_800_i64,
+    ) {
+        roundtrip(&ProofReceipt {
+            proof_request_id,
+            requester,
+            proof_hash,
+            operator_id: operator_id.into(),
+            proven_at: DateTime::from_timestamp(proven_at, 0).unwrap(),
+            issued_at: DateTime::from_timestamp(issued_at, 0).unwrap(),
+        })?;
+    }
+}
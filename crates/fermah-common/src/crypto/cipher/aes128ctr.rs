@@ -3,6 +3,7 @@ use const_hex::ToHexExt;
 use ctr::{
     cipher::{KeyIvInit, StreamCipher, StreamCipherCoreWrapper},
     flavors,
+    Ctr128BE,
     CtrCore,
 };
 use rand_core::{OsRng, RngCore};
@@ -25,6 +26,15 @@ pub struct Aes128Params {
     pub iv: Vec<u8>,
 }
 
+/// Counter layout of the keystream. Fermah keystores count little-endian, while Web3 Secret Storage keystores
+/// (geth, clef, eth-keystore) count big-endian.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CtrCounter {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Aes128CtrCipherError {
     #[error("kdf error: {0}")]
@@ -58,6 +68,10 @@ pub struct Aes128CtrCipher<KDF: Kdf> {
     #[zeroize(skip)]
     #[serde(rename = "mac", with = "hex_encoded_no_prefix")]
     pub mac: Vec<u8>,
+
+    #[zeroize(skip)]
+    #[serde(skip)]
+    counter: CtrCounter,
 }
 
 impl<KDF: Kdf> Aes128CtrCipher<KDF> {
@@ -69,6 +83,7 @@ impl<KDF: Kdf> Aes128CtrCipher<KDF> {
             kdf,
             data,
             mac: vec![],
+            counter: CtrCounter::default(),
         }
     }
 
@@ -90,9 +105,27 @@ impl<KDF: Kdf> Aes128CtrCipher<KDF> {
             kdf,
             data,
             mac: vec![],
+            counter: CtrCounter::default(),
         }
     }
 
+    pub fn with_counter(mut self, counter: CtrCounter) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn set_counter(&mut self, counter: CtrCounter) {
+        self.counter = counter;
+    }
+
+    pub fn kdf_name(&self) -> &str {
+        &self.kdf_name
+    }
+
+    pub fn kdf(&self) -> &KDF {
+        &self.kdf
+    }
+
     fn derive_key(
         &mut self,
         password: &[u8],
@@ -105,11 +138,20 @@ impl<KDF: Kdf> Aes128CtrCipher<KDF> {
         Ok(key)
     }
 
-    fn apply_xor(iv: &[u8], key: [u8; AES128CTR_KEY_LEN], data: &mut [u8]) {
+    fn apply_xor(counter: CtrCounter, iv: &[u8], key: [u8; AES128CTR_KEY_LEN], data: &mut [u8]) {
         let iv: [u8; AES128CTR_KEY_LEN] = iv.try_into().unwrap();
 
-        let mut cipher = <Aes128CtrCipher<KDF> as Cipher>::CoreCipher::new(&key.into(), &iv.into());
-        cipher.apply_keystream(data);
+        match counter {
+            CtrCounter::LittleEndian => {
+                let mut cipher =
+                    <Aes128CtrCipher<KDF> as Cipher>::CoreCipher::new(&key.into(), &iv.into());
+                cipher.apply_keystream(data);
+            }
+            CtrCounter::BigEndian => {
+                let mut cipher = Ctr128BE::<Aes128>::new(&key.into(), &iv.into());
+                cipher.apply_keystream(data);
+            }
+        }
     }
 }
 
@@ -129,6 +171,7 @@ impl<KDF: Kdf> Cipher for Aes128CtrCipher<KDF> {
     fn encrypt(&mut self, password: &[u8]) -> Result<(), Self::Error> {
         let cipher_key = self.derive_key(password)?;
         Self::apply_xor(
+            self.counter,
             self.params.iv.as_slice(),
             cipher_key[..AES128CTR_KEY_LEN].try_into().unwrap(),
             self.data.as_mut_slice(),
@@ -161,6 +204,7 @@ impl<KDF: Kdf> Cipher for Aes128CtrCipher<KDF> {
         let mac = hasher.finalize().as_ref().to_vec();
 
         Self::apply_xor(
+            self.counter,
             self.params.iv.as_slice(),
             cipher_key[..AES128CTR_KEY_LEN].try_into().unwrap(),
             self.data.as_mut_slice(),
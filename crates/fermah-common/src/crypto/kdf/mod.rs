@@ -1,5 +1,7 @@
 use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
 
+pub mod pbkdf2;
 pub mod scrypt;
 
 pub trait Kdf: Sized {
@@ -15,3 +17,56 @@ pub trait Kdf: Sized {
 
     fn derive_key(&self, password: &[u8], out: &mut [u8]) -> Result<(), Self::Error>;
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnyKdfError {
+    #[error("scrypt error: {0}")]
+    Scrypt(#[from] ::scrypt::errors::InvalidOutputLen),
+
+    #[error("pbkdf2 error: {0}")]
+    Pbkdf2(#[from] pbkdf2::Pbkdf2KdfError),
+}
+
+/// Either of the KDFs found in Web3 Secret Storage keystores, told apart by their params.
+/// New keys are derived by scrypt.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum AnyKdf {
+    Scrypt(scrypt::ScryptKdf),
+    Pbkdf2(pbkdf2::Pbkdf2Kdf),
+}
+
+impl AnyKdf {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnyKdf::Scrypt(_) => scrypt::ScryptKdf::NAME,
+            AnyKdf::Pbkdf2(_) => pbkdf2::Pbkdf2Kdf::NAME,
+        }
+    }
+}
+
+impl Kdf for AnyKdf {
+    const NAME: &'static str = scrypt::ScryptKdf::NAME;
+
+    fn fast(rng: impl CryptoRngCore) -> Self {
+        AnyKdf::Scrypt(scrypt::ScryptKdf::fast(rng))
+    }
+
+    fn secure(rng: impl CryptoRngCore) -> Self {
+        AnyKdf::Scrypt(scrypt::ScryptKdf::secure(rng))
+    }
+
+    type Error = AnyKdfError;
+    type Params = AnyKdf;
+
+    fn new(params: Self::Params) -> Self {
+        params
+    }
+
+    fn derive_key(&self, password: &[u8], out: &mut [u8]) -> Result<(), Self::Error> {
+        match self {
+            AnyKdf::Scrypt(kdf) => Ok(kdf.derive_key(password, out)?),
+            AnyKdf::Pbkdf2(kdf) => Ok(kdf.derive_key(password, out)?),
+        }
+    }
+}
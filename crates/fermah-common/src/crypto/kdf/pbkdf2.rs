@@ -0,0 +1,116 @@
+use pbkdf2::pbkdf2_hmac;
+use rand_core::{CryptoRngCore, OsRng};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{crypto::kdf::Kdf, serialization::encoding::hex_encoded_no_prefix};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Pbkdf2KdfError {
+    #[error("unsupported prf: {0}")]
+    UnsupportedPrf(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Pbkdf2KdfParams {
+    /// Number of iterations
+    pub c: u32,
+    /// Derived key length
+    pub dklen: usize,
+    /// Pseudorandom function, only `hmac-sha256` is supported
+    pub prf: String,
+    /// Salt used when deriving the key
+    #[serde(with = "hex_encoded_no_prefix")]
+    pub salt: Vec<u8>,
+}
+
+impl Pbkdf2KdfParams {
+    pub const PRF: &'static str = "hmac-sha256";
+
+    pub const FAST_ITERATIONS: u32 = 4096;
+    pub const SECURE_ITERATIONS: u32 = 262144;
+
+    pub const SALT_LEN: usize = 32;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Pbkdf2Kdf {
+    #[serde(flatten)]
+    params: Pbkdf2KdfParams,
+}
+
+impl Pbkdf2Kdf {
+    fn with_iterations(c: u32, mut rng: impl CryptoRngCore) -> Self {
+        let mut salt = vec![0u8; Pbkdf2KdfParams::SALT_LEN];
+        rng.fill_bytes(&mut salt);
+
+        Self::new(Pbkdf2KdfParams {
+            c,
+            dklen: 32,
+            prf: Pbkdf2KdfParams::PRF.to_string(),
+            salt,
+        })
+    }
+}
+
+impl Default for Pbkdf2Kdf {
+    fn default() -> Self {
+        Self::secure(&mut OsRng)
+    }
+}
+
+impl Kdf for Pbkdf2Kdf {
+    const NAME: &'static str = "pbkdf2";
+
+    fn fast(rng: impl CryptoRngCore) -> Self {
+        Self::with_iterations(Pbkdf2KdfParams::FAST_ITERATIONS, rng)
+    }
+
+    fn secure(rng: impl CryptoRngCore) -> Self {
+        Self::with_iterations(Pbkdf2KdfParams::SECURE_ITERATIONS, rng)
+    }
+
+    type Error = Pbkdf2KdfError;
+    type Params = Pbkdf2KdfParams;
+
+    fn new(params: Self::Params) -> Self {
+        Self { params }
+    }
+
+    fn derive_key(&self, password: &[u8], out: &mut [u8]) -> Result<(), Self::Error> {
+        if self.params.prf != Pbkdf2KdfParams::PRF {
+            return Err(Pbkdf2KdfError::UnsupportedPrf(self.params.prf.clone()));
+        }
+
+        pbkdf2_hmac::<Sha256>(password, &self.params.salt, self.params.c, out);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_hex::ToHexExt;
+
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_kdf() {
+        // Web3 Secret Storage test vector
+        let kdf = Pbkdf2Kdf::new(Pbkdf2KdfParams {
+            c: 262144,
+            dklen: 32,
+            prf: "hmac-sha256".to_string(),
+            salt: const_hex::decode(
+                "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd",
+            )
+            .unwrap(),
+        });
+        let mut key = [0u8; 32];
+        kdf.derive_key(b"testpassword", &mut key).unwrap();
+
+        assert_eq!(
+            key.encode_hex_with_prefix(),
+            "0xf06d69cdc7da0faffb1008270bca38f5e31891a3a773950e6d0fea48a7188551"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Keystore passphrases kept in the OS keychain (macOS Keychain, Windows Credential Manager, Secret Service)
+
+use keyring::Entry;
+
+pub const KEYCHAIN_SERVICE: &str = "fermah";
+
+/// Stores the passphrase of the keystore `name`, replacing the previous one
+pub fn store_password(name: &str, password: &str) -> Result<(), keyring::Error> {
+    Entry::new(KEYCHAIN_SERVICE, name)?.set_password(password)
+}
+
+pub fn get_password(name: &str) -> Result<String, keyring::Error> {
+    Entry::new(KEYCHAIN_SERVICE, name)?.get_password()
+}
+
+pub fn delete_password(name: &str) -> Result<(), keyring::Error> {
+    Entry::new(KEYCHAIN_SERVICE, name)?.delete_credential()
+}
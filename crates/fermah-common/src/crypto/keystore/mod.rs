@@ -1,6 +1,14 @@
-use std::env;
+#[cfg(feature = "keychain")]
+pub mod keychain;
 
-use clap::Parser;
+use std::{
+    env,
+    io::{stdin, IsTerminal},
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Parser};
+use const_hex::ToHexExt;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use tokio::io;
@@ -9,10 +17,15 @@ use uuid::Uuid;
 use zeroize::ZeroizeOnDrop;
 
 use crate::{
+    cli::prompts::prompt_for_password_unlock,
     crypto::{
-        cipher::{aes128ctr::Aes128CtrCipher, Cipher},
-        kdf::scrypt::ScryptKdf,
-        signer::Signer,
+        cipher::{
+            aes128ctr::{Aes128CtrCipher, CtrCounter},
+            Cipher,
+        },
+        kdf::{scrypt::ScryptKdf, AnyKdf},
+        secret::SecretBytes,
+        signer::{ecdsa::EcdsaSigner, Signer},
     },
     fs::json::Json,
     serialization::encoding::hex_encoded_no_prefix,
@@ -38,6 +51,18 @@ pub enum KeystoreFileError {
 
     #[error("aes128ctr cipher error: {0}")]
     Aes128CtrError(#[from] crate::crypto::cipher::aes128ctr::Aes128CtrCipherError),
+
+    #[error(
+        "no passphrase for keystore {0}: use --password-file, --ask-pass, --no-password or {KEYSTORE_PASS_ENV}"
+    )]
+    NoPassword(String),
+
+    #[error("keystore address mismatch: {expected} != {found}")]
+    AddressMismatch { expected: String, found: String },
+
+    #[cfg(feature = "keychain")]
+    #[error("keychain error: {0}")]
+    Keychain(#[from] keyring::Error),
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
@@ -56,36 +81,22 @@ impl KeystoreFile {
         Self::from_json_path(path).await
     }
 
-    pub async fn get_password() -> Result<String, KeystoreFileError> {
-        let password = env::var(KEYSTORE_PASS_ENV).ok();
-
-        match password {
-            Some(pw_file) => {
-                info!("attempting to read password file in {}", pw_file);
-                let pw = tokio::fs::read_to_string(pw_file).await?;
-                Ok(pw.trim().to_string())
-            }
-            None => {
-                info!(
-                    "{} not set - unlocking with empty password",
-                    KEYSTORE_PASS_ENV
-                );
-                Ok("".to_string())
-            }
-        }
-    }
-
-    pub async fn to_signer<S: Signer>(&mut self) -> Result<S, KeystoreFileError>
+    pub async fn to_signer<S: Signer>(
+        &mut self,
+        config: &KeystoreConfig,
+    ) -> Result<S, KeystoreFileError>
     where
         KeystoreFileError: From<<S as Signer>::SignerError>,
     {
         info!("creating signer from keystore");
-        let password = Self::get_password().await?;
+        let password = config.get_password().await?;
 
         let decrypted = self.cipher.crypto.decrypt(password.as_bytes())?;
 
         info!("✓ unlocked keystore");
-        Ok(S::from_bytes(decrypted.data.as_slice())?)
+        Ok(S::from_bytes(&SecretBytes::from(
+            decrypted.data.as_slice(),
+        ))?)
     }
 }
 
@@ -95,18 +106,92 @@ pub struct KeystoreConfig {
     /// Name of the keystore
     #[arg(long, default_value = "default")]
     pub key: String,
+    #[command(flatten)]
+    #[serde(default)]
+    pub password: KeystorePassword,
     // There is no support in clap for enum unit variants yet,
     // otherwise KeystoreLocation would go here
 }
 
+impl KeystoreConfig {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            password: KeystorePassword::default(),
+        }
+    }
+
+    /// Keystore passphrase, taken from the first available of:
+    /// `--password-file`, `--ask-pass`, `--no-password`, `--keychain`, the file in [`KEYSTORE_PASS_ENV`],
+    /// or a prompt when running in a terminal
+    pub async fn get_password(&self) -> Result<String, KeystoreFileError> {
+        let password = &self.password;
+
+        if let Some(pw_file) = &password.password_file {
+            return Self::read_password_file(pw_file).await;
+        }
+
+        if password.ask_pass {
+            return Ok(prompt_for_password_unlock(&self.key)?);
+        }
+
+        if password.no_password {
+            info!("unlocking with empty password");
+            return Ok("".to_string());
+        }
+
+        #[cfg(feature = "keychain")]
+        if password.keychain {
+            info!("reading password from the keychain");
+            return Ok(keychain::get_password(&self.key)?);
+        }
+
+        if let Ok(pw_file) = env::var(KEYSTORE_PASS_ENV) {
+            return Self::read_password_file(Path::new(&pw_file)).await;
+        }
+
+        if stdin().is_terminal() {
+            return Ok(prompt_for_password_unlock(&self.key)?);
+        }
+
+        Err(KeystoreFileError::NoPassword(self.key.clone()))
+    }
+
+    async fn read_password_file(path: &Path) -> Result<String, KeystoreFileError> {
+        info!("attempting to read password file in {}", path.display());
+        let pw = tokio::fs::read_to_string(path).await?;
+        Ok(pw.trim().to_string())
+    }
+}
+
+/// Source of the keystore passphrase
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct KeystorePassword {
+    /// File containing the keystore passphrase
+    #[arg(long, conflicts_with_all = ["ask_pass", "no_password"])]
+    pub password_file: Option<PathBuf>,
+    /// Prompt for the keystore passphrase
+    #[arg(long, conflicts_with = "no_password")]
+    pub ask_pass: bool,
+    /// Unlock a keystore created without a passphrase
+    #[arg(long)]
+    pub no_password: bool,
+    /// Read the keystore passphrase from the OS keychain
+    #[cfg(feature = "keychain")]
+    #[arg(long, conflicts_with_all = ["password_file", "ask_pass", "no_password"])]
+    pub keychain: bool,
+}
+
 /// Encrypted keystore as defined in:
 /// https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
 #[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, ZeroizeOnDrop)]
 pub struct KeystoreCipher<C: Cipher + ZeroizeOnDrop> {
+    #[serde(alias = "Crypto")]
     pub crypto: C,
 
-    #[serde(with = "hex_encoded_no_prefix")]
+    #[serde(with = "hex_encoded_no_prefix", default)]
     #[zeroize(skip)]
     pub address: Vec<u8>,
 
@@ -133,6 +218,31 @@ impl<C: Cipher + ZeroizeOnDrop> Keystore for KeystoreCipher<C> {
     type Data = C;
 }
 
+/// ECDSA keystore in the Web3 Secret Storage format, as written by geth, clef or eth-keystore,
+/// with either scrypt or pbkdf2 KDF
+pub type Web3Keystore = KeystoreCipher<Aes128CtrCipher<AnyKdf>>;
+
+impl Web3Keystore {
+    /// Decrypts the private key, and checks it against the keystore address when there is one
+    pub fn decrypt_private_key(
+        &mut self,
+        password: &[u8],
+    ) -> Result<SecretBytes, KeystoreFileError> {
+        self.crypto.set_counter(CtrCounter::BigEndian);
+        let private_key = SecretBytes::from(self.crypto.decrypt(password)?.data.as_slice());
+
+        let address = EcdsaSigner::from_bytes(&private_key)?.public_address();
+        if !self.address.is_empty() && self.address != address {
+            return Err(KeystoreFileError::AddressMismatch {
+                expected: self.address.encode_hex_with_prefix(),
+                found: address.encode_hex_with_prefix(),
+            });
+        }
+
+        Ok(private_key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::prelude::StdRng;
@@ -144,6 +254,74 @@ mod tests {
         kdf::{scrypt::ScryptKdf, Kdf},
     };
 
+    const WEB3_PRIVATE_KEY: &str =
+        "0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    /// Web3 Secret Storage test vector
+    const WEB3_PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    /// Same key encrypted by a reference scrypt/AES-CTR implementation, in the geth layout
+    const WEB3_SCRYPT_KEYSTORE: &str = r#"{
+        "address": "008aeeda4d805471df9b2a5b0f38a0c3bcba786b",
+        "Crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6faade04ed5684d03509ad8cccf9a34f" },
+            "ciphertext": "6a53ca7d6abd7cd3c5c9437ce1c0e534b0ddc9ac296a5804738d47e72835abc2",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 4096,
+                "p": 1,
+                "r": 8,
+                "salt": "3fe6ce4e12a2916c3170a339e84b28c5ba10d7b1ff5830e81993f09a05864f55"
+            },
+            "mac": "7a5f4b8e588042bb84d5b01d2a4959524020d8d00f96e490b82183980bb9c167"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    #[test]
+    fn test_web3_keystore_vectors() {
+        for (json, kdf) in [
+            (WEB3_PBKDF2_KEYSTORE, "pbkdf2"),
+            (WEB3_SCRYPT_KEYSTORE, "scrypt"),
+        ] {
+            let mut keystore: Web3Keystore = serde_json::from_str(json).unwrap();
+            assert_eq!(keystore.crypto.kdf_name(), kdf);
+            assert_eq!(keystore.crypto.kdf().name(), kdf);
+
+            let private_key = keystore.decrypt_private_key(b"testpassword").unwrap();
+            assert_eq!(
+                private_key.expose_secret().encode_hex_with_prefix(),
+                WEB3_PRIVATE_KEY
+            );
+        }
+
+        let mut keystore: Web3Keystore = serde_json::from_str(WEB3_SCRYPT_KEYSTORE).unwrap();
+        keystore.address = vec![0; 20];
+        assert!(matches!(
+            keystore.decrypt_private_key(b"testpassword"),
+            Err(KeystoreFileError::AddressMismatch { .. })
+        ));
+    }
+
     #[test]
     fn test_keystore_v3() {
         let mut iv = [0u8; 16];
@@ -1,4 +1,6 @@
 pub mod cipher;
 pub mod kdf;
 pub mod keystore;
+pub mod sealed;
+pub mod secret;
 pub mod signer;
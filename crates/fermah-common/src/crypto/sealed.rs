@@ -0,0 +1,180 @@
+//! Results sealed to the public key of their recipient, so only it can read them: ECIES over secp256k1, the key of
+//! the requesters' signers, with AES-128-CTR and a keccak256 MAC as in the keystores.
+
+use aes::Aes128;
+use ctr::{
+    cipher::{KeyIvInit, StreamCipher},
+    Ctr128BE,
+};
+use k256::{
+    ecdsa::{SigningKey, VerifyingKey},
+    elliptic_curve::{point::AffineCoordinates, sec1::ToEncodedPoint, subtle::ConstantTimeEq},
+    NonZeroScalar,
+    PublicKey,
+    SecretKey,
+};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+use crate::serialization::encoding::hex_encoded;
+
+/// Version of the sealed layout, its first byte
+const SEALED_V1: u8 = 1;
+/// Domain tag of the key derivation, so the shared secret isn't reused for another kind of payload
+const KDF_DOMAIN: &[u8] = b"fermah/sealed-result/v1";
+
+const EPHEMERAL_KEY_LEN: usize = 33;
+const IV_LEN: usize = 16;
+const KEY_LEN: usize = 16;
+const MAC_LEN: usize = 32;
+/// Bytes a sealed result takes on top of its plaintext
+pub const SEALED_OVERHEAD: usize = 1 + EPHEMERAL_KEY_LEN + IV_LEN + MAC_LEN;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SealError {
+    #[error("result key is not a secp256k1 public key")]
+    InvalidKey,
+
+    #[error("sealed result is malformed")]
+    Malformed,
+
+    #[error("sealed result doesn't open with the key, it's sealed to another one or was altered")]
+    MacMismatch,
+}
+
+/// Compressed SEC1 public key the results of a request are sealed to
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ResultKey(#[serde(with = "hex_encoded")] pub Vec<u8>);
+
+impl From<&VerifyingKey> for ResultKey {
+    fn from(key: &VerifyingKey) -> Self {
+        Self(key.to_encoded_point(true).as_bytes().to_vec())
+    }
+}
+
+impl ResultKey {
+    pub fn validate(&self) -> Result<(), SealError> {
+        self.public_key().map(|_| ())
+    }
+
+    fn public_key(&self) -> Result<PublicKey, SealError> {
+        PublicKey::from_sec1_bytes(&self.0).map_err(|_| SealError::InvalidKey)
+    }
+}
+
+/// AES key and MAC key derived from the ECDH secret of the two keys
+fn derive_keys(
+    secret: &NonZeroScalar,
+    public: &PublicKey,
+    ephemeral_key: &[u8],
+) -> ([u8; KEY_LEN], [u8; KEY_LEN]) {
+    let shared = (public.to_projective() * **secret).to_affine();
+    let derived = Keccak256::new()
+        .chain_update(KDF_DOMAIN)
+        .chain_update(shared.x())
+        .chain_update(ephemeral_key)
+        .finalize();
+
+    let (cipher_key, mac_key) = derived.split_at(KEY_LEN);
+    (cipher_key.try_into().unwrap(), mac_key.try_into().unwrap())
+}
+
+fn mac(mac_key: &[u8], iv: &[u8], ciphertext: &[u8]) -> [u8; MAC_LEN] {
+    Keccak256::new()
+        .chain_update(mac_key)
+        .chain_update(iv)
+        .chain_update(ciphertext)
+        .finalize()
+        .into()
+}
+
+/// Seals the plaintext to the key, under a fresh ephemeral key
+pub fn seal(recipient: &ResultKey, plaintext: &[u8]) -> Result<Vec<u8>, SealError> {
+    let recipient = recipient.public_key()?;
+    let ephemeral = SecretKey::random(&mut OsRng);
+    let ephemeral_key = ephemeral.public_key().to_encoded_point(true);
+    let (cipher_key, mac_key) = derive_keys(
+        &ephemeral.to_nonzero_scalar(),
+        &recipient,
+        ephemeral_key.as_bytes(),
+    );
+
+    let mut iv = [0u8; IV_LEN];
+    OsRng.fill_bytes(&mut iv);
+    let mut ciphertext = plaintext.to_vec();
+    Ctr128BE::<Aes128>::new(&cipher_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    let mac = mac(&mac_key, &iv, &ciphertext);
+    let parts: [&[u8]; 5] = [
+        &[SEALED_V1],
+        ephemeral_key.as_bytes(),
+        &iv,
+        &ciphertext,
+        &mac,
+    ];
+    Ok(parts.concat())
+}
+
+/// Whether the bytes have the layout of a sealed result, they may still not open
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.len() >= SEALED_OVERHEAD && bytes[0] == SEALED_V1
+}
+
+/// Plaintext of a result sealed to the public key of `key`
+pub fn open(key: &SigningKey, sealed: &[u8]) -> Result<Vec<u8>, SealError> {
+    if !is_sealed(sealed) {
+        return Err(SealError::Malformed);
+    }
+    let (ephemeral_key, rest) = sealed[1..].split_at(EPHEMERAL_KEY_LEN);
+    let (iv, rest) = rest.split_at(IV_LEN);
+    let (ciphertext, expected_mac) = rest.split_at(rest.len() - MAC_LEN);
+
+    let ephemeral = PublicKey::from_sec1_bytes(ephemeral_key).map_err(|_| SealError::Malformed)?;
+    let (cipher_key, mac_key) = derive_keys(key.as_nonzero_scalar(), &ephemeral, ephemeral_key);
+    // Compared in constant time, so the time it takes doesn't tell how much of a forged MAC is right
+    if !bool::from(mac(&mac_key, iv, ciphertext)[..].ct_eq(expected_mac)) {
+        return Err(SealError::MacMismatch);
+    }
+
+    let mut plaintext = ciphertext.to_vec();
+    let iv: [u8; IV_LEN] = iv.try_into().unwrap();
+    Ctr128BE::<Aes128>::new(&cipher_key.into(), &iv.into()).apply_keystream(&mut plaintext);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_seal_open() {
+        let key = SigningKey::random(&mut StdRng::seed_from_u64(0));
+        let other = SigningKey::random(&mut StdRng::seed_from_u64(1));
+        let result_key = ResultKey::from(key.verifying_key());
+        assert_eq!(result_key.validate(), Ok(()));
+
+        let proof = b"groth16 proof".to_vec();
+        let sealed = seal(&result_key, &proof).unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(sealed.len(), proof.len() + SEALED_OVERHEAD);
+        assert_eq!(open(&key, &sealed), Ok(proof.clone()));
+        // Fresh ephemeral key every time
+        assert_ne!(seal(&result_key, &proof).unwrap(), sealed);
+
+        assert_eq!(open(&other, &sealed), Err(SealError::MacMismatch));
+        let mut altered = sealed.clone();
+        altered[SEALED_OVERHEAD - MAC_LEN] ^= 1;
+        assert_eq!(open(&key, &altered), Err(SealError::MacMismatch));
+        assert_eq!(open(&key, &proof), Err(SealError::Malformed));
+
+        assert_eq!(
+            ResultKey(vec![5; 33]).validate(),
+            Err(SealError::InvalidKey)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::{self, Debug};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Secret bytes, e.g. a decrypted private key.
+///
+/// The buffer is allocated once and never reallocated, so no copies of the secret are left behind. It is zeroized on
+/// drop and, with the `mlock` feature, locked in memory so that it is never swapped to disk.
+pub struct SecretBytes(Box<[u8]>);
+
+impl SecretBytes {
+    /// Zero filled secret of the given length, to be filled in place
+    pub fn zeroed(len: usize) -> Self {
+        let secret = Self(vec![0u8; len].into_boxed_slice());
+        secret.lock();
+        secret
+    }
+
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn expose_secret_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[cfg(all(feature = "mlock", unix))]
+    fn lock(&self) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        // Fails when RLIMIT_MEMLOCK is exhausted, the secret is still zeroized then
+        if unsafe { libc::mlock(self.0.as_ptr().cast(), self.0.len()) } != 0 {
+            tracing::warn!(
+                "failed to lock secret memory: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    #[cfg(all(feature = "mlock", unix))]
+    fn unlock(&self) {
+        if !self.0.is_empty() {
+            unsafe { libc::munlock(self.0.as_ptr().cast(), self.0.len()) };
+        }
+    }
+
+    #[cfg(not(all(feature = "mlock", unix)))]
+    fn lock(&self) {}
+
+    #[cfg(not(all(feature = "mlock", unix)))]
+    fn unlock(&self) {}
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(bytes: &[u8]) -> Self {
+        let mut secret = Self::zeroed(bytes.len());
+        secret.0.copy_from_slice(bytes);
+        secret
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    /// Moves the bytes into the secret buffer, wiping the vector including its spare capacity
+    fn from(mut bytes: Vec<u8>) -> Self {
+        let secret = Self::from(bytes.as_slice());
+        bytes.zeroize();
+        secret
+    }
+}
+
+impl Clone for SecretBytes {
+    fn clone(&self) -> Self {
+        Self::from(self.expose_secret())
+    }
+}
+
+impl Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+        self.unlock();
+    }
+}
+
+impl ZeroizeOnDrop for SecretBytes {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_bytes() {
+        let secret = SecretBytes::from(vec![1u8, 2, 3]);
+        assert_eq!(secret.expose_secret(), &[1, 2, 3]);
+        assert_eq!(secret.clone().expose_secret(), secret.expose_secret());
+        assert_eq!(format!("{:?}", secret), "SecretBytes([REDACTED; 3])");
+    }
+}
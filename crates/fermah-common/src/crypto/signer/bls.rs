@@ -12,7 +12,7 @@ use rand_core::CryptoRngCore;
 use zeroize::ZeroizeOnDrop;
 
 use crate::{
-    crypto::signer::Signer,
+    crypto::{secret::SecretBytes, signer::Signer},
     hash::{
         keccak256::{Keccak256Hash, Keccak256Hasher},
         Hashable,
@@ -180,20 +180,23 @@ impl Signer for BlsSigner {
         }
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::SignerError>
+    fn from_bytes(bytes: &SecretBytes) -> Result<Self, Self::SignerError>
     where
         Self: Sized,
     {
-        let key = Fr::from_le_bytes_mod_order(bytes);
+        let key = Fr::from_le_bytes_mod_order(bytes.expose_secret());
         Ok(Self::from_key(key))
     }
 
-    fn from_random(mut rng: impl CryptoRngCore) -> Result<(Self, Vec<u8>), Self::SignerError>
+    fn from_random(mut rng: impl CryptoRngCore) -> Result<(Self, SecretBytes), Self::SignerError>
     where
         Self: Sized,
     {
         let key = Fr::rand(&mut rng);
-        Ok((Self::from_key(key), key.into_bigint().to_bytes_le()))
+        Ok((
+            Self::from_key(key),
+            SecretBytes::from(key.into_bigint().to_bytes_le()),
+        ))
     }
 
     fn hash_and_sign<D: Hashable>(&self, data: D) -> Result<Self::Signature, Self::SignerError> {
@@ -11,9 +11,14 @@ use ethers::{
 };
 use k256::ecdsa::VerifyingKey;
 use rand_core::CryptoRngCore;
+use zeroize::Zeroize;
 
 use crate::{
-    crypto::signer::Signer,
+    crypto::{
+        sealed::{self, ResultKey, SealError},
+        secret::SecretBytes,
+        signer::Signer,
+    },
     hash::{
         blake3::{Blake3Hash, Blake3Hasher},
         Hashable,
@@ -59,6 +64,18 @@ impl Debug for EcdsaSigner {
     }
 }
 
+impl EcdsaSigner {
+    /// Key the results of the signer's requests are sealed to when they ask for it
+    pub fn result_key(&self) -> ResultKey {
+        ResultKey::from(&self.public_key)
+    }
+
+    /// Plaintext of a result sealed to [`Self::result_key`]
+    pub fn open_result(&self, sealed: &[u8]) -> Result<Vec<u8>, SealError> {
+        sealed::open(self.wallet.signer(), sealed)
+    }
+}
+
 impl Signer for EcdsaSigner {
     type PrivateKey = SigningKey;
     type PublicKey = VerifyingKey;
@@ -77,23 +94,25 @@ impl Signer for EcdsaSigner {
         }
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::SignerError>
+    fn from_bytes(bytes: &SecretBytes) -> Result<Self, Self::SignerError>
     where
         Self: Sized,
     {
-        let private_key = SigningKey::from_bytes(bytes.into())?;
+        let private_key = SigningKey::from_slice(bytes.expose_secret())?;
         Ok(Self::from_key(private_key))
     }
 
-    fn from_random(mut rng: impl CryptoRngCore) -> Result<(Self, Vec<u8>), Self::SignerError>
+    fn from_random(mut rng: impl CryptoRngCore) -> Result<(Self, SecretBytes), Self::SignerError>
     where
         Self: Sized,
     {
         let private_key = SigningKey::random(&mut rng);
-        Ok((
-            Self::from_key(private_key.clone()),
-            private_key.to_bytes().to_vec(),
-        ))
+
+        let mut bytes = private_key.to_bytes();
+        let secret = SecretBytes::from(bytes.as_slice());
+        bytes.as_mut_slice().zeroize();
+
+        Ok((Self::from_key(private_key), secret))
     }
 
     fn hash_and_sign<D: Hashable>(&self, data: D) -> Result<Self::Signature, Self::SignerError> {
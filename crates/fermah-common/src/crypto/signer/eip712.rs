@@ -0,0 +1,112 @@
+use std::convert::Infallible;
+
+use ethers::{
+    signers::Signer as EthereumSigner,
+    types::transaction::eip712::{EIP712Domain, Eip712},
+};
+use serde::Serialize;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::{
+        blake3::{Blake3Hash, Blake3Hasher},
+        Hashable,
+    },
+};
+
+/// Name of the EIP-712 signing domain, the domain is also bound to the chain id of the network
+pub const DOMAIN_NAME: &str = "Fermah";
+pub const DOMAIN_VERSION: &str = "1";
+
+pub fn domain(chain_id: u64) -> EIP712Domain {
+    EIP712Domain {
+        name: Some(DOMAIN_NAME.to_string()),
+        version: Some(DOMAIN_VERSION.to_string()),
+        chain_id: Some(chain_id.into()),
+        verifying_contract: None,
+        salt: None,
+    }
+}
+
+/// Payloads having an EIP-712 typed-data encoding
+pub trait TypedPayload {
+    /// `keccak256` of the encoded primary type
+    fn type_hash() -> [u8; 32];
+    /// `hashStruct` of the payload
+    fn struct_hash(&self) -> [u8; 32];
+}
+
+/// Payload bound to the signing domain of a chain, can be signed by any EIP-712 capable signer, e.g. a hardware wallet
+#[derive(Debug, Clone)]
+pub struct Typed<D> {
+    pub payload: D,
+    pub chain_id: u64,
+}
+
+impl<D: TypedPayload> Eip712 for Typed<D> {
+    type Error = Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(domain(self.chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(D::type_hash())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.payload.struct_hash())
+    }
+}
+
+impl<D: TypedPayload + Clone> Typed<D> {
+    /// EIP-712 digest which is signed
+    pub fn digest(&self) -> Blake3Hash {
+        self.encode_eip712().unwrap_or_else(|e| match e {}).into()
+    }
+}
+
+/// How the hash of [`SignedData`] was derived from its payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    /// Blake3 hash of the payload
+    Raw,
+    /// EIP-712 typed-data digest of the payload
+    Eip712,
+}
+
+impl<D> SignedData<D, EcdsaSigner>
+where
+    D: Serialize + Hashable + TypedPayload + Clone + Send + Sync,
+{
+    /// Signs the EIP-712 typed-data encoding of the payload, so wallets can display what is signed.
+    /// The hash of the signed data is the EIP-712 digest, instead of the Blake3 hash of the payload.
+    pub async fn new_eip712<S: EthereumSigner>(
+        payload: D,
+        chain_id: u64,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        let typed = Typed { payload, chain_id };
+        let signature = signer.sign_typed_data(&typed).await?;
+
+        Ok(SignedData {
+            hash: typed.digest(),
+            payload: typed.payload,
+            public_key: signer.address(),
+            signature,
+        })
+    }
+
+    /// Scheme by which the hash was derived from the payload, `None` if it matches neither of them for the chain
+    pub fn scheme(&self, chain_id: u64) -> Option<SignatureScheme> {
+        if self.hash == self.payload.hash::<Blake3Hasher>() {
+            return Some(SignatureScheme::Raw);
+        }
+
+        let typed = Typed {
+            payload: self.payload.clone(),
+            chain_id,
+        };
+        (self.hash == typed.digest()).then_some(SignatureScheme::Eip712)
+    }
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StaleCommand {
+    #[error("deadline passed at {0}")]
+    Expired(DateTime<Utc>),
+    #[error(
+        "deadline {0} is more than {} seconds ahead",
+        Freshness::MAX_VALIDITY_SECS
+    )]
+    TooFarAhead(DateTime<Utc>),
+}
+
+/// Signed with a command changing the state of the server, so an intercepted command can't be replayed. It's
+/// accepted before its deadline, at most [`Freshness::MAX_VALIDITY_SECS`] ahead, and only with a nonce above the
+/// last one accepted from its signer for the same kind of command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Freshness {
+    pub nonce: u64,
+    pub deadline: DateTime<Utc>,
+}
+
+impl Freshness {
+    pub const MAX_VALIDITY_SECS: i64 = 600;
+
+    /// Valid for the longest accepted time, with the nonce of [`Self::nonce_at`]
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            nonce: Self::nonce_at(now),
+            deadline: now + Duration::seconds(Self::MAX_VALIDITY_SECS),
+        }
+    }
+
+    /// Time in microseconds, so the nonce grows with each command signed by the same key
+    pub fn nonce_at(now: DateTime<Utc>) -> u64 {
+        now.timestamp_micros().max(0) as u64
+    }
+
+    /// Checks the deadline, the nonce is checked against the last accepted one by the server
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), StaleCommand> {
+        if self.deadline <= now {
+            return Err(StaleCommand::Expired(self.deadline));
+        }
+        if self.deadline > now + Duration::seconds(Self::MAX_VALIDITY_SECS) {
+            return Err(StaleCommand::TooFarAhead(self.deadline));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness() {
+        let now = Utc::now();
+        let freshness = Freshness::new(now);
+        assert_eq!(freshness.validate(now), Ok(()));
+        assert_eq!(
+            freshness.validate(now + Duration::seconds(Freshness::MAX_VALIDITY_SECS)),
+            Err(StaleCommand::Expired(freshness.deadline))
+        );
+        assert_eq!(
+            freshness.validate(now - Duration::seconds(1)),
+            Err(StaleCommand::TooFarAhead(freshness.deadline))
+        );
+        assert!(Freshness::new(now + Duration::milliseconds(1)).nonce > freshness.nonce);
+    }
+}
@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{MessageType, SigningAlgorithmSpec},
+    Client,
+};
+
+use super::{KmsBackend, KmsError};
+
+/// AWS KMS key of the `ECC_SECG_P256K1` spec. Credentials and region are taken from the environment.
+#[derive(Debug, Clone)]
+pub struct AwsKms {
+    client: Client,
+    key_id: String,
+}
+
+impl AwsKms {
+    pub async fn new(key_id: impl Into<String>) -> Self {
+        let config = aws_config::load_from_env().await;
+        Self::with_client(Client::new(&config), key_id)
+    }
+
+    pub fn with_client(client: Client, key_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_id: key_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl KmsBackend for AwsKms {
+    async fn public_key_der(&self) -> Result<Vec<u8>, KmsError> {
+        let response = self
+            .client
+            .get_public_key()
+            .key_id(&self.key_id)
+            .send()
+            .await
+            .map_err(|e| KmsError::Request(e.to_string()))?;
+
+        response
+            .public_key
+            .map(Blob::into_inner)
+            .ok_or_else(|| KmsError::Request("no public key in the response".to_string()))
+    }
+
+    async fn sign_digest_der(&self, digest: [u8; 32]) -> Result<Vec<u8>, KmsError> {
+        let response = self
+            .client
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(digest))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+            .send()
+            .await
+            .map_err(|e| KmsError::Request(e.to_string()))?;
+
+        response
+            .signature
+            .map(Blob::into_inner)
+            .ok_or_else(|| KmsError::Request("no signature in the response".to_string()))
+    }
+}
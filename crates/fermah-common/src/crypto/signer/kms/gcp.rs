@@ -0,0 +1,103 @@
+use std::env;
+
+use async_trait::async_trait;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{KmsBackend, KmsError};
+
+pub const GCP_ACCESS_TOKEN_ENV: &str = "GCP_ACCESS_TOKEN";
+
+const KMS_API: &str = "https://cloudkms.googleapis.com/v1";
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/// GCP Cloud KMS key version of the `EC_SIGN_SECP256K1_SHA256` algorithm.
+///
+/// The access token is taken from [`GCP_ACCESS_TOKEN_ENV`], otherwise from the metadata server of the instance.
+#[derive(Debug, Clone)]
+pub struct GcpKms {
+    http: reqwest::Client,
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct PublicKey {
+    pem: String,
+}
+
+#[derive(Deserialize)]
+struct AsymmetricSign {
+    signature: String,
+}
+
+impl GcpKms {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            name: name.into(),
+        }
+    }
+
+    async fn access_token(&self) -> Result<String, KmsError> {
+        if let Ok(token) = env::var(GCP_ACCESS_TOKEN_ENV) {
+            return Ok(token);
+        }
+
+        let token: AccessToken = self
+            .http
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl KmsBackend for GcpKms {
+    async fn public_key_der(&self) -> Result<Vec<u8>, KmsError> {
+        let key: PublicKey = self
+            .http
+            .get(format!("{KMS_API}/{}/publicKey", self.name))
+            .bearer_auth(self.access_token().await?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // PEM body is the base64 encoded DER
+        let body: String = key
+            .pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        Ok(BASE64_STANDARD.decode(body)?)
+    }
+
+    async fn sign_digest_der(&self, digest: [u8; 32]) -> Result<Vec<u8>, KmsError> {
+        let response: AsymmetricSign = self
+            .http
+            .post(format!("{KMS_API}/{}:asymmetricSign", self.name))
+            .bearer_auth(self.access_token().await?)
+            .json(&json!({ "digest": { "sha256": BASE64_STANDARD.encode(digest) } }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(BASE64_STANDARD.decode(response.signature)?)
+    }
+}
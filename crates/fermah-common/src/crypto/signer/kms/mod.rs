@@ -0,0 +1,279 @@
+//! ECDSA signing delegated to a cloud KMS, so that the private key never leaves it.
+
+#[cfg(feature = "aws-kms")]
+pub mod aws;
+#[cfg(feature = "gcp-kms")]
+pub mod gcp;
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use ethers::{
+    signers::Signer as EthereumSigner,
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::Eip712},
+        Address,
+        Signature,
+        H256,
+        U256,
+    },
+    utils::{hash_message, public_key_to_address},
+};
+use k256::{
+    ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use tracing::info;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::{blake3::Blake3Hasher, Hashable},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum KmsError {
+    #[error("kms request error: {0}")]
+    Request(String),
+    #[error("kms provider {0} is not compiled in")]
+    Unsupported(KmsProvider),
+    #[error("invalid kms public key: {0}")]
+    PublicKey(#[from] k256::pkcs8::spki::Error),
+    #[error("invalid kms signature: {0}")]
+    Signature(#[from] k256::ecdsa::Error),
+    #[error("kms signature does not recover to the kms public key")]
+    Recovery,
+    #[error("eip712 error: {0}")]
+    Eip712(String),
+    #[error("base64 error: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Key management service holding a secp256k1 key
+#[async_trait]
+pub trait KmsBackend: Send + Sync + Debug {
+    /// DER encoded SubjectPublicKeyInfo of the key
+    async fn public_key_der(&self) -> Result<Vec<u8>, KmsError>;
+
+    /// DER encoded ECDSA signature of the 32 bytes digest
+    async fn sign_digest_der(&self, digest: [u8; 32]) -> Result<Vec<u8>, KmsError>;
+}
+
+#[derive(Serialize, Deserialize, Display, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum KmsProvider {
+    Aws,
+    Gcp,
+}
+
+#[derive(Parser, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KmsConfig {
+    /// KMS holding the signing key
+    #[arg(long)]
+    pub kms_provider: KmsProvider,
+    /// Key id or ARN for AWS, key version resource name for GCP
+    #[arg(long)]
+    pub kms_key: String,
+}
+
+impl KmsConfig {
+    pub async fn to_signer(&self, chain_id: u64) -> Result<KmsSigner, KmsError> {
+        match self.kms_provider {
+            #[cfg(feature = "aws-kms")]
+            KmsProvider::Aws => {
+                KmsSigner::new(aws::AwsKms::new(&self.kms_key).await, chain_id).await
+            }
+            #[cfg(feature = "gcp-kms")]
+            KmsProvider::Gcp => KmsSigner::new(gcp::GcpKms::new(&self.kms_key), chain_id).await,
+            #[allow(unreachable_patterns)]
+            provider => {
+                let _ = chain_id;
+                Err(KmsError::Unsupported(provider))
+            }
+        }
+    }
+}
+
+/// Ethereum signer whose key is held by a KMS
+#[derive(Clone, Debug)]
+pub struct KmsSigner {
+    backend: Arc<dyn KmsBackend>,
+    public_key: VerifyingKey,
+    address: Address,
+    chain_id: u64,
+}
+
+impl KmsSigner {
+    pub async fn new(backend: impl KmsBackend + 'static, chain_id: u64) -> Result<Self, KmsError> {
+        let der = backend.public_key_der().await?;
+        let public_key = VerifyingKey::from_public_key_der(&der)?;
+        let address = public_key_to_address(&public_key);
+        info!(?address, ?backend, "kms signer ready");
+
+        Ok(Self {
+            backend: Arc::new(backend),
+            public_key,
+            address,
+            chain_id,
+        })
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.public_key
+    }
+
+    /// Signs the digest, `v` of the signature is 27 or 28
+    pub async fn sign_digest(&self, digest: H256) -> Result<Signature, KmsError> {
+        let der = self.backend.sign_digest_der(digest.0).await?;
+        let signature = EcdsaSignature::from_der(&der)?;
+        // KMS doesn't normalize signatures, while Ethereum only accepts low-s ones
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        // KMS doesn't return the recovery id either, so find the one recovering our key
+        for recovery_id in 0..2u8 {
+            let recovered = VerifyingKey::recover_from_prehash(
+                digest.as_bytes(),
+                &signature,
+                RecoveryId::from_byte(recovery_id).expect("recovery id is valid"),
+            );
+            if recovered.is_ok_and(|key| key == self.public_key) {
+                let (r, s) = signature.split_bytes();
+                return Ok(Signature {
+                    r: U256::from_big_endian(&r),
+                    s: U256::from_big_endian(&s),
+                    v: 27 + recovery_id as u64,
+                });
+            }
+        }
+
+        Err(KmsError::Recovery)
+    }
+}
+
+#[async_trait]
+impl EthereumSigner for KmsSigner {
+    type Error = KmsError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest(hash_message(message)).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        tx.set_chain_id(chain_id);
+
+        // EIP-155 replay protection
+        let mut signature = self.sign_digest(tx.sighash()).await?;
+        signature.v = (signature.v - 27) + chain_id * 2 + 35;
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload
+            .encode_eip712()
+            .map_err(|e| KmsError::Eip712(e.to_string()))?;
+        self.sign_digest(digest.into()).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+impl<D: Serialize + Hashable + Clone> SignedData<D, EcdsaSigner> {
+    /// Signs the payload by the KMS key, the result is verified as any data signed by [`EcdsaSigner`]
+    pub async fn new_kms(payload: D, signer: &KmsSigner) -> Result<Self, KmsError> {
+        let hash = payload.hash::<Blake3Hasher>();
+        let signature = signer.sign_digest(H256::from_slice(hash.as_ref())).await?;
+
+        Ok(SignedData {
+            hash,
+            payload,
+            public_key: signer.address(),
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+    use k256::{
+        ecdsa::{signature::hazmat::PrehashSigner, SigningKey},
+        pkcs8::EncodePublicKey,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    /// KMS imitation holding a local key
+    #[derive(Debug)]
+    struct LocalKms(SigningKey);
+
+    #[async_trait]
+    impl KmsBackend for LocalKms {
+        async fn public_key_der(&self) -> Result<Vec<u8>, KmsError> {
+            Ok(self.0.verifying_key().to_public_key_der()?.into_vec())
+        }
+
+        async fn sign_digest_der(&self, digest: [u8; 32]) -> Result<Vec<u8>, KmsError> {
+            let signature: EcdsaSignature = self.0.sign_prehash(&digest)?;
+            Ok(signature.to_der().as_bytes().to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kms_signer_matches_local_wallet() {
+        let key = SigningKey::random(&mut StdRng::seed_from_u64(0));
+        let wallet = LocalWallet::from(key.clone()).with_chain_id(17000_u64);
+        let kms = KmsSigner::new(LocalKms(key), 17000).await.unwrap();
+
+        assert_eq!(kms.address(), wallet.address());
+
+        let signature = kms.sign_message("fermah").await.unwrap();
+        assert_eq!(signature, wallet.sign_message("fermah").await.unwrap());
+        assert!(signature.verify("fermah", kms.address()).is_ok());
+
+        let tx: TypedTransaction = ethers::types::Eip1559TransactionRequest::new()
+            .to(Address::random())
+            .nonce(1)
+            .chain_id(17000)
+            .into();
+        assert_eq!(
+            kms.sign_transaction(&tx).await.unwrap(),
+            wallet.sign_transaction(&tx).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kms_signed_data() {
+        let key = SigningKey::random(&mut StdRng::seed_from_u64(1));
+        let kms = KmsSigner::new(LocalKms(key), 17000).await.unwrap();
+
+        let signed = SignedData::new_kms(Address::random(), &kms).await.unwrap();
+        assert!(signed.verify().is_ok());
+        assert_eq!(signed.public_key, kms.address());
+    }
+}
@@ -1,5 +1,9 @@
 pub mod bls;
 pub mod ecdsa;
+pub mod eip712;
+pub mod freshness;
+#[cfg(feature = "kms")]
+pub mod kms;
 
 use std::fmt::Debug;
 
@@ -10,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use strum::Display;
 
 use crate::{
+    crypto::secret::SecretBytes,
     hash::{Hashable, Hasher},
     serialization::encoding::hex_encoded,
 };
@@ -34,11 +39,11 @@ pub trait Signer {
 
     fn from_key(key: Self::PrivateKey) -> Self;
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, Self::SignerError>
+    fn from_bytes(bytes: &SecretBytes) -> Result<Self, Self::SignerError>
     where
         Self: Sized;
 
-    fn from_random(rng: impl CryptoRngCore) -> Result<(Self, Vec<u8>), Self::SignerError>
+    fn from_random(rng: impl CryptoRngCore) -> Result<(Self, SecretBytes), Self::SignerError>
     where
         Self: Sized;
 
@@ -58,6 +63,15 @@ pub trait Signer {
     ) -> Result<(), Self::SignerError>;
 }
 
+/// Signed data refused by [`SignedData::verify_payload`]
+#[derive(thiserror::Error, Debug)]
+pub enum PayloadVerificationError<E> {
+    #[error("hash doesn't match the payload")]
+    HashMismatch,
+    #[error("invalid signature")]
+    Signature(E),
+}
+
 /// Container that holds a payload and signature
 #[derive(Serialize, Deserialize, Hash, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -99,9 +113,20 @@ impl<D: Serialize + Hashable + Clone, S: Signer> SignedData<D, S> {
         })
     }
 
+    /// Checks the signature of the hash only, the hash may not be the one of the payload, see
+    /// [`Self::verify_payload`]
     pub fn verify(&self) -> Result<(), S::SignerError> {
         S::verify(&self.hash, &self.public_key, &self.signature)
     }
+
+    /// Checks the hash is the one of the payload and the signature of the hash, so the signature can't be paired with
+    /// another payload
+    pub fn verify_payload(&self) -> Result<(), PayloadVerificationError<S::SignerError>> {
+        if self.payload.hash::<S::Hasher>().as_ref() != self.hash.as_ref() {
+            return Err(PayloadVerificationError::HashMismatch);
+        }
+        self.verify().map_err(PayloadVerificationError::Signature)
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +158,28 @@ mod tests {
         let signed_data = SignedData::new(data, &signer).unwrap();
 
         assert!(signed_data.verify().is_ok());
+        assert!(signed_data.verify_payload().is_ok());
         assert_eq!(format!("{}", signed_data.signature), "9db6e894c27b4a3b50a3cd3142f2d0a0b7c6c674f1624144a5a842d7cc2d43865a303aafffe5d0144bd9621bd3fb565387c3a18c25e2c812d93a1652fe627b001b");
     }
+
+    #[tokio::test]
+    async fn test_signed_data_forged_payload() {
+        let (signer, _) = EcdsaSigner::from_random(&mut StdRng::seed_from_u64(0)).unwrap();
+
+        let mut forged = SignedData::new(
+            TestData {
+                data: "test".to_string(),
+            },
+            &signer,
+        )
+        .unwrap();
+        forged.payload.data = "forged".to_string();
+
+        // The signature still matches the hash, not the payload
+        assert!(forged.verify().is_ok());
+        assert!(matches!(
+            forged.verify_payload(),
+            Err(PayloadVerificationError::HashMismatch)
+        ));
+    }
 }
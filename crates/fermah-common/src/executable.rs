@@ -1,19 +1,68 @@
 use std::{borrow::Cow, collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use tracing::warn;
 
 use crate::{
     hash::Hashable,
+    operator::runtime::RuntimeFeature,
+    proof::template::{self, TemplateError, TemplateVars},
     resources::{LocalResource, RemoteResource},
 };
 
 pub type ImageName = String;
 
+/// Most linear memory a wasm module can be given, all a 32-bit module addresses
+pub const MAX_WASM_MEMORY: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Fuel, roughly the instructions, a wasm module can be given
+pub const MAX_WASM_FUEL: u64 = 1 << 40;
+
+/// Limits a wasm module runs within, it's stopped once it goes over one
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmLimits {
+    /// Linear memory in Bytes
+    pub max_memory: u64,
+    pub max_fuel: u64,
+}
+
+/// WebAssembly module run in a WASI sandbox instead of a container, for provers and verifiers too small to ship a
+/// container image for. Mounts, injected and extracted files are preopened directories, and the executable's
+/// entrypoint and command are its arguments.
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmModule {
+    pub module: RemoteResource,
+    /// Exported function called without arguments, e.g. `_start`
+    pub entry: String,
+    pub limits: WasmLimits,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExecutableError {
+    #[error("wasm entry function {0:?} is not a valid export name")]
+    InvalidWasmEntry(String),
+
+    #[error("wasm limits are out of range, at most {MAX_WASM_MEMORY} bytes of memory and {MAX_WASM_FUEL} fuel")]
+    InvalidWasmLimits,
+
+    #[error("wasm modules can't be {0}")]
+    UnsupportedByWasm(&'static str),
+
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+}
+
+/// What the executable runs: an OCI image, the `Docker` names are kept for compatibility, any [`ContainerRuntime`]
+/// runs them, or a wasm module
+///
+/// [`ContainerRuntime`]: crate::operator::runtime::ContainerRuntime
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum Image {
-    // Uses the general docker
+    // Pulled by name from the registry of the runtime
     Docker(ImageName),
     /// Image name, remote resource to load the image. todo: make it contain ID (docker's image hash) of the image, not Hash
     /// Note: we are not checking that remote resource contains the image with image name that is ImageName. RemoteResource is
@@ -21,9 +70,12 @@ pub enum Image {
     RemoteDocker((RemoteResource, ImageName)),
     // Dev only
     LocalDocker((LocalResource, ImageName)),
+    /// Run by the operators advertising [`RuntimeFeature::Wasm`]
+    Wasm(WasmModule),
 }
 
 impl Image {
+    /// Image name, the entry function for wasm modules
     pub fn name(&self) -> &str {
         match self {
             Self::Docker(name) => name,
@@ -32,8 +84,30 @@ impl Image {
                 warn!("Local docker is for local development only!");
                 name
             }
+            Self::Wasm(wasm) => &wasm.entry,
         }
     }
+
+    /// Resource the image is downloaded from, `None` if the prover pulls it by name
+    pub fn remote(&self) -> Option<&RemoteResource> {
+        match self {
+            Self::RemoteDocker((resource, _)) => Some(resource),
+            Self::Wasm(wasm) => Some(&wasm.module),
+            Self::Docker(_) | Self::LocalDocker(_) => None,
+        }
+    }
+
+    pub fn remote_mut(&mut self) -> Option<&mut RemoteResource> {
+        match self {
+            Self::RemoteDocker((resource, _)) => Some(resource),
+            Self::Wasm(wasm) => Some(&mut wasm.module),
+            Self::Docker(_) | Self::LocalDocker(_) => None,
+        }
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        matches!(self, Self::Wasm(_))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
@@ -44,6 +118,9 @@ pub enum Source {
     Files(Vec<(PathBuf, RemoteResource)>),
     /// Unzip a directory as a target directory
     UnZipDirectory(RemoteResource),
+    /// Take all the files of the signed [`FileManifest`](crate::file_manifest::FileManifest) the resource points to,
+    /// and put them into the target directory
+    Manifest(RemoteResource),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
@@ -128,35 +205,385 @@ pub struct Executable {
     pub docker_access: bool,
 }
 
-impl Hashable for Executable {
-    fn collect(&self) -> Cow<[u8]> {
-        let mut buf = vec![];
-        buf.extend_from_slice(&serde_json::to_vec(&self.image).unwrap());
-        buf.extend_from_slice(&serde_json::to_vec(&self.in_mounts).unwrap());
-        buf.extend_from_slice(&serde_json::to_vec(&self.result_extractor).unwrap());
-        buf.extend_from_slice(&serde_json::to_vec(&self.injector).unwrap());
-        buf.extend_from_slice(&serde_json::to_vec(&self.entrypoint).unwrap());
-        if let Some(ev) = &self.env_vars {
-            let mut ev = ev.iter().collect::<Vec<(&String, &String)>>();
-            ev.sort();
-            ev.iter().for_each(|(k, v)| {
-                buf.extend_from_slice(k.as_bytes());
-                buf.extend_from_slice(v.as_bytes());
-            })
-        } else {
-            buf.extend_from_slice("ev".as_bytes());
+/// Encoding executables are hashed with. The hash is part of the proof request hash, so a new version changes the
+/// ids of new requests only, stored requests keep the hash they were signed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashVersion {
+    /// Leaves out the platform and the command, so executables only differing by them hash the same
+    Legacy,
+    /// Every field, length-prefixed after a domain tag
+    V1,
+}
+
+impl HashVersion {
+    pub const LATEST: Self = Self::V1;
+}
+
+/// Domain tag of [`HashVersion::V1`], so an executable encoding never collides with another kind of payload
+const HASH_DOMAIN_V1: &[u8] = b"fermah/executable/v1";
+
+fn encode_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn encode_strings<'a>(buf: &mut Vec<u8>, strings: impl ExactSizeIterator<Item = &'a String>) {
+    buf.extend_from_slice(&(strings.len() as u64).to_be_bytes());
+    strings.for_each(|s| encode_bytes(buf, s.as_bytes()));
+}
+
+impl Executable {
+    /// Resources the executable is run from: its image and everything it mounts
+    pub fn remote_resources(&self) -> Vec<&RemoteResource> {
+        let mounted = self.in_mounts.iter().flat_map(|mount| {
+            match &mount.source {
+                Source::File(resource)
+                | Source::UnZipDirectory(resource)
+                | Source::Manifest(resource) => {
+                    vec![resource]
+                }
+                Source::Files(files) => files.iter().map(|(_, resource)| resource).collect(),
+            }
+        });
+        self.image.remote().into_iter().chain(mounted).collect()
+    }
+
+    /// Features the runtime of the operator has to provide to run the executable
+    pub fn required_features(&self) -> Vec<RuntimeFeature> {
+        [
+            (self.privileged, RuntimeFeature::Privileged),
+            (self.network_enabled, RuntimeFeature::Network),
+            (self.docker_access, RuntimeFeature::DockerAccess),
+            (self.image.is_wasm(), RuntimeFeature::Wasm),
+        ]
+        .into_iter()
+        .filter_map(|(required, feature)| required.then_some(feature))
+        .collect()
+    }
+
+    /// Executable as the operator runs it, with the [`template`] placeholders of its command and environment
+    /// variable values substituted
+    pub fn resolve_templates(&self, vars: &TemplateVars) -> Result<Self, TemplateError> {
+        let mut resolved = self.clone();
+        let env_values = resolved.env_vars.iter_mut().flat_map(|ev| ev.values_mut());
+        for text in resolved.cmd.iter_mut().chain(env_values) {
+            *text = template::substitute(text, vars)?;
+        }
+        Ok(resolved)
+    }
+
+    /// Checks the executable can run at all: it only refers to known placeholders, and wasm modules are sandboxed,
+    /// so they can't ask for the container features, nor for a platform.
+    pub fn validate(&self) -> Result<(), ExecutableError> {
+        let env_values = self.env_vars.iter().flat_map(|ev| ev.values());
+        for text in self.cmd.iter().chain(env_values) {
+            template::check(text)?;
+        }
+
+        let Image::Wasm(wasm) = &self.image else {
+            return Ok(());
         };
-        let flags = (self.docker_access as u8) << 2
+
+        let valid_entry = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        if wasm.entry.is_empty() || !wasm.entry.chars().all(valid_entry) {
+            return Err(ExecutableError::InvalidWasmEntry(wasm.entry.clone()));
+        }
+        let limits = wasm.limits;
+        if !(1..=MAX_WASM_MEMORY).contains(&limits.max_memory)
+            || !(1..=MAX_WASM_FUEL).contains(&limits.max_fuel)
+        {
+            return Err(ExecutableError::InvalidWasmLimits);
+        }
+        for (set, feature) in [
+            (self.privileged, "privileged"),
+            (self.network_enabled, "network enabled"),
+            (self.docker_access, "given docker access"),
+            (self.platform.is_some(), "built for a platform"),
+        ] {
+            if set {
+                return Err(ExecutableError::UnsupportedByWasm(feature));
+            }
+        }
+        Ok(())
+    }
+
+    fn flags(&self) -> u8 {
+        (self.docker_access as u8) << 2
             | (self.privileged as u8) << 1
-            | (self.network_enabled as u8);
-        buf.extend_from_slice(&[flags]);
-        Cow::Owned(buf)
+            | (self.network_enabled as u8)
+    }
+
+    fn sorted_env_vars(&self) -> Option<Vec<(&String, &String)>> {
+        self.env_vars.as_ref().map(|ev| {
+            let mut ev = ev.iter().collect::<Vec<(&String, &String)>>();
+            ev.sort();
+            ev
+        })
+    }
+
+    /// Bytes the executable is hashed from in the given encoding
+    pub fn collect_versioned(&self, version: HashVersion) -> Vec<u8> {
+        let mut buf = vec![];
+        match version {
+            HashVersion::Legacy => {
+                buf.extend_from_slice(&serde_json::to_vec(&self.image).unwrap());
+                buf.extend_from_slice(&serde_json::to_vec(&self.in_mounts).unwrap());
+                buf.extend_from_slice(&serde_json::to_vec(&self.result_extractor).unwrap());
+                buf.extend_from_slice(&serde_json::to_vec(&self.injector).unwrap());
+                buf.extend_from_slice(&serde_json::to_vec(&self.entrypoint).unwrap());
+                if let Some(ev) = self.sorted_env_vars() {
+                    ev.iter().for_each(|(k, v)| {
+                        buf.extend_from_slice(k.as_bytes());
+                        buf.extend_from_slice(v.as_bytes());
+                    })
+                } else {
+                    buf.extend_from_slice("ev".as_bytes());
+                };
+            }
+            HashVersion::V1 => {
+                buf.extend_from_slice(HASH_DOMAIN_V1);
+                encode_bytes(&mut buf, &serde_json::to_vec(&self.image).unwrap());
+                match &self.platform {
+                    Some(platform) => {
+                        buf.push(1);
+                        encode_bytes(&mut buf, platform.as_bytes());
+                    }
+                    None => buf.push(0),
+                }
+                encode_bytes(&mut buf, &serde_json::to_vec(&self.in_mounts).unwrap());
+                encode_bytes(
+                    &mut buf,
+                    &serde_json::to_vec(&self.result_extractor).unwrap(),
+                );
+                encode_bytes(&mut buf, &serde_json::to_vec(&self.injector).unwrap());
+                encode_strings(&mut buf, self.entrypoint.iter());
+                encode_strings(&mut buf, self.cmd.iter());
+                match self.sorted_env_vars() {
+                    Some(ev) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&(ev.len() as u64).to_be_bytes());
+                        ev.iter().for_each(|(k, v)| {
+                            encode_bytes(&mut buf, k.as_bytes());
+                            encode_bytes(&mut buf, v.as_bytes());
+                        });
+                    }
+                    None => buf.push(0),
+                }
+            }
+        }
+        buf.push(self.flags());
+        buf
+    }
+}
+
+impl Hashable for Executable {
+    fn collect(&self) -> Cow<[u8]> {
+        Cow::Owned(self.collect_versioned(HashVersion::LATEST))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use const_hex::ToHexExt;
+
     use super::*;
+    use crate::hash::blake3::Blake3Hasher;
+
+    fn reference_executable() -> Executable {
+        Executable {
+            image: Image::Docker("prover:latest".to_string()),
+            platform: Some("linux/amd64".to_string()),
+            in_mounts: vec![],
+            result_extractor: Some(ResultExtractor::File("/output/proof.bin".into())),
+            injector: None,
+            entrypoint: vec!["/bin/prove".to_string()],
+            cmd: vec!["--fast".to_string()],
+            env_vars: Some(HashMap::from([("MODE".to_string(), "release".to_string())])),
+            network_enabled: false,
+            privileged: false,
+            docker_access: false,
+        }
+    }
+
+    #[test]
+    fn test_hash_is_locked() {
+        let executable = reference_executable();
+
+        // Changing these breaks the ids of proof requests, add a new `HashVersion` instead
+        assert_eq!(
+            executable.hash::<Blake3Hasher>().encode_hex(),
+            "b4362a6bca3abf07c573c2c1856ac6792c73f374619448561be9b75584a17ee2"
+        );
+        let legacy = executable.collect_versioned(HashVersion::Legacy);
+        assert_eq!(
+            blake3::hash(&legacy).to_hex().as_str(),
+            "3ee9a388f00dda1125dca3c2db371310e4ff9d199e06bf35b7dcfd99305ad8da"
+        );
+    }
+
+    #[test]
+    fn test_hash_covers_every_field() {
+        let executable = reference_executable();
+        let variants = [
+            Executable {
+                platform: None,
+                ..executable.clone()
+            },
+            Executable {
+                cmd: vec!["--slow".to_string()],
+                ..executable.clone()
+            },
+            // Moving an argument between the entrypoint and the command isn't the same executable
+            Executable {
+                entrypoint: vec![],
+                cmd: vec!["/bin/prove".to_string(), "--fast".to_string()],
+                ..executable.clone()
+            },
+            Executable {
+                network_enabled: true,
+                ..executable.clone()
+            },
+            Executable {
+                privileged: true,
+                ..executable.clone()
+            },
+            Executable {
+                env_vars: None,
+                ..executable.clone()
+            },
+        ];
+
+        for variant in variants {
+            assert_ne!(
+                variant.hash::<Blake3Hasher>(),
+                executable.hash::<Blake3Hasher>(),
+                "{variant:?}"
+            );
+        }
+
+        // The legacy encoding couldn't tell them apart
+        let cmd_only = Executable {
+            cmd: vec![],
+            platform: None,
+            ..executable.clone()
+        };
+        assert_eq!(
+            cmd_only.collect_versioned(HashVersion::Legacy),
+            executable.collect_versioned(HashVersion::Legacy)
+        );
+    }
+
+    #[test]
+    fn test_wasm() {
+        let module = WasmModule {
+            module: RemoteResource {
+                url: "http://localhost:3000/verifier.wasm".parse().unwrap(),
+                hash: [7; 32].into(),
+                delta: None,
+            },
+            entry: "verify".to_string(),
+            limits: WasmLimits {
+                max_memory: 64 * 1024 * 1024,
+                max_fuel: 1_000_000_000,
+            },
+        };
+        let executable = Executable {
+            image: Image::Wasm(module.clone()),
+            platform: None,
+            cmd: vec![],
+            env_vars: None,
+            ..reference_executable()
+        };
+        assert_eq!(executable.validate(), Ok(()));
+        assert_eq!(executable.image.remote(), Some(&module.module));
+        assert!(executable
+            .required_features()
+            .contains(&RuntimeFeature::Wasm));
+
+        // The limits are signed too
+        let more_fuel = Executable {
+            image: Image::Wasm(WasmModule {
+                limits: WasmLimits {
+                    max_fuel: 2_000_000_000,
+                    ..module.limits
+                },
+                ..module.clone()
+            }),
+            ..executable.clone()
+        };
+        assert_ne!(
+            more_fuel.hash::<Blake3Hasher>(),
+            executable.hash::<Blake3Hasher>()
+        );
+
+        let invalid = [
+            (
+                Image::Wasm(WasmModule {
+                    entry: "not exported".to_string(),
+                    ..module.clone()
+                }),
+                false,
+            ),
+            (
+                Image::Wasm(WasmModule {
+                    limits: WasmLimits {
+                        max_memory: 0,
+                        ..module.limits
+                    },
+                    ..module.clone()
+                }),
+                false,
+            ),
+            // Sandboxed, so no network
+            (Image::Wasm(module.clone()), true),
+        ];
+        for (image, network_enabled) in invalid {
+            let executable = Executable {
+                image,
+                network_enabled,
+                ..executable.clone()
+            };
+            assert!(executable.validate().is_err(), "{executable:?}");
+        }
+        // Containers aren't restricted
+        assert_eq!(reference_executable().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_resolve_templates() {
+        let vars = TemplateVars {
+            request_id: [3; 32].into(),
+            operator_id: ethers::types::Address::from_low_u64_be(4).into(),
+            block: 100,
+        };
+        let executable = Executable {
+            cmd: vec!["--block".to_string(), "${FERMAH_BLOCK}".to_string()],
+            env_vars: Some(HashMap::from([(
+                "REQUEST".to_string(),
+                "${FERMAH_REQUEST_ID}".to_string(),
+            )])),
+            ..reference_executable()
+        };
+        assert_eq!(executable.validate(), Ok(()));
+
+        let resolved = executable.resolve_templates(&vars).unwrap();
+        assert_eq!(resolved.cmd, vec!["--block", "100"]);
+        assert_eq!(
+            resolved.env_vars.unwrap()["REQUEST"],
+            vars.request_id.to_string()
+        );
+
+        let unknown = Executable {
+            cmd: vec!["${FERMAH_SECRET}".to_string()],
+            ..reference_executable()
+        };
+        assert!(matches!(
+            unknown.validate(),
+            Err(ExecutableError::Template(
+                TemplateError::UnknownPlaceholder(_)
+            ))
+        ));
+    }
 
     #[test]
     fn test_serialization() {
@@ -0,0 +1,265 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
+use ethers::types::Address;
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    fs::mountable::PathBufMirror,
+    hash::Hashable,
+    http::url_policy::UrlPolicy,
+    resources::{DownloadError, RemoteResource},
+};
+
+/// Domain tag of the manifest encoding, so a manifest signature is never valid for another kind of payload
+const HASH_DOMAIN: &[u8] = b"fermah/file-manifest/v1";
+
+/// Downloads running at once by default
+pub const DEFAULT_MANIFEST_CONCURRENCY: usize = 8;
+
+/// File listed in a [`FileManifest`]
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// Location of the file, relative to the mount target
+    pub path: PathBuf,
+    pub resource: RemoteResource,
+}
+
+/// Files of a [`Source::Manifest`](crate::executable::Source::Manifest), listed once instead of one by one in the
+/// proof request. The manifest is signed, and the signed manifest is the remote resource the request points to.
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileManifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+pub type SignedFileManifest = SignedData<FileManifest, EcdsaSigner>;
+
+impl Hashable for FileManifest {
+    fn collect(&self) -> Cow<[u8]> {
+        let mut buf = HASH_DOMAIN.to_vec();
+        buf.extend_from_slice(&(self.files.len() as u64).to_be_bytes());
+        for entry in &self.files {
+            for field in [
+                entry.path.to_string_lossy().as_bytes(),
+                entry.resource.url.as_str().as_bytes(),
+            ] {
+                buf.extend_from_slice(&(field.len() as u64).to_be_bytes());
+                buf.extend_from_slice(field);
+            }
+            buf.extend_from_slice(entry.resource.hash.as_32_bytes());
+        }
+        Cow::Owned(buf)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("failed to download the manifest: {0}")]
+    Download(#[from] DownloadError),
+
+    #[error("invalid manifest: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("manifest signature doesn't match its files")]
+    Signature,
+
+    #[error("manifest signed by {found:?} instead of {expected:?}")]
+    Publisher { expected: Address, found: Address },
+
+    #[error("manifest path {0:?} isn't relative to the mount target")]
+    InvalidPath(PathBuf),
+
+    #[error("manifest path {0:?} is listed more than once")]
+    DuplicatePath(PathBuf),
+
+    #[error("{} of {} manifest files failed to download", .0.failed.len(), .0.failed.len() + .0.downloaded.len())]
+    Incomplete(ManifestDownload),
+}
+
+/// Files of a manifest which were downloaded with a matching hash, and the ones which weren't
+#[derive(Debug, Default)]
+pub struct ManifestDownload {
+    /// Manifest path and local location of the file
+    pub downloaded: Vec<(PathBuf, PathBufMirror)>,
+    pub failed: Vec<(PathBuf, DownloadError)>,
+}
+
+impl SignedData<FileManifest, EcdsaSigner> {
+    /// Checks the manifest is signed by the `publisher`, the signature covers the listed files, and the paths stay
+    /// inside the target
+    pub fn check(&self, publisher: Address) -> Result<(), ManifestError> {
+        if self.verify_payload().is_err() {
+            return Err(ManifestError::Signature);
+        }
+        // Anyone can sign a manifest, only the publisher's lists the files to run with
+        if publisher != self.public_key {
+            return Err(ManifestError::Publisher {
+                expected: publisher,
+                found: self.public_key,
+            });
+        }
+
+        let mut paths = std::collections::HashSet::new();
+        for entry in &self.payload.files {
+            if !is_contained(&entry.path) {
+                return Err(ManifestError::InvalidPath(entry.path.clone()));
+            }
+            if !paths.insert(&entry.path) {
+                return Err(ManifestError::DuplicatePath(entry.path.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Relative path without `..`, so it can't point outside the directory it's joined to
+fn is_contained(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+impl RemoteResource {
+    /// Downloads the signed manifest the resource points to and checks it's signed by the `publisher`, then downloads up
+    /// to `concurrency` of its files at once, from where the `policy` allows only. The files are only returned if every
+    /// one of them matches its hash, otherwise the error reports which ones failed.
+    pub async fn download_manifest(
+        &self,
+        policy: &UrlPolicy,
+        publisher: Address,
+        concurrency: usize,
+    ) -> Result<(SignedFileManifest, ManifestDownload), ManifestError> {
+        let location = self.download(policy, None).await?;
+        let manifest: SignedFileManifest =
+            serde_json::from_slice(&std::fs::read(location.local()).map_err(DownloadError::from)?)?;
+        manifest.check(publisher)?;
+        debug!(
+            url = %self.url,
+            files = manifest.payload.files.len(),
+            "downloading manifest files"
+        );
+
+        let results: Vec<(PathBuf, Result<PathBufMirror, DownloadError>)> =
+            stream::iter(manifest.payload.files.iter())
+                .map(|entry| {
+                    async move {
+                        (
+                            entry.path.clone(),
+                            entry.resource.download(policy, None).await,
+                        )
+                    }
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+
+        let mut download = ManifestDownload::default();
+        for (path, result) in results {
+            match result {
+                Ok(location) => download.downloaded.push((path, location)),
+                Err(err) => {
+                    warn!(?path, %err, "failed to download manifest file");
+                    download.failed.push((path, err));
+                }
+            }
+        }
+
+        if !download.failed.is_empty() {
+            return Err(ManifestError::Incomplete(download));
+        }
+        Ok((manifest, download))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{crypto::signer::Signer, hash::blake3::Blake3Hasher, resources::ImageHash};
+
+    fn entry(path: &str) -> ManifestEntry {
+        ManifestEntry {
+            path: path.into(),
+            resource: RemoteResource {
+                url: format!("http://localhost:3000/inputs/{path}")
+                    .parse()
+                    .unwrap(),
+                hash: ImageHash::from([7u8; 32]),
+                delta: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_check_manifest() {
+        let (signer, _) = EcdsaSigner::from_random(&mut StdRng::seed_from_u64(7)).unwrap();
+        let publisher = signer.verifying_key();
+
+        let manifest = FileManifest {
+            files: vec![entry("a.bin"), entry("inputs/b.bin")],
+        };
+        let signed = SignedData::new(manifest.clone(), &signer).unwrap();
+        assert!(signed.check(publisher).is_ok());
+
+        // The signature covers the listed files
+        let mut tampered = signed.clone();
+        tampered.payload.files.push(entry("c.bin"));
+        assert!(matches!(
+            tampered.check(publisher),
+            Err(ManifestError::Signature)
+        ));
+
+        // Validly signed, by someone else
+        let (other, _) = EcdsaSigner::from_random(&mut StdRng::seed_from_u64(8)).unwrap();
+        let forged = SignedData::new(manifest.clone(), &other).unwrap();
+        assert!(matches!(
+            forged.check(publisher),
+            Err(ManifestError::Publisher { expected, found })
+                if expected == publisher && found == other.verifying_key()
+        ));
+
+        for path in ["../escape.bin", "/etc/passwd", ""] {
+            let manifest = FileManifest {
+                files: vec![entry(path)],
+            };
+            let signed = SignedData::new(manifest, &signer).unwrap();
+            assert!(matches!(
+                signed.check(publisher),
+                Err(ManifestError::InvalidPath(_))
+            ));
+        }
+
+        let manifest = FileManifest {
+            files: vec![entry("a.bin"), entry("a.bin")],
+        };
+        let signed = SignedData::new(manifest, &signer).unwrap();
+        assert!(matches!(
+            signed.check(publisher),
+            Err(ManifestError::DuplicatePath(_))
+        ));
+    }
+
+    #[test]
+    fn test_manifest_hash_covers_files() {
+        let a = FileManifest {
+            files: vec![entry("a.bin")],
+        };
+        let mut b = a.clone();
+        b.files[0].path = "b.bin".into();
+        let mut c = a.clone();
+        c.files[0].resource.hash = ImageHash::from([8u8; 32]);
+
+        assert_ne!(a.hash::<Blake3Hasher>(), b.hash::<Blake3Hasher>());
+        assert_ne!(a.hash::<Blake3Hasher>(), c.hash::<Blake3Hasher>());
+    }
+}
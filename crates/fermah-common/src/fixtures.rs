@@ -0,0 +1,143 @@
+//! Deterministic values for tests across the crates, enabled by the `test-utils` feature.
+//!
+//! Everything is built in code from fixed seeds, so the same call returns the same request, key and signature on
+//! every run, and a change of a payload type breaks the build instead of a JSON blob in some test.
+
+use std::collections::HashMap;
+
+use const_hex::traits::FromHex;
+use ethers::core::k256::ecdsa::SigningKey;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Serialize;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData, Signer},
+    executable::{Executable, Image, Injector, ResultExtractor},
+    hash::{blake3::Blake3Hash, Hashable},
+    proof::request::ProofRequest,
+    resource::requirement::ResourceRequirement,
+    resources::{ImageHash, RemoteResource},
+};
+
+/// Seed of the [`requester`] key
+pub const REQUESTER_SEED: u64 = 1;
+/// Seed of the [`matchmaker`] key
+pub const MATCHMAKER_SEED: u64 = 2;
+
+const GROTH16_IMAGE_URL: &str = "http://localhost:3000/images/groth16_latest.tar.gz";
+const GROTH16_IMAGE_HASH: &str =
+    "0x2a7504ffa9ca644ffbd70d76d3ad30795878a2d3efcc37416368e01da44baf39";
+const GROTH16_IMAGE_NAME: &str = "groth16:latest";
+const STATE_LOCATION: &str = "/output/state.bin";
+
+/// Signer with a key derived from `seed`, the same seed always gives the same address
+pub fn signer(seed: u64) -> EcdsaSigner {
+    EcdsaSigner::from_key(SigningKey::random(&mut StdRng::seed_from_u64(seed)))
+}
+
+/// Signer of the requests built by [`signed_proof_request`]
+pub fn requester() -> EcdsaSigner {
+    signer(REQUESTER_SEED)
+}
+
+/// Signer of the matchmaker issued payloads, e.g. receipts
+pub fn matchmaker() -> EcdsaSigner {
+    signer(MATCHMAKER_SEED)
+}
+
+/// Signs any payload, panicking on failure as tests have no use for the error
+pub fn signed<D: Serialize + Hashable + Clone>(
+    payload: D,
+    signer: &EcdsaSigner,
+) -> SignedData<D, EcdsaSigner> {
+    SignedData::new(payload, signer).expect("fixture payload should be signable")
+}
+
+/// Groth16 image served by the local image server of the devnet
+pub fn remote_resource() -> RemoteResource {
+    RemoteResource {
+        url: GROTH16_IMAGE_URL.parse().unwrap(),
+        hash: ImageHash::from(Blake3Hash::from_hex(GROTH16_IMAGE_HASH).unwrap()),
+        delta: None,
+    }
+}
+
+fn groth16_executable(entrypoint: &str) -> Executable {
+    Executable {
+        image: Image::RemoteDocker((remote_resource(), GROTH16_IMAGE_NAME.to_string())),
+        platform: None,
+        in_mounts: vec![],
+        result_extractor: None,
+        injector: None,
+        entrypoint: vec![entrypoint.to_string()],
+        cmd: vec![],
+        env_vars: Some(HashMap::from([(
+            "STATE_LOCATION".to_string(),
+            STATE_LOCATION.to_string(),
+        )])),
+        network_enabled: false,
+        privileged: false,
+        docker_access: false,
+    }
+}
+
+/// Groth16 prover writing the proof to the state file
+pub fn prover() -> Executable {
+    Executable {
+        result_extractor: Some(ResultExtractor::File(STATE_LOCATION.into())),
+        ..groth16_executable("/bin/prove")
+    }
+}
+
+/// Groth16 verifier reading the proof from the state file
+pub fn verifier() -> Executable {
+    Executable {
+        result_extractor: Some(ResultExtractor::NegativeExitCode(58)),
+        injector: Some(Injector::File(STATE_LOCATION.into())),
+        ..groth16_executable("/bin/verify")
+    }
+}
+
+/// Valid request of the [`requester`] for a groth16 proof on any operator with 2 cores, without a deadline
+pub fn proof_request() -> ProofRequest {
+    ProofRequest {
+        requester: Some(requester().verifying_key()),
+        prover: prover(),
+        verifier: verifier(),
+        resource_requirement: ResourceRequirement {
+            min_cpu_cores: Some(2),
+            ..Default::default()
+        },
+        callback_url: None,
+        deadline: None,
+        nonce: 217,
+        depends_on: vec![],
+        redundancy: None,
+        preferred_regions: None,
+        proof: None,
+        result_key: None,
+        retention: None,
+        result_validator: None,
+    }
+}
+
+/// [`proof_request`] signed by the [`requester`]
+pub fn signed_proof_request() -> SignedData<ProofRequest, EcdsaSigner> {
+    signed(proof_request(), &requester())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_deterministic() {
+        assert_eq!(requester(), signer(REQUESTER_SEED));
+        assert_ne!(requester(), matchmaker());
+
+        let signed = signed_proof_request();
+        assert!(signed.verify().is_ok());
+        assert_eq!(signed.payload.requester, Some(requester().verifying_key()));
+        assert_eq!(signed, signed_proof_request());
+    }
+}
@@ -28,15 +28,18 @@ impl From<[u8; 32]> for Blake3Hash {
     }
 }
 
-impl From<FixedBytes> for Blake3Hash {
-    fn from(value: FixedBytes) -> Self {
-        assert_eq!(value.len(), 32, "Length of the array is not equal to 32");
-        let mut buf = [0_u8; 32];
-
-        for (i, b) in value.into_iter().enumerate() {
-            buf[i] = b;
-        }
-        buf.into()
+/// Bytes which aren't a 32 bytes hash, e.g. a corrupted key column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("hash of {0} bytes instead of 32")]
+pub struct InvalidHashLength(pub usize);
+
+impl TryFrom<FixedBytes> for Blake3Hash {
+    type Error = InvalidHashLength;
+
+    fn try_from(value: FixedBytes) -> Result<Self, Self::Error> {
+        <[u8; 32]>::try_from(value)
+            .map(Self::from)
+            .map_err(|value| InvalidHashLength(value.len()))
     }
 }
 
@@ -0,0 +1,170 @@
+//! Typed identifiers backed by a [`Blake3Hash`](crate::hash::blake3::Blake3Hash), so proof request ids, proof hashes
+//! and image hashes can't be mixed up.
+//!
+//! They encode exactly like the hash in bincode. Human readable formats use `0x` prefixed hex, and also accept the
+//! byte array the bare hash was encoded as.
+
+use serde::Deserialize;
+
+/// Human readable encodings an identifier is decoded from
+#[doc(hidden)]
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum HumanReadableId {
+    Hex(String),
+    Bytes([u8; 32]),
+}
+
+/// Declares a newtype over [`Blake3Hash`](crate::hash::blake3::Blake3Hash) with hex (de)serialization and
+/// conversions from and to the bare hash
+macro_rules! blake3_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Hash, Eq, PartialEq)]
+        pub struct $name(pub $crate::hash::blake3::Blake3Hash);
+
+        impl $name {
+            pub fn as_32_bytes(&self) -> &[u8; 32] {
+                self.0.as_32_bytes()
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_ref()
+            }
+        }
+
+        impl From<[u8; 32]> for $name {
+            fn from(value: [u8; 32]) -> Self {
+                Self(value.into())
+            }
+        }
+
+        impl From<$crate::hash::blake3::Blake3Hash> for $name {
+            fn from(value: $crate::hash::blake3::Blake3Hash) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $crate::hash::blake3::Blake3Hash {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<$crate::serialization::hash::SerializableHash<$crate::hash::blake3::Blake3Hasher>> for $name {
+            fn from(
+                value: $crate::serialization::hash::SerializableHash<$crate::hash::blake3::Blake3Hasher>,
+            ) -> Self {
+                Self(value.0)
+            }
+        }
+
+        impl TryFrom<ethers::abi::FixedBytes> for $name {
+            type Error = $crate::hash::blake3::InvalidHashLength;
+
+            fn try_from(value: ethers::abi::FixedBytes) -> Result<Self, Self::Error> {
+                $crate::hash::blake3::Blake3Hash::try_from(value).map(Self)
+            }
+        }
+
+        impl TryFrom<&[u8]> for $name {
+            type Error = std::array::TryFromSliceError;
+
+            fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+                <[u8; 32]>::try_from(value).map(Self::from)
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl const_hex::traits::FromHex for $name {
+            type Error = const_hex::FromHexError;
+
+            fn from_hex<T: AsRef<[u8]>>(hex: T) -> Result<Self, Self::Error> {
+                <$crate::hash::blake3::Blake3Hash as const_hex::traits::FromHex>::from_hex(hex).map(Self)
+            }
+        }
+
+        impl $crate::hash::Hashable for $name {
+            fn collect(&self) -> std::borrow::Cow<[u8]> {
+                self.as_ref().into()
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                if s.is_human_readable() {
+                    $crate::serialization::encoding::hex_encoded::serialize(self, s)
+                } else {
+                    serde::Serialize::serialize(&self.0, s)
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                if !d.is_human_readable() {
+                    return <$crate::hash::blake3::Blake3Hash as serde::Deserialize>::deserialize(d).map(Self);
+                }
+
+                match <$crate::hash::id::HumanReadableId as serde::Deserialize>::deserialize(d)? {
+                    $crate::hash::id::HumanReadableId::Hex(hex) => {
+                        <Self as const_hex::traits::FromHex>::from_hex(hex)
+                            .map_err(serde::de::Error::custom)
+                    }
+                    $crate::hash::id::HumanReadableId::Bytes(bytes) => Ok(Self::from(bytes)),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use blake3_id;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hash::blake3::{Blake3Hash, InvalidHashLength},
+        proof::{request::ProofRequestId, ProofId},
+    };
+
+    #[test]
+    fn test_id_encoding() {
+        let id = ProofRequestId::from([7; 32]);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+        assert_eq!(serde_json::from_str::<ProofRequestId>(&json).unwrap(), id);
+
+        // Ids used to be encoded as the bare hash
+        let legacy = serde_json::to_string(&[7_u8; 32]).unwrap();
+        assert_eq!(serde_json::from_str::<ProofRequestId>(&legacy).unwrap(), id);
+        assert_eq!(
+            bincode::serialize(&id).unwrap(),
+            bincode::serialize(&Blake3Hash::from([7; 32])).unwrap()
+        );
+
+        assert_eq!(
+            Blake3Hash::from(ProofId::from(Blake3Hash::from([7; 32]))),
+            Blake3Hash::from(id)
+        );
+        assert!(ProofRequestId::try_from([7_u8; 31].as_slice()).is_err());
+        assert_eq!(ProofRequestId::try_from(vec![7_u8; 32]), Ok(id));
+        assert_eq!(
+            ProofRequestId::try_from(vec![7_u8; 33]),
+            Err(InvalidHashLength(33))
+        );
+    }
+}
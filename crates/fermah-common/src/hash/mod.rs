@@ -4,6 +4,7 @@ use const_hex::{traits::FromHex, FromHexError};
 use ethers::types::Address;
 
 pub mod blake3;
+pub mod id;
 pub mod keccak256;
 
 /// Hasher trait that defines the common interface for hashing algorithms.
@@ -1,29 +1,330 @@
-use std::{net::SocketAddr, path::PathBuf};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::SeekFrom,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
 
-use tracing::info;
-use warp::Filter;
+use async_compression::tokio::bufread::ZstdEncoder;
+use futures_util::StreamExt;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt, BufReader},
+    sync::Semaphore,
+};
+use tokio_util::io::ReaderStream;
+use tracing::{debug, info, warn};
+use warp::{
+    http::{
+        header::{
+            ACCEPT_ENCODING,
+            ACCEPT_RANGES,
+            CONTENT_ENCODING,
+            CONTENT_LENGTH,
+            CONTENT_RANGE,
+            CONTENT_TYPE,
+            ETAG,
+            IF_NONE_MATCH,
+            IF_RANGE,
+            RANGE,
+            RETRY_AFTER,
+            VARY,
+        },
+        HeaderMap,
+        HeaderValue,
+        Response,
+        StatusCode,
+    },
+    hyper::Body,
+    path::Tail,
+    Filter,
+};
 
-/// A simple file server that serves a single file over HTTP.
+use crate::{fs::hash::hash_path, hash::blake3::Blake3Hasher};
+
+/// Seconds the clients turned away by the download limit are asked to wait
+const RETRY_AFTER_SECS: u64 = 5;
+
+/// A simple file server that serves the files of a directory over HTTP, e.g. the images the operators download.
+///
+/// The files are tagged with their blake3 hash, so clients holding a copy revalidate it with `If-None-Match`
+/// instead of downloading it again, and interrupted downloads resume with `Range`.
 pub struct FileServer {
     pub addr: SocketAddr,
+    /// Compresses the files with zstd for the clients accepting it
+    pub compression: bool,
+    /// Downloads served at once, the other clients are asked to retry later. Unlimited if `None`
+    pub max_concurrent_downloads: Option<usize>,
 }
 
 impl FileServer {
     pub fn new(port: u16) -> Self {
         let addr = SocketAddr::new([0, 0, 0, 0].into(), port);
-        Self { addr }
+        Self {
+            addr,
+            compression: false,
+            max_concurrent_downloads: None,
+        }
+    }
+
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn with_max_concurrent_downloads(mut self, max_concurrent_downloads: usize) -> Self {
+        self.max_concurrent_downloads = Some(max_concurrent_downloads);
+        self
     }
 
     pub async fn serve_dir(&self, path: String, dir: PathBuf) {
-        let route = warp::get().and(warp::path(path)).and(warp::fs::dir(dir));
+        let files = Arc::new(ServedDir {
+            dir,
+            compression: self.compression,
+            downloads: self
+                .max_concurrent_downloads
+                .map(|max| Arc::new(Semaphore::new(max))),
+            etags: Mutex::default(),
+        });
+
+        let route = warp::get()
+            .and(warp::path(path))
+            .and(warp::path::tail())
+            .and(warp::header::headers_cloned())
+            .and_then(move |tail: Tail, headers: HeaderMap| {
+                let files = files.clone();
+                async move { Ok::<_, Infallible>(files.respond(tail.as_str(), &headers).await) }
+            });
 
         info!("starting file server on {}", self.addr);
         warp::serve(route).run(self.addr).await;
     }
 }
 
+/// ETag of a file, recomputed once it's modified
+struct CachedEtag {
+    len: u64,
+    modified: Option<SystemTime>,
+    etag: HeaderValue,
+}
+
+struct ServedDir {
+    dir: PathBuf,
+    compression: bool,
+    downloads: Option<Arc<Semaphore>>,
+    etags: Mutex<HashMap<PathBuf, CachedEtag>>,
+}
+
+/// Bytes asked for by the `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteRange {
+    /// Inclusive bounds
+    Satisfiable(u64, u64),
+    Unsatisfiable,
+}
+
+impl ServedDir {
+    async fn respond(&self, tail: &str, headers: &HeaderMap) -> Response<Body> {
+        let Some(path) = self.resolve(tail) else {
+            return status(StatusCode::NOT_FOUND);
+        };
+        let metadata = match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return status(StatusCode::NOT_FOUND),
+        };
+        let etag = match self.etag(&path, &metadata).await {
+            Ok(etag) => etag,
+            Err(err) => {
+                warn!(?path, %err, "failed to hash served file");
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+
+        if headers
+            .get(IF_NONE_MATCH)
+            .is_some_and(|value| etag_matches(value, &etag))
+        {
+            return Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header(ETAG, etag)
+                .body(Body::empty())
+                .unwrap();
+        }
+
+        // Held until the body is sent
+        let permit = match &self.downloads {
+            Some(downloads) => {
+                match downloads.clone().try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => {
+                        debug!(?path, "download limit reached");
+                        return Response::builder()
+                            .status(StatusCode::SERVICE_UNAVAILABLE)
+                            .header(RETRY_AFTER, RETRY_AFTER_SECS)
+                            .body(Body::empty())
+                            .unwrap();
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let len = metadata.len();
+        // A range of another version of the file can't be resumed, the whole file is sent instead
+        let range = headers
+            .get(RANGE)
+            .filter(|_| headers.get(IF_RANGE).map_or(true, |value| *value == etag))
+            .and_then(|value| parse_range(value.to_str().ok()?, len));
+
+        let mut file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(err) => {
+                warn!(?path, %err, "failed to open served file");
+                return status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
+        let response = Response::builder()
+            .header(ETAG, &etag)
+            .header(ACCEPT_RANGES, "bytes")
+            .header(CONTENT_TYPE, "application/octet-stream");
+
+        let (response, reader): (_, Pin<Box<dyn AsyncRead + Send>>) = match range {
+            Some(ByteRange::Unsatisfiable) => {
+                return response
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(CONTENT_RANGE, format!("bytes */{len}"))
+                    .body(Body::empty())
+                    .unwrap();
+            }
+            Some(ByteRange::Satisfiable(start, end)) => {
+                if let Err(err) = file.seek(SeekFrom::Start(start)).await {
+                    warn!(?path, %err, "failed to seek served file");
+                    return status(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+                let response = response
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+                    .header(CONTENT_LENGTH, end - start + 1);
+                (response, Box::pin(file.take(end - start + 1)))
+            }
+            None if self.compression && accepts_zstd(headers) => {
+                let response = response
+                    .header(CONTENT_ENCODING, "zstd")
+                    .header(VARY, ACCEPT_ENCODING.as_str());
+                (response, Box::pin(ZstdEncoder::new(BufReader::new(file))))
+            }
+            None => (response.header(CONTENT_LENGTH, len), Box::pin(file)),
+        };
+
+        let body = ReaderStream::new(reader).map(move |chunk| {
+            let _permit = &permit;
+            chunk
+        });
+        response.body(Body::wrap_stream(body)).unwrap()
+    }
+
+    /// Path of the file in the served directory, `None` if the request tries to get out of it
+    fn resolve(&self, tail: &str) -> Option<PathBuf> {
+        let mut path = self.dir.clone();
+        for segment in tail.split('/') {
+            if segment.is_empty() || segment == "." || segment == ".." || segment.contains('\\') {
+                return None;
+            }
+            path.push(segment);
+        }
+        Some(path)
+    }
+
+    /// Quoted blake3 hash of the file, hashed again only if its size or modification time changed
+    async fn etag(
+        &self,
+        path: &Path,
+        metadata: &std::fs::Metadata,
+    ) -> Result<HeaderValue, crate::fs::error::Error> {
+        let (len, modified) = (metadata.len(), metadata.modified().ok());
+        if let Some(cached) = self.etags.lock().unwrap().get(path) {
+            if cached.len == len && cached.modified.is_some() && cached.modified == modified {
+                return Ok(cached.etag.clone());
+            }
+        }
+
+        let hash = hash_path::<Blake3Hasher>(path).await?;
+        let etag = HeaderValue::from_str(&format!("\"{hash}\"")).unwrap();
+        self.etags.lock().unwrap().insert(
+            path.to_path_buf(),
+            CachedEtag {
+                len,
+                modified,
+                etag: etag.clone(),
+            },
+        );
+        Ok(etag)
+    }
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Whether the `If-None-Match` list names the ETag, weak comparison as RFC 9110 requires for it
+fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default();
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+fn accepts_zstd(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|coding| {
+            let mut params = coding.trim().split(';');
+            params.next() == Some("zstd") && !params.any(|param| param.trim() == "q=0")
+        })
+}
+
+/// Single byte range of a `Range` header, `None` if there isn't one, e.g. for multiple ranges which are answered
+/// with the whole file
+fn parse_range(range: &str, len: u64) -> Option<ByteRange> {
+    let range = range.trim().strip_prefix("bytes=")?;
+    if range.contains(',') {
+        return None;
+    }
+    let (start, end) = range.trim().split_once('-')?;
+
+    let (start, end) = match (start.parse::<u64>().ok(), end.parse::<u64>().ok()) {
+        (Some(start), Some(end)) if start <= end => (start, end.min(len.saturating_sub(1))),
+        (Some(start), None) if end.is_empty() => (start, len.saturating_sub(1)),
+        // Last `suffix` bytes
+        (None, Some(suffix)) if start.is_empty() && suffix > 0 => {
+            (len.saturating_sub(suffix), len.saturating_sub(1))
+        }
+        _ => return None,
+    };
+    Some(if start >= len {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Satisfiable(start, end)
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use reqwest::header;
+
     use super::*;
 
     #[tokio::test]
@@ -39,4 +340,105 @@ mod tests {
             .unwrap();
         assert_eq!(res.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_etag_and_range() {
+        tokio::spawn(async {
+            FileServer::new(3001)
+                .with_compression(true)
+                .serve_dir("files".to_string(), "./".into())
+                .await;
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let url = "http://localhost:3001/files/Cargo.toml";
+        let client = reqwest::Client::new();
+        let full = client.get(url).send().await.unwrap();
+        let etag = full.headers()[header::ETAG].clone();
+        let content = full.bytes().await.unwrap();
+        assert_eq!(content, tokio::fs::read("Cargo.toml").await.unwrap());
+
+        let cached = client
+            .get(url)
+            .header(header::IF_NONE_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(cached.status(), 304);
+
+        let partial = client
+            .get(url)
+            .header(header::RANGE, "bytes=2-5")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(partial.status(), 206);
+        assert_eq!(
+            partial.headers()[header::CONTENT_RANGE],
+            format!("bytes 2-5/{}", content.len())
+        );
+        assert_eq!(partial.bytes().await.unwrap(), content[2..=5]);
+
+        let unsatisfiable = client
+            .get(url)
+            .header(header::RANGE, format!("bytes={}-", content.len()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsatisfiable.status(), 416);
+
+        let compressed = client
+            .get(url)
+            .header(header::ACCEPT_ENCODING, "zstd")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(compressed.headers()[header::CONTENT_ENCODING], "zstd");
+
+        let escaping = client
+            .get("http://localhost:3001/files/../Cargo.toml")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(escaping.status(), 404);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("bytes=0-9", 100),
+            Some(ByteRange::Satisfiable(0, 9))
+        );
+        assert_eq!(
+            parse_range("bytes=90-", 100),
+            Some(ByteRange::Satisfiable(90, 99))
+        );
+        assert_eq!(
+            parse_range("bytes=-10", 100),
+            Some(ByteRange::Satisfiable(90, 99))
+        );
+        assert_eq!(
+            parse_range("bytes=50-500", 100),
+            Some(ByteRange::Satisfiable(50, 99))
+        );
+        assert_eq!(
+            parse_range("bytes=100-", 100),
+            Some(ByteRange::Unsatisfiable)
+        );
+        assert_eq!(parse_range("bytes=0-1,5-6", 100), None);
+        assert_eq!(parse_range("bytes=9-0", 100), None);
+        assert_eq!(parse_range("items=0-9", 100), None);
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let etag = HeaderValue::from_static("\"0xab\"");
+        assert!(etag_matches(&HeaderValue::from_static("\"0xab\""), &etag));
+        assert!(etag_matches(
+            &HeaderValue::from_static("\"0xcd\", W/\"0xab\""),
+            &etag
+        ));
+        assert!(etag_matches(&HeaderValue::from_static("*"), &etag));
+        assert!(!etag_matches(&HeaderValue::from_static("\"0xcd\""), &etag));
+    }
 }
@@ -1,2 +1,3 @@
 pub mod file_download;
 pub mod file_server;
+pub mod url_policy;
@@ -0,0 +1,317 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use clap::Args;
+use reqwest::{
+    header::{self, HeaderMap},
+    redirect,
+    Response,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug;
+use url::{Host, Url};
+
+/// Redirects followed before the fetch is given up
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UrlPolicyError {
+    #[error("URL scheme {0:?} is not allowed")]
+    Scheme(String),
+
+    #[error("URL {0} has no host")]
+    NoHost(Url),
+
+    #[error("domain {0} is not allowed")]
+    Domain(String),
+
+    #[error("{host} is a non-public address {addr}")]
+    NonPublicAddress { host: String, addr: IpAddr },
+
+    #[error("failed to resolve {0}")]
+    Resolve(String),
+
+    #[error("more than {MAX_REDIRECTS} redirects from {0}")]
+    TooManyRedirects(Url),
+}
+
+/// URLs the matchmaker and the operators fetch resources from. Requests name arbitrary URLs, so without a policy they
+/// could make the fetching side call the services of its internal network. Each fetcher is given the policy it
+/// fetches with, e.g. a devnet matchmaker and its local operators allow the private addresses.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UrlPolicy {
+    /// Schemes resources can be fetched with
+    #[arg(long = "allowed-url-scheme", default_values_t = default_schemes())]
+    pub allowed_schemes: Vec<String>,
+    /// Domains resources can be fetched from, with their subdomains. Any domain is allowed if empty
+    #[arg(long = "allowed-url-domain")]
+    pub allowed_domains: Vec<String>,
+    /// Lets resources be fetched from loopback, private and link-local addresses, for local devnets only
+    #[arg(long)]
+    pub allow_private_addresses: bool,
+}
+
+fn default_schemes() -> Vec<String> {
+    vec!["https".to_string(), "http".to_string()]
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_schemes: default_schemes(),
+            allowed_domains: vec![],
+            allow_private_addresses: false,
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// Checks what's known without resolving the host: the scheme, the domain and a literal address
+    pub fn check(&self, url: &Url) -> Result<(), UrlPolicyError> {
+        if !self.allowed_schemes.iter().any(|s| s == url.scheme()) {
+            return Err(UrlPolicyError::Scheme(url.scheme().to_string()));
+        }
+
+        match url.host() {
+            None => Err(UrlPolicyError::NoHost(url.clone())),
+            Some(Host::Domain(domain)) => {
+                let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                let allowed = self.allowed_domains.is_empty()
+                    || self.allowed_domains.iter().any(|allowed| {
+                        let allowed = allowed.trim_end_matches('.').to_ascii_lowercase();
+                        domain == allowed || domain.ends_with(&format!(".{allowed}"))
+                    });
+                if !allowed {
+                    return Err(UrlPolicyError::Domain(domain));
+                }
+                Ok(())
+            }
+            Some(Host::Ipv4(ip)) => self.check_addr(url, ip.into()),
+            Some(Host::Ipv6(ip)) => self.check_addr(url, ip.into()),
+        }
+    }
+
+    fn check_addr(&self, url: &Url, addr: IpAddr) -> Result<(), UrlPolicyError> {
+        // A literal address can't match a domain of the allowlist
+        if !self.allowed_domains.is_empty() {
+            return Err(UrlPolicyError::Domain(addr.to_string()));
+        }
+        if !self.allow_private_addresses && !is_public(addr) {
+            return Err(UrlPolicyError::NonPublicAddress {
+                host: url.host_str().unwrap_or_default().to_string(),
+                addr,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the URL and every address its host resolves to
+    pub async fn resolve(&self, url: &Url) -> Result<Vec<SocketAddr>, UrlPolicyError> {
+        self.check(url)?;
+
+        let host = url
+            .host_str()
+            .ok_or_else(|| UrlPolicyError::NoHost(url.clone()))?;
+        let port = url.port_or_known_default().unwrap_or(80);
+        // IPv6 literals are bracketed in URLs, not when resolved
+        let name = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name, port))
+            .await
+            .map_err(|_| UrlPolicyError::Resolve(host.to_string()))?
+            .collect();
+        if addrs.is_empty() {
+            return Err(UrlPolicyError::Resolve(host.to_string()));
+        }
+
+        if !self.allow_private_addresses {
+            if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+                return Err(UrlPolicyError::NonPublicAddress {
+                    host: host.to_string(),
+                    addr: addr.ip(),
+                });
+            }
+        }
+        Ok(addrs)
+    }
+
+    /// GETs the URL, following the redirects. Every hop is resolved and checked, and connected to the checked
+    /// addresses only, so a host can't resolve to a public address for the check and an internal one for the fetch.
+    pub async fn get(&self, url: &Url) -> Result<Response, GetError> {
+        self.get_with(url, HeaderMap::new()).await
+    }
+
+    /// [`Self::get`] with the headers sent along, to every hop
+    pub async fn get_with(&self, url: &Url, headers: HeaderMap) -> Result<Response, GetError> {
+        let mut url = url.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            let addrs = self.resolve(&url).await?;
+            let host = url.host_str().unwrap_or_default().to_string();
+            let client = reqwest::Client::builder()
+                .redirect(redirect::Policy::none())
+                .resolve_to_addrs(&host, &addrs)
+                .build()?;
+            let response = client
+                .get(url.clone())
+                .headers(headers.clone())
+                .send()
+                .await?;
+
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .map(str::to_string);
+            match location {
+                Some(location) if response.status().is_redirection() => {
+                    let next = url
+                        .join(&location)
+                        .map_err(|_| UrlPolicyError::Resolve(location))?;
+                    debug!(from=%url, to=%next, "following redirect");
+                    url = next;
+                }
+                _ => return Ok(response),
+            }
+        }
+        Err(UrlPolicyError::TooManyRedirects(url).into())
+    }
+}
+
+/// Failure of [`UrlPolicy::get`]
+#[derive(Error, Debug)]
+pub enum GetError {
+    #[error(transparent)]
+    Refused(#[from] UrlPolicyError),
+
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+}
+
+/// Address reachable over the internet, not one of the host or of a private network
+pub fn is_public(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => {
+            match ip.to_ipv4_mapped() {
+                Some(ip) => is_public_v4(ip),
+                None => is_public_v6(ip),
+            }
+        }
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+        // Reserved, 240.0.0.0/4
+        || a >= 240
+        || a == 0)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let policy = UrlPolicy::default();
+        assert_eq!(policy.check(&url("https://example.com/image.tar")), Ok(()));
+        assert_eq!(policy.check(&url("http://8.8.8.8/image.tar")), Ok(()));
+        assert_eq!(
+            policy.check(&url("file:///etc/passwd")),
+            Err(UrlPolicyError::Scheme("file".to_string()))
+        );
+        assert!(matches!(
+            policy.check(&url("gopher://example.com")),
+            Err(UrlPolicyError::Scheme(_))
+        ));
+        for internal in [
+            "http://127.0.0.1:8080/",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:192.168.0.1]/",
+        ] {
+            assert!(
+                matches!(
+                    policy.check(&url(internal)),
+                    Err(UrlPolicyError::NonPublicAddress { .. })
+                ),
+                "{internal} should be refused"
+            );
+        }
+
+        let devnet = UrlPolicy {
+            allow_private_addresses: true,
+            ..Default::default()
+        };
+        assert_eq!(devnet.check(&url("http://127.0.0.1:3000/image")), Ok(()));
+    }
+
+    #[test]
+    fn test_domain_allowlist() {
+        let policy = UrlPolicy {
+            allowed_domains: vec!["images.fermah.xyz".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(policy.check(&url("https://images.fermah.xyz/a")), Ok(()));
+        assert_eq!(policy.check(&url("https://eu.images.fermah.xyz/a")), Ok(()));
+        assert_eq!(
+            policy.check(&url("https://evilimages.fermah.xyz/a")),
+            Err(UrlPolicyError::Domain("evilimages.fermah.xyz".to_string()))
+        );
+        assert!(matches!(
+            policy.check(&url("https://1.1.1.1/a")),
+            Err(UrlPolicyError::Domain(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let policy = UrlPolicy::default();
+        // Names resolving to the host are refused even though the name itself looks harmless
+        assert!(matches!(
+            policy.resolve(&url("http://localhost:3000/image")).await,
+            Err(UrlPolicyError::NonPublicAddress { .. })
+        ));
+
+        let devnet = UrlPolicy {
+            allow_private_addresses: true,
+            ..Default::default()
+        };
+        let addrs = devnet
+            .resolve(&url("http://localhost:3000/image"))
+            .await
+            .unwrap();
+        assert!(addrs.iter().all(|addr| addr.port() == 3000));
+    }
+}
@@ -0,0 +1,216 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::Hashable,
+    resources::RemoteResource,
+};
+
+/// Domain tag of the record encoding, so a record signature is never valid for another kind of payload
+const HASH_DOMAIN: &[u8] = b"fermah/image-record/v1";
+
+const MAX_NAME_LEN: usize = 128;
+const MAX_VERSION_LEN: usize = 64;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ImageRecordError {
+    #[error("invalid image name {0:?}, expected lowercase letters, digits and `-_./` separators")]
+    InvalidName(String),
+
+    #[error("invalid image version {0:?}, expected letters, digits and `-_.` separators")]
+    InvalidVersion(String),
+
+    #[error("image record signature doesn't match the record")]
+    Signature,
+
+    #[error("image record published by {found:?} instead of {expected:?}")]
+    Publisher { expected: Address, found: Address },
+}
+
+/// Image a publisher vouches for under a name and version, e.g. `groth16:1.2.0`. Versions are immutable once
+/// published, and a name belongs to the first publisher of one of its versions.
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageRecord {
+    pub name: String,
+    pub version: String,
+    /// Where the image and its delta are downloaded from
+    pub image: RemoteResource,
+}
+
+pub type SignedImageRecord = SignedData<ImageRecord, EcdsaSigner>;
+
+impl Hashable for ImageRecord {
+    fn collect(&self) -> Cow<[u8]> {
+        let mut buf = HASH_DOMAIN.to_vec();
+        for field in [
+            self.name.as_bytes(),
+            self.version.as_bytes(),
+            self.image.url.as_str().as_bytes(),
+        ] {
+            buf.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            buf.extend_from_slice(field);
+        }
+        buf.extend_from_slice(self.image.hash.as_32_bytes());
+        // Appended last, so the hashes of records without a delta don't change
+        if let Some(delta) = &self.image.delta {
+            buf.extend_from_slice(delta.base.as_32_bytes());
+            let url = delta.url.as_str().as_bytes();
+            buf.extend_from_slice(&(url.len() as u64).to_be_bytes());
+            buf.extend_from_slice(url);
+        }
+        Cow::Owned(buf)
+    }
+}
+
+impl ImageRecord {
+    /// `name:version`, as the docker image is tagged
+    pub fn reference(&self) -> String {
+        format!("{}:{}", self.name, self.version)
+    }
+
+    /// Checks the name and version are usable as a docker image reference
+    pub fn validate(&self) -> Result<(), ImageRecordError> {
+        let is_separator = |c: char| matches!(c, '-' | '_' | '.' | '/');
+        let valid_name = !self.name.is_empty()
+            && self.name.len() <= MAX_NAME_LEN
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || is_separator(c))
+            && !self.name.starts_with(is_separator)
+            && !self.name.ends_with(is_separator);
+        if !valid_name {
+            return Err(ImageRecordError::InvalidName(self.name.clone()));
+        }
+
+        let valid_version = !self.version.is_empty()
+            && self.version.len() <= MAX_VERSION_LEN
+            && self
+                .version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !self.version.starts_with(['-', '.']);
+        if !valid_version {
+            return Err(ImageRecordError::InvalidVersion(self.version.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Splits `name:version`, the version is `None` for the latest one of the name
+pub fn parse_reference(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once(':') {
+        // A colon before the last slash is the port of a registry host, not a version
+        Some((name, version)) if !version.contains('/') => (name, Some(version)),
+        _ => (reference, None),
+    }
+}
+
+/// Record stored by the matchmaker's registry
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedImage {
+    pub record: SignedImageRecord,
+    pub published_at: DateTime<Utc>,
+}
+
+impl PublishedImage {
+    pub fn publisher(&self) -> Address {
+        self.record.public_key
+    }
+
+    /// Checks the record is signed by the trusted publisher, since the registry serving it isn't. Returns the verified
+    /// record.
+    pub fn verify(&self, publisher: Address) -> Result<&ImageRecord, ImageRecordError> {
+        let record = &self.record;
+        if record.verify_payload().is_err() {
+            return Err(ImageRecordError::Signature);
+        }
+        if publisher != record.public_key {
+            return Err(ImageRecordError::Publisher {
+                expected: publisher,
+                found: record.public_key,
+            });
+        }
+        Ok(&record.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::signer::Signer, fixtures, resources::ImageHash};
+
+    fn record() -> ImageRecord {
+        ImageRecord {
+            name: "fermah/groth16".to_string(),
+            version: "1.2.0".to_string(),
+            image: fixtures::remote_resource(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(record().validate().is_ok());
+        for name in ["", "Groth16", "/groth16", "groth16 ", "groth16:latest"] {
+            let record = ImageRecord {
+                name: name.to_string(),
+                ..record()
+            };
+            assert_eq!(
+                record.validate(),
+                Err(ImageRecordError::InvalidName(name.to_string()))
+            );
+        }
+        for version in ["", ".1", "1/2", "1 2"] {
+            let record = ImageRecord {
+                version: version.to_string(),
+                ..record()
+            };
+            assert_eq!(
+                record.validate(),
+                Err(ImageRecordError::InvalidVersion(version.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_reference() {
+        assert_eq!(parse_reference("groth16:1.2.0"), ("groth16", Some("1.2.0")));
+        assert_eq!(parse_reference("groth16"), ("groth16", None));
+        assert_eq!(
+            parse_reference("localhost:5000/groth16"),
+            ("localhost:5000/groth16", None)
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let publisher = fixtures::requester();
+        let published = PublishedImage {
+            record: fixtures::signed(record(), &publisher),
+            published_at: Utc::now(),
+        };
+        assert_eq!(published.verify(published.publisher()), Ok(&record()));
+
+        let other = fixtures::matchmaker();
+        assert!(matches!(
+            published.verify(other.verifying_key()),
+            Err(ImageRecordError::Publisher { .. })
+        ));
+
+        // The registry can't swap the image of a signed record
+        let mut tampered = published.clone();
+        tampered.record.payload.image.hash = ImageHash::from([1; 32]);
+        assert_eq!(
+            tampered.verify(published.publisher()),
+            Err(ImageRecordError::Signature)
+        );
+    }
+}
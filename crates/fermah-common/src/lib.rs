@@ -1,11 +1,17 @@
 extern crate core;
 
 pub mod cli;
+#[cfg(all(test, feature = "compat"))]
+mod compat;
 pub mod crypto;
 pub mod executable;
+pub mod file_manifest;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod fixtures;
 pub mod fs;
 pub mod hash;
 pub mod http;
+pub mod image_registry;
 pub mod manifest;
 pub mod operator;
 pub mod proof;
@@ -13,5 +19,6 @@ pub mod releaser;
 pub mod resource;
 pub mod resources;
 pub mod serialization;
+pub mod supervisor;
 pub mod types;
 pub mod vec;
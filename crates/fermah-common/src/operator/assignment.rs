@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::OperatorId;
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::Hashable,
+    proof::request::{ProofRequest, ProofRequestId},
+    serialization::encoding::base64_encoded,
+};
+
+/// Order the available operators are offered to the matching in
+#[derive(
+    Serialize,
+    Deserialize,
+    strum::Display,
+    ValueEnum,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum OperatorOrdering {
+    /// As they are loaded, the matching shuffles them
+    #[default]
+    Unordered,
+    /// The operators assigned least recently first, the ones never assigned before all the others. The work spreads
+    /// across the operators instead of going to the ones winning every shuffle
+    LeastRecentlyAssigned,
+}
+
+/// Work an operator was given since it registered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorAssignments {
+    pub operator: OperatorId,
+    /// Requests assigned to the operator, redundant copies included
+    pub assignments: u64,
+    /// `None` if the operator was never assigned a request
+    pub last_assignment: Option<DateTime<Utc>>,
+}
+
+/// Request assigned to the operator polling for its work, handed out until its proof or verdict is submitted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assignment {
+    /// Request as the requester signed it, the operator can check the signature itself
+    pub request: SignedData<ProofRequest, EcdsaSigner>,
+    /// The operator acknowledged it already
+    pub acknowledged: bool,
+    /// A redundant copy, proven by other operators too
+    pub replica: bool,
+}
+
+impl Assignment {
+    pub fn proof_request_id(&self) -> ProofRequestId {
+        self.request.id()
+    }
+}
+
+/// Proof of an assigned request, signed by the operator who proved it
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofDelivery {
+    pub proof_request_id: ProofRequestId,
+    #[serde(with = "base64_encoded")]
+    pub proof: Vec<u8>,
+}
+
+impl std::fmt::Debug for ProofDelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProofDelivery")
+            .field("proof_request_id", &self.proof_request_id)
+            .field("proof_len", &self.proof.len())
+            .finish()
+    }
+}
+
+impl Hashable for ProofDelivery {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{hash::Hashable, proof::request::ProofRequestId};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidDeregistration {
+    #[error("deregistration deadline passed at {0}")]
+    Expired(DateTime<Utc>),
+    #[error(
+        "deregistration deadline {0} is more than {} seconds ahead",
+        Deregistration::MAX_VALIDITY_SECS
+    )]
+    TooFarAhead(DateTime<Utc>),
+}
+
+/// Operator stopping for good, signed by the operator. It's only accepted before the deadline, at most
+/// [`Deregistration::MAX_VALIDITY_SECS`] ahead, so a deregistration intercepted on the way can't take the operator
+/// offline again once it came back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deregistration {
+    /// Also deregister the operator from the AVS, it has to register on chain again to come back
+    #[serde(default)]
+    pub leave_avs: bool,
+    pub deadline: DateTime<Utc>,
+}
+
+impl Deregistration {
+    pub const MAX_VALIDITY_SECS: i64 = 600;
+
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), InvalidDeregistration> {
+        if self.deadline <= now {
+            return Err(InvalidDeregistration::Expired(self.deadline));
+        }
+        if self.deadline > now + Duration::seconds(Self::MAX_VALIDITY_SECS) {
+            return Err(InvalidDeregistration::TooFarAhead(self.deadline));
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for Deregistration {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// What a deregistration did to the work of the operator
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorExit {
+    /// In-flight requests taken back from the operator, they're assigned to other operators
+    pub released: Vec<ProofRequestId>,
+    /// Proven requests the operator wasn't paid for yet, they're paid out with the exit
+    pub unpaid: Vec<ProofRequestId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::signer::{SignedData, Signer},
+        fixtures,
+    };
+
+    #[test]
+    fn test_deregistration_deadline() {
+        let now = Utc::now();
+        let deregistration = Deregistration {
+            leave_avs: false,
+            deadline: now + Duration::minutes(5),
+        };
+        assert_eq!(deregistration.validate(now), Ok(()));
+        assert_eq!(
+            deregistration.validate(now + Duration::minutes(5)),
+            Err(InvalidDeregistration::Expired(deregistration.deadline))
+        );
+
+        // Replayable for too long
+        let deregistration = Deregistration {
+            leave_avs: false,
+            deadline: now + Duration::days(1),
+        };
+        assert_eq!(
+            deregistration.validate(now),
+            Err(InvalidDeregistration::TooFarAhead(deregistration.deadline))
+        );
+    }
+
+    #[test]
+    fn test_forged_deregistration() {
+        let operator = fixtures::signer(7);
+        let deregistration = Deregistration {
+            leave_avs: true,
+            deadline: Utc::now() + Duration::minutes(5),
+        };
+        assert!(fixtures::signed(deregistration.clone(), &operator)
+            .verify_payload()
+            .is_ok());
+
+        // The signature of an assignments query of the operator paired with a deregistration
+        let query = fixtures::signed(operator.verifying_key(), &operator);
+        let forged = SignedData {
+            hash: query.hash,
+            payload: deregistration,
+            public_key: query.public_key,
+            signature: query.signature,
+        };
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use crate::{hash::Hashable, resources::ImageHash, types::region::Region};
+
+/// How recently an operator must have been heard from to count as online. Networks whose operators send heartbeats
+/// less often need a longer timeout, or their operators are reported offline between two heartbeats.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Liveness {
+    /// Seconds since the last interaction an operator is still considered online
+    #[arg(long, default_value_t = 180)]
+    pub heartbeat_timeout_secs: u64,
+}
+
+impl Default for Liveness {
+    fn default() -> Self {
+        Self {
+            heartbeat_timeout_secs: 180,
+        }
+    }
+}
+
+impl Liveness {
+    pub fn heartbeat_timeout(&self) -> Duration {
+        Duration::seconds(self.heartbeat_timeout_secs.min(i64::MAX as u64 / 1000) as i64)
+    }
+
+    /// Whether an operator last heard from at `last_interaction` is still within the timeout at `now`
+    pub fn is_live(&self, last_interaction: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        now - last_interaction < self.heartbeat_timeout()
+    }
+}
+
+/// Heartbeat of an operator, signed by the operator. Keeps it online and declares what it can take on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorHeartbeat {
+    /// Requests the operator proves at once, assignments are held back once it's busy with as many
+    pub max_concurrent_jobs: u32,
+    /// Images the operator has downloaded, requests using them are assigned to it first
+    #[serde(default)]
+    pub cached_images: Vec<ImageHash>,
+    #[serde(default)]
+    pub region: Option<Region>,
+}
+
+impl Hashable for OperatorHeartbeat {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_live() {
+        let now = Utc::now();
+        let liveness = Liveness::default();
+        assert!(liveness.is_live(now - Duration::seconds(179), now));
+        assert!(!liveness.is_live(now - Duration::seconds(180), now));
+
+        let slow = Liveness {
+            heartbeat_timeout_secs: 600,
+        };
+        assert!(slow.is_live(now - Duration::seconds(300), now));
+    }
+}
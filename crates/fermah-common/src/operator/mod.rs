@@ -6,6 +6,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::crypto::signer::{ecdsa::EcdsaSigner, Signer};
 
+pub mod assignment;
+pub mod deregistration;
+pub mod liveness;
+pub mod offense;
+pub mod readiness;
+pub mod registration;
+pub mod reputation;
+pub mod restriction;
+pub mod runtime;
+
 #[derive(Serialize, Deserialize, Clone, Hash, Eq, PartialEq, Copy)]
 pub struct OperatorId(pub <EcdsaSigner as Signer>::VerifyingKey); // #[serde(with = "hex_encoded")]
 
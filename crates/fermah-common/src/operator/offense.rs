@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Operator misbehaviour which can be penalised on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Offense {
+    /// The operator acknowledged the assignment, but didn't deliver a proof before the deadline
+    MissedDeadlineAfterAck,
+    /// The operator delivered a proof which failed verification
+    InvalidProof,
+}
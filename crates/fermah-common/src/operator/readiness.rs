@@ -0,0 +1,257 @@
+use std::{borrow::Cow, fmt::Display};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{runtime::OperatorRuntime, OperatorId};
+use crate::{hash::Hashable, resource::Resource};
+
+/// Most checks a report holds
+pub const MAX_READINESS_CHECKS: usize = 16;
+/// Longest detail of a check, the error of a failed run is truncated to it
+pub const MAX_CHECK_DETAIL_LEN: usize = 1024;
+/// Age past which a report isn't accepted anymore, the node has to run its self-test again
+pub const MAX_REPORT_AGE: TimeDelta = TimeDelta::hours(1);
+/// Drift tolerated between the clocks of the operator and the matchmaker
+pub const MAX_REPORT_CLOCK_SKEW: TimeDelta = TimeDelta::minutes(5);
+
+/// What the self-test of an operator checks before it goes live
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ReadinessCheckKind {
+    /// The operator keystore decrypts with the configured password
+    Keystore,
+    /// The operator key signs, and its signatures verify
+    Key,
+    /// The resources of the host are detected
+    Resource,
+    /// The prover of the reference request runs and produces a proof
+    Prover,
+    /// The verifier of the reference request accepts the proof
+    Verifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessCheck {
+    pub kind: ReadinessCheckKind,
+    pub passed: bool,
+    /// Why the check failed, or what it found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Time the check took, pulling the image included for the runs
+    pub millis: u64,
+}
+
+impl ReadinessCheck {
+    pub fn passed(kind: ReadinessCheckKind, millis: u64) -> Self {
+        Self {
+            kind,
+            passed: true,
+            detail: None,
+            millis,
+        }
+    }
+
+    pub fn failed<D: Display>(kind: ReadinessCheckKind, detail: D, millis: u64) -> Self {
+        let mut detail = detail.to_string();
+        if detail.len() > MAX_CHECK_DETAIL_LEN {
+            let mut end = MAX_CHECK_DETAIL_LEN;
+            while !detail.is_char_boundary(end) {
+                end -= 1;
+            }
+            detail.truncate(end);
+        }
+        Self {
+            kind,
+            passed: false,
+            detail: Some(detail),
+            millis,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidReadinessReport {
+    #[error("report has more than {MAX_READINESS_CHECKS} checks")]
+    TooManyChecks,
+    #[error("detail of the {0} check is longer than {MAX_CHECK_DETAIL_LEN} bytes")]
+    DetailTooLong(ReadinessCheckKind),
+    #[error("report of {0} is stale, run the self-test again")]
+    Stale(DateTime<Utc>),
+    #[error("report is tested at {0}, in the future")]
+    InTheFuture(DateTime<Utc>),
+}
+
+/// Outcome of the self-test of an operator, printed for its owner and optionally submitted to the matchmaker along
+/// with its registration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    pub operator_id: OperatorId,
+    /// Version of the SDK the prover node is built with
+    pub sdk_version: String,
+    pub runtime: OperatorRuntime,
+    /// Resources the operator would claim, `None` if they weren't detected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<Resource>,
+    pub checks: Vec<ReadinessCheck>,
+    pub tested_at: DateTime<Utc>,
+}
+
+impl ReadinessReport {
+    /// Whether every check passed, the operator is ready to be assigned requests then
+    pub fn is_ready(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|check| check.passed)
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidReadinessReport> {
+        if self.checks.len() > MAX_READINESS_CHECKS {
+            return Err(InvalidReadinessReport::TooManyChecks);
+        }
+        if let Some(check) = self.checks.iter().find(|check| {
+            check
+                .detail
+                .as_ref()
+                .is_some_and(|detail| detail.len() > MAX_CHECK_DETAIL_LEN)
+        }) {
+            return Err(InvalidReadinessReport::DetailTooLong(check.kind));
+        }
+        Ok(())
+    }
+
+    /// Whether the report was made recently enough at `now` to be accepted, at most [`MAX_REPORT_AGE`] ago
+    pub fn check_fresh(&self, now: DateTime<Utc>) -> Result<(), InvalidReadinessReport> {
+        if self.tested_at > now + MAX_REPORT_CLOCK_SKEW {
+            return Err(InvalidReadinessReport::InTheFuture(self.tested_at));
+        }
+        if self.tested_at < now - MAX_REPORT_AGE {
+            return Err(InvalidReadinessReport::Stale(self.tested_at));
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for ReadinessReport {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+impl Display for ReadinessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operator {}, SDK {}", self.operator_id, self.sdk_version)?;
+        for check in &self.checks {
+            let outcome = if check.passed { "ok" } else { "FAILED" };
+            write!(
+                f,
+                "  {:<10} {outcome:<6} {:>8} ms",
+                check.kind.to_string(),
+                check.millis
+            )?;
+            if let Some(detail) = &check.detail {
+                write!(f, "  {detail}")?;
+            }
+            writeln!(f)?;
+        }
+        if self.is_ready() {
+            write!(f, "Ready")
+        } else {
+            write!(f, "Not ready, fix the failed checks before registering")
+        }
+    }
+}
+
+/// Latest report an operator submitted, for the matchmaker and its admins
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorReadiness {
+    pub report: ReadinessReport,
+    pub submitted_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_readiness_report() {
+        let mut report = ReadinessReport {
+            operator_id: Address::random().into(),
+            sdk_version: "0.1.0".to_string(),
+            runtime: OperatorRuntime::default(),
+            resource: None,
+            checks: vec![
+                ReadinessCheck::passed(ReadinessCheckKind::Key, 1),
+                ReadinessCheck::passed(ReadinessCheckKind::Prover, 2_500),
+            ],
+            tested_at: Utc::now(),
+        };
+        assert!(report.is_ready());
+        assert!(report.to_string().ends_with("Ready"));
+
+        report.checks.push(ReadinessCheck::failed(
+            ReadinessCheckKind::Verifier,
+            "é".repeat(MAX_CHECK_DETAIL_LEN),
+            40,
+        ));
+        assert!(!report.is_ready());
+        assert_eq!(report.validate(), Ok(()));
+        assert!(report.to_string().contains("verifier   FAILED"));
+        assert!(report.to_string().ends_with("before registering"));
+
+        report.checks[2].detail = Some("x".repeat(MAX_CHECK_DETAIL_LEN + 1));
+        assert_eq!(
+            report.validate(),
+            Err(InvalidReadinessReport::DetailTooLong(
+                ReadinessCheckKind::Verifier
+            ))
+        );
+    }
+
+    #[test]
+    fn test_readiness_report_freshness() {
+        let now = Utc::now();
+        let report = |tested_at| {
+            ReadinessReport {
+                operator_id: Address::random().into(),
+                sdk_version: "0.1.0".to_string(),
+                runtime: OperatorRuntime::default(),
+                resource: None,
+                checks: vec![ReadinessCheck::passed(ReadinessCheckKind::Key, 1)],
+                tested_at,
+            }
+        };
+
+        assert_eq!(report(now).check_fresh(now), Ok(()));
+        assert_eq!(report(now - MAX_REPORT_AGE).check_fresh(now), Ok(()));
+        // Behind the operator's clock
+        assert_eq!(report(now + MAX_REPORT_CLOCK_SKEW).check_fresh(now), Ok(()));
+
+        let stale = now - MAX_REPORT_AGE - TimeDelta::seconds(1);
+        assert_eq!(
+            report(stale).check_fresh(now),
+            Err(InvalidReadinessReport::Stale(stale))
+        );
+        let future = now + MAX_REPORT_CLOCK_SKEW + TimeDelta::seconds(1);
+        assert_eq!(
+            report(future).check_fresh(now),
+            Err(InvalidReadinessReport::InTheFuture(future))
+        );
+    }
+}
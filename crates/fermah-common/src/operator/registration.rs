@@ -0,0 +1,27 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::runtime::OperatorRuntime;
+use crate::{hash::Hashable, resource::Resource, types::region::Region};
+
+/// What an operator claims when it registers with the matchmaker, signed by the operator. Registering again replaces
+/// the claim, e.g. after the host was upgraded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRegistration {
+    /// Resources of the host, usually detected on it, see [`crate::resource::detect::detect_resource`]
+    pub resource: Resource,
+    /// Requests the operator proves at once
+    pub max_concurrent_jobs: u32,
+    #[serde(default)]
+    pub region: Option<Region>,
+    #[serde(default)]
+    pub runtime: OperatorRuntime,
+}
+
+impl Hashable for OperatorRegistration {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use super::offense::Offense;
+
+/// What the operator did with the requests assigned to it, as recorded in the request history
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorHistory {
+    /// Requests proven by the operator
+    pub completed: u64,
+    /// Requests rejected while assigned to the operator
+    pub rejected: u64,
+    /// Offenses recorded against the operator, settled or not
+    pub offenses: BTreeMap<Offense, u64>,
+}
+
+/// Weights the reputation of an operator is scored with from its history. The score only depends on the history, so
+/// replaying it with the same policy always gives the same reputation.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReputationPolicy {
+    /// Reputation gained for every proven request
+    #[arg(long, default_value_t = 10)]
+    pub proven_weight: i64,
+    /// Reputation lost for every request rejected while assigned to the operator
+    #[arg(long, default_value_t = 5)]
+    pub rejected_penalty: i64,
+    /// Reputation lost for every assignment acknowledged but not delivered in time
+    #[arg(long, default_value_t = 20)]
+    pub missed_deadline_penalty: i64,
+    /// Reputation lost for every proof which failed verification
+    #[arg(long, default_value_t = 50)]
+    pub invalid_proof_penalty: i64,
+}
+
+impl Default for ReputationPolicy {
+    fn default() -> Self {
+        Self {
+            proven_weight: 10,
+            rejected_penalty: 5,
+            missed_deadline_penalty: 20,
+            invalid_proof_penalty: 50,
+        }
+    }
+}
+
+impl ReputationPolicy {
+    pub fn score(&self, history: &OperatorHistory) -> i64 {
+        let count = |n: u64| i64::try_from(n).unwrap_or(i64::MAX);
+        let offenses = history.offenses.iter().fold(0i64, |sum, (offense, n)| {
+            let penalty = match offense {
+                Offense::MissedDeadlineAfterAck => self.missed_deadline_penalty,
+                Offense::InvalidProof => self.invalid_proof_penalty,
+            };
+            sum.saturating_add(penalty.saturating_mul(count(*n)))
+        });
+
+        self.proven_weight
+            .saturating_mul(count(history.completed))
+            .saturating_sub(
+                self.rejected_penalty
+                    .saturating_mul(count(history.rejected)),
+            )
+            .saturating_sub(offenses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let policy = ReputationPolicy::default();
+        assert_eq!(policy.score(&OperatorHistory::default()), 0);
+
+        let history = OperatorHistory {
+            completed: 12,
+            rejected: 2,
+            offenses: BTreeMap::from([
+                (Offense::MissedDeadlineAfterAck, 1),
+                (Offense::InvalidProof, 1),
+            ]),
+        };
+        assert_eq!(policy.score(&history), 120 - 10 - 20 - 50);
+
+        let lenient = ReputationPolicy {
+            invalid_proof_penalty: 0,
+            ..policy
+        };
+        assert_eq!(lenient.score(&history), 120 - 10 - 20);
+
+        let busy = OperatorHistory {
+            completed: u64::MAX,
+            ..OperatorHistory::default()
+        };
+        assert_eq!(policy.score(&busy), i64::MAX);
+    }
+}
@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use super::OperatorId;
+use crate::{crypto::signer::freshness::Freshness, hash::Hashable};
+
+/// Why an operator isn't assigned requests
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RestrictionKind {
+    /// Excluded by an admin
+    Banned,
+    /// Excluded for a while, by an admin or after failing too many proofs in a row
+    Greylisted,
+}
+
+/// Exclusion of an operator from the assignments, until it expires or is cleared by an admin
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRestriction {
+    pub kind: RestrictionKind,
+    pub reason: String,
+    pub since: DateTime<Utc>,
+    /// Never expires if `None`
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl OperatorRestriction {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.until.map_or(true, |until| now < until)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestrictedOperator {
+    pub operator_id: OperatorId,
+    pub restriction: OperatorRestriction,
+}
+
+/// Restriction an admin puts on an operator, replacing the one it has
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestrictionRequest {
+    pub operator_id: OperatorId,
+    pub kind: RestrictionKind,
+    pub reason: String,
+    /// Never expires if `None`
+    pub until: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub freshness: Freshness,
+}
+
+impl Hashable for RestrictionRequest {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Lifts the restriction of an operator, and forgets its failures
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestrictionRemoval {
+    pub operator_id: OperatorId,
+    #[serde(flatten)]
+    pub freshness: Freshness,
+}
+
+impl Hashable for RestrictionRemoval {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// When operators are greylisted for failing proofs in a row. A proven request resets the count.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GreylistPolicy {
+    /// Proofs failed in a row after which the operator is greylisted, never greylisted if 0
+    #[arg(long, default_value_t = 3)]
+    pub greylist_after_failures: u32,
+    /// Seconds the operator is greylisted for
+    #[arg(long, default_value_t = 3600)]
+    pub greylist_secs: u64,
+}
+
+impl Default for GreylistPolicy {
+    fn default() -> Self {
+        Self {
+            greylist_after_failures: 3,
+            greylist_secs: 3600,
+        }
+    }
+}
+
+impl GreylistPolicy {
+    pub fn greylist_duration(&self) -> Duration {
+        Duration::seconds(self.greylist_secs.min(i64::MAX as u64 / 1000) as i64)
+    }
+
+    /// Whether an operator which failed `failures` proofs in a row is greylisted
+    pub fn greylists(&self, failures: u32) -> bool {
+        self.greylist_after_failures > 0 && failures >= self.greylist_after_failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::Address;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_forged_restriction() {
+        let admin = fixtures::signer(7);
+        let operator_id = OperatorId::from(Address::random());
+        let request = fixtures::signed(
+            RestrictionRequest {
+                operator_id,
+                kind: RestrictionKind::Greylisted,
+                reason: "flaky".to_string(),
+                until: Some(Utc::now() + Duration::hours(1)),
+                freshness: Freshness::new(Utc::now()),
+            },
+            &admin,
+        );
+        assert!(request.verify_payload().is_ok());
+
+        // The admin's signature paired with a ban of another operator, with a fresh nonce
+        let mut forged = request.clone();
+        forged.payload.operator_id = OperatorId::from(Address::random());
+        forged.payload.kind = RestrictionKind::Banned;
+        forged.payload.until = None;
+        forged.payload.freshness.nonce += 1;
+        assert!(forged.verify_payload().is_err());
+
+        let mut removal = fixtures::signed(
+            RestrictionRemoval {
+                operator_id,
+                freshness: Freshness::new(Utc::now()),
+            },
+            &admin,
+        );
+        assert!(removal.verify_payload().is_ok());
+        removal.payload.operator_id = OperatorId::from(Address::random());
+        assert!(removal.verify_payload().is_err());
+    }
+
+    #[test]
+    fn test_greylist_policy() {
+        let policy = GreylistPolicy::default();
+        assert!(!policy.greylists(2));
+        assert!(policy.greylists(3));
+
+        let disabled = GreylistPolicy {
+            greylist_after_failures: 0,
+            ..policy
+        };
+        assert!(!disabled.greylists(100));
+
+        let now = Utc::now();
+        let restriction = OperatorRestriction {
+            kind: RestrictionKind::Greylisted,
+            reason: "3 failed proofs in a row".to_string(),
+            since: now,
+            until: Some(now + policy.greylist_duration()),
+        };
+        assert!(restriction.is_active(now));
+        assert!(!restriction.is_active(now + Duration::hours(1)));
+        assert!(OperatorRestriction {
+            until: None,
+            ..restriction
+        }
+        .is_active(now + Duration::days(365)));
+    }
+}
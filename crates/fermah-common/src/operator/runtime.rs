@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{executable::Executable, proof::request::ProofRequest};
+
+/// Container runtime an operator runs executables with. Images are OCI images, pulled by name or loaded from docker
+/// archives, which every runtime runs, the runtimes only differ by the features they provide.
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+    /// containerd, driven by nerdctl
+    Containerd,
+}
+
+/// Feature an executable can ask for which a runtime may not provide
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RuntimeFeature {
+    /// Privileged containers, for `privileged` executables
+    Privileged,
+    /// Network access, for `network_enabled` executables
+    Network,
+    /// Docker socket mounted in the container, for `docker_access` executables
+    DockerAccess,
+    /// WASI sandbox next to the container runtime, for executables of wasm modules
+    Wasm,
+}
+
+impl RuntimeFeature {
+    /// Features of the containers themselves
+    pub const CONTAINER: [Self; 3] = [Self::Privileged, Self::Network, Self::DockerAccess];
+}
+
+impl ContainerRuntime {
+    /// Features of a default install of the runtime, only docker has a docker socket to mount. Running wasm modules
+    /// is advertised by the operators which installed a wasm runtime.
+    pub fn default_features(&self) -> Vec<RuntimeFeature> {
+        match self {
+            Self::Docker => RuntimeFeature::CONTAINER.to_vec(),
+            Self::Podman | Self::Containerd => {
+                vec![RuntimeFeature::Privileged, RuntimeFeature::Network]
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{runtime} runtime of the operator doesn't provide {missing:?}")]
+pub struct UnsupportedFeatures {
+    pub runtime: ContainerRuntime,
+    pub missing: Vec<RuntimeFeature>,
+}
+
+/// Runtime an operator advertises when it registers, with the features it provides. Operators which don't advertise
+/// one run docker with its default features, as before runtimes were negotiated.
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRuntime {
+    pub runtime: ContainerRuntime,
+    pub features: Vec<RuntimeFeature>,
+}
+
+impl Default for OperatorRuntime {
+    fn default() -> Self {
+        Self::new(ContainerRuntime::Docker)
+    }
+}
+
+impl OperatorRuntime {
+    /// Runtime with its default features
+    pub fn new(runtime: ContainerRuntime) -> Self {
+        Self {
+            runtime,
+            features: runtime.default_features(),
+        }
+    }
+
+    /// Checks the runtime provides every feature the executable needs, operators refuse executables it doesn't
+    pub fn check(&self, executable: &Executable) -> Result<(), UnsupportedFeatures> {
+        let missing: Vec<RuntimeFeature> = executable
+            .required_features()
+            .into_iter()
+            .filter(|feature| !self.features.contains(feature))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(UnsupportedFeatures {
+                runtime: self.runtime,
+                missing,
+            })
+        }
+    }
+
+    /// Whether the operator can run both the prover and the verifier of the request
+    pub fn supports(&self, proof_request: &ProofRequest) -> bool {
+        self.check(&proof_request.prover).is_ok() && self.check(&proof_request.verifier).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        executable::{Image, WasmLimits, WasmModule},
+        resources::RemoteResource,
+    };
+
+    fn executable() -> Executable {
+        Executable {
+            image: Image::Docker("prover:latest".to_string()),
+            platform: None,
+            in_mounts: vec![],
+            result_extractor: None,
+            injector: None,
+            entrypoint: vec![],
+            cmd: vec![],
+            env_vars: None,
+            network_enabled: false,
+            privileged: false,
+            docker_access: false,
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let plain = executable();
+        let docker_access = Executable {
+            docker_access: true,
+            network_enabled: true,
+            ..executable()
+        };
+
+        for runtime in [
+            ContainerRuntime::Docker,
+            ContainerRuntime::Podman,
+            ContainerRuntime::Containerd,
+        ] {
+            assert_eq!(OperatorRuntime::new(runtime).check(&plain), Ok(()));
+        }
+        assert_eq!(OperatorRuntime::default().check(&docker_access), Ok(()));
+        assert_eq!(
+            OperatorRuntime::new(ContainerRuntime::Podman).check(&docker_access),
+            Err(UnsupportedFeatures {
+                runtime: ContainerRuntime::Podman,
+                missing: vec![RuntimeFeature::DockerAccess],
+            })
+        );
+
+        let wasm = Executable {
+            image: Image::Wasm(WasmModule {
+                module: RemoteResource {
+                    url: "http://localhost:3000/verifier.wasm".parse().unwrap(),
+                    hash: [7; 32].into(),
+                    delta: None,
+                },
+                entry: "verify".to_string(),
+                limits: WasmLimits {
+                    max_memory: 1024 * 1024,
+                    max_fuel: 1_000_000,
+                },
+            }),
+            ..executable()
+        };
+        assert!(OperatorRuntime::default().check(&wasm).is_err());
+        let with_wasm = OperatorRuntime {
+            runtime: ContainerRuntime::Podman,
+            features: vec![RuntimeFeature::Network, RuntimeFeature::Wasm],
+        };
+        assert_eq!(with_wasm.check(&wasm), Ok(()));
+
+        // Operators can advertise less than the defaults, e.g. no network
+        let offline = OperatorRuntime {
+            runtime: ContainerRuntime::Containerd,
+            features: vec![RuntimeFeature::Privileged],
+        };
+        assert_eq!(
+            offline.check(&docker_access).unwrap_err().missing,
+            vec![RuntimeFeature::Network, RuntimeFeature::DockerAccess]
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            serde_json::to_string(&ContainerRuntime::Containerd).unwrap(),
+            "\"containerd\""
+        );
+        assert_eq!(
+            "podman".parse::<ContainerRuntime>().unwrap(),
+            ContainerRuntime::Podman
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use chrono::{DateTime, Utc};
+use ethers::{signers::Signer as EthereumSigner, types::Address};
+use rand::Rng;
+use reqwest::Url;
+use thiserror::Error;
+
+use crate::{
+    crypto::{
+        sealed::{ResultKey, SealError},
+        signer::{
+            ecdsa::{EcdsaSigner, EcdsaSignerError},
+            eip712::SignatureScheme,
+            SignedData,
+        },
+    },
+    executable::{Executable, ExecutableError},
+    proof::{
+        redundancy::{Redundancy, RedundancyError},
+        request::{ProofRequest, ProofRequestId, ProvidedProof, VerificationOnlyError},
+        result_validation::{InvalidResultValidator, ResultValidator},
+        retention::ProofRetention,
+    },
+    resource::requirement::ResourceRequirement,
+    types::region::{PreferredRegions, RegionError},
+};
+
+pub type SignedProofRequest = SignedData<ProofRequest, EcdsaSigner>;
+
+#[derive(Error, Debug)]
+pub enum ProofRequestBuilderError {
+    #[error("{0} is not set")]
+    Missing(&'static str),
+
+    #[error("prover has no result extractor, the proof can't be extracted")]
+    NoResultExtractor,
+
+    #[error("verifier has no injector, the provided proof can't be given to it")]
+    NoInjector,
+
+    #[error("provided proof is empty")]
+    EmptyProof,
+
+    #[error("a verification-only request has no prover, the verifier stands in for it")]
+    ProverWithProof,
+
+    #[error("invalid prover: {0}")]
+    InvalidProver(ExecutableError),
+
+    #[error("invalid verifier: {0}")]
+    InvalidVerifier(ExecutableError),
+
+    #[error("deadline {0} has passed")]
+    DeadlinePassed(DateTime<Utc>),
+
+    #[error("callback url {0} is not http(s)")]
+    InvalidCallbackUrl(Url),
+
+    #[error("dependency {0:?} is listed more than once")]
+    DuplicateDependency(ProofRequestId),
+
+    #[error("invalid redundancy: {0}")]
+    InvalidRedundancy(#[from] RedundancyError),
+
+    #[error("invalid preferred regions: {0}")]
+    InvalidPreferredRegions(#[from] RegionError),
+
+    #[error("invalid result key: {0}")]
+    InvalidResultKey(SealError),
+
+    #[error("invalid result validator: {0}")]
+    InvalidResultValidator(#[from] InvalidResultValidator),
+
+    #[error("sealed results can't be validated, only their requester reads them")]
+    SealedResultValidator,
+
+    #[error("failed to sign the proof request: {0}")]
+    Signer(#[from] EcdsaSignerError),
+
+    #[error("failed to sign the typed proof request: {0}")]
+    TypedSigner(String),
+
+    #[error("signed proof request doesn't verify: {0}")]
+    Verification(String),
+}
+
+/// Builds a proof request and signs it the way the RPC server checks it: the requester is the signer, or delegated
+/// it, the hash is computed from the payload, and the signature verifies against it. Integrators should build
+/// requests with it instead of filling in [`SignedData`] by hand.
+#[derive(Debug, Clone, Default)]
+pub struct ProofRequestBuilder {
+    prover: Option<Executable>,
+    verifier: Option<Executable>,
+    resource_requirement: ResourceRequirement,
+    callback_url: Option<Url>,
+    deadline: Option<DateTime<Utc>>,
+    nonce: Option<u64>,
+    depends_on: Vec<ProofRequestId>,
+    redundancy: Option<Redundancy>,
+    preferred_regions: Option<PreferredRegions>,
+    proof: Option<ProvidedProof>,
+    result_key: Option<ResultKey>,
+    retention: Option<ProofRetention>,
+    result_validator: Option<ResultValidator>,
+}
+
+impl ProofRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prover(mut self, prover: Executable) -> Self {
+        self.prover = Some(prover);
+        self
+    }
+
+    pub fn verifier(mut self, verifier: Executable) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    pub fn resource_requirement(mut self, resource_requirement: ResourceRequirement) -> Self {
+        self.resource_requirement = resource_requirement;
+        self
+    }
+
+    /// Asks for a named resource class of the server instead of an explicit requirement
+    pub fn resource_class(mut self, class: impl Into<String>) -> Self {
+        self.resource_requirement = ResourceRequirement {
+            class: Some(class.into()),
+            ..Default::default()
+        };
+        self
+    }
+
+    pub fn callback_url(mut self, callback_url: Url) -> Self {
+        self.callback_url = Some(callback_url);
+        self
+    }
+
+    pub fn deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Random if not set, so identical requests get different ids
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn depends_on(mut self, dependency: ProofRequestId) -> Self {
+        self.depends_on.push(dependency);
+        self
+    }
+
+    pub fn redundancy(mut self, redundancy: Redundancy) -> Self {
+        self.redundancy = Some(redundancy);
+        self
+    }
+
+    pub fn preferred_regions(mut self, preferred_regions: PreferredRegions) -> Self {
+        self.preferred_regions = Some(preferred_regions);
+        self
+    }
+
+    /// Only verifies the proof, the request skips the prover stage. The prover doesn't have to be set, the verifier
+    /// stands in for it.
+    pub fn verify_only(mut self, proof: ProvidedProof) -> Self {
+        self.proof = Some(proof);
+        self
+    }
+
+    /// Seals the proof to the key, e.g. the [`EcdsaSigner::result_key`] of the requester, so only its holder can
+    /// read it
+    pub fn result_key(mut self, result_key: ResultKey) -> Self {
+        self.result_key = Some(result_key);
+        self
+    }
+
+    /// Deletes the proof from the matchmaker after the retention, instead of after its default one
+    pub fn retention(mut self, retention: ProofRetention) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Rejects the request instead of proving it if its result fails the structural checks of the validator
+    pub fn result_validator(mut self, result_validator: ResultValidator) -> Self {
+        self.result_validator = Some(result_validator);
+        self
+    }
+
+    /// Unsigned request with the given requester, after checking its fields
+    pub fn build(self, requester: Address) -> Result<ProofRequest, ProofRequestBuilderError> {
+        let verifier = self
+            .verifier
+            .ok_or(ProofRequestBuilderError::Missing("verifier"))?;
+        let prover = match (self.prover, &self.proof) {
+            (Some(_), Some(_)) => return Err(ProofRequestBuilderError::ProverWithProof),
+            (Some(prover), None) => prover,
+            (None, Some(_)) => verifier.clone(),
+            (None, None) => return Err(ProofRequestBuilderError::Missing("prover")),
+        };
+
+        if self.proof.is_none() {
+            if prover.result_extractor.is_none() {
+                return Err(ProofRequestBuilderError::NoResultExtractor);
+            }
+            prover
+                .validate()
+                .map_err(ProofRequestBuilderError::InvalidProver)?;
+        }
+        verifier
+            .validate()
+            .map_err(ProofRequestBuilderError::InvalidVerifier)?;
+        if let Some(deadline) = self.deadline.filter(|deadline| *deadline <= Utc::now()) {
+            return Err(ProofRequestBuilderError::DeadlinePassed(deadline));
+        }
+        if let Some(url) = self
+            .callback_url
+            .as_ref()
+            .filter(|url| !matches!(url.scheme(), "http" | "https"))
+        {
+            return Err(ProofRequestBuilderError::InvalidCallbackUrl(url.clone()));
+        }
+        let mut dependencies = std::collections::HashSet::new();
+        if let Some(dependency) = self.depends_on.iter().find(|id| !dependencies.insert(*id)) {
+            return Err(ProofRequestBuilderError::DuplicateDependency(*dependency));
+        }
+        if let Some(redundancy) = &self.redundancy {
+            redundancy.validate()?;
+        }
+        if let Some(preferred_regions) = &self.preferred_regions {
+            preferred_regions.validate()?;
+        }
+        if let Some(result_key) = &self.result_key {
+            result_key
+                .validate()
+                .map_err(ProofRequestBuilderError::InvalidResultKey)?;
+        }
+        if let Some(result_validator) = &self.result_validator {
+            if self.result_key.is_some() {
+                return Err(ProofRequestBuilderError::SealedResultValidator);
+            }
+            result_validator.validate()?;
+        }
+
+        let proof_request = ProofRequest {
+            requester: Some(requester),
+            prover,
+            verifier,
+            resource_requirement: self.resource_requirement,
+            callback_url: self.callback_url,
+            deadline: self.deadline,
+            nonce: self.nonce.unwrap_or_else(|| rand::thread_rng().gen()),
+            depends_on: self.depends_on,
+            redundancy: self.redundancy,
+            preferred_regions: self.preferred_regions,
+            proof: self.proof,
+            result_key: self.result_key,
+            retention: self.retention,
+            result_validator: self.result_validator,
+        };
+        proof_request.check_verification_only().map_err(|err| {
+            match err {
+                VerificationOnlyError::Prover => ProofRequestBuilderError::ProverWithProof,
+                VerificationOnlyError::NoInjector => ProofRequestBuilderError::NoInjector,
+                VerificationOnlyError::EmptyProof => ProofRequestBuilderError::EmptyProof,
+            }
+        })?;
+        Ok(proof_request)
+    }
+
+    /// Signs the Blake3 hash of the request, the signer is the requester
+    pub fn sign(
+        self,
+        signer: &EcdsaSigner,
+    ) -> Result<SignedProofRequest, ProofRequestBuilderError> {
+        self.sign_for(signer.address(), signer)
+    }
+
+    /// Signs the Blake3 hash of a request paid by the requester, which delegated the signer to submit for it
+    pub fn sign_for(
+        self,
+        requester: Address,
+        signer: &EcdsaSigner,
+    ) -> Result<SignedProofRequest, ProofRequestBuilderError> {
+        let proof_request = self.build(requester)?;
+        let signed = SignedData::new(proof_request, signer)?;
+        check_signed(&signed, requester, None)?;
+        Ok(signed)
+    }
+
+    /// Signs the EIP-712 typed-data encoding of the request, bound to the chain. Any EIP-712 capable signer works,
+    /// e.g. a hardware wallet or a KMS key, the signer is the requester.
+    pub async fn sign_eip712<S: EthereumSigner>(
+        self,
+        chain_id: u64,
+        signer: &S,
+    ) -> Result<SignedProofRequest, ProofRequestBuilderError> {
+        let proof_request = self.build(signer.address())?;
+        let signed = SignedData::new_eip712(proof_request, chain_id, signer)
+            .await
+            .map_err(|err| ProofRequestBuilderError::TypedSigner(err.to_string()))?;
+        check_signed(&signed, signer.address(), Some(chain_id))?;
+        Ok(signed)
+    }
+}
+
+impl From<ProofRequest> for ProofRequestBuilder {
+    /// Keeps every field but the requester, which is set to the signer
+    fn from(proof_request: ProofRequest) -> Self {
+        Self {
+            prover: Some(proof_request.prover),
+            verifier: Some(proof_request.verifier),
+            resource_requirement: proof_request.resource_requirement,
+            callback_url: proof_request.callback_url,
+            deadline: proof_request.deadline,
+            nonce: Some(proof_request.nonce),
+            depends_on: proof_request.depends_on,
+            redundancy: proof_request.redundancy,
+            preferred_regions: proof_request.preferred_regions,
+            proof: proof_request.proof,
+            result_key: proof_request.result_key,
+            retention: proof_request.retention,
+            result_validator: proof_request.result_validator,
+        }
+    }
+}
+
+/// Same checks as the RPC server, so a request the builder signed isn't rejected for its signature
+fn check_signed(
+    signed: &SignedProofRequest,
+    requester: Address,
+    chain_id: Option<u64>,
+) -> Result<(), ProofRequestBuilderError> {
+    signed
+        .verify()
+        .map_err(|err| ProofRequestBuilderError::Verification(err.to_string()))?;
+
+    let expected = match chain_id {
+        Some(_) => SignatureScheme::Eip712,
+        None => SignatureScheme::Raw,
+    };
+    if signed.payload.requester != Some(requester)
+        || signed.scheme(chain_id.unwrap_or_default()) != Some(expected)
+    {
+        return Err(ProofRequestBuilderError::Verification(
+            "hash or requester doesn't match the payload".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        executable::{Image, WasmLimits, WasmModule},
+        fixtures,
+    };
+
+    fn builder() -> ProofRequestBuilder {
+        fixtures::proof_request().into()
+    }
+
+    fn signer() -> EcdsaSigner {
+        fixtures::signer(3)
+    }
+
+    #[tokio::test]
+    async fn test_sign() {
+        let signer = signer();
+
+        let signed = builder().sign(&signer).unwrap();
+        assert_eq!(signed.payload.requester, Some(signed.public_key));
+        assert_eq!(signed.payload.nonce, 217);
+        assert_eq!(signed.scheme(17000), Some(SignatureScheme::Raw));
+
+        let typed = builder().sign_eip712(17000, &signer).await.unwrap();
+        assert_eq!(typed.scheme(17000), Some(SignatureScheme::Eip712));
+        assert_eq!(typed.payload, signed.payload);
+
+        let requester = Address::random();
+        let delegated = builder().sign_for(requester, &signer).unwrap();
+        assert_eq!(delegated.payload.requester, Some(requester));
+        assert_eq!(delegated.public_key, signer.address());
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let signer = signer();
+
+        assert!(matches!(
+            ProofRequestBuilder::new().sign(&signer),
+            Err(ProofRequestBuilderError::Missing("prover"))
+        ));
+        assert!(matches!(
+            builder()
+                .deadline(Utc::now() - Duration::minutes(1))
+                .sign(&signer),
+            Err(ProofRequestBuilderError::DeadlinePassed(_))
+        ));
+        assert!(matches!(
+            builder()
+                .callback_url("ftp://example.com/proofs".parse().unwrap())
+                .sign(&signer),
+            Err(ProofRequestBuilderError::InvalidCallbackUrl(_))
+        ));
+
+        let dependency = ProofRequestId::from([1; 32]);
+        assert!(matches!(
+            builder()
+                .depends_on(dependency)
+                .depends_on(dependency)
+                .sign(&signer),
+            Err(ProofRequestBuilderError::DuplicateDependency(_))
+        ));
+        assert!(matches!(
+            builder()
+                .redundancy(Redundancy {
+                    copies: 4,
+                    quorum: 2
+                })
+                .sign(&signer),
+            Err(ProofRequestBuilderError::InvalidRedundancy(_))
+        ));
+        assert!(matches!(
+            builder()
+                .preferred_regions(PreferredRegions {
+                    regions: vec![],
+                    required: true
+                })
+                .sign(&signer),
+            Err(ProofRequestBuilderError::InvalidPreferredRegions(_))
+        ));
+
+        let mut verifier = fixtures::verifier();
+        verifier.image = Image::Wasm(WasmModule {
+            module: verifier.image.remote().unwrap().clone(),
+            entry: "verify".to_string(),
+            limits: WasmLimits {
+                max_memory: 0,
+                max_fuel: 1_000,
+            },
+        });
+        assert!(matches!(
+            builder().verifier(verifier).sign(&signer),
+            Err(ProofRequestBuilderError::InvalidVerifier(
+                ExecutableError::InvalidWasmLimits
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_verify_only() {
+        let signer = signer();
+        let proof = ProvidedProof::Inline(vec![1, 2, 3]);
+
+        let signed = ProofRequestBuilder::new()
+            .verifier(fixtures::verifier())
+            .verify_only(proof.clone())
+            .sign(&signer)
+            .unwrap();
+        assert!(signed.payload.is_verification_only());
+        assert_eq!(signed.payload.proof, Some(proof.clone()));
+        // Only the verifier is downloaded, and a tenth of the price is quoted
+        assert_eq!(signed.payload.remote_images().len(), 1);
+        assert_eq!(
+            signed.payload.quote() * 10,
+            fixtures::proof_request().quote()
+        );
+
+        assert!(matches!(
+            ProofRequestBuilder::new()
+                .verifier(fixtures::verifier())
+                .verify_only(ProvidedProof::Inline(vec![]))
+                .sign(&signer),
+            Err(ProofRequestBuilderError::EmptyProof)
+        ));
+        let mut verifier = fixtures::verifier();
+        verifier.injector = None;
+        assert!(matches!(
+            ProofRequestBuilder::new()
+                .verifier(verifier)
+                .verify_only(proof)
+                .sign(&signer),
+            Err(ProofRequestBuilderError::NoInjector)
+        ));
+        assert!(matches!(
+            builder()
+                .verify_only(ProvidedProof::Inline(vec![1]))
+                .sign(&signer),
+            Err(ProofRequestBuilderError::ProverWithProof)
+        ));
+
+        // Signed with a prover of its own by another client, refused by the matchmaker
+        let mut with_prover = signed.payload;
+        with_prover.prover = fixtures::prover();
+        assert_eq!(
+            with_prover.check_verification_only(),
+            Err(VerificationOnlyError::Prover)
+        );
+    }
+}
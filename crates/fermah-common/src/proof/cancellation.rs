@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{request::ProofRequestId, status::ProofStatus};
+use crate::{hash::Hashable, types::fee::MAX_FEE_BPS};
+
+/// Why a request was cancelled. Unlike a rejection, a cancellation isn't a verdict on the request or its proof: the
+/// request was withdrawn before it was proven, and only the cancellation fee of the stage it reached is charged.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum CancellationReason {
+    /// Cancelled by its requester
+    Requester,
+    /// Cancelled by an admin of the matchmaker
+    Admin,
+    /// A request it depends on was cancelled
+    Dependency,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidCancelRequest {
+    #[error("cancellation deadline passed at {0}")]
+    Expired(DateTime<Utc>),
+    #[error(
+        "cancellation deadline {0} is more than {} seconds ahead",
+        CancelRequest::MAX_VALIDITY_SECS
+    )]
+    TooFarAhead(DateTime<Utc>),
+}
+
+/// Cancellation of a proof request, signed by its requester or an admin. The signed hash is tagged with
+/// [`CancelRequest::DOMAIN`], so the signature of another payload naming the request, e.g. a status check, can't be
+/// replayed to cancel it. It's only accepted before a deadline at most [`CancelRequest::MAX_VALIDITY_SECS`] ahead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CancelRequest {
+    pub proof_request_id: ProofRequestId,
+    pub deadline: DateTime<Utc>,
+}
+
+impl CancelRequest {
+    pub const DOMAIN: &'static [u8] = b"fermah:cancelProofRequest:";
+    pub const MAX_VALIDITY_SECS: i64 = 600;
+
+    /// Cancellation of the request valid for the longest accepted time
+    pub fn new(proof_request_id: ProofRequestId, now: DateTime<Utc>) -> Self {
+        Self {
+            proof_request_id,
+            deadline: now + Duration::seconds(Self::MAX_VALIDITY_SECS),
+        }
+    }
+
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), InvalidCancelRequest> {
+        if self.deadline <= now {
+            return Err(InvalidCancelRequest::Expired(self.deadline));
+        }
+        if self.deadline > now + Duration::seconds(Self::MAX_VALIDITY_SECS) {
+            return Err(InvalidCancelRequest::TooFarAhead(self.deadline));
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for CancelRequest {
+    fn collect(&self) -> Cow<[u8]> {
+        [Self::DOMAIN, &serde_json::to_vec(self).unwrap()]
+            .concat()
+            .into()
+    }
+}
+
+/// Assignment taken back from the operator, which should stop proving the request and acknowledge it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cancellation {
+    pub proof_request_id: ProofRequestId,
+    pub reason: String,
+}
+
+/// Share of the reserved amount the operator is paid for a request cancelled while it was working on it, by the stage
+/// the request reached. The rest is refunded to the requester.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CancellationFees {
+    /// Basis points paid for a request assigned to the operator, but not acknowledged yet
+    #[arg(long = "cancellation-fee-assigned-bps", default_value_t = 0, value_parser = clap::value_parser!(u16).range(0..=MAX_FEE_BPS as i64))]
+    pub assigned_bps: u16,
+    /// Basis points paid for a request the operator acknowledged, and likely started proving
+    #[arg(long = "cancellation-fee-acknowledged-bps", default_value_t = 0, value_parser = clap::value_parser!(u16).range(0..=MAX_FEE_BPS as i64))]
+    pub acknowledged_bps: u16,
+}
+
+impl CancellationFees {
+    /// Fee for a request cancelled in the status, nothing unless it was assigned
+    pub fn bps(&self, status: &ProofStatus) -> u16 {
+        match status {
+            ProofStatus::Assigned(_) => self.assigned_bps,
+            ProofStatus::AcknowledgedAssignment(_) => self.acknowledged_bps,
+            _ => 0,
+        }
+    }
+
+    /// Splits the amount reserved for a request cancelled in the status, the fee is rounded down
+    pub fn settle(&self, status: &ProofStatus, reserved: U256) -> CancellationSettlement {
+        let bps = self.bps(status).min(MAX_FEE_BPS);
+        let operator_fee = reserved.full_mul(U256::from(bps)) / U256::from(MAX_FEE_BPS);
+        // The fee is at most the reserved amount, so it fits back
+        let operator_fee = U256::try_from(operator_fee).unwrap_or(reserved);
+        CancellationSettlement {
+            operator_fee,
+            refund: reserved - operator_fee,
+        }
+    }
+}
+
+/// Amount reserved for a cancelled request, split between the operator and the requester
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancellationSettlement {
+    /// Paid to the operator the request was assigned to, like the price of a proven request
+    pub operator_fee: U256,
+    /// Released back to the requester
+    pub refund: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::Address;
+
+    use super::*;
+    use crate::{crypto::signer::SignedData, fixtures};
+
+    #[test]
+    fn test_cancellation_settlement() {
+        let fees = CancellationFees {
+            assigned_bps: 1_000,
+            acknowledged_bps: 5_000,
+        };
+        let operator = Address::random().into();
+        let reserved = U256::from(1_001);
+
+        assert_eq!(
+            fees.settle(&ProofStatus::Accepted, reserved),
+            CancellationSettlement {
+                operator_fee: U256::zero(),
+                refund: reserved,
+            }
+        );
+        assert_eq!(
+            fees.settle(&ProofStatus::Assigned(operator), reserved),
+            CancellationSettlement {
+                operator_fee: U256::from(100),
+                refund: U256::from(901),
+            }
+        );
+        assert_eq!(
+            fees.settle(&ProofStatus::AcknowledgedAssignment(operator), reserved),
+            CancellationSettlement {
+                operator_fee: U256::from(500),
+                refund: U256::from(501),
+            }
+        );
+    }
+
+    #[test]
+    fn test_cancel_request() {
+        let now = Utc::now();
+        let cancel = CancelRequest::new(ProofRequestId::from([7; 32]), now);
+        assert_eq!(cancel.validate(now), Ok(()));
+        assert_eq!(
+            cancel.validate(now + Duration::seconds(CancelRequest::MAX_VALIDITY_SECS)),
+            Err(InvalidCancelRequest::Expired(cancel.deadline))
+        );
+        assert_eq!(
+            cancel.validate(now - Duration::seconds(1)),
+            Err(InvalidCancelRequest::TooFarAhead(cancel.deadline))
+        );
+        assert!(cancel.collect().starts_with(CancelRequest::DOMAIN));
+
+        // Another payload naming the request isn't a cancellation
+        let status_check = serde_json::to_value(cancel.proof_request_id).unwrap();
+        assert!(serde_json::from_value::<CancelRequest>(status_check).is_err());
+    }
+
+    #[test]
+    fn test_forged_cancel_request() {
+        let requester = fixtures::requester();
+        let pr_id = ProofRequestId::from([7; 32]);
+        let cancel = fixtures::signed(CancelRequest::new(pr_id, Utc::now()), &requester);
+        assert!(cancel.verify_payload().is_ok());
+
+        // The requester's signature of a status check paired with a cancellation of the request
+        let status_check = fixtures::signed(pr_id, &requester);
+        let forged = SignedData {
+            hash: status_check.hash,
+            payload: cancel.payload.clone(),
+            public_key: status_check.public_key,
+            signature: status_check.signature,
+        };
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use clap::{Args, ValueEnum};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::request::ProofRequest;
+use crate::executable::Executable;
+
+/// What the matchmaker does with a request asking for a container access
+#[derive(
+    Serialize, Deserialize, strum::Display, ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum AccessAction {
+    /// The request gets the access
+    Allow,
+    /// The request is accepted, but its executables are run without the access
+    Downgrade,
+    /// The request is rejected at submission
+    Reject,
+}
+
+/// Decision taken on the access a request asked for, recorded with the request
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    Default,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum AccessDecision {
+    /// Neither the prover nor the verifier asked for it
+    #[default]
+    NotRequested,
+    /// Allowed by the deployment
+    Allowed,
+    /// Allowed because the requester is on the allowlist of the access
+    Allowlisted,
+    /// Taken away, the executables run without it
+    Downgraded,
+}
+
+impl AccessDecision {
+    /// Whether the executables run with the access they asked for
+    pub fn is_granted(&self) -> bool {
+        matches!(self, Self::Allowed | Self::Allowlisted)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{access} containers are not allowed for the requester")]
+pub struct AccessRejected {
+    pub access: &'static str,
+}
+
+/// Policy applied to the submitted requests asking for privileged containers, the docker socket or network access, so
+/// a deployment decides which requesters can run containers able to escape them or to reach out of the operator.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContainerPolicy {
+    /// What is done with requests asking for privileged containers
+    #[arg(long = "privileged-containers", value_enum, default_value_t = AccessAction::Reject)]
+    pub privileged: AccessAction,
+    /// What is done with requests asking for network access
+    #[arg(long = "network-containers", value_enum, default_value_t = AccessAction::Allow)]
+    pub network: AccessAction,
+    /// What is done with requests asking for the docker socket, which controls the operator's host like a privileged
+    /// container
+    #[arg(long = "docker-containers", value_enum, default_value_t = AccessAction::Reject)]
+    pub docker: AccessAction,
+    /// Requesters allowed privileged containers whatever the policy
+    #[arg(long = "privileged-requester")]
+    pub privileged_requesters: Vec<Address>,
+    /// Requesters allowed network access whatever the policy
+    #[arg(long = "network-requester")]
+    pub network_requesters: Vec<Address>,
+    /// Requesters allowed the docker socket whatever the policy
+    #[arg(long = "docker-requester")]
+    pub docker_requesters: Vec<Address>,
+}
+
+impl Default for ContainerPolicy {
+    fn default() -> Self {
+        Self {
+            privileged: AccessAction::Reject,
+            network: AccessAction::Allow,
+            docker: AccessAction::Reject,
+            privileged_requesters: vec![],
+            network_requesters: vec![],
+            docker_requesters: vec![],
+        }
+    }
+}
+
+/// Decisions of the [`ContainerPolicy`] on a request
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerPolicyDecision {
+    pub privileged: AccessDecision,
+    pub network: AccessDecision,
+    /// Not decided on for the requests submitted before the docker socket was part of the policy
+    #[serde(default)]
+    pub docker: AccessDecision,
+}
+
+impl ContainerPolicyDecision {
+    /// Whether any access was taken away from the request
+    pub fn is_downgraded(&self) -> bool {
+        [self.privileged, self.network, self.docker].contains(&AccessDecision::Downgraded)
+    }
+
+    /// Takes the downgraded accesses away from the executable before it's run. The request keeps the flags it was
+    /// signed with, only the executable run is changed.
+    pub fn apply(&self, executable: &mut Executable) {
+        if self.privileged == AccessDecision::Downgraded {
+            executable.privileged = false;
+        }
+        if self.network == AccessDecision::Downgraded {
+            executable.network_enabled = false;
+        }
+        if self.docker == AccessDecision::Downgraded {
+            executable.docker_access = false;
+        }
+    }
+}
+
+impl ContainerPolicy {
+    /// Decides on the accesses the prover and the verifier of the request ask for, fails on the first rejected one
+    pub fn decide(
+        &self,
+        request: &ProofRequest,
+    ) -> Result<ContainerPolicyDecision, AccessRejected> {
+        let executables = [&request.prover, &request.verifier];
+        let requester = request.requester;
+
+        Ok(ContainerPolicyDecision {
+            privileged: Self::decide_access(
+                "privileged",
+                executables.iter().any(|e| e.privileged),
+                self.privileged,
+                &self.privileged_requesters,
+                requester,
+            )?,
+            network: Self::decide_access(
+                "network enabled",
+                executables.iter().any(|e| e.network_enabled),
+                self.network,
+                &self.network_requesters,
+                requester,
+            )?,
+            docker: Self::decide_access(
+                "docker access",
+                executables.iter().any(|e| e.docker_access),
+                self.docker,
+                &self.docker_requesters,
+                requester,
+            )?,
+        })
+    }
+
+    fn decide_access(
+        access: &'static str,
+        requested: bool,
+        action: AccessAction,
+        allowlist: &[Address],
+        requester: Option<Address>,
+    ) -> Result<AccessDecision, AccessRejected> {
+        if !requested {
+            return Ok(AccessDecision::NotRequested);
+        }
+        match action {
+            AccessAction::Allow => Ok(AccessDecision::Allowed),
+            _ if requester.is_some_and(|r| allowlist.contains(&r)) => {
+                Ok(AccessDecision::Allowlisted)
+            }
+            AccessAction::Downgrade => Ok(AccessDecision::Downgraded),
+            AccessAction::Reject => Err(AccessRejected { access }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn request(privileged: bool, network_enabled: bool) -> ProofRequest {
+        let mut request = fixtures::proof_request();
+        request.prover.privileged = privileged;
+        request.verifier.network_enabled = network_enabled;
+        request
+    }
+
+    fn docker_request() -> ProofRequest {
+        let mut request = fixtures::proof_request();
+        request.verifier.docker_access = true;
+        request
+    }
+
+    #[test]
+    fn test_decide() {
+        let policy = ContainerPolicy::default();
+        assert_eq!(
+            policy.decide(&request(false, true)),
+            Ok(ContainerPolicyDecision {
+                privileged: AccessDecision::NotRequested,
+                network: AccessDecision::Allowed,
+                docker: AccessDecision::NotRequested,
+            })
+        );
+        assert_eq!(
+            policy.decide(&request(true, false)),
+            Err(AccessRejected {
+                access: "privileged"
+            })
+        );
+        // The docker socket controls the host, it's rejected like privileged containers
+        assert_eq!(
+            policy.decide(&docker_request()),
+            Err(AccessRejected {
+                access: "docker access"
+            })
+        );
+
+        // Allowlisted requesters get the access whatever the deployment does with the others
+        let privileged = request(true, true);
+        let policy = ContainerPolicy {
+            privileged: AccessAction::Reject,
+            network: AccessAction::Downgrade,
+            docker: AccessAction::Reject,
+            privileged_requesters: vec![privileged.requester.unwrap()],
+            network_requesters: vec![Address::random()],
+            docker_requesters: vec![],
+        };
+        let decision = policy.decide(&privileged).unwrap();
+        assert_eq!(
+            decision,
+            ContainerPolicyDecision {
+                privileged: AccessDecision::Allowlisted,
+                network: AccessDecision::Downgraded,
+                docker: AccessDecision::NotRequested,
+            }
+        );
+        assert!(decision.is_downgraded());
+        assert!(decision.privileged.is_granted());
+
+        let mut verifier = privileged.verifier.clone();
+        decision.apply(&mut verifier);
+        assert!(!verifier.network_enabled);
+        let mut prover = privileged.prover.clone();
+        decision.apply(&mut prover);
+        assert!(prover.privileged);
+
+        // Downgraded, the executables run without the docker socket
+        let policy = ContainerPolicy {
+            docker: AccessAction::Downgrade,
+            ..ContainerPolicy::default()
+        };
+        let docker = docker_request();
+        let decision = policy.decide(&docker).unwrap();
+        assert_eq!(decision.docker, AccessDecision::Downgraded);
+        assert!(decision.is_downgraded());
+        let mut verifier = docker.verifier.clone();
+        decision.apply(&mut verifier);
+        assert!(!verifier.docker_access);
+
+        let policy = ContainerPolicy {
+            docker_requesters: vec![docker.requester.unwrap()],
+            ..ContainerPolicy::default()
+        };
+        assert_eq!(
+            policy.decide(&docker).unwrap().docker,
+            AccessDecision::Allowlisted
+        );
+    }
+
+    #[test]
+    fn test_decision_roundtrip() {
+        for decision in [
+            AccessDecision::NotRequested,
+            AccessDecision::Allowed,
+            AccessDecision::Allowlisted,
+            AccessDecision::Downgraded,
+        ] {
+            assert_eq!(decision.to_string().parse::<AccessDecision>(), Ok(decision));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use super::{request::ProofRequestId, Proof};
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::Hashable,
+    operator::OperatorId,
+    proof::request::ProofRequest,
+};
+
+/// Challenge of a proven request's proof by its requester, within the dispute window, before the prover is paid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+    pub proof_request_id: ProofRequestId,
+    pub reason: String,
+}
+
+impl Hashable for Dispute {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// How long the proven requests can be disputed for, and how long an operator has to verify a disputed proof before
+/// it's assigned to another one
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DisputePolicy {
+    /// Seconds after the proof the requester can dispute it and the prover isn't paid, no disputes if 0
+    #[arg(long = "dispute-window-secs", default_value_t = 0)]
+    pub window_secs: u64,
+    /// Seconds the operator assigned a dispute has to submit its verdict
+    #[arg(long = "dispute-verification-timeout-secs", default_value_t = 3600)]
+    pub verification_timeout_secs: u64,
+}
+
+impl Default for DisputePolicy {
+    fn default() -> Self {
+        Self {
+            window_secs: 0,
+            verification_timeout_secs: 3600,
+        }
+    }
+}
+
+impl DisputePolicy {
+    pub fn window(&self) -> Duration {
+        seconds(self.window_secs)
+    }
+
+    pub fn verification_timeout(&self) -> Duration {
+        seconds(self.verification_timeout_secs)
+    }
+}
+
+/// Capped at a century, so adding them to a timestamp can't overflow
+fn seconds(secs: u64) -> Duration {
+    const MAX_SECS: u64 = 100 * 365 * 24 * 3600;
+    Duration::seconds(secs.min(MAX_SECS) as i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum DisputeStatus {
+    /// Waiting for an independent operator to verify the proof again
+    Open,
+    /// Being verified again by the operator
+    Verifying(OperatorId),
+    /// The proof is invalid, the requester is refunded
+    Upheld,
+    /// The proof is valid, the prover is paid
+    Dismissed,
+}
+
+impl DisputeStatus {
+    pub fn is_resolved(&self) -> bool {
+        matches!(self, DisputeStatus::Upheld | DisputeStatus::Dismissed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeInfo {
+    pub proof_request_id: ProofRequestId,
+    pub reason: String,
+    pub status: DisputeStatus,
+    pub opened_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Disputed proof an operator has to verify again, with the verifier of the request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeVerification {
+    pub proof_request: SignedData<ProofRequest, EcdsaSigner>,
+    pub proof: Proof,
+}
+
+/// Outcome of verifying a disputed proof again, signed by the operator it was assigned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeVerdict {
+    pub proof_request_id: ProofRequestId,
+    pub proof_valid: bool,
+}
+
+impl Hashable for DisputeVerdict {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_forged_dispute() {
+        let dispute = fixtures::signed(
+            Dispute {
+                proof_request_id: ProofRequestId::from([1; 32]),
+                reason: "wrong output".to_string(),
+            },
+            &fixtures::requester(),
+        );
+        assert!(dispute.verify_payload().is_ok());
+
+        // The requester's signature paired with a dispute of another proof
+        let mut forged = dispute;
+        forged.payload.proof_request_id = ProofRequestId::from([2; 32]);
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+    }
+}
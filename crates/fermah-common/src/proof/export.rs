@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::Hashable,
+    operator::OperatorId,
+    proof::{
+        container_policy::ContainerPolicyDecision,
+        dispute::DisputeInfo,
+        image_validation::ImageValidation,
+        receipt::ProofReceipt,
+        redundancy::RedundancyStatus,
+        request::ProofRequest,
+        status::ProofStatus,
+    },
+    types::audit::AuditEntry,
+};
+
+/// What changed on the request
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RequestEventKind {
+    Status,
+    Payment,
+}
+
+/// Transition of a proof request, as committed by the matchmaker
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEvent {
+    /// Position in the events of all the requests, orders the events of one request
+    pub seq: i64,
+    pub kind: RequestEventKind,
+    /// Status or payment status the request moved to, e.g. `assigned` or `reserved`
+    pub value: String,
+    /// Operator the request was assigned to or acknowledged by
+    pub operator_id: Option<OperatorId>,
+    /// Amount of the payment status
+    pub amount: Option<U256>,
+    /// Anything else of the transition, e.g. the rejection reason
+    pub details: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Everything known about a proof request, from its submission on, to replay what happened to it when debugging a
+/// dispute. Signed by the exporting matchmaker.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestExport {
+    pub proof_request: SignedData<ProofRequest, EcdsaSigner>,
+    /// Status at the time of the export
+    pub status: ProofStatus,
+    /// Status and payment transitions, oldest first. Requests submitted before the events were recorded only have
+    /// the later ones.
+    pub events: Vec<RequestEvent>,
+    /// Copies of a redundantly proven request, with their assignments
+    pub redundancy: Option<RedundancyStatus>,
+    pub image_validation: Option<ImageValidation>,
+    pub container_policy: Option<ContainerPolicyDecision>,
+    pub dispute: Option<DisputeInfo>,
+    pub receipt: Option<SignedData<ProofReceipt, EcdsaSigner>>,
+    /// Audit log entries naming the request, e.g. its payment or refund
+    pub audit_entries: Vec<AuditEntry>,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl Hashable for RequestExport {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Progress of the image checks a proof request goes through before it's accepted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageValidation {
+    pub state: ImageValidationState,
+    /// Remote images downloaded with a matching hash so far
+    pub verified: u32,
+    /// Remote images of the request
+    pub total: u32,
+    /// Download attempts so far
+    pub attempts: u32,
+    /// Why the images were rejected, or why the last attempt failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageValidationState {
+    /// Waiting for the validator
+    Pending,
+    /// Images are being downloaded
+    Validating,
+    /// All the images are available, the request is accepted
+    Passed,
+    /// An image is unavailable or doesn't match its hash, the request is rejected
+    Failed,
+}
+
+impl ImageValidationState {
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Passed | Self::Failed)
+    }
+}
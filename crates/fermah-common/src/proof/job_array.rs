@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    hash::blake3::Blake3Hash,
+    proof::{request::ProofRequestId, status::ProofStatus},
+};
+
+/// Id of a job array, the blake3 hash of its member proof request ids in order
+pub type JobArrayId = Blake3Hash;
+
+pub fn job_array_id(members: &[ProofRequestId]) -> JobArrayId {
+    let mut hasher = blake3::Hasher::new();
+    for member in members {
+        hasher.update(member.as_32_bytes());
+    }
+    Blake3Hash(hasher.finalize())
+}
+
+/// Aggregated status of the proof requests submitted as one job array
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobArrayStatus {
+    pub total: usize,
+    /// Number of members in each status, by status name
+    pub statuses: BTreeMap<String, usize>,
+    /// Number of rejected members for each rejection reason
+    pub failures: BTreeMap<String, usize>,
+}
+
+impl JobArrayStatus {
+    pub fn add(&mut self, status: &ProofStatus) {
+        self.total += 1;
+        *self
+            .statuses
+            .entry(status.to_const_str().to_string())
+            .or_default() += 1;
+
+        if let ProofStatus::Rejected(reason) = status {
+            *self.failures.entry(reason.clone()).or_default() += 1;
+        }
+    }
+
+    /// Number of members in the status, by status name
+    pub fn count(&self, status: &str) -> usize {
+        self.statuses.get(status).copied().unwrap_or_default()
+    }
+
+    pub fn proven(&self) -> usize {
+        self.count("Proven")
+    }
+
+    pub fn rejected(&self) -> usize {
+        self.failures.values().sum()
+    }
+
+    pub fn cancelled(&self) -> usize {
+        self.count("Cancelled")
+    }
+
+    /// Members in a final status
+    pub fn completed(&self) -> usize {
+        self.proven() + self.rejected() + self.cancelled()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.completed() == self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_array_status() {
+        let mut status = JobArrayStatus::default();
+        for s in [
+            ProofStatus::Created,
+            ProofStatus::Accepted,
+            ProofStatus::reject("timeout"),
+            ProofStatus::reject("timeout"),
+            ProofStatus::reject("invalid proof"),
+            ProofStatus::Cancelled,
+        ] {
+            status.add(&s);
+        }
+
+        assert_eq!(status.total, 6);
+        assert_eq!(status.rejected(), 3);
+        assert_eq!(status.completed(), 4);
+        assert_eq!(status.failures.get("timeout"), Some(&2));
+        assert!(!status.is_done());
+
+        let ids: Vec<ProofRequestId> = vec![[1; 32].into(), [2; 32].into()];
+        assert_ne!(
+            job_array_id(&ids),
+            job_array_id(&[ids[1], ids[0]]),
+            "member order matters"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Proof requests as they were stored before the fields appended since their first release. Bincode is positional,
+//! so the stored requests are decoded with the layout they were encoded with and converted, see
+//! [`decode_signed_proof_request`].
+
+use std::{collections::HashMap, path::PathBuf};
+
+use bincode::Options;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData, Signer},
+    executable::{Executable, Image, ImageName, InMount, Injector, ResultExtractor, Source},
+    proof::request::ProofRequest,
+    resource::{gpu::GPUModel, requirement::ResourceRequirement},
+    resources::{ImageHash, LocalResource, RemoteResource},
+    serialization::encoding::hex_encoded,
+};
+
+/// Remote resource before it could have a delta
+#[derive(Deserialize)]
+struct LegacyRemoteResource {
+    url: Url,
+    #[serde(with = "hex_encoded")]
+    hash: ImageHash,
+}
+
+impl From<LegacyRemoteResource> for RemoteResource {
+    fn from(legacy: LegacyRemoteResource) -> Self {
+        Self {
+            url: legacy.url,
+            hash: legacy.hash,
+            delta: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum LegacyImage {
+    Docker(ImageName),
+    RemoteDocker((LegacyRemoteResource, ImageName)),
+    LocalDocker((LocalResource, ImageName)),
+}
+
+#[derive(Deserialize)]
+enum LegacySource {
+    File(LegacyRemoteResource),
+    Files(Vec<(PathBuf, LegacyRemoteResource)>),
+    UnZipDirectory(LegacyRemoteResource),
+}
+
+#[derive(Deserialize)]
+struct LegacyInMount {
+    source: LegacySource,
+    target: PathBuf,
+    temporary: bool,
+}
+
+#[derive(Deserialize)]
+struct LegacyExecutable {
+    image: LegacyImage,
+    platform: Option<String>,
+    in_mounts: Vec<LegacyInMount>,
+    result_extractor: Option<ResultExtractor>,
+    injector: Option<Injector>,
+    entrypoint: Vec<String>,
+    cmd: Vec<String>,
+    env_vars: Option<HashMap<String, String>>,
+    network_enabled: bool,
+    privileged: bool,
+    docker_access: bool,
+}
+
+impl From<LegacyExecutable> for Executable {
+    fn from(legacy: LegacyExecutable) -> Self {
+        let image = match legacy.image {
+            LegacyImage::Docker(name) => Image::Docker(name),
+            LegacyImage::RemoteDocker((resource, name)) => {
+                Image::RemoteDocker((resource.into(), name))
+            }
+            LegacyImage::LocalDocker(local) => Image::LocalDocker(local),
+        };
+        let in_mounts = legacy
+            .in_mounts
+            .into_iter()
+            .map(|mount| {
+                let source = match mount.source {
+                    LegacySource::File(resource) => Source::File(resource.into()),
+                    LegacySource::Files(files) => {
+                        Source::Files(
+                            files
+                                .into_iter()
+                                .map(|(path, resource)| (path, resource.into()))
+                                .collect(),
+                        )
+                    }
+                    LegacySource::UnZipDirectory(resource) => {
+                        Source::UnZipDirectory(resource.into())
+                    }
+                };
+                InMount {
+                    source,
+                    target: mount.target,
+                    temporary: mount.temporary,
+                }
+            })
+            .collect();
+
+        Self {
+            image,
+            platform: legacy.platform,
+            in_mounts,
+            result_extractor: legacy.result_extractor,
+            injector: legacy.injector,
+            entrypoint: legacy.entrypoint,
+            cmd: legacy.cmd,
+            env_vars: legacy.env_vars,
+            network_enabled: legacy.network_enabled,
+            privileged: legacy.privileged,
+            docker_access: legacy.docker_access,
+        }
+    }
+}
+
+/// Resource requirement before it had a TEE, GPU constraints and a class
+#[derive(Deserialize)]
+struct LegacyResourceRequirement {
+    min_vram: Option<u64>,
+    min_ram: Option<u64>,
+    min_ssd: Option<u64>,
+    min_gpu: Vec<GPUModel>,
+    min_cpu_cores: Option<u64>,
+}
+
+impl From<LegacyResourceRequirement> for ResourceRequirement {
+    fn from(legacy: LegacyResourceRequirement) -> Self {
+        Self {
+            min_vram: legacy.min_vram,
+            min_ram: legacy.min_ram,
+            min_ssd: legacy.min_ssd,
+            min_gpu: legacy.min_gpu,
+            min_cpu_cores: legacy.min_cpu_cores,
+            ..Default::default()
+        }
+    }
+}
+
+/// Proof request before it had dependencies, redundancy, regions, a provided proof, a result key, a retention and a
+/// result validator
+#[derive(Deserialize)]
+struct LegacyProofRequest {
+    requester: Option<Address>,
+    prover: LegacyExecutable,
+    verifier: LegacyExecutable,
+    resource_requirement: LegacyResourceRequirement,
+    callback_url: Option<Url>,
+    deadline: Option<DateTime<Utc>>,
+    nonce: u64,
+}
+
+impl From<LegacyProofRequest> for ProofRequest {
+    fn from(legacy: LegacyProofRequest) -> Self {
+        Self {
+            requester: legacy.requester,
+            prover: legacy.prover.into(),
+            verifier: legacy.verifier.into(),
+            resource_requirement: legacy.resource_requirement.into(),
+            callback_url: legacy.callback_url,
+            deadline: legacy.deadline,
+            nonce: legacy.nonce,
+            depends_on: vec![],
+            redundancy: None,
+            preferred_regions: None,
+            proof: None,
+            result_key: None,
+            retention: None,
+            result_validator: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacySignedProofRequest {
+    #[serde(with = "hex_encoded")]
+    hash: <EcdsaSigner as Signer>::Hash,
+    payload: LegacyProofRequest,
+    public_key: <EcdsaSigner as Signer>::VerifyingKey,
+    signature: <EcdsaSigner as Signer>::Signature,
+}
+
+impl From<LegacySignedProofRequest> for SignedData<ProofRequest, EcdsaSigner> {
+    fn from(legacy: LegacySignedProofRequest) -> Self {
+        Self {
+            hash: legacy.hash,
+            payload: legacy.payload.into(),
+            public_key: legacy.public_key,
+            signature: legacy.signature,
+        }
+    }
+}
+
+/// Bincode options the layouts are told apart with: both are decoded to the last byte, so one isn't mistaken for the
+/// other
+fn strict() -> impl Options {
+    bincode::options().with_fixint_encoding()
+}
+
+/// Signed proof request encoded with bincode, of the current layout or of the legacy one. The error is the one of the
+/// current layout if it's of neither.
+pub fn decode_signed_proof_request(
+    bytes: &[u8],
+) -> Result<SignedData<ProofRequest, EcdsaSigner>, bincode::Error> {
+    strict()
+        .deserialize(bytes)
+        .or_else(|err| decode_legacy_signed_proof_request(bytes).map_err(|_| err))
+}
+
+/// Signed proof request encoded with bincode in the legacy layout only, converted to the current one. The hash it was
+/// signed with is kept, the fields it doesn't have don't change it.
+pub fn decode_legacy_signed_proof_request(
+    bytes: &[u8],
+) -> Result<SignedData<ProofRequest, EcdsaSigner>, bincode::Error> {
+    strict()
+        .deserialize::<LegacySignedProofRequest>(bytes)
+        .map(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, hash::blake3::Blake3Hasher};
+
+    /// Vectors of the `compat` suite from before the fields were appended, their JSON still decodes as is
+    fn legacy_vector<T: serde::de::DeserializeOwned>(
+        json: &str,
+        bincode_hex: &str,
+    ) -> (T, Vec<u8>) {
+        (
+            serde_json::from_str(json).unwrap(),
+            const_hex::decode(bincode_hex.trim_end()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_decode_current_layout() {
+        let signed = fixtures::signed_proof_request();
+        let bytes = bincode::serialize(&signed).unwrap();
+        assert_eq!(decode_signed_proof_request(&bytes).unwrap(), signed);
+        assert!(decode_legacy_signed_proof_request(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_legacy_signed_proof_request() {
+        let (signed, bytes): (SignedData<ProofRequest, EcdsaSigner>, _) = legacy_vector(
+            include_str!("../../compat/legacy/signed_proof_request.json"),
+            include_str!("../../compat/legacy/signed_proof_request.bincode.hex"),
+        );
+        assert!(bincode::deserialize::<SignedData<ProofRequest, EcdsaSigner>>(&bytes).is_err());
+
+        assert_eq!(decode_signed_proof_request(&bytes).unwrap(), signed);
+        assert_eq!(decode_legacy_signed_proof_request(&bytes).unwrap(), signed);
+        // Keeps the hash it was signed with
+        assert_eq!(signed.hash, signed.payload.legacy_hash::<Blake3Hasher>());
+    }
+
+    #[test]
+    fn test_decode_legacy_resource_requirement() {
+        let (requirement, bytes): (ResourceRequirement, _) = legacy_vector(
+            include_str!("../../compat/legacy/resource_requirement.json"),
+            include_str!("../../compat/legacy/resource_requirement.bincode.hex"),
+        );
+        assert!(strict().deserialize::<ResourceRequirement>(&bytes).is_err());
+
+        let legacy: LegacyResourceRequirement = strict().deserialize(&bytes).unwrap();
+        assert_eq!(ResourceRequirement::from(legacy), requirement);
+    }
+}
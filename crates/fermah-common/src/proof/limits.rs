@@ -0,0 +1,303 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+use super::request::{ProofRequest, ProvidedProof};
+use crate::executable::{Executable, Source};
+
+/// Default of [`PayloadLimits::max_in_mounts`]
+pub const DEFAULT_MAX_IN_MOUNTS: usize = 16;
+/// Default of [`PayloadLimits::max_mounted_files`]
+pub const DEFAULT_MAX_MOUNTED_FILES: usize = 256;
+/// Default of [`PayloadLimits::max_env_bytes`]
+pub const DEFAULT_MAX_ENV_BYTES: usize = 64 * 1024;
+/// Default of [`PayloadLimits::max_args_bytes`]
+pub const DEFAULT_MAX_ARGS_BYTES: usize = 16 * 1024;
+/// Default of [`PayloadLimits::max_url_len`]
+pub const DEFAULT_MAX_URL_LEN: usize = 2048;
+/// Default of [`PayloadLimits::max_proof_bytes`]
+pub const DEFAULT_MAX_PROOF_BYTES: usize = 1024 * 1024;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PayloadLimitError {
+    #[error("{executable} has {count} mounts, at most {max} are allowed")]
+    TooManyMounts {
+        executable: &'static str,
+        count: usize,
+        max: usize,
+    },
+
+    #[error("{executable} mounts {count} files, at most {max} are allowed")]
+    TooManyMountedFiles {
+        executable: &'static str,
+        count: usize,
+        max: usize,
+    },
+
+    #[error(
+        "environment variables of the {executable} take {bytes} bytes, at most {max} are allowed"
+    )]
+    EnvTooLarge {
+        executable: &'static str,
+        bytes: usize,
+        max: usize,
+    },
+
+    #[error(
+        "entrypoint and command of the {executable} take {bytes} bytes, at most {max} are allowed"
+    )]
+    ArgsTooLarge {
+        executable: &'static str,
+        bytes: usize,
+        max: usize,
+    },
+
+    #[error("URL is {len} characters long, at most {max} are allowed")]
+    UrlTooLong { len: usize, max: usize },
+
+    #[error("provided proof takes {bytes} bytes, at most {max} are allowed")]
+    ProofTooLarge { bytes: usize, max: usize },
+}
+
+/// Bounds of the proof requests accepted at submission, so a requester can't bloat the database with huge payloads
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PayloadLimits {
+    /// Most mounts of the prover or the verifier
+    #[arg(long, default_value_t = DEFAULT_MAX_IN_MOUNTS)]
+    pub max_in_mounts: usize,
+    /// Most files the mounts of the prover or the verifier list
+    #[arg(long, default_value_t = DEFAULT_MAX_MOUNTED_FILES)]
+    pub max_mounted_files: usize,
+    /// Most bytes of the environment variable names and values of the prover or the verifier
+    #[arg(long, default_value_t = DEFAULT_MAX_ENV_BYTES)]
+    pub max_env_bytes: usize,
+    /// Most bytes of the entrypoint and command arguments of the prover or the verifier
+    #[arg(long, default_value_t = DEFAULT_MAX_ARGS_BYTES)]
+    pub max_args_bytes: usize,
+    /// Longest URL of an image, a mounted resource, a provided proof or the callback
+    #[arg(long, default_value_t = DEFAULT_MAX_URL_LEN)]
+    pub max_url_len: usize,
+    /// Most bytes of a proof provided inline for verification
+    #[arg(long, default_value_t = DEFAULT_MAX_PROOF_BYTES)]
+    pub max_proof_bytes: usize,
+}
+
+impl Default for PayloadLimits {
+    fn default() -> Self {
+        Self {
+            max_in_mounts: DEFAULT_MAX_IN_MOUNTS,
+            max_mounted_files: DEFAULT_MAX_MOUNTED_FILES,
+            max_env_bytes: DEFAULT_MAX_ENV_BYTES,
+            max_args_bytes: DEFAULT_MAX_ARGS_BYTES,
+            max_url_len: DEFAULT_MAX_URL_LEN,
+            max_proof_bytes: DEFAULT_MAX_PROOF_BYTES,
+        }
+    }
+}
+
+impl PayloadLimits {
+    /// Checks the request stays within the limits, the first one it goes over is returned
+    pub fn check(&self, request: &ProofRequest) -> Result<(), PayloadLimitError> {
+        self.check_executable("prover", &request.prover)?;
+        self.check_executable("verifier", &request.verifier)?;
+
+        if let Some(ProvidedProof::Inline(proof)) = &request.proof {
+            if proof.len() > self.max_proof_bytes {
+                return Err(PayloadLimitError::ProofTooLarge {
+                    bytes: proof.len(),
+                    max: self.max_proof_bytes,
+                });
+            }
+        }
+        request
+            .urls()
+            .into_iter()
+            .try_for_each(|url| self.check_url(url))
+    }
+
+    fn check_executable(
+        &self,
+        executable: &'static str,
+        exec: &Executable,
+    ) -> Result<(), PayloadLimitError> {
+        let count = exec.in_mounts.len();
+        if count > self.max_in_mounts {
+            return Err(PayloadLimitError::TooManyMounts {
+                executable,
+                count,
+                max: self.max_in_mounts,
+            });
+        }
+
+        let count = exec
+            .in_mounts
+            .iter()
+            .map(|mount| {
+                match &mount.source {
+                    Source::Files(files) => files.len(),
+                    _ => 1,
+                }
+            })
+            .sum();
+        if count > self.max_mounted_files {
+            return Err(PayloadLimitError::TooManyMountedFiles {
+                executable,
+                count,
+                max: self.max_mounted_files,
+            });
+        }
+
+        let bytes = exec
+            .env_vars
+            .iter()
+            .flatten()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        if bytes > self.max_env_bytes {
+            return Err(PayloadLimitError::EnvTooLarge {
+                executable,
+                bytes,
+                max: self.max_env_bytes,
+            });
+        }
+
+        let bytes = exec
+            .entrypoint
+            .iter()
+            .chain(&exec.cmd)
+            .map(String::len)
+            .sum();
+        if bytes > self.max_args_bytes {
+            return Err(PayloadLimitError::ArgsTooLarge {
+                executable,
+                bytes,
+                max: self.max_args_bytes,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn check_url(&self, url: &Url) -> Result<(), PayloadLimitError> {
+        let len = url.as_str().len();
+        if len > self.max_url_len {
+            return Err(PayloadLimitError::UrlTooLong {
+                len,
+                max: self.max_url_len,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use super::*;
+    use crate::{executable::InMount, fixtures};
+
+    fn mount(source: Source) -> InMount {
+        InMount {
+            source,
+            target: PathBuf::from("/input"),
+            temporary: false,
+        }
+    }
+
+    #[test]
+    fn test_payload_limits() {
+        let limits = PayloadLimits::default();
+        assert_eq!(limits.check(&fixtures::proof_request()), Ok(()));
+
+        let mut request = fixtures::proof_request();
+        request.prover.in_mounts =
+            vec![mount(Source::File(fixtures::remote_resource())); DEFAULT_MAX_IN_MOUNTS + 1];
+        assert_eq!(
+            limits.check(&request),
+            Err(PayloadLimitError::TooManyMounts {
+                executable: "prover",
+                count: DEFAULT_MAX_IN_MOUNTS + 1,
+                max: DEFAULT_MAX_IN_MOUNTS,
+            })
+        );
+
+        let mut request = fixtures::proof_request();
+        let files =
+            vec![(PathBuf::from("a"), fixtures::remote_resource()); DEFAULT_MAX_MOUNTED_FILES];
+        request.verifier.in_mounts = vec![mount(Source::Files(files))];
+        assert_eq!(limits.check(&request), Ok(()));
+        request
+            .verifier
+            .in_mounts
+            .push(mount(Source::UnZipDirectory(fixtures::remote_resource())));
+        assert!(matches!(
+            limits.check(&request),
+            Err(PayloadLimitError::TooManyMountedFiles {
+                executable: "verifier",
+                ..
+            })
+        ));
+
+        let mut request = fixtures::proof_request();
+        request.prover.env_vars = Some(HashMap::from([(
+            "KEY".to_string(),
+            "v".repeat(DEFAULT_MAX_ENV_BYTES),
+        )]));
+        assert_eq!(
+            limits.check(&request),
+            Err(PayloadLimitError::EnvTooLarge {
+                executable: "prover",
+                bytes: DEFAULT_MAX_ENV_BYTES + 3,
+                max: DEFAULT_MAX_ENV_BYTES,
+            })
+        );
+
+        let mut request = fixtures::proof_request();
+        request.verifier.cmd = vec!["a".repeat(DEFAULT_MAX_ARGS_BYTES)];
+        assert!(matches!(
+            limits.check(&request),
+            Err(PayloadLimitError::ArgsTooLarge {
+                executable: "verifier",
+                ..
+            })
+        ));
+
+        let long_url: Url = format!("http://localhost/{}", "a".repeat(DEFAULT_MAX_URL_LEN))
+            .parse()
+            .unwrap();
+        let mut request = fixtures::proof_request();
+        request.callback_url = Some(long_url.clone());
+        assert!(matches!(
+            limits.check(&request),
+            Err(PayloadLimitError::UrlTooLong { .. })
+        ));
+        let mut request = fixtures::proof_request();
+        let mut resource = fixtures::remote_resource();
+        resource.url = long_url;
+        request.prover.in_mounts = vec![mount(Source::Manifest(resource))];
+        assert!(matches!(
+            limits.check(&request),
+            Err(PayloadLimitError::UrlTooLong { .. })
+        ));
+
+        let mut request = fixtures::proof_request();
+        request.proof = Some(ProvidedProof::Inline(vec![0; DEFAULT_MAX_PROOF_BYTES + 1]));
+        assert_eq!(
+            limits.check(&request),
+            Err(PayloadLimitError::ProofTooLarge {
+                bytes: DEFAULT_MAX_PROOF_BYTES + 1,
+                max: DEFAULT_MAX_PROOF_BYTES,
+            })
+        );
+
+        // Looser limits let the same request through
+        let limits = PayloadLimits {
+            max_proof_bytes: DEFAULT_MAX_PROOF_BYTES + 1,
+            ..Default::default()
+        };
+        assert_eq!(limits.check(&request), Ok(()));
+    }
+}
@@ -7,15 +7,36 @@ use const_hex::ToHexExt;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    hash::{blake3::Blake3Hash, Hashable},
+    hash::{id::blake3_id, Hashable},
     operator::OperatorId,
+    resource::usage::ResourceUsage,
     serialization::encoding::base64_encoded,
 };
 
+pub mod builder;
+pub mod cancellation;
+pub mod container_policy;
+pub mod dispute;
+pub mod export;
+pub mod image_validation;
+pub mod job_array;
+pub mod legacy;
+pub mod limits;
+pub mod prefetch;
+pub mod receipt;
+pub mod redundancy;
 pub mod request;
+pub mod result_validation;
+pub mod retention;
+pub mod scheduling;
 pub mod status;
+pub mod submission;
+pub mod template;
 
-pub type ProofId = Blake3Hash;
+blake3_id!(
+    /// Hash of a [`Proof`]
+    ProofId
+);
 
 #[derive(Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -56,6 +77,28 @@ impl Hashable for Proof {
     }
 }
 
+/// Proof delivered by the operator, with what the job consumed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofSubmission {
+    pub proof: Proof,
+    /// Left out by operators which don't measure it
+    #[serde(default)]
+    pub usage: Option<ResourceUsage>,
+}
+
+impl Hashable for ProofSubmission {
+    fn collect(&self) -> Cow<[u8]> {
+        let usage = self.usage.as_ref().map(|u| u.collect().into_owned());
+        [
+            self.proof.collect().as_ref(),
+            usage.as_deref().unwrap_or_default(),
+        ]
+        .concat()
+        .into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Proof;
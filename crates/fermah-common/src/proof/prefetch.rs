@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use super::request::ProofRequestId;
+use crate::resources::RemoteResource;
+
+/// Image of an accepted request the operator is likely to be assigned, it should download the image ahead of the
+/// assignment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefetchHint {
+    pub proof_request_id: ProofRequestId,
+    pub image: RemoteResource,
+}
@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    hash::{blake3::Blake3Hasher, Hashable},
+    operator::OperatorId,
+    proof::{request::ProofRequestId, Proof, ProofId},
+};
+
+/// Attestation by the matchmaker that the operator produced the proof for the proof request.
+/// Issued, signed by the matchmaker key, once the request reaches `Proven`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofReceipt {
+    pub proof_request_id: ProofRequestId,
+    pub requester: Address,
+    pub proof_hash: ProofId,
+    pub operator_id: OperatorId,
+    /// When the proof passed verification
+    pub proven_at: DateTime<Utc>,
+    /// When the receipt was issued
+    pub issued_at: DateTime<Utc>,
+}
+
+impl ProofReceipt {
+    pub fn new(
+        proof_request_id: ProofRequestId,
+        requester: Address,
+        proof: &Proof,
+        proven_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            proof_request_id,
+            requester,
+            proof_hash: proof.hash::<Blake3Hasher>().into(),
+            operator_id: proof.prover,
+            proven_at,
+            issued_at: Utc::now(),
+        }
+    }
+}
+
+impl Hashable for ProofReceipt {
+    fn collect(&self) -> Cow<[u8]> {
+        [
+            self.proof_request_id.as_ref(),
+            self.requester.as_bytes(),
+            self.proof_hash.as_ref(),
+            self.operator_id.0.as_bytes(),
+            &self.proven_at.timestamp().to_be_bytes(),
+            &self.issued_at.timestamp().to_be_bytes(),
+        ]
+        .concat()
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::crypto::signer::{ecdsa::EcdsaSigner, SignedData, Signer};
+
+    #[test]
+    fn test_signed_receipt() {
+        let signer = EcdsaSigner::from_key(SigningKey::random(&mut StdRng::seed_from_u64(0)));
+        let proof = Proof::new(vec![1, 2, 3], OperatorId::from(&[1_u8; 20]));
+
+        let receipt = ProofReceipt::new(
+            ProofRequestId::from([7; 32]),
+            Address::random(),
+            &proof,
+            Utc::now(),
+        );
+        assert_eq!(receipt.proof_hash, proof.hash::<Blake3Hasher>().into());
+
+        let signed = SignedData::new(receipt.clone(), &signer).unwrap();
+        assert!(signed.verify().is_ok());
+        assert_eq!(signed.public_key, signer.verifying_key());
+
+        // Any change of the attested data changes the signed hash
+        let mut other = receipt;
+        other.operator_id = OperatorId::from(&[2_u8; 20]);
+        assert_ne!(signed.hash, other.hash::<Blake3Hasher>());
+    }
+}
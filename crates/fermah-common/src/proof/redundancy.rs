@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{request::ProofRequestId, Proof};
+use crate::{hash::blake3::Blake3Hash, operator::OperatorId};
+
+/// Most operators a request can be proven by
+pub const MAX_COPIES: u8 = 8;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedundancyError {
+    #[error("redundancy needs 2 to {MAX_COPIES} copies, got {0}")]
+    Copies(u8),
+
+    #[error("quorum {quorum} of {copies} copies isn't a strict majority")]
+    Quorum { copies: u8, quorum: u8 },
+}
+
+/// Redundant proving: the request is proven independently by `copies` operators, and accepted once `quorum` of them
+/// delivered the same proof. The quorum is a strict majority, so two different proofs can't both reach it.
+///
+/// Proofs are compared byte for byte, the prover has to be deterministic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Redundancy {
+    pub copies: u8,
+    pub quorum: u8,
+}
+
+impl Redundancy {
+    pub fn new(copies: u8, quorum: u8) -> Result<Self, RedundancyError> {
+        let redundancy = Self { copies, quorum };
+        redundancy.validate()?;
+        Ok(redundancy)
+    }
+
+    /// Every copy has to agree
+    pub fn unanimous(copies: u8) -> Result<Self, RedundancyError> {
+        Self::new(copies, copies)
+    }
+
+    pub fn validate(&self) -> Result<(), RedundancyError> {
+        if !(2..=MAX_COPIES).contains(&self.copies) {
+            return Err(RedundancyError::Copies(self.copies));
+        }
+        if self.quorum <= self.copies / 2 || self.quorum > self.copies {
+            return Err(RedundancyError::Quorum {
+                copies: self.copies,
+                quorum: self.quorum,
+            });
+        }
+        Ok(())
+    }
+
+    /// Compares the delivered proofs by their digests, `outstanding` copies are still being proven
+    pub fn agreement(&self, delivered: &[Blake3Hash], outstanding: usize) -> Agreement {
+        let mut groups: HashMap<Blake3Hash, usize> = HashMap::new();
+        for digest in delivered {
+            *groups.entry(*digest).or_default() += 1;
+        }
+
+        let largest = groups.iter().max_by_key(|(_, count)| **count);
+        match largest {
+            Some((digest, count)) if *count >= self.quorum as usize => Agreement::Agreed(*digest),
+            Some((_, count)) if count + outstanding < self.quorum as usize => Agreement::Disagreed,
+            None if outstanding < self.quorum as usize => Agreement::Disagreed,
+            _ => Agreement::Pending,
+        }
+    }
+
+    pub(crate) fn collect(&self) -> [u8; 2] {
+        [self.copies, self.quorum]
+    }
+}
+
+/// Digest redundant proofs are compared by, the proof bytes without the prover
+pub fn proof_digest(proof: &Proof) -> Blake3Hash {
+    Blake3Hash(blake3::hash(&proof.proof))
+}
+
+/// Outcome of comparing the proofs delivered for a redundant request.
+///
+/// On agreement the request is proven and the reserved amount is split among the operators which delivered the
+/// agreed proof, the others aren't paid. Once the quorum can't be reached anymore the request is rejected and the
+/// requester refunded, no operator is paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agreement {
+    /// The quorum can still be reached
+    Pending,
+    Agreed(Blake3Hash),
+    Disagreed,
+}
+
+/// Splits the amount into even shares, the first shares take the remainder
+pub fn split_evenly(amount: U256, parts: usize) -> Vec<U256> {
+    if parts == 0 {
+        return vec![];
+    }
+    let (share, remainder) = amount.div_mod(U256::from(parts));
+    (0..parts)
+        .map(|i| {
+            if U256::from(i) < remainder {
+                share + 1
+            } else {
+                share
+            }
+        })
+        .collect()
+}
+
+/// Copy of a redundant request assigned to one operator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replica {
+    pub operator_id: OperatorId,
+    pub assigned_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// Whether its proof matches the agreed one, unknown until the copies are compared
+    pub agrees: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedundancyStatus {
+    pub proof_request_id: ProofRequestId,
+    pub redundancy: Redundancy,
+    pub replicas: Vec<Replica>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(byte: u8) -> Blake3Hash {
+        Blake3Hash::from([byte; 32])
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Redundancy::new(3, 2).is_ok());
+        assert!(Redundancy::unanimous(2).is_ok());
+        assert_eq!(Redundancy::new(1, 1), Err(RedundancyError::Copies(1)));
+        assert_eq!(
+            Redundancy::new(MAX_COPIES + 1, MAX_COPIES),
+            Err(RedundancyError::Copies(MAX_COPIES + 1))
+        );
+        // Two halves could both reach it
+        assert_eq!(
+            Redundancy::new(4, 2),
+            Err(RedundancyError::Quorum {
+                copies: 4,
+                quorum: 2
+            })
+        );
+        assert!(Redundancy::new(3, 4).is_err());
+    }
+
+    #[test]
+    fn test_agreement() {
+        let redundancy = Redundancy::new(3, 2).unwrap();
+        assert_eq!(redundancy.agreement(&[], 3), Agreement::Pending);
+        assert_eq!(redundancy.agreement(&[digest(1)], 2), Agreement::Pending);
+        assert_eq!(
+            redundancy.agreement(&[digest(1), digest(1)], 1),
+            Agreement::Agreed(digest(1))
+        );
+        // The last copy decides
+        assert_eq!(
+            redundancy.agreement(&[digest(1), digest(2)], 1),
+            Agreement::Pending
+        );
+        assert_eq!(
+            redundancy.agreement(&[digest(1), digest(2), digest(3)], 0),
+            Agreement::Disagreed
+        );
+        // Released copies can't make up the quorum
+        assert_eq!(redundancy.agreement(&[digest(1)], 0), Agreement::Disagreed);
+        assert_eq!(redundancy.agreement(&[], 1), Agreement::Disagreed);
+    }
+
+    #[test]
+    fn test_split_evenly() {
+        assert_eq!(
+            split_evenly(U256::from(10), 3),
+            vec![U256::from(4), U256::from(3), U256::from(3)]
+        );
+        assert_eq!(split_evenly(U256::from(10), 2), vec![U256::from(5); 2]);
+        assert!(split_evenly(U256::from(10), 0).is_empty());
+    }
+}
@@ -1,17 +1,121 @@
 use std::borrow::Cow;
 
 use chrono::{DateTime, Utc};
-use ethers::types::Address;
+use ethers::{
+    abi::{encode, Token},
+    types::{Address, U256},
+    utils::keccak256,
+};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
-    executable::Executable,
-    hash::{blake3::Blake3Hash, Hashable},
-    resource::requirement::ResourceRequirement,
+    crypto::{
+        sealed::ResultKey,
+        signer::{ecdsa::EcdsaSigner, eip712::TypedPayload, SignedData},
+    },
+    executable::{Executable, HashVersion},
+    hash::{id::blake3_id, Hashable, Hasher},
+    http::url_policy::UrlPolicy,
+    operator::OperatorId,
+    proof::{
+        redundancy::Redundancy,
+        result_validation::ResultValidator,
+        retention::ProofRetention,
+        Proof,
+    },
+    resource::{requirement::ResourceRequirement, traits::Price},
+    resources::{DownloadError, RemoteResource},
+    serialization::encoding::base64_encoded,
+    types::{fee::MAX_FEE_BPS, region::PreferredRegions},
 };
 
-pub type ProofRequestId = Blake3Hash;
+blake3_id!(
+    /// Hash of the signed proof request
+    ProofRequestId
+);
+
+/// Share of the price of the resource requirement a verification-only request pays, in basis points. The operator
+/// only runs the verifier.
+pub const VERIFICATION_PRICE_BPS: u16 = 1_000;
+
+/// Proof the requester already has, the network only verifies it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProvidedProof {
+    /// Proof bytes carried in the request
+    Inline(#[serde(with = "base64_encoded")] Vec<u8>),
+    /// Proof artifact the operator downloads and checks against its hash
+    Remote(RemoteResource),
+}
+
+impl ProvidedProof {
+    /// Proof recorded for the operator which verified it. A remote artifact isn't stored, its hash stands in for it.
+    pub fn to_proof(&self, operator: OperatorId) -> Proof {
+        let proof = match self {
+            Self::Inline(proof) => proof.clone(),
+            Self::Remote(resource) => resource.hash.as_32_bytes().to_vec(),
+        };
+        Proof::new(proof, operator)
+    }
+
+    /// Bytes of the proof to give to the verifier, a remote artifact is downloaded and checked against its hash first.
+    /// The URL has to be allowed by the `policy`.
+    pub async fn fetch(&self, policy: &UrlPolicy) -> Result<Vec<u8>, DownloadError> {
+        match self {
+            Self::Inline(proof) => Ok(proof.clone()),
+            Self::Remote(resource) => {
+                let path = resource.download(policy, None).await?;
+                Ok(tokio::fs::read(path.local()).await?)
+            }
+        }
+    }
+}
+
+/// Verification-only request breaking the invariants of its kind
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VerificationOnlyError {
+    #[error("a verification-only request has no prover, the verifier stands in for it")]
+    Prover,
+
+    #[error("verifier has no injector, the provided proof can't be given to it")]
+    NoInjector,
+
+    #[error("provided proof is empty")]
+    EmptyProof,
+}
+
+/// Outcome of verifying the proof provided with a verification-only request, signed by the operator it's assigned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationVerdict {
+    pub proof_request_id: ProofRequestId,
+    pub proof_valid: bool,
+}
+
+impl Hashable for VerificationVerdict {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+impl Hashable for ProvidedProof {
+    fn collect(&self) -> Cow<[u8]> {
+        match self {
+            Self::Inline(proof) => [&[0], proof.as_slice()].concat().into(),
+            Self::Remote(resource) => {
+                [
+                    &[1],
+                    resource.hash.as_32_bytes().as_slice(),
+                    resource.url.as_str().as_bytes(),
+                ]
+                .concat()
+                .into()
+            }
+        }
+    }
+}
 
 /// Proof request payload.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -34,10 +138,167 @@ pub struct ProofRequest {
     /// Nonce
     #[serde(default)]
     pub nonce: u64,
+    /// Requests which have to be proven before this one is assigned. If one of them fails, this one fails too.
+    #[serde(default)]
+    pub depends_on: Vec<ProofRequestId>,
+    /// Prove the request with several operators and cross-check their proofs, proven by one operator if not set
+    #[serde(default)]
+    pub redundancy: Option<Redundancy>,
+    /// Regions of the operators to prove the request with, any operator if not set
+    #[serde(default)]
+    pub preferred_regions: Option<PreferredRegions>,
+    /// Proof to verify instead of proving the request, operators only run the verifier on it. The prover isn't run
+    #[serde(default)]
+    pub proof: Option<ProvidedProof>,
+    /// Key the operator seals the proof to, so only its holder can read it. The matchmaker only stores the sealed
+    /// proof, it isn't tested nor disputable, the operator checks it with the verifier before sealing it
+    #[serde(default)]
+    pub result_key: Option<ResultKey>,
+    /// How long the matchmaker keeps the proof, its default retention if not set
+    #[serde(default)]
+    pub retention: Option<ProofRetention>,
+    /// Structural checks of the result, the request is rejected instead of proven if its result fails them. Not
+    /// allowed for sealed results, which only their requester reads
+    #[serde(default)]
+    pub result_validator: Option<ResultValidator>,
 }
 
-impl Hashable for ProofRequest {
-    fn collect(&self) -> Cow<[u8]> {
+impl ProofRequest {
+    /// Price quoted for the request, used to check the requester can afford it before it is matched. Every redundant
+    /// copy is paid for, a verification-only request pays [`VERIFICATION_PRICE_BPS`] of the price.
+    pub fn quote(&self) -> U256 {
+        self.quote_at(U256::from(
+            self.resource_requirement.price().max(0.0).ceil() as u128,
+        ))
+    }
+
+    /// Price quoted for the request when proving it once costs `price`, e.g. the price of its resource class
+    pub fn quote_at(&self, price: U256) -> U256 {
+        let copies = self.redundancy.map_or(1, |redundancy| redundancy.copies);
+        let price = if self.is_verification_only() {
+            price * U256::from(VERIFICATION_PRICE_BPS) / U256::from(MAX_FEE_BPS)
+        } else {
+            price
+        };
+        price * U256::from(copies)
+    }
+
+    /// Only the provided proof is verified, the request skips the prover stage
+    pub fn is_verification_only(&self) -> bool {
+        self.proof.is_some()
+    }
+
+    /// Checks a verification-only request has a proof to give to its verifier, and no prover: the prover field holds
+    /// the verifier standing in for it, so nothing else can be run for the request. Other requests pass.
+    pub fn check_verification_only(&self) -> Result<(), VerificationOnlyError> {
+        let Some(proof) = &self.proof else {
+            return Ok(());
+        };
+        if self.prover != self.verifier {
+            return Err(VerificationOnlyError::Prover);
+        }
+        if self.verifier.injector.is_none() {
+            return Err(VerificationOnlyError::NoInjector);
+        }
+        if matches!(proof, ProvidedProof::Inline(bytes) if bytes.is_empty()) {
+            return Err(VerificationOnlyError::EmptyProof);
+        }
+        Ok(())
+    }
+
+    /// Executables which have to be downloaded, they are validated before the request is accepted. The prover of a
+    /// verification-only request isn't run, so it isn't downloaded either.
+    pub fn remote_images(&self) -> Vec<&RemoteResource> {
+        let prover = (!self.is_verification_only()).then_some(&self.prover);
+        prover
+            .into_iter()
+            .chain([&self.verifier])
+            .filter_map(|executable| executable.image.remote())
+            .collect()
+    }
+
+    /// Every URL of the request: of the images and their deltas, the mounted resources, the provided proof and the
+    /// callback
+    pub fn urls(&self) -> Vec<&Url> {
+        let resources = self
+            .prover
+            .remote_resources()
+            .into_iter()
+            .chain(self.verifier.remote_resources())
+            .chain(match &self.proof {
+                Some(ProvidedProof::Remote(resource)) => Some(resource),
+                _ => None,
+            })
+            .flat_map(|resource| {
+                [&resource.url]
+                    .into_iter()
+                    .chain(resource.delta.as_ref().map(|delta| &delta.url))
+            });
+        resources.chain(&self.callback_url).collect()
+    }
+
+    fn dependencies_bytes(&self) -> Vec<u8> {
+        self.depends_on
+            .iter()
+            .flat_map(|dependency| dependency.as_32_bytes().to_vec())
+            .collect()
+    }
+
+    fn redundancy_bytes(&self) -> Vec<u8> {
+        self.redundancy
+            .map(|redundancy| redundancy.collect().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn preferred_regions_bytes(&self) -> Vec<u8> {
+        self.preferred_regions
+            .as_ref()
+            .map(PreferredRegions::collect)
+            .unwrap_or_default()
+    }
+
+    fn proof_bytes(&self) -> Vec<u8> {
+        self.proof
+            .as_ref()
+            .map(|proof| proof.collect().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn result_key_bytes(&self) -> Vec<u8> {
+        self.result_key
+            .as_ref()
+            .map(|key| key.0.clone())
+            .unwrap_or_default()
+    }
+
+    fn retention_bytes(&self) -> Vec<u8> {
+        self.retention
+            .map(|retention| retention.collect())
+            .unwrap_or_default()
+    }
+
+    fn result_validator_bytes(&self) -> Vec<u8> {
+        self.result_validator
+            .as_ref()
+            .map(ResultValidator::collect)
+            .unwrap_or_default()
+    }
+
+    /// The proof is sealed to the result key, only its holder can read it
+    pub fn is_sealed(&self) -> bool {
+        self.result_key.is_some()
+    }
+}
+
+impl SignedData<ProofRequest, EcdsaSigner> {
+    pub fn id(&self) -> ProofRequestId {
+        self.hash.into()
+    }
+}
+
+impl ProofRequest {
+    /// Bytes the request is hashed from, with its executables in the given encoding
+    pub fn collect_versioned(&self, version: HashVersion) -> Vec<u8> {
         let mut optionals = vec![];
 
         if let Some(report_url) = &self.callback_url {
@@ -54,15 +315,169 @@ impl Hashable for ProofRequest {
             None => &empty_vec,
         };
 
+        // Appended last, so the hashes of requests without dependencies, redundancy, regions, a proof, a result key,
+        // a retention or a result validator don't change
+        let dependencies = self.dependencies_bytes();
+        let redundancy = self.redundancy_bytes();
+        let preferred_regions = self.preferred_regions_bytes();
+        let proof = self.proof_bytes();
+        let result_key = self.result_key_bytes();
+        let retention = self.retention_bytes();
+        let result_validator = self.result_validator_bytes();
+
         [
             req_bytes,
-            self.prover.collect().as_ref(),
-            self.verifier.collect().as_ref(),
+            self.prover.collect_versioned(version).as_ref(),
+            self.verifier.collect_versioned(version).as_ref(),
             self.resource_requirement.collect().as_ref(),
             optionals.as_ref(),
             self.nonce.to_be_bytes().as_ref(),
+            dependencies.as_ref(),
+            redundancy.as_ref(),
+            preferred_regions.as_ref(),
+            proof.as_ref(),
+            result_key.as_ref(),
+            retention.as_ref(),
+            result_validator.as_ref(),
         ]
         .concat()
-        .into()
+    }
+
+    /// Hash of the request as clients built before [`HashVersion::V1`] still compute it
+    pub fn legacy_hash<HSH: Hasher>(&self) -> HSH::Hash {
+        let mut hasher = HSH::new();
+        hasher.update(&self.collect_versioned(HashVersion::Legacy));
+        hasher.finalize()
+    }
+}
+
+impl Hashable for ProofRequest {
+    fn collect(&self) -> Cow<[u8]> {
+        self.collect_versioned(HashVersion::LATEST).into()
+    }
+}
+
+/// Executables and resource requirement are too complex for wallets to display, so they are committed to by their hashes
+impl TypedPayload for ProofRequest {
+    fn type_hash() -> [u8; 32] {
+        keccak256(
+            "ProofRequest(address requester,bytes32 prover,bytes32 verifier,bytes32 resourceRequirement,\
+             string callbackUrl,uint256 deadline,uint256 nonce,bytes32 dependsOn,bytes32 redundancy,\
+             bytes32 preferredRegions,bytes32 proof,bytes32 resultKey,bytes32 retention,\
+             bytes32 resultValidator)",
+        )
+    }
+
+    fn struct_hash(&self) -> [u8; 32] {
+        let callback_url = self
+            .callback_url
+            .as_ref()
+            .map(|url| url.as_str())
+            .unwrap_or_default();
+        let deadline = self
+            .deadline
+            .map(|d| d.timestamp().max(0) as u64)
+            .unwrap_or_default();
+
+        keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash().to_vec()),
+            Token::Address(self.requester.unwrap_or_default()),
+            Token::FixedBytes(keccak256(self.prover.collect()).to_vec()),
+            Token::FixedBytes(keccak256(self.verifier.collect()).to_vec()),
+            Token::FixedBytes(keccak256(self.resource_requirement.collect()).to_vec()),
+            Token::FixedBytes(keccak256(callback_url).to_vec()),
+            Token::Uint(deadline.into()),
+            Token::Uint(self.nonce.into()),
+            Token::FixedBytes(keccak256(self.dependencies_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.redundancy_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.preferred_regions_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.proof_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.result_key_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.retention_bytes()).to_vec()),
+            Token::FixedBytes(keccak256(self.result_validator_bytes()).to_vec()),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_hex::traits::FromHex;
+    use k256::ecdsa::SigningKey;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        crypto::signer::{eip712::SignatureScheme, Signer},
+        hash::blake3::{Blake3Hash, Blake3Hasher},
+        resources::ImageDelta,
+    };
+
+    const PROOF_REQUEST_JSON: &str = r##"{"requester":"0x70997970c51812dc3a010c7d01b50e0d17dc79c8","prover":{"image":{"remoteDocker":[{"url":"http://localhost:3000/images/groth16_latest.tar.gz","hash":"0x2a7504ffa9ca644ffbd70d76d3ad30795878a2d3efcc37416368e01da44baf39"},"groth16:latest"]},"platform":null,"inMounts":[],"resultExtractor":{"file":"/output/state.bin"},"injector":null,"entrypoint":["/bin/prove"],"cmd":[],"envVars":{"STATE_LOCATION":"/output/state.bin"},"networkEnabled":false,"privileged":false,"dockerAccess":false},"verifier":{"image":{"remoteDocker":[{"url":"http://localhost:3000/images/groth16_latest.tar.gz","hash":"0x2a7504ffa9ca644ffbd70d76d3ad30795878a2d3efcc37416368e01da44baf39"},"groth16:latest"]},"platform":null,"inMounts":[],"resultExtractor":{"negativeExitCode":58},"injector":{"file":"/output/state.bin"},"entrypoint":["/bin/verify"],"cmd":[],"envVars":{"STATE_LOCATION":"/output/state.bin"},"networkEnabled":false,"privileged":false,"dockerAccess":false},"resourceRequirement":{"minVram":null,"minRam":null,"minSsd":null,"minGpu":[],"minCpuCores":2},"callbackUrl":null,"deadline":null,"nonce":217}"##;
+
+    #[tokio::test]
+    async fn test_eip712_signed_request() {
+        let signer = EcdsaSigner::from_key(SigningKey::random(&mut StdRng::seed_from_u64(0)));
+        let mut proof_request: ProofRequest = serde_json::from_str(PROOF_REQUEST_JSON).unwrap();
+        proof_request.requester = Some(signer.verifying_key());
+
+        let typed = SignedData::new_eip712(proof_request.clone(), 17000, &signer)
+            .await
+            .unwrap();
+        assert!(typed.verify().is_ok());
+        assert_eq!(typed.scheme(17000), Some(SignatureScheme::Eip712));
+        // Domain is bound to the chain
+        assert_eq!(typed.scheme(1), None);
+
+        let raw = SignedData::new(proof_request, &signer).unwrap();
+        assert_eq!(raw.scheme(17000), Some(SignatureScheme::Raw));
+        assert_ne!(raw.hash, typed.hash);
+    }
+
+    #[test]
+    fn test_legacy_hash() {
+        let proof_request: ProofRequest = serde_json::from_str(PROOF_REQUEST_JSON).unwrap();
+        // Captured from a client hashing executables with the legacy encoding
+        assert_eq!(
+            proof_request.legacy_hash::<Blake3Hasher>(),
+            Blake3Hash::from_hex(
+                "0x99e6070bde0937991360bdc960ef7f683cd8b3d6514f30ac4f2b04283c76c803"
+            )
+            .unwrap()
+        );
+
+        let mut with_cmd = proof_request.clone();
+        with_cmd.prover.cmd = vec!["--fast".into()];
+        assert_eq!(
+            with_cmd.legacy_hash::<Blake3Hasher>(),
+            proof_request.legacy_hash::<Blake3Hasher>()
+        );
+        assert_ne!(
+            with_cmd.hash::<Blake3Hasher>(),
+            proof_request.hash::<Blake3Hasher>()
+        );
+    }
+
+    #[test]
+    fn test_image_delta_signed() {
+        let proof_request: ProofRequest = serde_json::from_str(PROOF_REQUEST_JSON).unwrap();
+        let delta_url: Url = "http://localhost:3000/images/groth16_latest.delta.zst"
+            .parse()
+            .unwrap();
+        let mut with_delta = proof_request.clone();
+        with_delta.prover.image.remote_mut().unwrap().delta = Some(ImageDelta {
+            base: [7; 32].into(),
+            url: delta_url.clone(),
+        });
+
+        assert_ne!(
+            with_delta.hash::<Blake3Hasher>(),
+            proof_request.hash::<Blake3Hasher>()
+        );
+        assert_ne!(
+            with_delta.legacy_hash::<Blake3Hasher>(),
+            proof_request.legacy_hash::<Blake3Hasher>()
+        );
+        assert!(with_delta.urls().contains(&&delta_url));
+        assert!(!proof_request.urls().contains(&&delta_url));
     }
 }
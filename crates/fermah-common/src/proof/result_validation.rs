@@ -0,0 +1,185 @@
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::serialization::encoding::hex_encoded;
+
+/// Prefix of the reason the requests whose result fails their validator are rejected with
+pub const INVALID_RESULT_FORMAT: &str = "InvalidResultFormat";
+
+/// Longest JSON schema a validator may carry
+pub const MAX_JSON_SCHEMA_LEN: usize = 64 * 1024;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidResultValidator {
+    #[error("minimum length {min} is above the maximum length {max}")]
+    LengthBounds { min: u64, max: u64 },
+    #[error("JSON schema is longer than {MAX_JSON_SCHEMA_LEN} bytes")]
+    SchemaTooLong,
+    #[error("invalid JSON schema: {0}")]
+    Schema(String),
+}
+
+/// Why a result doesn't pass the validator of its request
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidResultFormat {
+    #[error("result is {len} bytes, shorter than {min}")]
+    TooShort { len: u64, min: u64 },
+    #[error("result is {len} bytes, longer than {max}")]
+    TooLong { len: u64, max: u64 },
+    #[error("result doesn't start with the magic bytes")]
+    Magic,
+    #[error("result isn't a JSON document: {0}")]
+    NotJson(String),
+    #[error("result doesn't match the JSON schema: {0}")]
+    Schema(String),
+}
+
+/// Structural checks of the extracted result, run by the matchmaker once the proof verified. A proof whose result
+/// fails them isn't proven, its request is rejected with [`INVALID_RESULT_FORMAT`]. Only the set checks are run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultValidator {
+    /// Fewest bytes the result has
+    #[serde(default)]
+    pub min_len: Option<u64>,
+    /// Most bytes the result has
+    #[serde(default)]
+    pub max_len: Option<u64>,
+    /// Bytes the result starts with, e.g. the magic number of its file format
+    #[serde(default, with = "hex_encoded")]
+    pub magic: Vec<u8>,
+    /// JSON schema the result is a document of, as JSON text
+    #[serde(default)]
+    pub json_schema: Option<String>,
+}
+
+impl ResultValidator {
+    pub fn validate(&self) -> Result<(), InvalidResultValidator> {
+        if let (Some(min), Some(max)) = (self.min_len, self.max_len) {
+            if min > max {
+                return Err(InvalidResultValidator::LengthBounds { min, max });
+            }
+        }
+        if let Some(schema) = &self.json_schema {
+            if schema.len() > MAX_JSON_SCHEMA_LEN {
+                return Err(InvalidResultValidator::SchemaTooLong);
+            }
+            compile_schema(schema).map_err(InvalidResultValidator::Schema)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the result, the cheap checks first
+    pub fn check(&self, result: &[u8]) -> Result<(), InvalidResultFormat> {
+        let len = result.len() as u64;
+        if let Some(min) = self.min_len.filter(|min| len < *min) {
+            return Err(InvalidResultFormat::TooShort { len, min });
+        }
+        if let Some(max) = self.max_len.filter(|max| len > *max) {
+            return Err(InvalidResultFormat::TooLong { len, max });
+        }
+        if !result.starts_with(&self.magic) {
+            return Err(InvalidResultFormat::Magic);
+        }
+
+        let Some(schema) = &self.json_schema else {
+            return Ok(());
+        };
+        // Accepted with the request, it only fails to compile if the request was stored unchecked
+        let schema = compile_schema(schema).map_err(InvalidResultFormat::Schema)?;
+        let document: serde_json::Value = serde_json::from_slice(result)
+            .map_err(|err| InvalidResultFormat::NotJson(err.to_string()))?;
+        if let Err(mut errors) = schema.validate(&document) {
+            let reason = errors
+                .next()
+                .map(|err| format!("{err} at {}", err.instance_path))
+                .unwrap_or_default();
+            return Err(InvalidResultFormat::Schema(reason));
+        }
+        Ok(())
+    }
+
+    /// Bytes the request commits to the validator with
+    pub(crate) fn collect(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+}
+
+fn compile_schema(schema: &str) -> Result<JSONSchema, String> {
+    let schema: serde_json::Value = serde_json::from_str(schema).map_err(|err| err.to_string())?;
+    JSONSchema::compile(&schema).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_result() {
+        let validator = ResultValidator {
+            min_len: Some(4),
+            max_len: Some(64),
+            magic: b"{\"".to_vec(),
+            json_schema: Some(
+                r#"{"type":"object","required":["root"],"properties":{"root":{"type":"string"}}}"#
+                    .to_string(),
+            ),
+        };
+        assert_eq!(validator.validate(), Ok(()));
+
+        assert_eq!(validator.check(br#"{"root":"0x01"}"#), Ok(()));
+        assert_eq!(
+            validator.check(b"{}"),
+            Err(InvalidResultFormat::TooShort { len: 2, min: 4 })
+        );
+        assert_eq!(
+            validator.check(&[b'{'; 65]),
+            Err(InvalidResultFormat::TooLong { len: 65, max: 64 })
+        );
+        assert_eq!(
+            validator.check(b"[\"root\"]"),
+            Err(InvalidResultFormat::Magic)
+        );
+        assert!(matches!(
+            validator.check(br#"{"root""#),
+            Err(InvalidResultFormat::NotJson(_))
+        ));
+        assert!(matches!(
+            validator.check(br#"{"root":1}"#),
+            Err(InvalidResultFormat::Schema(_))
+        ));
+
+        // Nothing set, anything passes
+        assert_eq!(ResultValidator::default().check(&[0, 9, 6, 0]), Ok(()));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            ResultValidator {
+                min_len: Some(8),
+                max_len: Some(4),
+                ..Default::default()
+            }
+            .validate(),
+            Err(InvalidResultValidator::LengthBounds { min: 8, max: 4 })
+        );
+        assert!(matches!(
+            ResultValidator {
+                json_schema: Some(r#"{"type":"nothing"}"#.to_string()),
+                ..Default::default()
+            }
+            .validate(),
+            Err(InvalidResultValidator::Schema(_))
+        ));
+        assert_eq!(
+            ResultValidator {
+                json_schema: Some(" ".repeat(MAX_JSON_SCHEMA_LEN + 1)),
+                ..Default::default()
+            }
+            .validate(),
+            Err(InvalidResultValidator::SchemaTooLong)
+        );
+    }
+}
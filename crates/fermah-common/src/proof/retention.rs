@@ -0,0 +1,39 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::request::ProofRequestId;
+use crate::hash::Hashable;
+
+/// How long the matchmaker keeps the proof of a proven request once the dispute window closed. Only the proof is
+/// deleted, the request and its status are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProofRetention {
+    /// Deleted this many days after the request is proven
+    Days(u16),
+    /// Deleted once the requester acknowledged retrieving it
+    UntilRetrieved,
+}
+
+impl ProofRetention {
+    pub(crate) fn collect(&self) -> Vec<u8> {
+        match self {
+            ProofRetention::Days(days) => [&[0][..], &days.to_be_bytes()].concat(),
+            ProofRetention::UntilRetrieved => vec![1],
+        }
+    }
+}
+
+/// Retrieval of a proven request's proof, acknowledged by its requester
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofRetrieval {
+    pub proof_request_id: ProofRequestId,
+}
+
+impl Hashable for ProofRetrieval {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
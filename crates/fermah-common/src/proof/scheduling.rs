@@ -0,0 +1,162 @@
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+/// Order the requests waiting for an operator are offered to the matching in
+#[derive(
+    Serialize,
+    Deserialize,
+    strum::Display,
+    ValueEnum,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RequestOrdering {
+    /// As they are loaded
+    #[default]
+    Unordered,
+    /// The requests due first before the others, so the ones close to their deadline jump the queue
+    EarliestDeadlineFirst,
+}
+
+/// Deadline of a submitted request which can't be scheduled
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DeadlineError {
+    #[error("deadline {0} has passed")]
+    Passed(DateTime<Utc>),
+    #[error("deadline {deadline} is less than {min_lead_secs}s away")]
+    TooNear {
+        deadline: DateTime<Utc>,
+        min_lead_secs: u64,
+    },
+}
+
+/// How the requests waiting for an operator are ordered. With the earliest deadline first, a request without a
+/// deadline is due once it waited `max_wait_without_deadline_secs` since it was accepted, so the requests with a
+/// deadline can't hold it back for good. The requests whose deadline passed go after the others.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssignmentScheduling {
+    #[arg(long, value_enum, default_value_t)]
+    pub request_ordering: RequestOrdering,
+    /// Seconds after its acceptance a request without a deadline is scheduled as due
+    #[arg(long, default_value_t = 300)]
+    pub max_wait_without_deadline_secs: u64,
+    /// Seconds a submitted request's deadline must at least be away, so it isn't submitted to jump the queue
+    #[arg(long, default_value_t = 60)]
+    pub min_deadline_lead_secs: u64,
+}
+
+impl Default for AssignmentScheduling {
+    fn default() -> Self {
+        Self {
+            request_ordering: RequestOrdering::default(),
+            max_wait_without_deadline_secs: 300,
+            min_deadline_lead_secs: 60,
+        }
+    }
+}
+
+impl AssignmentScheduling {
+    pub fn max_wait_without_deadline(&self) -> Duration {
+        Duration::seconds(
+            self.max_wait_without_deadline_secs
+                .min(i64::MAX as u64 / 1000) as i64,
+        )
+    }
+
+    pub fn min_deadline_lead(&self) -> Duration {
+        Duration::seconds(self.min_deadline_lead_secs.min(i64::MAX as u64 / 1000) as i64)
+    }
+
+    /// Checks the deadline of a request submitted at `now`, it has to be at least the minimum lead away
+    pub fn check_deadline(
+        &self,
+        deadline: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<(), DeadlineError> {
+        match deadline {
+            Some(deadline) if deadline <= now => Err(DeadlineError::Passed(deadline)),
+            Some(deadline) if deadline < now + self.min_deadline_lead() => {
+                Err(DeadlineError::TooNear {
+                    deadline,
+                    min_lead_secs: self.min_deadline_lead_secs,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// When the request accepted at `accepted` is due, its deadline if it has one
+    pub fn due(&self, deadline: Option<DateTime<Utc>>, accepted: DateTime<Utc>) -> DateTime<Utc> {
+        deadline.unwrap_or_else(|| accepted + self.max_wait_without_deadline())
+    }
+
+    /// Key the waiting requests are sorted by at `now`, the requests whose deadline passed after the others as
+    /// they can't be proven in time anymore
+    pub fn priority(
+        &self,
+        deadline: Option<DateTime<Utc>>,
+        accepted: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> (bool, DateTime<Utc>) {
+        (
+            deadline.is_some_and(|deadline| deadline <= now),
+            self.due(deadline, accepted),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due() {
+        let now = Utc::now();
+        let scheduling = AssignmentScheduling::default();
+        let deadline = now + Duration::minutes(1);
+        assert_eq!(scheduling.due(Some(deadline), now), deadline);
+
+        // Waiting long enough, it goes before the requests with a later deadline
+        let accepted = now - Duration::minutes(10);
+        assert!(scheduling.due(None, accepted) < deadline);
+        assert!(scheduling.due(None, now) > deadline);
+
+        // Missed, it goes after the others
+        let missed = now - Duration::minutes(1);
+        assert!(scheduling.priority(Some(missed), now, now) > scheduling.priority(None, now, now));
+    }
+
+    #[test]
+    fn test_check_deadline() {
+        let now = Utc::now();
+        let scheduling = AssignmentScheduling::default();
+        assert_eq!(scheduling.check_deadline(None, now), Ok(()));
+        assert_eq!(
+            scheduling.check_deadline(Some(now + Duration::minutes(5)), now),
+            Ok(())
+        );
+
+        let passed = now - Duration::seconds(1);
+        assert_eq!(
+            scheduling.check_deadline(Some(passed), now),
+            Err(DeadlineError::Passed(passed))
+        );
+        let near = now + Duration::seconds(10);
+        assert_eq!(
+            scheduling.check_deadline(Some(near), now),
+            Err(DeadlineError::TooNear {
+                deadline: near,
+                min_lead_secs: 60
+            })
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
-use crate::{operator::OperatorId, proof::Proof};
+use crate::{
+    operator::OperatorId,
+    proof::{cancellation::CancellationReason, Proof},
+};
 
 #[derive(Serialize, Deserialize, Display, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -14,9 +18,10 @@ pub enum ProofStatus {
     /// Passed most basic checks like signature
     Accepted,
 
-    /// Closed, primarely due to PRer returning their funds
+    /// Withdrawn before it was proven, the [`CancellationReason`] is reported with the status in a [`StatusReport`].
+    /// The reservation is refunded, minus the cancellation fee of an assigned request
     Cancelled,
-    /// Rejected with explanation
+    /// Rejected with explanation, the request or its proof was found invalid
     /// - Eligible for payment
     Rejected(String),
 
@@ -34,6 +39,9 @@ pub enum ProofStatus {
 }
 
 impl ProofStatus {
+    /// Reason of the requests rejected for staying in Created too long
+    pub const EXPIRED: &'static str = "expired";
+
     pub fn reject<R: Display>(reason: R) -> Self {
         Self::Rejected(reason.to_string())
     }
@@ -55,3 +63,20 @@ impl ProofStatus {
         }
     }
 }
+
+/// Status of a request with why it was cancelled and whether its proof is still kept, which the status itself doesn't
+/// carry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusReport {
+    pub status: ProofStatus,
+    /// Set for the cancelled requests, `None` for the ones cancelled before the reasons were recorded
+    #[serde(default)]
+    pub cancellation_reason: Option<CancellationReason>,
+    /// Set once the proof of the proven request is deleted past its retention, the proof of the status is empty then
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_deleted_at: Option<DateTime<Utc>>,
+    /// Whether the proofs of the request are sealed to its result key, the requester opens them with its key then
+    #[serde(default)]
+    pub sealed: bool,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use super::{request::ProofRequestId, status::ProofStatus};
+
+/// Outcome of a proof request submission. Submitting the identical signed request again, e.g. when retrying after a
+/// network error, isn't an error, the request is reported as `existing` with its current status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofSubmission {
+    pub proof_request_id: ProofRequestId,
+    pub status: ProofStatus,
+    /// The request was already submitted, it wasn't submitted again
+    pub existing: bool,
+}
@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{operator::OperatorId, proof::request::ProofRequestId};
+
+/// Prefix of the placeholder names, the `${...}` references without it are left as they are, e.g. for a shell
+pub const PLACEHOLDER_PREFIX: &str = "FERMAH_";
+
+/// Value only known once the request is assigned, substituted by the operator in the command and the environment
+/// variables of the executables when it runs them. Written `${FERMAH_REQUEST_ID}`, the signed request keeps the
+/// placeholders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum Placeholder {
+    /// Id of the proof request, `${FERMAH_REQUEST_ID}`
+    RequestId,
+    /// Id of the operator running the executable, `${FERMAH_OPERATOR_ID}`
+    OperatorId,
+    /// Latest block the operator saw when the run started, `${FERMAH_BLOCK}`
+    Block,
+}
+
+impl Placeholder {
+    pub const ALL: [Self; 3] = [Self::RequestId, Self::OperatorId, Self::Block];
+
+    /// Reference to the placeholder in a template, e.g. `${FERMAH_BLOCK}`
+    pub fn reference(&self) -> String {
+        format!("${{{PLACEHOLDER_PREFIX}{self}}}")
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("unknown placeholder ${{{0}}}, the known ones are {known}", known = known_placeholders())]
+    UnknownPlaceholder(String),
+
+    #[error("placeholder {0:?} isn't closed")]
+    Unclosed(String),
+}
+
+fn known_placeholders() -> String {
+    Placeholder::ALL
+        .iter()
+        .map(Placeholder::reference)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Values the placeholders are substituted with for a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateVars {
+    pub request_id: ProofRequestId,
+    pub operator_id: OperatorId,
+    pub block: u64,
+}
+
+impl TemplateVars {
+    pub fn value(&self, placeholder: Placeholder) -> String {
+        match placeholder {
+            Placeholder::RequestId => self.request_id.to_string(),
+            Placeholder::OperatorId => self.operator_id.to_string(),
+            Placeholder::Block => self.block.to_string(),
+        }
+    }
+}
+
+/// Text with its placeholders replaced by what `value` gives for them
+fn render(
+    text: &str,
+    mut value: impl FnMut(Placeholder) -> String,
+) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let (before, reference) = rest.split_at(start);
+        rendered.push_str(before);
+
+        let inner = &reference[2..];
+        let Some(end) = inner.find('}') else {
+            if inner.starts_with(PLACEHOLDER_PREFIX) {
+                return Err(TemplateError::Unclosed(reference.to_string()));
+            }
+            rendered.push_str(reference);
+            return Ok(rendered);
+        };
+
+        let name = &inner[..end];
+        match name.strip_prefix(PLACEHOLDER_PREFIX) {
+            Some(placeholder) => {
+                let placeholder = Placeholder::from_str(placeholder)
+                    .map_err(|_| TemplateError::UnknownPlaceholder(name.to_string()))?;
+                rendered.push_str(&value(placeholder));
+            }
+            None => rendered.push_str(&reference[..end + 3]),
+        }
+        rest = &inner[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Fails if the text refers to an unknown placeholder
+pub fn check(text: &str) -> Result<(), TemplateError> {
+    render(text, |_| String::new()).map(|_| ())
+}
+
+/// Text with its placeholders substituted
+pub fn substitute(text: &str, vars: &TemplateVars) -> Result<String, TemplateError> {
+    render(text, |placeholder| vars.value(placeholder))
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let vars = TemplateVars {
+            request_id: ProofRequestId::from([1; 32]),
+            operator_id: OperatorId::from(Address::from_low_u64_be(2)),
+            block: 42,
+        };
+
+        assert_eq!(
+            substitute(
+                "--block=${FERMAH_BLOCK} --out=${HOME}/${FERMAH_BLOCK}",
+                &vars
+            ),
+            Ok("--block=42 --out=${HOME}/42".to_string())
+        );
+        assert_eq!(
+            substitute(&Placeholder::RequestId.reference(), &vars),
+            Ok(vars.request_id.to_string())
+        );
+        assert_eq!(
+            substitute("no placeholder $ {", &vars),
+            Ok("no placeholder $ {".to_string())
+        );
+        assert_eq!(
+            substitute("${unclosed", &vars),
+            Ok("${unclosed".to_string())
+        );
+
+        assert_eq!(
+            check("${FERMAH_TIMESTAMP}"),
+            Err(TemplateError::UnknownPlaceholder(
+                "FERMAH_TIMESTAMP".to_string()
+            ))
+        );
+        assert_eq!(
+            check("--id=${FERMAH_REQUEST_ID"),
+            Err(TemplateError::Unclosed("${FERMAH_REQUEST_ID".to_string()))
+        );
+        for placeholder in Placeholder::ALL {
+            assert_eq!(check(&placeholder.reference()), Ok(()));
+        }
+    }
+}
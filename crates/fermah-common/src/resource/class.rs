@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{gpu::GPUVendor, memory::GIGA_BYTE, requirement::ResourceRequirement};
+use crate::proof::request::ProofRequest;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ResourceClassError {
+    #[error("unknown resource class {0:?}")]
+    Unknown(String),
+
+    #[error("resource class {0:?} can't be combined with explicit requirements")]
+    Combined(String),
+}
+
+/// Requirement a class name stands for, and the price of a request of the class
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceClass {
+    pub requirement: ResourceRequirement,
+    pub price: u64,
+}
+
+/// Named resource classes the requests may ask for instead of spelling their requirement out, e.g. `small` or
+/// `gpu-large`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ResourceClasses(pub BTreeMap<String, ResourceClass>);
+
+impl Default for ResourceClasses {
+    fn default() -> Self {
+        let class = |requirement, price| ResourceClass { requirement, price };
+        Self(BTreeMap::from([
+            (
+                "small".to_string(),
+                class(
+                    ResourceRequirement {
+                        min_cpu_cores: Some(2),
+                        min_ram: Some(4 * GIGA_BYTE),
+                        ..Default::default()
+                    },
+                    100,
+                ),
+            ),
+            (
+                "medium".to_string(),
+                class(
+                    ResourceRequirement {
+                        min_cpu_cores: Some(8),
+                        min_ram: Some(16 * GIGA_BYTE),
+                        min_ssd: Some(50 * GIGA_BYTE),
+                        ..Default::default()
+                    },
+                    400,
+                ),
+            ),
+            (
+                "large".to_string(),
+                class(
+                    ResourceRequirement {
+                        min_cpu_cores: Some(32),
+                        min_ram: Some(64 * GIGA_BYTE),
+                        min_ssd: Some(200 * GIGA_BYTE),
+                        ..Default::default()
+                    },
+                    1600,
+                ),
+            ),
+            (
+                "gpu-large".to_string(),
+                class(
+                    ResourceRequirement {
+                        min_cpu_cores: Some(16),
+                        min_ram: Some(64 * GIGA_BYTE),
+                        min_ssd: Some(200 * GIGA_BYTE),
+                        min_vram: Some(24 * GIGA_BYTE),
+                        gpu_vendor: Some(GPUVendor::Nvidia),
+                        ..Default::default()
+                    },
+                    5000,
+                ),
+            ),
+        ]))
+    }
+}
+
+impl ResourceClasses {
+    pub fn get(&self, name: &str) -> Option<&ResourceClass> {
+        self.0.get(name)
+    }
+
+    /// Requirement the class named by the requirement stands for, keeping the name. It's `None` for requirements
+    /// without a class, the ones naming an unknown class or setting fields of their own as well are rejected. The
+    /// requirement itself is left as it is, a signed request keeps the hash it was signed with.
+    pub fn expanded(
+        &self,
+        requirement: &ResourceRequirement,
+    ) -> Result<Option<ResourceRequirement>, ResourceClassError> {
+        let Some(name) = requirement.class.clone() else {
+            return Ok(None);
+        };
+        let class = self
+            .get(&name)
+            .ok_or_else(|| ResourceClassError::Unknown(name.clone()))?;
+        if !requirement.is_class_only() {
+            return Err(ResourceClassError::Combined(name));
+        }
+
+        Ok(Some(ResourceRequirement {
+            class: Some(name),
+            ..class.requirement.clone()
+        }))
+    }
+
+    /// Price quoted for the request at the price of its class, see [`ProofRequest::quote`] for the requests without
+    /// one
+    pub fn quote(&self, proof_request: &ProofRequest) -> U256 {
+        match proof_request
+            .resource_requirement
+            .class
+            .as_deref()
+            .and_then(|name| self.get(name))
+        {
+            Some(class) => proof_request.quote_at(U256::from(class.price)),
+            None => proof_request.quote(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_expanded() {
+        let classes = ResourceClasses::default();
+        let requirement = ResourceRequirement {
+            class: Some("gpu-large".to_string()),
+            ..Default::default()
+        };
+        assert!(requirement.is_class_only());
+        let expanded = classes.expanded(&requirement).unwrap().unwrap();
+        assert_eq!(expanded.min_vram, Some(24 * GIGA_BYTE));
+        assert_eq!(expanded.gpu_vendor, Some(GPUVendor::Nvidia));
+        assert_eq!(expanded.class.as_deref(), Some("gpu-large"));
+        // The signed requirement isn't touched
+        assert!(requirement.is_class_only());
+
+        let explicit = ResourceRequirement {
+            min_cpu_cores: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(classes.expanded(&explicit), Ok(None));
+
+        let unknown = ResourceRequirement {
+            class: Some("huge".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            classes.expanded(&unknown),
+            Err(ResourceClassError::Unknown("huge".to_string()))
+        );
+
+        let combined = ResourceRequirement {
+            class: Some("small".to_string()),
+            min_cpu_cores: Some(64),
+            ..Default::default()
+        };
+        assert_eq!(
+            classes.expanded(&combined),
+            Err(ResourceClassError::Combined("small".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        let classes = ResourceClasses::default();
+        let mut proof_request = fixtures::proof_request();
+        assert_eq!(classes.quote(&proof_request), proof_request.quote());
+
+        proof_request.resource_requirement = ResourceRequirement {
+            class: Some("large".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(classes.quote(&proof_request), U256::from(1600));
+    }
+}
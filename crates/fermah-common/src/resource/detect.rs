@@ -0,0 +1,443 @@
+//! Detection of the resources of the host an operator runs on, the [`Resource`] it claims when it registers. RAM,
+//! disks and CPU come from sysinfo, which reads `/proc`, and from `/sys`, NVIDIA GPUs from NVML. What can't be
+//! probed without privileges, e.g. the RAM type, or is wrong in a VM, is overridden from the config.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use nvml_wrapper::{enum_wrappers::device::Clock, error::NvmlError, Nvml};
+use serde::{Deserialize, Serialize};
+use sysinfo::{CpuRefreshKind, DiskKind, Disks, MemoryRefreshKind, RefreshKind, System};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use super::{
+    cpu::{CPUSpecs, CPU},
+    gpu::{GPUDriver, GPUMemoryType, GPUSpecs, GPUVendor, GPUVersion, GPU},
+    memory::{Memory, RAMMemoryType, SSDMemoryType},
+    tee::Tee,
+    Resource,
+};
+
+/// Highest clock rate of the first CPU, in kHz
+const CPU_MAX_FREQ: &str = "/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq";
+const SGX_DEVICE: &str = "/dev/sgx_enclave";
+const SEV_GUEST_DEVICE: &str = "/dev/sev-guest";
+
+#[derive(Error, Debug)]
+pub enum DetectError {
+    #[error("no disk is mounted at {0:?} or above it")]
+    NoDisk(PathBuf),
+
+    #[error("failed to count the CPU cores: {0}")]
+    Cores(#[source] std::io::Error),
+}
+
+/// Parts of the detected resource replaced by the config, each one left unset is detected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceOverrides {
+    /// Directory the images and the results are stored in, the SSD is the disk it's on. The root one by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+    /// RAM in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram: Option<u64>,
+    /// Not exposed to unprivileged processes, the default type is claimed unless it's set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_type: Option<RAMMemoryType>,
+    /// SSD in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssd: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssd_type: Option<SSDMemoryType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<CPU>,
+    /// GPUs claimed instead of the detected ones, e.g. to keep some of them for another workload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpus: Option<Vec<GPU>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_driver: Option<GPUDriver>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tee: Option<Tee>,
+}
+
+impl ResourceOverrides {
+    /// Detected resource with the overridden parts replaced
+    pub fn apply(&self, mut resource: Resource) -> Resource {
+        if let Some(ram) = self.ram {
+            resource.ram.size = ram;
+        }
+        if let Some(ram_type) = &self.ram_type {
+            resource.ram.r#type = ram_type.clone();
+        }
+        if let Some(ssd) = self.ssd {
+            resource.ssd.size = ssd;
+        }
+        if let Some(ssd_type) = &self.ssd_type {
+            resource.ssd.r#type = ssd_type.clone();
+        }
+        if let Some(cpu) = &self.cpu {
+            resource.cpu = cpu.clone();
+        }
+        if let Some(gpus) = &self.gpus {
+            resource.gpus = gpus.clone();
+        }
+        if let Some(gpu_driver) = self.gpu_driver {
+            resource.gpu_driver = Some(gpu_driver);
+        }
+        if let Some(tee) = self.tee {
+            resource.tee = tee;
+        }
+        resource
+    }
+}
+
+/// Resource of the host, with the overrides applied
+pub fn detect_resource(overrides: &ResourceOverrides) -> Result<Resource, DetectError> {
+    let system = System::new_with_specifics(
+        RefreshKind::new()
+            .with_memory(MemoryRefreshKind::new().with_ram())
+            .with_cpu(CpuRefreshKind::new().with_frequency()),
+    );
+
+    let data_dir = overrides
+        .data_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("/"));
+    let (gpus, gpu_driver) = if overrides.gpus.is_some() {
+        (vec![], overrides.gpu_driver)
+    } else {
+        detect_nvidia_gpus()
+    };
+
+    let detected = Resource {
+        ram: Memory {
+            size: ram_size(&system),
+            r#type: Memory::<RAMMemoryType>::default().r#type,
+        },
+        ssd: detect_ssd(&data_dir)?,
+        gpus,
+        cpu: CPU::Specs(detect_cpu(&system)?),
+        tee: detect_tee(),
+        gpu_driver,
+    };
+    let resource = overrides.apply(detected);
+    debug!(?resource, "detected the resource of the host");
+    Ok(resource)
+}
+
+/// RAM the operator can use, the limit of its cgroup in a container
+fn ram_size(system: &System) -> u64 {
+    system
+        .cgroup_limits()
+        .map(|limits| limits.total_memory)
+        .filter(|limit| *limit > 0)
+        .unwrap_or_else(|| system.total_memory())
+        .min(system.total_memory())
+}
+
+/// Cores the operator can run on, which are fewer than the host's under a CPU quota or affinity mask
+fn detect_cpu(system: &System) -> Result<CPUSpecs, DetectError> {
+    let cores = std::thread::available_parallelism()
+        .map_err(DetectError::Cores)?
+        .get() as u64;
+    // The frequencies sysinfo reads are the current ones, below the highest while the CPU idles
+    let clock_rate = fs::read_to_string(CPU_MAX_FREQ)
+        .ok()
+        .and_then(|khz| parse_khz(&khz))
+        .unwrap_or_else(|| {
+            system
+                .cpus()
+                .iter()
+                .map(|cpu| cpu.frequency())
+                .max()
+                .unwrap_or_default()
+                * 1_000_000
+        });
+    Ok(CPUSpecs { cores, clock_rate })
+}
+
+/// Clock rate in Hz of a frequency in kHz, as cpufreq gives it
+fn parse_khz(khz: &str) -> Option<u64> {
+    khz.trim().parse::<u64>().ok().map(|khz| khz * 1_000)
+}
+
+fn detect_ssd(data_dir: &Path) -> Result<Memory<SSDMemoryType>, DetectError> {
+    let data_dir = data_dir
+        .canonicalize()
+        .unwrap_or_else(|_| data_dir.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    let mounts = disks
+        .list()
+        .iter()
+        .map(|disk| disk.mount_point())
+        .collect::<Vec<_>>();
+    let disk = mount_of(&data_dir, &mounts)
+        .map(|index| &disks.list()[index])
+        .ok_or_else(|| DetectError::NoDisk(data_dir.clone()))?;
+
+    let name = disk.name().to_string_lossy();
+    let r#type = match nvme_controller(&name) {
+        Some(controller) => {
+            fs::read_to_string(format!(
+                "/sys/class/nvme/{controller}/device/current_link_speed"
+            ))
+            .ok()
+            .map(|speed| nvme_type(&speed))
+            .unwrap_or(SSDMemoryType::NVMeGen3)
+        }
+        None => {
+            if disk.kind() == DiskKind::HDD {
+                warn!(disk = %name, "data directory is on a spinning disk, claimed as a SATA SSD");
+            }
+            SSDMemoryType::SATAIII
+        }
+    };
+    Ok(Memory {
+        size: disk.total_space(),
+        r#type,
+    })
+}
+
+/// Index of the mount point the path is on, the deepest one above it
+fn mount_of(path: &Path, mounts: &[&Path]) -> Option<usize> {
+    mounts
+        .iter()
+        .enumerate()
+        .filter(|(_, mount)| path.starts_with(mount))
+        .max_by_key(|(_, mount)| mount.components().count())
+        .map(|(index, _)| index)
+}
+
+/// NVMe controller of a block device, e.g. `nvme0` of `/dev/nvme0n1p2`
+fn nvme_controller(device: &str) -> Option<&str> {
+    let name = device.rsplit('/').next()?;
+    let namespace = name.strip_prefix("nvme")?;
+    let digits = namespace
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(namespace.len());
+    (digits > 0).then(|| &name[..4 + digits])
+}
+
+/// NVMe generation told by the PCIe link speed of the controller, e.g. `16.0 GT/s PCIe` for a gen 4 one
+fn nvme_type(link_speed: &str) -> SSDMemoryType {
+    let gts = link_speed
+        .split_whitespace()
+        .next()
+        .and_then(|gts| gts.parse::<f64>().ok())
+        .unwrap_or_default();
+    if gts >= 32.0 {
+        SSDMemoryType::NVMeGen5
+    } else if gts >= 16.0 {
+        SSDMemoryType::NVMeGen4
+    } else {
+        SSDMemoryType::NVMeGen3
+    }
+}
+
+fn detect_tee() -> Tee {
+    if Path::new(SGX_DEVICE).exists() {
+        Tee::Sgx
+    } else if Path::new(SEV_GUEST_DEVICE).exists() {
+        Tee::SevSnp
+    } else {
+        Tee::None
+    }
+}
+
+/// NVIDIA GPUs and their driver, none if NVML doesn't load, i.e. there's no NVIDIA driver. A field NVML fails to
+/// query is claimed as nothing, the GPU is still claimed with the others, and a GPU NVML can't open is skipped.
+fn detect_nvidia_gpus() -> (Vec<GPU>, Option<GPUDriver>) {
+    let nvml = match Nvml::init() {
+        Ok(nvml) => nvml,
+        Err(err) => {
+            debug!(%err, "NVML not loaded, no NVIDIA GPU detected");
+            return (vec![], None);
+        }
+    };
+
+    let count = nvml_field(None, "device count", nvml.device_count(), 0);
+    let mut gpus = vec![];
+    for index in 0..count {
+        let device = match nvml.device_by_index(index) {
+            Ok(device) => device,
+            Err(err) => {
+                warn!(%err, index, "failed to open the NVIDIA GPU, not claimed");
+                continue;
+            }
+        };
+        let name = nvml_field(Some(index), "name", device.name(), String::new());
+        let capability = nvml_field(
+            Some(index),
+            "compute capability",
+            device.cuda_compute_capability().map(Some),
+            None,
+        );
+        let memory = nvml_field(
+            Some(index),
+            "memory",
+            device.memory_info().map(|info| info.total),
+            0,
+        );
+        let clock = nvml_field(
+            Some(index),
+            "clock rate",
+            device.max_clock_info(Clock::Graphics),
+            0,
+        );
+        gpus.push(GPU::Specs(GPUSpecs {
+            cores: nvml_field(Some(index), "cores", device.num_cores(), 0).into(),
+            memory: Memory {
+                size: memory,
+                r#type: gpu_memory_type(&name),
+            },
+            clock_rate: u64::from(clock) * 1_000_000,
+            vendor: Some(GPUVendor::Nvidia),
+            compute_capability: capability.map(|capability| {
+                GPUVersion::new(capability.major as u32, capability.minor as u32, 0)
+            }),
+        }));
+    }
+
+    let driver = nvml_field(
+        None,
+        "driver version",
+        nvml.sys_driver_version().map(Some),
+        None,
+    )
+    .and_then(|version| {
+        version
+            .parse()
+            .inspect_err(|err| warn!(%err, %version, "unexpected NVIDIA driver version"))
+            .ok()
+    })
+    .map(|version| {
+        GPUDriver {
+            version,
+            cuda_version: nvml_field(
+                None,
+                "CUDA version",
+                nvml.sys_cuda_driver_version().map(Some),
+                None,
+            )
+            .map(cuda_version),
+        }
+    });
+    (gpus, driver)
+}
+
+/// Value NVML gave for a field of the GPU at `index`, or of the driver, `unknown` if it failed to query it
+fn nvml_field<T>(index: Option<u32>, field: &str, value: Result<T, NvmlError>, unknown: T) -> T {
+    value.unwrap_or_else(|err| {
+        warn!(%err, ?index, field, "failed to query NVML, not claimed");
+        unknown
+    })
+}
+
+/// CUDA version of its NVML encoding, `1000 * major + 10 * minor`
+fn cuda_version(version: i32) -> GPUVersion {
+    GPUVersion::new((version / 1000) as u32, (version % 1000 / 10) as u32, 0)
+}
+
+/// Memory type of an NVIDIA GPU, which NVML doesn't give: the datacenter ones have HBM, the others are claimed as
+/// GDDR6, the slowest type they may have
+fn gpu_memory_type(name: &str) -> GPUMemoryType {
+    const HBM: [&str; 5] = ["A100", "H100", "H200", "V100", "GP100"];
+    if HBM.iter().any(|model| name.contains(model)) {
+        GPUMemoryType::HBM2
+    } else {
+        GPUMemoryType::GDDR6
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::memory::GIGA_BYTE;
+
+    #[test]
+    fn test_apply_overrides() {
+        let detected = Resource::default();
+        assert_eq!(
+            ResourceOverrides::default().apply(detected.clone()),
+            detected
+        );
+
+        let overrides = ResourceOverrides {
+            ram: Some(64 * GIGA_BYTE),
+            ram_type: Some(RAMMemoryType::DDR4),
+            gpus: Some(vec![]),
+            tee: Some(Tee::SevSnp),
+            ..Default::default()
+        };
+        let resource = overrides.apply(detected.clone());
+        assert_eq!(
+            resource.ram,
+            Memory {
+                size: 64 * GIGA_BYTE,
+                r#type: RAMMemoryType::DDR4
+            }
+        );
+        assert_eq!(resource.gpus, vec![]);
+        assert_eq!(resource.tee, Tee::SevSnp);
+        assert_eq!(resource.ssd, detected.ssd);
+        assert_eq!(resource.cpu, detected.cpu);
+    }
+
+    #[test]
+    fn test_ssd() {
+        let mounts = [
+            Path::new("/"),
+            Path::new("/data"),
+            Path::new("/data/fermah"),
+        ];
+        assert_eq!(mount_of(Path::new("/data/fermah/images"), &mounts), Some(2));
+        assert_eq!(mount_of(Path::new("/data/other"), &mounts), Some(1));
+        assert_eq!(mount_of(Path::new("/home"), &mounts), Some(0));
+        assert_eq!(mount_of(Path::new("/home"), &mounts[1..]), None);
+
+        assert_eq!(nvme_controller("/dev/nvme0n1p2"), Some("nvme0"));
+        assert_eq!(nvme_controller("nvme12n1"), Some("nvme12"));
+        assert_eq!(nvme_controller("/dev/sda1"), None);
+        assert_eq!(nvme_controller("/dev/nvmex"), None);
+
+        assert_eq!(nvme_type("8.0 GT/s PCIe\n"), SSDMemoryType::NVMeGen3);
+        assert_eq!(nvme_type("16.0 GT/s PCIe\n"), SSDMemoryType::NVMeGen4);
+        assert_eq!(nvme_type("32.0 GT/s PCIe\n"), SSDMemoryType::NVMeGen5);
+        assert_eq!(nvme_type("Unknown"), SSDMemoryType::NVMeGen3);
+    }
+
+    #[test]
+    fn test_probes() {
+        assert_eq!(parse_khz("4672000\n"), Some(4_672_000_000));
+        assert_eq!(parse_khz(""), None);
+        assert_eq!(cuda_version(12040), GPUVersion::new(12, 4, 0));
+        assert_eq!(cuda_version(11080), GPUVersion::new(11, 8, 0));
+        assert_eq!(
+            gpu_memory_type("NVIDIA H100 80GB HBM3"),
+            GPUMemoryType::HBM2
+        );
+        assert_eq!(
+            gpu_memory_type("NVIDIA GeForce RTX 4090"),
+            GPUMemoryType::GDDR6
+        );
+        assert_eq!(gpu_memory_type("NVIDIA RTX A4000"), GPUMemoryType::GDDR6);
+
+        // A field NVML fails to query is claimed as nothing
+        assert_eq!(
+            nvml_field(Some(0), "memory", Ok(80 * GIGA_BYTE), 0),
+            80 * GIGA_BYTE
+        );
+        assert_eq!(
+            nvml_field(Some(0), "memory", Err(NvmlError::NotSupported), 0),
+            0
+        );
+        assert_eq!(
+            nvml_field(None, "CUDA version", Err(NvmlError::Unknown), None::<i32>),
+            None
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use super::{
     memory::{Memory, GIGA_BYTE},
@@ -187,6 +188,92 @@ pub enum GPUMemoryType {
     HBM2,
 }
 
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum GPUVendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid version {0:?}, expected `major.minor[.patch]`")]
+pub struct InvalidGPUVersion(String);
+
+/// Version of a GPU compute capability, driver or CUDA toolkit, e.g. `8.6`, `550.54.15` or `12.4`
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct GPUVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GPUVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for GPUVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GPUVersion {
+    type Err = InvalidGPUVersion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidGPUVersion(s.to_string());
+        let parts = s
+            .split('.')
+            .map(|part| part.parse::<u32>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [major, minor] => Ok(Self::new(major, minor, 0)),
+            [major, minor, patch] => Ok(Self::new(major, minor, patch)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for GPUVersion {
+    type Error = InvalidGPUVersion;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<GPUVersion> for String {
+    fn from(version: GPUVersion) -> Self {
+        version.to_string()
+    }
+}
+
+/// GPU driver of the machine, shared by all of its GPUs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GPUDriver {
+    pub version: GPUVersion,
+    /// Highest CUDA version the driver supports, `None` for non NVIDIA drivers
+    pub cuda_version: Option<GPUVersion>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct GPUSpecs {
@@ -194,6 +281,11 @@ pub struct GPUSpecs {
     pub memory: Memory<GPUMemoryType>,
     /// Clock rate in HZ
     pub clock_rate: u64,
+    #[serde(default)]
+    pub vendor: Option<GPUVendor>,
+    /// CUDA compute capability, NVIDIA GPUs only
+    #[serde(default)]
+    pub compute_capability: Option<GPUVersion>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
@@ -239,6 +331,8 @@ impl GPUModel {
                         r#type: GPUMemoryType::GDDR6,
                     },
                     clock_rate: 1_320_000_000,
+                    vendor: Some(GPUVendor::Nvidia),
+                    compute_capability: Some(GPUVersion::new(8, 6, 0)),
                 }
             }
             Self::GeForceRtx3060_8GB => {
@@ -249,6 +343,8 @@ impl GPUModel {
                         r#type: GPUMemoryType::GDDR6,
                     },
                     clock_rate: 1_320_000_000,
+                    vendor: Some(GPUVendor::Nvidia),
+                    compute_capability: Some(GPUVersion::new(8, 6, 0)),
                 }
             }
             Self::GeForceRtx3060Ti => {
@@ -259,6 +355,8 @@ impl GPUModel {
                         r#type: GPUMemoryType::GDDR6X,
                     },
                     clock_rate: 1_410_000_000,
+                    vendor: Some(GPUVendor::Nvidia),
+                    compute_capability: Some(GPUVersion::new(8, 6, 0)),
                 }
             }
             _ => {
@@ -269,10 +367,185 @@ impl GPUModel {
                         r#type: GPUMemoryType::GDDR6X,
                     },
                     clock_rate: 1_410_000_000,
+                    vendor: None,
+                    compute_capability: None,
                 }
             }
         }
     }
+
+    /// Vendor of the model. `None` for virtual display adapters
+    pub fn vendor(&self) -> Option<GPUVendor> {
+        match self {
+            Self::RadeonProW6600x
+            | Self::RadeonRxVega56
+            | Self::RadeonRx6800M
+            | Self::RadeonVegaFrontierEdition
+            | Self::RadeonRx6700M
+            | Self::RadeonProW6600m
+            | Self::RadeonRx6600M
+            | Self::RadeonRx5600XT
+            | Self::RadeonRx6600S
+            | Self::RadeonTRx6850mXt
+            | Self::RadeonProWX8200
+            | Self::RadeonRx7600S
+            | Self::RadeonRxVega64
+            | Self::RadeonRx5700
+            | Self::RadeonProVega64X
+            | Self::RadeonProW7500
+            | Self::RadeonRx6700S
+            | Self::RadeonRx6650M
+            | Self::RadeonRx6600
+            | Self::RadeonProW5700
+            | Self::RadeonRx6800S
+            | Self::RadeonProVegaII
+            | Self::RadeonRx7700S
+            | Self::RadeonProW6600
+            | Self::RadeonProVegaIIDuo
+            | Self::RadeonRx7600
+            | Self::RadeonRx6600XT
+            | Self::RadeonRx5700XT50thAnniversary
+            | Self::RadeonRx5700XT
+            | Self::RadeonVII
+            | Self::RadeonRx6850mXt
+            | Self::RadeonRx6650XT
+            | Self::RadeonRx7600XT
+            | Self::RadeonProW5700X
+            | Self::RadeonProW7600
+            | Self::RadeonRx6750GRE12GB
+            | Self::RadeonRx6700
+            | Self::RadeonProW6800
+            | Self::RadeonRx6700XT
+            | Self::RadeonRx6750GRE10GB
+            | Self::RadeonRx6750XT
+            | Self::RadeonProW7700
+            | Self::Radeon610mRyzen9_7845hx
+            | Self::RadeonRx7900M
+            | Self::RadeonRx7700XT
+            | Self::RadeonRx6800
+            | Self::RadeonRx7800XT
+            | Self::RadeonRx6800XT
+            | Self::RadeonRx7900GRE
+            | Self::RadeonRx6900XT
+            | Self::RadeonRx6950XT
+            | Self::RadeonRx7900XT
+            | Self::RadeonProW7800
+            | Self::RadeonProW7900
+            | Self::RadeonRx7900XTX => Some(GPUVendor::Amd),
+            Self::IntelArcA770 => Some(GPUVendor::Intel),
+            Self::GeForceRtx2080MaxQ
+            | Self::GeForceRtx3060Laptop
+            | Self::Rtx1000AdaGenerationLaptop
+            | Self::GeForceGtx1070
+            | Self::QuadroRtx5000MaxQ
+            | Self::NvidiaTitanX
+            | Self::RtxA2000_12gb
+            | Self::RtxA2000
+            | Self::GeForceRtx2080SuperMaxQ
+            | Self::GeForceGtx980Ti
+            | Self::GeForceRtx2070SuperMaxQ
+            | Self::GeForceRtx2060
+            | Self::GeForceRtx4050Laptop
+            | Self::RtxA3000_12gbLaptop
+            | Self::NvidiaA40
+            | Self::GeForceGtx1070Ti
+            | Self::QuadroRtx5000Mobile
+            | Self::GeForceRtx2080Mobile
+            | Self::QuadroP6000
+            | Self::QuadroK2200
+            | Self::QuadroK4200
+            | Self::GeForceRtx3060_8GB
+            | Self::QuadroRtx4000
+            | Self::Rtx2000AdaGenerationLaptop
+            | Self::RtxA4000laptop
+            | Self::GeForceRtx3070Laptop
+            | Self::GeForceGtx1080
+            | Self::Rtx3000AdaGenerationLaptop
+            | Self::GeForceRtx2060_12GB
+            | Self::QuadroRtx5000
+            | Self::RtxA5000laptop
+            | Self::GeForceRtx2070
+            | Self::TeslaV100SXM2_16GB
+            | Self::QuadroGP100
+            | Self::GeForceRtx3080Laptop
+            | Self::GeForceRtx2060SUPER
+            | Self::TitanVCeoEdition
+            | Self::M60
+            | Self::P4
+            | Self::P40
+            | Self::AmpereA2
+            | Self::T4
+            | Self::A16
+            | Self::A10
+            | Self::A10G
+            | Self::GeForceRtx3060_12GB
+            | Self::Rtx2000AdaGeneration
+            | Self::RtxA5500laptop
+            | Self::RtxA4500laptop
+            | Self::GeForceRtx4060Laptop
+            | Self::A40_48Q
+            | Self::GeForceRtx3070TiLaptop
+            | Self::GeForceRtx2070SUPER
+            | Self::NvidiaA10G
+            | Self::NvidiaTitanXp
+            | Self::GeForceGtx1080Ti
+            | Self::QuadroRtx6000
+            | Self::GeForceRtx2080
+            | Self::TitanXpCollectorsEdition
+            | Self::RtxA4000
+            | Self::QuadroRtx8000
+            | Self::GeForceRtx4060
+            | Self::GeForceRtx2080SUPER
+            | Self::Rtx3500AdaGenerationLaptop
+            | Self::GeForceRtx4070Laptop
+            | Self::QuadroGV100
+            | Self::TtitanV
+            | Self::GeForceRtx3080TiLaptop
+            | Self::TitanRtx
+            | Self::GeForceRtx3060Ti
+            | Self::Rtx4000sffAdaGeneration
+            | Self::RtxA5500
+            | Self::GeForceRtx2080Ti
+            | Self::RtxA4500
+            | Self::GridRtx6000_6Q
+            | Self::NvidiaA10
+            | Self::GeForceRtx3070
+            | Self::RtxA6000
+            | Self::RtxA5000
+            | Self::GeForceRtx4060Ti16GB
+            | Self::GeForceRtx4060Ti
+            | Self::Rtx4000AdaGenerationLaptop
+            | Self::GeForceRtx3070Ti
+            | Self::Rtx5000AdaGenerationLaptop
+            | Self::Rtx5000AdaGeneration
+            | Self::GeForceRtx3080
+            | Self::GeForceRtx4080Laptop
+            | Self::Rtx4000AdaGeneration
+            | Self::GeForceRtx3080_12GB
+            | Self::GeForceRtx3090
+            | Self::GeForceRtx4070
+            | Self::GeForceRtx3080Ti
+            | Self::Rtx6000AdaGeneration
+            | Self::GeForceRtx4090Laptop
+            | Self::GeForceRtx3090Ti
+            | Self::Rtx4500AdaGeneration
+            | Self::GeForceRtx4070SUPER
+            | Self::L4
+            | Self::V100
+            | Self::V100S
+            | Self::GA100Ampere
+            | Self::A100
+            | Self::L40S
+            | Self::GeForceRtx4070TiSUPER
+            | Self::GeForceRtx4070Ti
+            | Self::GeForceRtx4080SUPER
+            | Self::GeForceRtx4080
+            | Self::GeForceRtx4090D
+            | Self::GeForceRtx4090
+            | Self::H100 => Some(GPUVendor::Nvidia),
+            Self::MiracastdisplayportdriverV3 => None,
+        }
+    }
 }
 
 impl GPU {
@@ -282,6 +555,17 @@ impl GPU {
             Self::Specs(s) => s,
         }
     }
+
+    pub fn vendor(&self) -> Option<GPUVendor> {
+        match self {
+            Self::Model(m) => m.vendor(),
+            Self::Specs(s) => s.vendor,
+        }
+    }
+
+    pub fn compute_capability(&self) -> Option<GPUVersion> {
+        self.specs().compute_capability
+    }
 }
 
 impl Default for GPU {
@@ -308,3 +592,32 @@ impl Fulfillable<GPU> for GPU {
         self.specs().memory.size >= other.specs().memory.size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        assert_eq!(
+            "550.54.15".parse::<GPUVersion>().unwrap(),
+            GPUVersion::new(550, 54, 15)
+        );
+        assert_eq!(GPUVersion::new(12, 4, 0).to_string(), "12.4");
+        assert!("12".parse::<GPUVersion>().is_err());
+        assert!("12.x".parse::<GPUVersion>().is_err());
+        assert!(GPUVersion::new(8, 9, 0) > GPUVersion::new(8, 6, 0));
+        assert!(GPUVersion::new(12, 10, 0) > GPUVersion::new(12, 4, 0));
+    }
+
+    #[test]
+    fn test_vendor() {
+        assert_eq!(
+            GPUModel::GeForceRtx3060_12GB.vendor(),
+            Some(GPUVendor::Nvidia)
+        );
+        assert_eq!(GPUModel::RadeonRx6800M.vendor(), Some(GPUVendor::Amd));
+        assert_eq!(GPUModel::IntelArcA770.vendor(), Some(GPUVendor::Intel));
+        assert_eq!(GPUModel::MiracastdisplayportdriverV3.vendor(), None);
+    }
+}
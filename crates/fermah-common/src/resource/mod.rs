@@ -5,18 +5,24 @@ use serde::{Deserialize, Serialize};
 
 use self::{
     cpu::CPU,
-    gpu::GPU,
+    gpu::{GPUDriver, GPU},
     memory::Memory,
     requirement::ResourceRequirement,
+    tee::Tee,
     traits::{Fulfillable, Price},
 };
 use crate::hash::Hashable;
 
+pub mod class;
 pub mod cpu;
+#[cfg(feature = "detect")]
+pub mod detect;
 pub mod gpu;
 pub mod memory;
 pub mod requirement;
+pub mod tee;
 pub mod traits;
+pub mod usage;
 
 /// Resource claims for prover server.
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Default)]
@@ -30,6 +36,12 @@ pub struct Resource {
     pub gpus: Vec<GPU>,
     /// CPU properties.
     pub cpu: CPU,
+    /// TEE the prover runs in.
+    #[serde(default)]
+    pub tee: Tee,
+    /// GPU driver, if there are GPUs.
+    #[serde(default)]
+    pub gpu_driver: Option<GPUDriver>,
 }
 
 impl PartialOrd for Resource {
@@ -87,6 +99,10 @@ impl Hashable for Resource {
 
 impl Fulfillable<ResourceRequirement> for Resource {
     fn fulfills(&self, req: &ResourceRequirement) -> bool {
+        if !req.tee.is_none() && self.tee != req.tee {
+            return false;
+        }
+
         if let Some(min_ram) = req.min_ram {
             if self.ram.size < min_ram {
                 return false;
@@ -113,11 +129,44 @@ impl Fulfillable<ResourceRequirement> for Resource {
             }
         }
 
+        if let Some(min_gpu_driver) = req.min_gpu_driver {
+            if !self
+                .gpu_driver
+                .is_some_and(|driver| driver.version >= min_gpu_driver)
+            {
+                return false;
+            }
+        }
+
+        if let Some(min_cuda) = req.min_cuda {
+            if !self
+                .gpu_driver
+                .and_then(|driver| driver.cuda_version)
+                .is_some_and(|cuda_version| cuda_version >= min_cuda)
+            {
+                return false;
+            }
+        }
+
+        // GPUs of another vendor or of a lower compute capability can't run the prover, however much memory they have
+        let usable_gpu = |gpu: &GPU| {
+            req.gpu_vendor
+                .map_or(true, |vendor| gpu.vendor() == Some(vendor))
+                && req.min_compute_capability.map_or(true, |min| {
+                    gpu.compute_capability().is_some_and(|cc| cc >= min)
+                })
+        };
+        if (req.gpu_vendor.is_some() || req.min_compute_capability.is_some())
+            && !self.gpus.iter().any(usable_gpu)
+        {
+            return false;
+        }
+
         let mut fulfilled_gpu_is: Vec<usize> = vec![];
 
         for gpu_req in req.min_gpu.iter() {
             for (i, gpu) in self.gpus.iter().enumerate() {
-                if fulfilled_gpu_is.contains(&i) {
+                if fulfilled_gpu_is.contains(&i) || !usable_gpu(gpu) {
                     continue;
                 }
                 if gpu.fulfills(gpu_req) {
@@ -142,7 +191,7 @@ mod tests {
     use super::*;
     use crate::resource::{
         cpu::CPUSpecs,
-        gpu::{GPUMemoryType, GPUSpecs},
+        gpu::{GPUMemoryType, GPUModel, GPUSpecs, GPUVendor, GPUVersion},
         memory::RAMMemoryType,
     };
 
@@ -165,11 +214,18 @@ mod tests {
                         r#type: GPUMemoryType::GDDR6,
                     },
                     clock_rate: 1_320_000_000,
+                    vendor: Some(GPUVendor::Nvidia),
+                    compute_capability: Some(GPUVersion::new(7, 5, 0)),
                 })],
                 cpu: CPU::Specs(CPUSpecs {
                     cores: 16,
                     clock_rate: 3_800_000_000,
                 }),
+                tee: Tee::None,
+                gpu_driver: Some(GPUDriver {
+                    version: GPUVersion::new(550, 54, 15),
+                    cuda_version: Some(GPUVersion::new(12, 4, 0)),
+                }),
             },
             Resource {
                 ram: Memory {
@@ -182,6 +238,8 @@ mod tests {
                 },
                 gpus: vec![GPU::Model(gpu::GPUModel::GeForceRtx3060_12GB)],
                 cpu: CPU::Model(cpu::CPUModel::Ryzen7),
+                tee: Tee::SevSnp,
+                gpu_driver: None,
             },
         ];
 
@@ -192,4 +250,87 @@ mod tests {
         let rs: Vec<Resource> = serde_json::from_str(&s).unwrap();
         println!("{:?}", rs);
     }
+
+    #[test]
+    fn test_fulfills_tee() {
+        let sgx = Resource {
+            tee: Tee::Sgx,
+            ..Default::default()
+        };
+        let any = ResourceRequirement::default();
+        let needs_sgx = ResourceRequirement {
+            tee: Tee::Sgx,
+            ..Default::default()
+        };
+        let needs_sev = ResourceRequirement {
+            tee: Tee::SevSnp,
+            ..Default::default()
+        };
+
+        assert!(sgx.fulfills(&any));
+        assert!(sgx.fulfills(&needs_sgx));
+        assert!(!sgx.fulfills(&needs_sev));
+        assert!(!Resource::default().fulfills(&needs_sgx));
+    }
+
+    #[test]
+    fn test_fulfills_gpu_vendor_and_driver() {
+        let rtx = Resource {
+            gpus: vec![GPU::Model(GPUModel::GeForceRtx3060_12GB)],
+            gpu_driver: Some(GPUDriver {
+                version: GPUVersion::new(535, 183, 1),
+                cuda_version: Some(GPUVersion::new(12, 2, 0)),
+            }),
+            ..Default::default()
+        };
+        let radeon = Resource {
+            gpus: vec![GPU::Model(GPUModel::RadeonRx6800M)],
+            gpu_driver: Some(GPUDriver {
+                version: GPUVersion::new(6, 1, 2),
+                cuda_version: None,
+            }),
+            ..Default::default()
+        };
+        let cuda = ResourceRequirement {
+            min_gpu: vec![GPUModel::GeForceRtx3060_8GB],
+            gpu_vendor: Some(GPUVendor::Nvidia),
+            min_compute_capability: Some(GPUVersion::new(8, 0, 0)),
+            min_cuda: Some(GPUVersion::new(12, 0, 0)),
+            ..Default::default()
+        };
+
+        // Same memory, the Radeon only fulfilled the spec before vendors were compared
+        assert!(rtx.fulfills(&cuda));
+        assert!(!radeon.fulfills(&cuda));
+        assert!(radeon.fulfills(&ResourceRequirement {
+            min_gpu: vec![GPUModel::GeForceRtx3060_8GB],
+            ..Default::default()
+        }));
+
+        assert!(!rtx.fulfills(&ResourceRequirement {
+            min_compute_capability: Some(GPUVersion::new(8, 9, 0)),
+            ..cuda.clone()
+        }));
+        assert!(!rtx.fulfills(&ResourceRequirement {
+            min_cuda: Some(GPUVersion::new(12, 4, 0)),
+            ..cuda.clone()
+        }));
+        assert!(rtx.fulfills(&ResourceRequirement {
+            min_gpu_driver: Some(GPUVersion::new(535, 0, 0)),
+            ..cuda.clone()
+        }));
+        assert!(!rtx.fulfills(&ResourceRequirement {
+            min_gpu_driver: Some(GPUVersion::new(550, 0, 0)),
+            ..cuda.clone()
+        }));
+        assert!(!Resource {
+            gpu_driver: None,
+            ..rtx.clone()
+        }
+        .fulfills(&cuda));
+        assert!(radeon.fulfills(&ResourceRequirement {
+            gpu_vendor: Some(GPUVendor::Amd),
+            ..Default::default()
+        }));
+    }
 }
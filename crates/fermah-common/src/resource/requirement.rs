@@ -2,7 +2,11 @@ use std::borrow::Cow;
 
 use serde::{Deserialize, Serialize};
 
-use super::gpu::GPUModel;
+use super::{
+    gpu::{GPUModel, GPUVendor, GPUVersion},
+    tee::Tee,
+    traits::Price,
+};
 use crate::hash::Hashable;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -13,11 +17,111 @@ pub struct ResourceRequirement {
     pub min_ssd: Option<u64>,
     pub min_gpu: Vec<GPUModel>,
     pub min_cpu_cores: Option<u64>,
+    /// TEE the operator has to prove in, any operator if `none`
+    #[serde(default)]
+    pub tee: Tee,
+    /// Vendor of the GPUs, e.g. NVIDIA for CUDA provers
+    #[serde(default)]
+    pub gpu_vendor: Option<GPUVendor>,
+    /// Lowest CUDA compute capability of the GPUs
+    #[serde(default)]
+    pub min_compute_capability: Option<GPUVersion>,
+    #[serde(default)]
+    pub min_gpu_driver: Option<GPUVersion>,
+    /// Lowest CUDA version the GPU driver has to support
+    #[serde(default)]
+    pub min_cuda: Option<GPUVersion>,
+    /// Named resource class instead of the fields above, the requirement it stands for is stored apart from the signed
+    /// request when it's submitted
+    #[serde(default)]
+    pub class: Option<String>,
+}
+
+impl ResourceRequirement {
+    fn has_gpu_constraints(&self) -> bool {
+        self.gpu_vendor.is_some()
+            || self.min_compute_capability.is_some()
+            || self.min_gpu_driver.is_some()
+            || self.min_cuda.is_some()
+    }
+
+    /// Whether only the class is set, any operator fulfills the requirement until it's expanded
+    pub fn is_class_only(&self) -> bool {
+        self.class.is_some()
+            && *self
+                == Self {
+                    class: self.class.clone(),
+                    ..Default::default()
+                }
+    }
+}
+
+/// Fields of the requirement before the GPU vendor and driver could be required, and the TEE when it isn't
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyResourceRequirement<'a> {
+    min_vram: Option<u64>,
+    min_ram: Option<u64>,
+    min_ssd: Option<u64>,
+    min_gpu: &'a [GPUModel],
+    min_cpu_cores: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tee: Option<Tee>,
+}
+
+/// Fields of the requirement since the GPU constraints, with the class only when there is one
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GpuResourceRequirement<'a> {
+    min_vram: Option<u64>,
+    min_ram: Option<u64>,
+    min_ssd: Option<u64>,
+    min_gpu: &'a [GPUModel],
+    min_cpu_cores: Option<u64>,
+    tee: Tee,
+    gpu_vendor: &'a Option<GPUVendor>,
+    min_compute_capability: &'a Option<GPUVersion>,
+    min_gpu_driver: &'a Option<GPUVersion>,
+    min_cuda: &'a Option<GPUVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    class: Option<&'a str>,
 }
 
 impl Hashable for ResourceRequirement {
     fn collect(&self) -> Cow<[u8]> {
-        serde_json::to_vec(self).unwrap().into()
+        // Requirements without the newer fields are encoded as before, so the requests signed then keep their hashes
+        if !self.has_gpu_constraints() && self.class.is_none() {
+            let legacy = LegacyResourceRequirement {
+                min_vram: self.min_vram,
+                min_ram: self.min_ram,
+                min_ssd: self.min_ssd,
+                min_gpu: &self.min_gpu,
+                min_cpu_cores: self.min_cpu_cores,
+                tee: (!self.tee.is_none()).then_some(self.tee),
+            };
+            return serde_json::to_vec(&legacy).unwrap().into();
+        }
+        let full = GpuResourceRequirement {
+            min_vram: self.min_vram,
+            min_ram: self.min_ram,
+            min_ssd: self.min_ssd,
+            min_gpu: &self.min_gpu,
+            min_cpu_cores: self.min_cpu_cores,
+            tee: self.tee,
+            gpu_vendor: &self.gpu_vendor,
+            min_compute_capability: &self.min_compute_capability,
+            min_gpu_driver: &self.min_gpu_driver,
+            min_cuda: &self.min_cuda,
+            class: self.class.as_deref(),
+        };
+        serde_json::to_vec(&full).unwrap().into()
+    }
+}
+
+impl Price for ResourceRequirement {
+    fn price(&self) -> f64 {
+        // Same fallback as for `Resource`, until pricing is per resource class
+        100.0
     }
 }
 
@@ -34,6 +138,12 @@ mod tests {
                 min_ssd: Some(16 * 1024 * 1024 * 1024),
                 min_gpu: vec![GPUModel::GeForceRtx3060_12GB],
                 min_cpu_cores: Some(16),
+                tee: Tee::None,
+                gpu_vendor: Some(GPUVendor::Nvidia),
+                min_compute_capability: Some(GPUVersion::new(8, 6, 0)),
+                min_gpu_driver: Some(GPUVersion::new(535, 0, 0)),
+                min_cuda: Some(GPUVersion::new(12, 2, 0)),
+                class: None,
             },
             ResourceRequirement {
                 min_vram: Some(4 * 1024 * 1024 * 1024),
@@ -41,6 +151,8 @@ mod tests {
                 min_ssd: Some(16 * 1024 * 1024 * 1024),
                 min_gpu: vec![GPUModel::GeForceRtx3060_12GB],
                 min_cpu_cores: Some(96),
+                tee: Tee::Sgx,
+                ..Default::default()
             },
             ResourceRequirement {
                 min_vram: None,
@@ -48,6 +160,8 @@ mod tests {
                 min_ssd: Some(16 * 1024 * 1024 * 1024),
                 min_gpu: vec![GPUModel::GeForceRtx3060_12GB],
                 min_cpu_cores: None,
+                tee: Tee::SevSnp,
+                ..Default::default()
             },
         ];
 
@@ -59,4 +173,36 @@ mod tests {
         assert_eq!(rrs, rs);
         println!("{:?}", rs);
     }
+
+    #[test]
+    fn test_hash_without_tee() {
+        let json = r#"{"minVram":null,"minRam":null,"minSsd":null,"minGpu":[],"minCpuCores":2}"#;
+        let requirement: ResourceRequirement = serde_json::from_str(json).unwrap();
+        assert_eq!(requirement.tee, Tee::None);
+        assert_eq!(requirement.collect().as_ref(), json.as_bytes());
+
+        let confidential = ResourceRequirement {
+            tee: Tee::SevSnp,
+            ..requirement.clone()
+        };
+        assert_ne!(confidential.collect(), requirement.collect());
+    }
+
+    #[test]
+    fn test_hash_without_gpu_constraints() {
+        let json = r#"{"minVram":null,"minRam":null,"minSsd":null,"minGpu":[],"minCpuCores":2,"tee":"sgx"}"#;
+        let requirement: ResourceRequirement = serde_json::from_str(json).unwrap();
+        assert_eq!(requirement.collect().as_ref(), json.as_bytes());
+
+        let cuda = ResourceRequirement {
+            gpu_vendor: Some(GPUVendor::Nvidia),
+            min_cuda: Some("12.4".parse().unwrap()),
+            ..requirement.clone()
+        };
+        assert_ne!(cuda.collect(), requirement.collect());
+        assert_eq!(
+            serde_json::to_value(&cuda).unwrap()["minCuda"],
+            serde_json::json!("12.4")
+        );
+    }
 }
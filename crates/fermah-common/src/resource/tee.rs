@@ -0,0 +1,124 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::Bytes;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{hash::Hashable, operator::OperatorId};
+
+/// Largest attestation quote stored, SGX quotes with their certification data are a few KiB
+pub const MAX_QUOTE_LEN: usize = 64 * 1024;
+
+/// Trusted execution environment an operator proves in, for requests whose inputs have to stay confidential
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Tee {
+    #[default]
+    None,
+    /// Intel SGX enclave
+    Sgx,
+    /// AMD SEV-SNP confidential VM
+    SevSnp,
+}
+
+impl Tee {
+    pub fn is_none(&self) -> bool {
+        *self == Tee::None
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TeeAttestationError {
+    #[error("attestation is not of a TEE")]
+    NoTee,
+
+    #[error("quote is longer than {MAX_QUOTE_LEN} bytes")]
+    QuoteTooLong,
+}
+
+/// Attestation document produced by the TEE of an operator. The matchmaker only stores the latest one of each
+/// operator, requesters verify the quote against the vendor's certificate chain themselves.
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TeeAttestation {
+    pub tee: Tee,
+    /// SGX DCAP quote or SEV-SNP attestation report, as returned by the hardware
+    pub quote: Bytes,
+}
+
+impl TeeAttestation {
+    pub fn validate(&self) -> Result<(), TeeAttestationError> {
+        if self.tee.is_none() {
+            return Err(TeeAttestationError::NoTee);
+        }
+        if self.quote.len() > MAX_QUOTE_LEN {
+            return Err(TeeAttestationError::QuoteTooLong);
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for TeeAttestation {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Whether the quote of an attestation was checked against the vendor's certificate chain
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuoteVerification {
+    /// Stored as the operator reported it, the requester has to verify it before trusting the operator's TEE
+    #[default]
+    Unverified,
+}
+
+/// Latest attestation an operator reported, for requesters to inspect
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorAttestation {
+    pub operator_id: OperatorId,
+    pub attestation: TeeAttestation,
+    pub reported_at: DateTime<Utc>,
+    /// The matchmaker doesn't verify the quotes, they're always unverified
+    #[serde(default)]
+    pub verification: QuoteVerification,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(serde_json::to_string(&Tee::SevSnp).unwrap(), "\"sev-snp\"");
+        assert_eq!(Tee::from_str("sev-snp").unwrap(), Tee::SevSnp);
+        assert_eq!(Tee::Sgx.to_string(), "sgx");
+        assert_eq!(
+            serde_json::from_str::<Tee>("\"none\"").unwrap(),
+            Tee::default()
+        );
+        assert_eq!(
+            serde_json::to_string(&QuoteVerification::Unverified).unwrap(),
+            "\"unverified\""
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::{borrow::Cow, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::requirement::ResourceRequirement;
+use crate::hash::Hashable;
+
+/// A requirement is over-specified when it asks for more than this many times the peak usage
+pub const OVER_SPECIFIED_FACTOR: u64 = 4;
+
+/// Resources a proving job consumed, as measured by the operator
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    /// Peak RAM in Bytes
+    pub peak_ram: Option<u64>,
+    /// Peak VRAM in Bytes, over all the GPUs
+    pub peak_vram: Option<u64>,
+    /// Wall time of the prover, in milliseconds
+    pub wall_time_ms: u64,
+}
+
+impl Hashable for ResourceUsage {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// How well the requirement of a request matched what its job consumed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Hash, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RequirementFit {
+    Accurate,
+    /// The job used more than it required, it may not fit on the operators it's matched with
+    UnderSpecified,
+    /// The job used a fraction of what it required, it blocks bigger operators than it needs
+    OverSpecified,
+}
+
+impl RequirementFit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Accurate => "accurate",
+            Self::UnderSpecified => "underSpecified",
+            Self::OverSpecified => "overSpecified",
+        }
+    }
+}
+
+impl Display for RequirementFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RequirementFit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "accurate" => Ok(Self::Accurate),
+            "underSpecified" => Ok(Self::UnderSpecified),
+            "overSpecified" => Ok(Self::OverSpecified),
+            _ => Err(format!("unknown requirement fit: {s}")),
+        }
+    }
+}
+
+impl ResourceUsage {
+    /// Compares the peaks with the requirement. Exceeding any requirement makes it under-specified, even if another
+    /// one is over-specified.
+    pub fn fit(&self, requirement: &ResourceRequirement) -> RequirementFit {
+        let pairs = [
+            (self.peak_ram, requirement.min_ram),
+            (self.peak_vram, requirement.min_vram),
+        ];
+
+        let mut fit = RequirementFit::Accurate;
+        for (peak, required) in pairs {
+            let (Some(peak), Some(required)) = (peak, required) else {
+                continue;
+            };
+            if peak > required {
+                return RequirementFit::UnderSpecified;
+            }
+            if required > peak.saturating_mul(OVER_SPECIFIED_FACTOR) {
+                fit = RequirementFit::OverSpecified;
+            }
+        }
+
+        fit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::memory::GIGA_BYTE;
+
+    #[test]
+    fn test_fit() {
+        let requirement = ResourceRequirement {
+            min_ram: Some(16 * GIGA_BYTE),
+            min_vram: Some(8 * GIGA_BYTE),
+            ..Default::default()
+        };
+        let usage = |ram, vram| {
+            ResourceUsage {
+                peak_ram: ram,
+                peak_vram: vram,
+                wall_time_ms: 1000,
+            }
+        };
+
+        assert_eq!(
+            usage(Some(12 * GIGA_BYTE), Some(6 * GIGA_BYTE)).fit(&requirement),
+            RequirementFit::Accurate
+        );
+        assert_eq!(
+            usage(Some(2 * GIGA_BYTE), None).fit(&requirement),
+            RequirementFit::OverSpecified
+        );
+        assert_eq!(
+            usage(Some(2 * GIGA_BYTE), Some(9 * GIGA_BYTE)).fit(&requirement),
+            RequirementFit::UnderSpecified
+        );
+        assert_eq!(
+            usage(None, None).fit(&ResourceRequirement::default()),
+            RequirementFit::Accurate
+        );
+    }
+
+    #[test]
+    fn test_fit_roundtrip() {
+        for fit in [
+            RequirementFit::Accurate,
+            RequirementFit::UnderSpecified,
+            RequirementFit::OverSpecified,
+        ] {
+            assert_eq!(fit.as_str().parse::<RequirementFit>(), Ok(fit));
+            assert_eq!(serde_json::to_string(&fit).unwrap(), format!("\"{}\"", fit));
+        }
+    }
+}
@@ -1,10 +1,32 @@
-use std::io::Write;
+use std::{
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+    pin::Pin,
+    time::Duration,
+};
 
-use futures_util::stream::StreamExt;
-use reqwest::StatusCode;
-use serde::{Deserialize, Serialize};
+use async_compression::tokio::bufread::ZstdDecoder;
+use const_hex::ToHexExt;
+use futures_util::stream::TryStreamExt;
+use reqwest::{
+    header::{
+        HeaderMap,
+        HeaderValue,
+        ACCEPT_ENCODING,
+        CONTENT_ENCODING,
+        ETAG,
+        IF_RANGE,
+        RANGE,
+        RETRY_AFTER,
+    },
+    Response,
+    StatusCode,
+};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use thiserror::Error;
-use tracing::{debug, error};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::StreamReader;
+use tracing::{debug, error, warn};
 use url::Url;
 
 use crate::{
@@ -13,13 +35,16 @@ use crate::{
         error::Error as FsError,
         mountable::{path_buf_mirror_serde, PathBufMirror},
     },
-    hash::{
-        blake3::{Blake3Hash, Blake3Hasher},
-        Hasher,
-    },
+    hash::{blake3::Blake3Hasher, id::blake3_id, Hasher},
+    http::url_policy::{GetError, UrlPolicy, UrlPolicyError},
     serialization::encoding::hex_encoded,
 };
 
+blake3_id!(
+    /// [`blake3`] hash of the downloaded program image or file
+    ImageHash
+);
+
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalResource {
@@ -28,17 +53,121 @@ pub struct LocalResource {
     pub path: PathBufMirror,
     /// [`blake3`] hash of the program image.
     #[serde(with = "hex_encoded")]
-    pub hash: Blake3Hash,
+    pub hash: ImageHash,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[derive(Debug, Deserialize, Clone, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RemoteResource {
     /// URL to a HTTP endpoint where the image can be downloaded.
     pub url: Url,
     /// [`blake3`] hash of the program image.
     #[serde(with = "hex_encoded")]
-    pub hash: Blake3Hash,
+    pub hash: ImageHash,
+    /// Smaller download for the operators which have an earlier version of the image, the full image is downloaded
+    /// from `url` by the others. Signed along with the executable, the reconstructed image has to match `hash` all
+    /// the same.
+    #[serde(default)]
+    pub delta: Option<ImageDelta>,
+}
+
+impl Serialize for RemoteResource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Executables are hashed from their JSON, so a resource without a delta keeps the hash it had before deltas.
+        // The binary encodings are positional and always carry the field.
+        let skip_delta = self.delta.is_none() && serializer.is_human_readable();
+        let mut state =
+            serializer.serialize_struct("RemoteResource", if skip_delta { 2 } else { 3 })?;
+        state.serialize_field("url", &self.url)?;
+        state.serialize_field("hash", &self.hash.encode_hex_with_prefix())?;
+        if skip_delta {
+            state.skip_field("delta")?;
+        } else {
+            state.serialize_field("delta", &self.delta)?;
+        }
+        state.end()
+    }
+}
+
+/// Binary diff of an image against an earlier one, as made by `zstd --patch-from` or [`create_patch`]
+#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDelta {
+    /// [`blake3`] hash of the image the delta is applied to.
+    #[serde(with = "hex_encoded")]
+    pub base: ImageHash,
+    /// URL to a HTTP endpoint where the delta can be downloaded.
+    pub url: Url,
+}
+
+/// Attempts at downloading an image, an interrupted download is resumed and a busy server waited for by the next one
+const DOWNLOAD_ATTEMPTS: usize = 5;
+/// Longest wait asked by a busy server which is honored, longer ones are cut to it
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// Wait for a busy server which doesn't say how long to wait
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Wait asked by the `Retry-After` header of a busy server, in seconds. HTTP dates aren't parsed, the default wait
+/// is used for them.
+fn retry_after(response: &Response) -> Duration {
+    response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok()?.trim().parse::<u64>().ok())
+        .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs)
+        .min(MAX_RETRY_AFTER)
+}
+
+/// Largest window of the patches, deltas of images up to 2 GiB reference the whole base
+const PATCH_WINDOW_LOG_MAX: u32 = 31;
+
+/// Maps the base of a patch into memory. zstd references it as one buffer, the mapping pages it in as the
+/// (de)compression reaches it instead of reading the whole image up front.
+fn map_base(base: &Path) -> std::io::Result<memmap2::Mmap> {
+    let file = std::fs::File::open(base)?;
+    // SAFETY: the bases are images in the download root, written once under their hash and never modified in place
+    unsafe { memmap2::Mmap::map(&file) }
+}
+
+/// Writes the delta turning the `base` file into the `image` file, a zstd frame with the base as the reference
+/// prefix, decompressed by `zstd -d --patch-from <base> --long=31`. The image is streamed into the patch.
+pub fn create_patch(base: &Path, image: &Path, out: &mut impl Write) -> std::io::Result<()> {
+    let base = map_base(base)?;
+    let mut image = std::fs::File::open(image)?;
+    let image_len = image.metadata()?.len();
+
+    let mut encoder = zstd::stream::write::Encoder::with_ref_prefix(out, 19, &base)?;
+    // The window has to reach back over the whole base, the long distance matching finds the unchanged parts in it
+    let window = (base.len() as u64).max(image_len).max(1);
+    encoder.window_log((u64::BITS - window.leading_zeros()).clamp(10, PATCH_WINDOW_LOG_MAX))?;
+    encoder.long_distance_matching(true)?;
+    encoder.set_pledged_src_size(Some(image_len))?;
+    std::io::copy(&mut image, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Writes the image reconstructed from the `base` file and the streamed delta, returning its hash
+pub fn apply_patch(
+    base: &Path,
+    patch: impl BufRead,
+    out: &mut impl Write,
+) -> std::io::Result<ImageHash> {
+    let base = map_base(base)?;
+    let mut decoder = zstd::stream::read::Decoder::with_ref_prefix(patch, &base)?;
+    decoder.window_log_max(PATCH_WINDOW_LOG_MAX)?;
+
+    let mut hasher = Blake3Hasher::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = decoder.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        out.write_all(&buf[..n])?;
+    }
+    Ok(ImageHash::from(hasher.finalize()))
 }
 
 #[derive(Error, Debug)]
@@ -55,19 +184,36 @@ pub enum DownloadError {
     #[error("Remote resource not found: {0}")]
     NotFound(Url),
 
+    #[error("Unexpected status {status} from {url}")]
+    UnexpectedStatus { url: Url, status: StatusCode },
+
     #[error("Hash mismatch for url {url}: {expected} != {found}")]
     HashMismatch {
         url: Url,
-        expected: Blake3Hash,
-        found: Blake3Hash,
+        expected: ImageHash,
+        found: ImageHash,
     },
+
+    #[error("URL refused by the policy: {0}")]
+    Refused(#[from] UrlPolicyError),
+}
+
+impl From<GetError> for DownloadError {
+    fn from(err: GetError) -> Self {
+        match err {
+            GetError::Refused(err) => Self::Refused(err),
+            GetError::Reqwest(err) => Self::Reqwest(err),
+        }
+    }
 }
 
 impl RemoteResource {
     /// Download the program image to a local file
     /// and check if its hash matches the computed hash.
+    /// The URL has to be allowed by the `policy`.
     pub async fn download(
         &self,
+        policy: &UrlPolicy,
         path: Option<PathBufMirror>,
     ) -> Result<PathBufMirror, DownloadError> {
         // todo: probably treat differently dirs and individual files?
@@ -83,6 +229,15 @@ impl RemoteResource {
             return Ok(location);
         }
 
+        if let Some(delta) = &self.delta {
+            match self.download_delta(policy, delta, &location).await {
+                Ok(true) => return Ok(location),
+                Ok(false) => debug!(base = %delta.base, "No base image for the delta"),
+                // The full image can still be downloaded
+                Err(err) => warn!(%err, url = %delta.url, "failed to apply the image delta"),
+            }
+        }
+
         // Create temporary file
         #[cfg(not(feature = "dockerized"))]
         // If we don't work with dockerization, we can create temop files anywhere, including in tmp, which belong to existing FS (hopefully)
@@ -92,61 +247,196 @@ impl RemoteResource {
         //          This is why we create a temp file inside of the mounted FS thanks to `PathBufMirror` from `random_path`.
         let (mut file, tmp_file_location) = Self::temp_file().await?;
 
-        // Download file.
-        let response = reqwest::get(self.url.as_ref()).await?;
-
-        let response = response.error_for_status();
-        match response {
-            Ok(response) => {
-                let mut stream = response.bytes_stream();
+        // Download file, from where the policy allows only
+        #[cfg(not(feature = "dockerized"))]
+        let fetched = self.fetch(policy, file.as_file_mut()).await;
+        #[cfg(feature = "dockerized")]
+        let fetched = self.fetch(policy, &mut file).await;
+        let hash = match fetched {
+            Ok(hash) => hash,
+            Err(err) => {
+                #[cfg(feature = "dockerized")]
+                let _ = std::fs::remove_file(tmp_file_location.local());
+                error!(%err, url = %self.url, "failed to download");
+                return Err(err);
+            }
+        };
+
+        // Check hash and persist the file.
+        if hash != self.hash {
+            #[cfg(not(feature = "dockerized"))]
+            // Generally, we can ignore the `file`, as it will be removed automatically when if gets out of scope. But, it could be
+            // more readable to do the explicit `drop` here.
+            drop(file);
+            #[cfg(feature = "dockerized")]
+            // With `dockerized` setup we created the temp file ourselves in the mounted FS, thus we need to take care about it ourselves too.
+            let _ = std::fs::remove_file(tmp_file_location.local());
+
+            error!(expected=?self.hash, got=?hash, "Invalid hash");
+            Err(DownloadError::HashMismatch {
+                url: self.url.clone(),
+                expected: self.hash,
+                found: hash,
+            })?
+        } else {
+            #[cfg(not(feature = "dockerized"))]
+            std::fs::rename(file.path(), location.local())?;
+            #[cfg(feature = "dockerized")]
+            std::fs::rename(tmp_file_location.local(), location.local())?;
+
+            Ok(location)
+        }
+    }
 
-                let mut hasher = Blake3Hasher::new();
-                // Write image to a temporary file and compute its hash.
-                while let Some(Ok(item)) = stream.next().await {
-                    hasher.update(&item);
-                    file.write_all(&item)?;
+    /// Downloads the image to the file, returning its hash. The image is asked for zstd compressed, an interrupted
+    /// download is resumed with a `Range` of the same version of the image, and a busy server is waited for as long
+    /// as its `Retry-After` asks, up to [`DOWNLOAD_ATTEMPTS`] requests in all.
+    async fn fetch(
+        &self,
+        policy: &UrlPolicy,
+        file: &mut std::fs::File,
+    ) -> Result<ImageHash, DownloadError> {
+        let mut hasher = Blake3Hasher::new();
+        let mut written = 0u64;
+        let mut etag: Option<HeaderValue> = None;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut headers = HeaderMap::new();
+            if written == 0 {
+                headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("zstd"));
+            } else {
+                // Ranges are served uncompressed, the offset is the one of the image
+                headers.insert(
+                    RANGE,
+                    HeaderValue::from_str(&format!("bytes={written}-")).unwrap(),
+                );
+                if let Some(etag) = &etag {
+                    headers.insert(IF_RANGE, etag.clone());
                 }
+            }
 
-                // Check hash and persist the file.
-                let hash = hasher.finalize();
-
-                if hash != self.hash {
-                    #[cfg(not(feature = "dockerized"))]
-                    // Generally, we can ignore the `file`, as it will be removed automatically when if gets out of scope. But, it could be
-                    // more readable to do the explicit `drop` here.
-                    drop(file);
-                    #[cfg(feature = "dockerized")]
-                    // With `dockerized` setup we created the temp file ourselves in the mounted FS, thus we need to take care about it ourselves too.
-                    let _ = std::fs::remove_file(tmp_file_location.local());
-
-                    error!(expected=?self.hash, got=?hash, "Invalid hash");
-                    Err(DownloadError::HashMismatch {
+            let response = policy.get_with(&self.url, headers).await?;
+            match response.status() {
+                StatusCode::SERVICE_UNAVAILABLE if attempt < DOWNLOAD_ATTEMPTS => {
+                    let wait = retry_after(&response);
+                    warn!(url = %self.url, ?wait, "image server busy, retrying");
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                StatusCode::NOT_FOUND => return Err(DownloadError::NotFound(self.url.clone())),
+                StatusCode::PARTIAL_CONTENT if written > 0 => {
+                    debug!(url = %self.url, written, "resuming the image download");
+                }
+                status if status.is_success() => {
+                    // The whole image, e.g. it changed since the interrupted download
+                    if written > 0 {
+                        hasher = Blake3Hasher::new();
+                        written = 0;
+                        file.set_len(0)?;
+                        file.seek(SeekFrom::Start(0))?;
+                    }
+                    etag = response.headers().get(ETAG).cloned();
+                }
+                status => {
+                    return Err(DownloadError::UnexpectedStatus {
                         url: self.url.clone(),
-                        expected: self.hash,
-                        found: hash,
-                    })?
-                } else {
-                    #[cfg(not(feature = "dockerized"))]
-                    std::fs::rename(file.path(), location.local())?;
-                    #[cfg(feature = "dockerized")]
-                    std::fs::rename(tmp_file_location.local(), location.local())?;
-
-                    Ok(location)
+                        status,
+                    })
                 }
             }
-            Err(e) => {
-                error!(err=?e, "failed to download");
-                let status_code = e.status();
-                if let Some(status_code) = status_code {
-                    if matches!(status_code, StatusCode::NOT_FOUND) {
-                        Err(DownloadError::NotFound(self.url.clone()))?
+
+            let zstd = response
+                .headers()
+                .get(CONTENT_ENCODING)
+                .is_some_and(|encoding| encoding == "zstd");
+            let body = StreamReader::new(response.bytes_stream().map_err(std::io::Error::other));
+            let mut body: Pin<Box<dyn AsyncRead + Send>> = if zstd {
+                Box::pin(ZstdDecoder::new(body))
+            } else {
+                Box::pin(body)
+            };
+
+            let mut buf = vec![0; 64 * 1024];
+            let interrupted = loop {
+                match body.read(&mut buf).await {
+                    Ok(0) => break None,
+                    Ok(n) => {
+                        hasher.update(&buf[..n]);
+                        file.write_all(&buf[..n])?;
+                        written += n as u64;
                     }
+                    Err(err) => break Some(err),
+                }
+            };
+            match interrupted {
+                None => return Ok(ImageHash::from(hasher.finalize())),
+                Some(err) if attempt < DOWNLOAD_ATTEMPTS => {
+                    warn!(%err, url = %self.url, written, "image download interrupted, resuming");
                 }
-                Err(e)?
+                Some(err) => return Err(err.into()),
             }
         }
     }
 
+    /// Reconstructs the image at `location` from the delta, if the base image was downloaded before. Returns `false`
+    /// when there is no base to apply the delta to.
+    async fn download_delta(
+        &self,
+        policy: &UrlPolicy,
+        delta: &ImageDelta,
+        location: &PathBufMirror,
+    ) -> Result<bool, DownloadError> {
+        let base = Self::root().await?.join(format!("{}", delta.base));
+        if !base.exists() {
+            return Ok(false);
+        }
+
+        // Spooled to disk, the patch of a large image can be large as well
+        let mut response = policy.get(&delta.url).await?.error_for_status()?;
+        let mut patch = tempfile::tempfile()?;
+        while let Some(chunk) = response.chunk().await? {
+            patch.write_all(&chunk)?;
+        }
+        patch.rewind()?;
+
+        #[cfg(not(feature = "dockerized"))]
+        let file = tempfile::NamedTempFile::new()?;
+        #[cfg(feature = "dockerized")]
+        let (file, tmp_file_location) = Self::temp_file().await?;
+
+        // Decompression is CPU-bound and pages in the whole base
+        let base = base.local();
+        let (hash, file) = tokio::task::spawn_blocking(move || {
+            let mut file = file;
+            apply_patch(&base, BufReader::new(patch), &mut file).map(|hash| (hash, file))
+        })
+        .await
+        .map_err(std::io::Error::from)??;
+
+        if hash != self.hash {
+            #[cfg(not(feature = "dockerized"))]
+            drop(file);
+            #[cfg(feature = "dockerized")]
+            let _ = std::fs::remove_file(tmp_file_location.local());
+
+            return Err(DownloadError::HashMismatch {
+                url: delta.url.clone(),
+                expected: self.hash,
+                found: hash,
+            });
+        }
+
+        #[cfg(not(feature = "dockerized"))]
+        std::fs::rename(file.path(), location.local())?;
+        #[cfg(feature = "dockerized")]
+        {
+            drop(file);
+            std::fs::rename(tmp_file_location.local(), location.local())?;
+        }
+        Ok(true)
+    }
+
     pub async fn root() -> Result<PathBufMirror, DownloadError> {
         let download_root = PathBufMirror::from_str("downloads").await?;
 
@@ -165,8 +455,8 @@ impl RemoteResource {
         Ok((file, file_location))
     }
 
-    pub async fn into_local(self) -> Result<LocalResource, DownloadError> {
-        let path = self.download(None).await?;
+    pub async fn into_local(self, policy: &UrlPolicy) -> Result<LocalResource, DownloadError> {
+        let path = self.download(policy, None).await?;
         Ok(LocalResource {
             hash: self.hash,
             path,
@@ -184,10 +474,16 @@ mod tests {
             url: "http://localhost:8082/dummy_prover_latest.tar.gz"
                 .parse()
                 .unwrap(),
-            hash: Blake3Hash(blake3::Hash::from_bytes([
+            hash: ImageHash::from([
                 50, 235, 26, 34, 170, 83, 73, 153, 59, 164, 55, 11, 174, 204, 153, 4, 87, 3, 75,
                 158, 8, 187, 32, 156, 174, 44, 132, 64, 14, 121, 100, 140,
-            ])),
+            ]),
+            delta: Some(ImageDelta {
+                base: ImageHash::from([7; 32]),
+                url: "http://localhost:8082/dummy_prover_latest.delta.zst"
+                    .parse()
+                    .unwrap(),
+            }),
         }];
 
         let s = serde_json::to_string_pretty(&rrs).unwrap();
@@ -202,6 +498,115 @@ mod tests {
 
         let x = bincode::deserialize::<Vec<RemoteResource>>(&x).unwrap();
 
-        assert_eq!(x, rs)
+        assert_eq!(x, rs);
+
+        // Without a delta the JSON, which executables are hashed from, is the one from before deltas
+        let without_delta = RemoteResource {
+            delta: None,
+            ..rs[0].clone()
+        };
+        assert_eq!(
+            serde_json::to_string(&without_delta).unwrap(),
+            r#"{"url":"http://localhost:8082/dummy_prover_latest.tar.gz","hash":"0x32eb1a22aa5349993ba4370baecc990457034b9e08bb209cae2c84400e79648c"}"#
+        );
+        assert_eq!(
+            bincode::deserialize::<RemoteResource>(&bincode::serialize(&without_delta).unwrap())
+                .unwrap(),
+            without_delta
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_under_policy() {
+        use crate::http::file_server::FileServer;
+
+        let dir = tempfile::tempdir().unwrap();
+        let image: Vec<u8> = (0..100_000u32)
+            .flat_map(|i| (i % 7).to_le_bytes())
+            .collect();
+        std::fs::write(dir.path().join("image"), &image).unwrap();
+        let served = dir.path().to_path_buf();
+        tokio::spawn(async move {
+            FileServer::new(3005)
+                .with_compression(true)
+                .serve_dir("images".to_string(), served)
+                .await;
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut hasher = Blake3Hasher::new();
+        hasher.update(&image);
+        let resource = RemoteResource {
+            url: "http://localhost:3005/images/image".parse().unwrap(),
+            hash: ImageHash::from(hasher.finalize()),
+            delta: None,
+        };
+
+        // Local addresses are refused by default, a devnet allows them
+        let mut file = tempfile::tempfile().unwrap();
+        assert!(matches!(
+            resource.fetch(&UrlPolicy::default(), &mut file).await,
+            Err(DownloadError::Refused(
+                UrlPolicyError::NonPublicAddress { .. }
+            ))
+        ));
+        let devnet = UrlPolicy {
+            allow_private_addresses: true,
+            ..Default::default()
+        };
+        // Served compressed, stored decompressed
+        assert_eq!(
+            resource.fetch(&devnet, &mut file).await.unwrap(),
+            resource.hash
+        );
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut fetched = vec![];
+        file.read_to_end(&mut fetched).unwrap();
+        assert_eq!(fetched, image);
+
+        let missing = RemoteResource {
+            url: "http://localhost:3005/images/missing".parse().unwrap(),
+            ..resource
+        };
+        assert!(matches!(
+            missing
+                .fetch(&devnet, &mut tempfile::tempfile().unwrap())
+                .await,
+            Err(DownloadError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_patch() {
+        let dir = tempfile::tempdir().unwrap();
+        let base: Vec<u8> = (0..200_000u32)
+            .flat_map(|i| (i % 251).to_le_bytes())
+            .collect();
+        let mut image = base.clone();
+        image[1000..1010].copy_from_slice(b"new layer!");
+        image.extend_from_slice(b"appended");
+        std::fs::write(dir.path().join("base"), &base).unwrap();
+        std::fs::write(dir.path().join("image"), &image).unwrap();
+
+        let mut patch = vec![];
+        create_patch(
+            &dir.path().join("base"),
+            &dir.path().join("image"),
+            &mut patch,
+        )
+        .unwrap();
+        assert!(patch.len() < image.len() / 100);
+
+        let mut reconstructed = vec![];
+        let hash = apply_patch(
+            &dir.path().join("base"),
+            patch.as_slice(),
+            &mut reconstructed,
+        )
+        .unwrap();
+        assert_eq!(reconstructed, image);
+        let mut hasher = Blake3Hasher::new();
+        hasher.update(&image);
+        assert_eq!(hash, ImageHash::from(hasher.finalize()));
     }
 }
@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::Display};
 
 use const_hex::{traits::FromHex, FromHexError, ToHexExt};
 use serde::{Deserialize, Serialize};
@@ -12,6 +12,39 @@ pub struct SerializableHash<HSH: Hasher>(#[serde(with = "hex_encoded")] pub HSH:
 where
     HSH::Hash: ToHexExt + FromHex;
 
+impl<HSH: Hasher> SerializableHash<HSH>
+where
+    HSH::Hash: ToHexExt + FromHex,
+{
+    pub fn new(hash: HSH::Hash) -> Self {
+        Self(hash)
+    }
+
+    pub fn into_inner(self) -> HSH::Hash {
+        self.0
+    }
+}
+
+impl<HSH: Hasher> PartialEq for SerializableHash<HSH>
+where
+    HSH::Hash: ToHexExt + FromHex + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<HSH: Hasher> Eq for SerializableHash<HSH> where HSH::Hash: ToHexExt + FromHex + Eq {}
+
+impl<HSH: Hasher> Display for SerializableHash<HSH>
+where
+    HSH::Hash: ToHexExt + FromHex,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.encode_hex_with_prefix())
+    }
+}
+
 impl<HSH: Hasher> FromHex for SerializableHash<HSH>
 where
     HSH::Hash: ToHexExt + FromHex,
@@ -58,5 +91,10 @@ mod tests {
             serde_json::from_str(&serialized).unwrap();
 
         assert_eq!(deserialized.0, hash);
+        assert_eq!(deserialized, SerializableHash::new(hash));
+        assert_eq!(
+            serde_json::to_string(&deserialized.to_string()).unwrap(),
+            serialized
+        );
     }
 }
@@ -0,0 +1,325 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use tokio::{sync::watch, task::JoinSet};
+use tracing::{error, info};
+
+/// How a failed background task is restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Delay before the first restart, doubled on every further one
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Restarts before the task is given up, restarted forever if `None`
+    pub max_restarts: Option<u32>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: None,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// The task is given up on its first failure
+    pub fn never() -> Self {
+        Self {
+            max_restarts: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// Delay before the `restarts`-th restart, the first one being 0
+    fn backoff(&self, restarts: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << restarts.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskState {
+    Running,
+    /// Failed, waiting for the backoff to restart
+    Restarting,
+    /// Stopped on shutdown, or returned on its own
+    Finished,
+    /// Failed more times than its policy allows, not restarted anymore
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHealth {
+    pub name: String,
+    pub state: TaskState,
+    /// Times the task was restarted after failing
+    pub restarts: u32,
+    /// Error or panic message of the last failure
+    pub last_error: Option<String>,
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// Last time the task was (re)started
+    pub started_at: DateTime<Utc>,
+}
+
+/// Background tasks of the process, healthy unless one of them was given up
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub tasks: Vec<TaskHealth>,
+}
+
+/// Runs the background loops of a process, restarting the ones which fail or panic with the backoff of their
+/// [`RestartPolicy`] and keeping track of their health. The tasks are stopped by the shutdown channel they are given,
+/// and aren't restarted once it changed.
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    shutdown_rx: watch::Receiver<bool>,
+    tasks: Arc<Mutex<BTreeMap<String, TaskHealth>>>,
+}
+
+impl Supervisor {
+    pub fn new(shutdown_rx: watch::Receiver<bool>) -> Self {
+        Self {
+            shutdown_rx,
+            tasks: Arc::default(),
+        }
+    }
+
+    /// Spawns the task in `tasks`, `task` creates it again on every restart. Its future should return when the
+    /// shutdown channel it's given changes.
+    pub fn spawn<F, Fut>(
+        &self,
+        tasks: &mut JoinSet<Result<()>>,
+        name: impl Into<String>,
+        policy: RestartPolicy,
+        task: F,
+    ) where
+        F: FnMut(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let name = name.into();
+        self.tasks.lock().unwrap().insert(
+            name.clone(),
+            TaskHealth {
+                name: name.clone(),
+                state: TaskState::Running,
+                restarts: 0,
+                last_error: None,
+                last_failure_at: None,
+                started_at: Utc::now(),
+            },
+        );
+
+        let supervisor = self.clone();
+        tasks.spawn(async move {
+            supervisor.supervise(&name, policy, task).await;
+            Ok(())
+        });
+    }
+
+    pub fn health(&self) -> HealthReport {
+        let tasks: Vec<TaskHealth> = self.tasks.lock().unwrap().values().cloned().collect();
+        HealthReport {
+            healthy: tasks.iter().all(|task| task.state != TaskState::Failed),
+            tasks,
+        }
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        // A dropped sender stops the tasks as well
+        self.shutdown_rx.has_changed().unwrap_or(true)
+    }
+
+    fn update(&self, name: &str, f: impl FnOnce(&mut TaskHealth)) {
+        if let Some(health) = self.tasks.lock().unwrap().get_mut(name) {
+            f(health);
+        }
+    }
+
+    async fn supervise<F, Fut>(&self, name: &str, policy: RestartPolicy, mut task: F)
+    where
+        F: FnMut(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let mut restarts = 0;
+        loop {
+            // Run in place, so the attempt is dropped along with the supervisor, and its panic is caught here
+            let outcome = AssertUnwindSafe(task(self.shutdown_rx.clone()))
+                .catch_unwind()
+                .await;
+            if self.is_shutting_down() {
+                self.update(name, |health| health.state = TaskState::Finished);
+                info!(task = name, "Task stopped");
+                return;
+            }
+
+            let err = match outcome {
+                Ok(Ok(())) => {
+                    self.update(name, |health| health.state = TaskState::Finished);
+                    info!(task = name, "Task finished");
+                    return;
+                }
+                Ok(Err(err)) => format!("{err:#}"),
+                Err(panic) => {
+                    panic
+                        .downcast_ref::<&str>()
+                        .map(|msg| msg.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "task panicked".to_string())
+                }
+            };
+
+            let given_up = policy.max_restarts.is_some_and(|max| restarts >= max);
+            self.update(name, |health| {
+                health.state = if given_up {
+                    TaskState::Failed
+                } else {
+                    TaskState::Restarting
+                };
+                health.last_error = Some(err.clone());
+                health.last_failure_at = Some(Utc::now());
+            });
+            if given_up {
+                error!(task = name, error = %err, restarts, "Task failed, given up");
+                return;
+            }
+
+            let backoff = policy.backoff(restarts);
+            error!(task = name, error = %err, restarts, ?backoff, "Task failed, restarting");
+            let mut shutdown_rx = self.shutdown_rx.clone();
+            tokio::select! {
+                _ = shutdown_rx.changed() => {
+                    self.update(name, |health| health.state = TaskState::Finished);
+                    info!(task = name, "Task stopped");
+                    return;
+                }
+
+                _ = tokio::time::sleep(backoff) => {}
+            }
+
+            restarts += 1;
+            self.update(name, |health| {
+                health.state = TaskState::Running;
+                health.restarts = restarts;
+                health.started_at = Utc::now();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_supervisor() {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let supervisor = Supervisor::new(shutdown_rx);
+        let mut tasks = JoinSet::new();
+        let policy = RestartPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            max_restarts: Some(2),
+        };
+
+        let runs = Arc::new(AtomicU32::new(0));
+        supervisor.spawn(&mut tasks, "flaky", policy, {
+            let runs = runs.clone();
+            move |_| {
+                let run = runs.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run == 0 {
+                        panic!("first run panics");
+                    }
+                    anyhow::bail!("run {run} fails")
+                }
+            }
+        });
+        supervisor.spawn(
+            &mut tasks,
+            "loop",
+            RestartPolicy::default(),
+            |mut shutdown_rx| {
+                async move {
+                    let _ = shutdown_rx.changed().await;
+                    Ok(())
+                }
+            },
+        );
+
+        while supervisor.health().tasks[0].state != TaskState::Failed {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let report = supervisor.health();
+        assert!(!report.healthy);
+        let flaky = &report.tasks[0];
+        assert_eq!((flaky.name.as_str(), flaky.restarts), ("flaky", 2));
+        assert_eq!(flaky.last_error.as_deref(), Some("run 2 fails"));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(report.tasks[1].state, TaskState::Running);
+
+        shutdown_tx.send(true).unwrap();
+        while tasks.join_next().await.is_some() {}
+        assert_eq!(supervisor.health().tasks[1].state, TaskState::Finished);
+    }
+
+    #[tokio::test]
+    async fn test_abort_drops_attempt() {
+        struct Running(Arc<AtomicU32>);
+        impl Drop for Running {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+        let supervisor = Supervisor::new(shutdown_rx);
+        let mut tasks = JoinSet::new();
+        let running = Arc::new(AtomicU32::new(0));
+        supervisor.spawn(&mut tasks, "endless", RestartPolicy::default(), {
+            let running = running.clone();
+            move |_| {
+                running.fetch_add(1, Ordering::SeqCst);
+                let guard = Running(running.clone());
+                async move {
+                    let _guard = guard;
+                    std::future::pending::<Result<()>>().await
+                }
+            }
+        });
+        while running.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // The attempt doesn't outlive its supervising task
+        tasks.abort_all();
+        while tasks.join_next().await.is_some() {}
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RestartPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(8));
+        assert_eq!(policy.backoff(40), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    hash::{
+        blake3::{Blake3Hash, Blake3Hasher},
+        Hasher,
+    },
+    proof::request::ProofRequestId,
+};
+
+/// Domain tag of the audit entry hashes, so an entry encoding never collides with another kind of payload
+const HASH_DOMAIN: &[u8] = b"fermah/audit/v1";
+
+/// Hash the first entry of the log is chained to
+pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// Operation moving funds, or an admin action on them, recorded in the audit log
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum AuditAction {
+    /// Amount held from the requester's balance for a withdrawal
+    WithdrawalRequested,
+    /// Withdrawal above the approval threshold approved by one of the approvers
+    WithdrawalApproved,
+    /// Withdrawal executed through the vault
+    WithdrawalSettled,
+    /// Withdrawal not executed, its amount released
+    WithdrawalFailed,
+    /// Requests of the requester paid to their operators
+    Payment,
+    /// Unspent reservation of a request returned to the requester
+    Refund,
+    /// Ledger entry of the requester overwritten by a reconciliation
+    LedgerRepaired,
+    /// Offenses of the operator settled by a slashing transaction
+    OffensesSettled,
+    /// Operator banned or greylisted by an admin
+    OperatorRestricted,
+    /// Restriction of the operator lifted by an admin
+    OperatorRestrictionCleared,
+    /// Request cancelled by an admin, its reservation is refunded once the cancellation is applied
+    AdminCancellation,
+}
+
+/// What is appended to the audit log
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    /// Whose funds the action is on: the requester, or the operator for slashings and restrictions
+    pub actor: Address,
+    pub action: AuditAction,
+    pub amount: Option<U256>,
+    pub tx_hash: Option<H256>,
+    pub proof_request_ids: Vec<ProofRequestId>,
+    /// Anything else identifying the action, e.g. the withdrawal id or the failure reason
+    pub details: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn new(actor: Address, action: AuditAction) -> Self {
+        Self {
+            actor,
+            action,
+            amount: None,
+            tx_hash: None,
+            proof_request_ids: vec![],
+            details: None,
+        }
+    }
+
+    pub fn with_amount(mut self, amount: U256) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_tx_hash(mut self, tx_hash: H256) -> Self {
+        self.tx_hash = Some(tx_hash);
+        self
+    }
+
+    pub fn with_proof_requests(mut self, ids: impl IntoIterator<Item = ProofRequestId>) -> Self {
+        self.proof_request_ids.extend(ids);
+        self
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+/// Entry of the append-only audit log. Every entry hashes the one before it, so an entry changed or removed after
+/// the fact breaks the chain from there on.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// Position in the log, starting at 1
+    pub seq: i64,
+    pub record: AuditRecord,
+    pub recorded_at: DateTime<Utc>,
+    pub prev_hash: Blake3Hash,
+    pub hash: Blake3Hash,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AuditChainError {
+    #[error("audit entry {seq} doesn't follow the entry before it")]
+    Broken { seq: i64 },
+
+    #[error("audit entry {seq} doesn't match its hash")]
+    Tampered { seq: i64 },
+}
+
+impl AuditEntry {
+    /// Hash of the record appended at `seq` after the entry hashed `prev_hash`. The time is hashed in microseconds,
+    /// as precise as it's stored.
+    pub fn chain_hash(
+        seq: i64,
+        record: &AuditRecord,
+        recorded_at: DateTime<Utc>,
+        prev_hash: &Blake3Hash,
+    ) -> Blake3Hash {
+        fn encode_bytes(hasher: &mut Blake3Hasher, bytes: &[u8]) {
+            hasher.update(&(bytes.len() as u64).to_be_bytes());
+            hasher.update(bytes);
+        }
+
+        let mut hasher = Blake3Hasher::new();
+        hasher
+            .update(HASH_DOMAIN)
+            .update(prev_hash.as_32_bytes())
+            .update(&seq.to_be_bytes())
+            .update(&recorded_at.timestamp_micros().to_be_bytes())
+            .update(record.actor.as_bytes());
+        encode_bytes(&mut hasher, record.action.to_string().as_bytes());
+
+        let mut amount = [0; 32];
+        match record.amount {
+            Some(value) => {
+                value.to_big_endian(&mut amount);
+                hasher.update(&[1]).update(&amount);
+            }
+            None => {
+                hasher.update(&[0]);
+            }
+        }
+        match record.tx_hash {
+            Some(tx_hash) => {
+                hasher.update(&[1]).update(tx_hash.as_bytes());
+            }
+            None => {
+                hasher.update(&[0]);
+            }
+        }
+        hasher.update(&(record.proof_request_ids.len() as u64).to_be_bytes());
+        for id in &record.proof_request_ids {
+            hasher.update(id.as_32_bytes());
+        }
+        match &record.details {
+            Some(details) => {
+                hasher.update(&[1]);
+                encode_bytes(&mut hasher, details.as_bytes());
+            }
+            None => {
+                hasher.update(&[0]);
+            }
+        }
+        hasher.finalize()
+    }
+
+    /// Checks the entry follows the one hashed `prev_hash` and wasn't changed since
+    pub fn verify(&self, prev_hash: &Blake3Hash) -> Result<(), AuditChainError> {
+        if self.prev_hash != *prev_hash {
+            return Err(AuditChainError::Broken { seq: self.seq });
+        }
+        if Self::chain_hash(self.seq, &self.record, self.recorded_at, prev_hash) != self.hash {
+            return Err(AuditChainError::Tampered { seq: self.seq });
+        }
+        Ok(())
+    }
+}
+
+/// Checks consecutive entries of the log, the first one following the entry hashed `prev_hash`. Returns the hash
+/// the next entries have to follow.
+pub fn verify_chain<'a>(
+    entries: impl IntoIterator<Item = &'a AuditEntry>,
+    prev_hash: Blake3Hash,
+) -> Result<Blake3Hash, AuditChainError> {
+    entries.into_iter().try_fold(prev_hash, |prev_hash, entry| {
+        entry.verify(&prev_hash)?;
+        Ok(entry.hash)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(records: Vec<AuditRecord>) -> Vec<AuditEntry> {
+        let mut prev_hash = Blake3Hash::from(GENESIS_HASH);
+        records
+            .into_iter()
+            .zip(1..)
+            .map(|(record, seq)| {
+                let recorded_at =
+                    DateTime::from_timestamp_micros(1_700_000_000_000_000 + seq).unwrap();
+                let hash = AuditEntry::chain_hash(seq, &record, recorded_at, &prev_hash);
+                let entry = AuditEntry {
+                    seq,
+                    record,
+                    recorded_at,
+                    prev_hash,
+                    hash,
+                };
+                prev_hash = hash;
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let requester = Address::repeat_byte(1);
+        let entries = chain(vec![
+            AuditRecord::new(requester, AuditAction::WithdrawalRequested)
+                .with_amount(100.into())
+                .with_details("withdrawal"),
+            AuditRecord::new(requester, AuditAction::Payment)
+                .with_amount(40.into())
+                .with_proof_requests([ProofRequestId::from([2; 32])]),
+            AuditRecord::new(requester, AuditAction::WithdrawalSettled)
+                .with_tx_hash(H256::repeat_byte(3)),
+        ]);
+        let genesis = Blake3Hash::from(GENESIS_HASH);
+        assert_eq!(verify_chain(&entries, genesis), Ok(entries[2].hash));
+
+        // A changed amount doesn't match the hash anymore
+        let mut tampered = entries.clone();
+        tampered[1].record.amount = Some(400.into());
+        assert_eq!(
+            verify_chain(&tampered, genesis),
+            Err(AuditChainError::Tampered { seq: 2 })
+        );
+
+        // Nor does the log with an entry removed
+        let mut removed = entries.clone();
+        removed.remove(1);
+        assert_eq!(
+            verify_chain(&removed, genesis),
+            Err(AuditChainError::Broken { seq: 3 })
+        );
+    }
+
+    #[test]
+    fn test_action_roundtrip() {
+        for action in [
+            AuditAction::WithdrawalRequested,
+            AuditAction::WithdrawalApproved,
+            AuditAction::WithdrawalSettled,
+            AuditAction::WithdrawalFailed,
+            AuditAction::Payment,
+            AuditAction::Refund,
+            AuditAction::LedgerRepaired,
+            AuditAction::OffensesSettled,
+            AuditAction::OperatorRestricted,
+            AuditAction::OperatorRestrictionCleared,
+            AuditAction::AdminCancellation,
+        ] {
+            assert_eq!(action.to_string().parse::<AuditAction>(), Ok(action));
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of a proof requester's funds as seen by the matchmaker.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequesterBalance {
+    /// Last known vault deposit of the requester
+    pub deposit: U256,
+    /// Funds locked by in-flight or not yet distributed proof requests, and by pending withdrawals
+    pub reserved: U256,
+    /// Funds that can still be spent on new proof requests
+    pub spendable: U256,
+}
+
+impl RequesterBalance {
+    pub fn new(deposit: U256, reserved: U256) -> Self {
+        Self {
+            deposit,
+            reserved,
+            spendable: deposit.saturating_sub(reserved),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spendable_saturates() {
+        let balance = RequesterBalance::new(U256::from(10), U256::from(25));
+        assert_eq!(balance.spendable, U256::zero());
+
+        let balance = RequesterBalance::new(U256::from(25), U256::from(10));
+        assert_eq!(balance.spendable, U256::from(15));
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let balance = RequesterBalance::new(U256::from(100), U256::from(40));
+        let json = serde_json::to_string(&balance).unwrap();
+        assert!(json.contains("spendable"));
+        assert_eq!(
+            serde_json::from_str::<RequesterBalance>(&json).unwrap(),
+            balance
+        );
+    }
+}
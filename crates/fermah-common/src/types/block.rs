@@ -0,0 +1,10 @@
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+
+/// Block at which a value was read from the chain, used to detect reorgs under cached values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservedBlock {
+    pub number: u64,
+    pub hash: H256,
+}
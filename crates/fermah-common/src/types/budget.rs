@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+
+use chrono::NaiveDate;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::{crypto::signer::freshness::Freshness, hash::Hashable};
+
+/// Spend caps a requester sets on itself, reservations over a cap are refused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetConfig {
+    /// Cap on the amount reserved per UTC day
+    pub daily_cap: Option<U256>,
+    /// Cap on the amount reserved since the budget was set
+    pub total_cap: Option<U256>,
+    /// Percentage of a cap, from 1 to 100, the webhooks are alerted at, once per crossing
+    pub alert_percent: Option<u8>,
+}
+
+impl BudgetConfig {
+    /// Amount of the cap the alert is sent at
+    pub fn alert_threshold(&self, cap: U256) -> Option<U256> {
+        self.alert_percent
+            .map(|percent| cap.saturating_mul(U256::from(percent)) / U256::from(100))
+    }
+
+    pub fn cap(&self, cap: BudgetCap) -> Option<U256> {
+        match cap {
+            BudgetCap::Daily => self.daily_cap,
+            BudgetCap::Total => self.total_cap,
+        }
+    }
+}
+
+/// Caps a requester signs to replace its budget, fresh so an old signed budget can't be replayed to lift them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetUpdate {
+    #[serde(flatten)]
+    pub config: BudgetConfig,
+    #[serde(flatten)]
+    pub freshness: Freshness,
+}
+
+impl Hashable for BudgetUpdate {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum BudgetCap {
+    Daily,
+    Total,
+}
+
+/// Budget of a requester with what it already consumed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    pub config: BudgetConfig,
+    /// UTC day `spent_today` is counted for
+    pub day: NaiveDate,
+    pub spent_today: U256,
+    pub spent_total: U256,
+}
+
+impl BudgetStatus {
+    pub fn spent(&self, cap: BudgetCap) -> U256 {
+        match cap {
+            BudgetCap::Daily => self.spent_today,
+            BudgetCap::Total => self.spent_total,
+        }
+    }
+
+    /// First cap the amount doesn't fit in
+    pub fn check(&self, requested: U256) -> Result<(), BudgetExceeded> {
+        for cap in [BudgetCap::Daily, BudgetCap::Total] {
+            let Some(limit) = self.config.cap(cap) else {
+                continue;
+            };
+            let spent = self.spent(cap);
+            if spent.saturating_add(requested) > limit {
+                return Err(BudgetExceeded {
+                    cap,
+                    limit,
+                    spent,
+                    requested,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reservation refused because it doesn't fit in the requester's budget, also the data of the RPC error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[error("{cap} budget of {limit} exceeded: {spent} already spent, {requested} requested")]
+pub struct BudgetExceeded {
+    pub cap: BudgetCap,
+    pub limit: U256,
+    pub spent: U256,
+    pub requested: U256,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_forged_budget() {
+        let budget = fixtures::signed(
+            BudgetUpdate {
+                config: BudgetConfig {
+                    daily_cap: Some(U256::from(100)),
+                    total_cap: None,
+                    alert_percent: None,
+                },
+                freshness: Freshness::new(Utc::now()),
+            },
+            &fixtures::requester(),
+        );
+        assert!(budget.verify_payload().is_ok());
+
+        // The requester's signature paired with lifted caps and a fresh nonce
+        let mut forged = budget;
+        forged.payload.config.daily_cap = None;
+        forged.payload.freshness.nonce += 1;
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+    }
+
+    #[test]
+    fn test_check_budget() {
+        let status = BudgetStatus {
+            config: BudgetConfig {
+                daily_cap: Some(U256::from(100)),
+                total_cap: Some(U256::from(150)),
+                alert_percent: Some(80),
+            },
+            day: NaiveDate::from_ymd_opt(2024, 10, 16).unwrap(),
+            spent_today: U256::from(60),
+            spent_total: U256::from(120),
+        };
+
+        assert!(status.check(U256::from(30)).is_ok());
+        assert_eq!(
+            status.check(U256::from(41)).unwrap_err().cap,
+            BudgetCap::Daily
+        );
+
+        let status = BudgetStatus {
+            spent_total: U256::from(140),
+            ..status
+        };
+        assert_eq!(
+            status.check(U256::from(20)).unwrap_err().cap,
+            BudgetCap::Total
+        );
+
+        assert_eq!(
+            status.config.alert_threshold(U256::from(150)),
+            Some(U256::from(120))
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::hash::Hashable;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidDelegation {
+    #[error("submitter is the zero address")]
+    ZeroSubmitter,
+    #[error("the requester doesn't need a delegation to submit its own requests")]
+    OwnKey,
+    #[error("delegation expired at {0}")]
+    Expired(DateTime<Utc>),
+}
+
+/// Limits on what a submitter spends of the requester's balance, unlimited if unset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationLimits {
+    /// Cap on the quoted price of the requests the submitter submits
+    pub spend_cap: Option<U256>,
+    /// Most requests the submitter submits
+    pub max_requests: Option<u64>,
+}
+
+/// Key allowed to submit proof requests paid by the requester which signs the delegation, until it expires. The
+/// requests name the requester and are signed by the submitter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    pub submitter: Address,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub limits: DelegationLimits,
+    /// Above the nonces of the delegations and revocations of the submitter signed before, so an older one can't be
+    /// replayed over it, e.g.
+    /// [`Freshness::nonce_at`](crate::crypto::signer::freshness::Freshness::nonce_at)
+    pub nonce: u64,
+}
+
+impl Delegation {
+    pub fn validate(
+        &self,
+        requester: &Address,
+        now: DateTime<Utc>,
+    ) -> Result<(), InvalidDelegation> {
+        if self.submitter.is_zero() {
+            return Err(InvalidDelegation::ZeroSubmitter);
+        }
+        if self.submitter == *requester {
+            return Err(InvalidDelegation::OwnKey);
+        }
+        if self.expires_at <= now {
+            return Err(InvalidDelegation::Expired(self.expires_at));
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for Delegation {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Revocation of the delegation of the submitter, signed by the requester
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationRevocation {
+    pub submitter: Address,
+    /// Same sequence as the nonces of the delegations, see [`Delegation::nonce`]
+    pub nonce: u64,
+}
+
+impl Hashable for DelegationRevocation {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Delegation of a requester with what its submitter already spent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationStatus {
+    pub requester: Address,
+    pub delegation: Delegation,
+    pub spent: U256,
+    pub requests: u64,
+    pub delegated_at: DateTime<Utc>,
+}
+
+impl DelegationStatus {
+    /// Whether the submitter may still submit `requests` requests quoted `quoted` in total
+    pub fn check(
+        &self,
+        quoted: U256,
+        requests: u64,
+        now: DateTime<Utc>,
+    ) -> Result<(), DelegationRefused> {
+        let limits = &self.delegation.limits;
+        if self.delegation.expires_at <= now {
+            return Err(DelegationRefused::Expired(self.delegation.expires_at));
+        }
+        if let Some(cap) = limits.spend_cap {
+            if self.spent.saturating_add(quoted) > cap {
+                return Err(DelegationRefused::SpendCap {
+                    cap,
+                    spent: self.spent,
+                    quoted,
+                });
+            }
+        }
+        if let Some(max) = limits.max_requests {
+            if self.requests.saturating_add(requests) > max {
+                return Err(DelegationRefused::MaxRequests(max));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Requests refused because their signer isn't delegated by their requester, or is over the limits of its delegation
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DelegationRefused {
+    #[error("signer isn't delegated by the requester")]
+    NotDelegated,
+    #[error("delegation expired at {0}")]
+    Expired(DateTime<Utc>),
+    #[error("delegation spend cap of {cap} exceeded: {spent} already spent, {quoted} quoted")]
+    SpendCap {
+        cap: U256,
+        spent: U256,
+        quoted: U256,
+    },
+    #[error("delegation allows at most {0} requests")]
+    MaxRequests(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_forged_delegation() {
+        let requester = fixtures::requester();
+        let delegation = fixtures::signed(
+            Delegation {
+                submitter: Address::random(),
+                expires_at: Utc::now() + Duration::days(30),
+                limits: DelegationLimits::default(),
+                nonce: 1,
+            },
+            &requester,
+        );
+        assert!(delegation.verify_payload().is_ok());
+
+        // The requester's signature paired with a delegation to another key
+        let mut forged = delegation.clone();
+        forged.payload.submitter = Address::random();
+        assert!(forged.verify_payload().is_err());
+
+        let mut revocation = fixtures::signed(
+            DelegationRevocation {
+                submitter: delegation.payload.submitter,
+                nonce: 2,
+            },
+            &requester,
+        );
+        assert!(revocation.verify_payload().is_ok());
+        revocation.payload.nonce = u64::MAX;
+        assert!(revocation.verify_payload().is_err());
+    }
+
+    #[test]
+    fn test_check_delegation() {
+        let now = Utc::now();
+        let requester = Address::random();
+        let delegation = Delegation {
+            submitter: Address::random(),
+            expires_at: now + Duration::days(30),
+            limits: DelegationLimits {
+                spend_cap: Some(U256::from(100)),
+                max_requests: Some(3),
+            },
+            nonce: 1,
+        };
+        assert_eq!(delegation.validate(&requester, now), Ok(()));
+        assert_eq!(
+            delegation.validate(&delegation.submitter, now),
+            Err(InvalidDelegation::OwnKey)
+        );
+
+        let status = DelegationStatus {
+            requester,
+            delegation: delegation.clone(),
+            spent: U256::from(60),
+            requests: 2,
+            delegated_at: now,
+        };
+        assert_eq!(status.check(U256::from(40), 1, now), Ok(()));
+        assert_eq!(
+            status.check(U256::from(41), 1, now),
+            Err(DelegationRefused::SpendCap {
+                cap: U256::from(100),
+                spent: U256::from(60),
+                quoted: U256::from(41),
+            })
+        );
+        assert_eq!(
+            status.check(U256::from(10), 2, now),
+            Err(DelegationRefused::MaxRequests(3))
+        );
+        assert_eq!(
+            status.check(U256::zero(), 1, delegation.expires_at),
+            Err(DelegationRefused::Expired(delegation.expires_at))
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use clap::Args;
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Basis points of a whole amount
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// Commission the protocol takes out of every reserved amount, the rest is paid out to the operator
+#[derive(Args, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolFee {
+    /// Commission in basis points, 0 takes no fee
+    #[arg(long = "protocol-fee-bps", default_value_t = 0, value_parser = clap::value_parser!(u16).range(0..=MAX_FEE_BPS as i64))]
+    #[serde(default)]
+    pub bps: u16,
+    /// Address the vault distributes the commission to, required with a commission
+    #[arg(
+        long = "protocol-treasury",
+        default_value = "0x0000000000000000000000000000000000000000"
+    )]
+    #[serde(default)]
+    pub treasury: Address,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidProtocolFee {
+    #[error("protocol fee of {0} bps is over {MAX_FEE_BPS} bps")]
+    TooHigh(u16),
+    #[error("protocol fee of {0} bps has no treasury, the commission would be reserved and never paid out")]
+    NoTreasury(u16),
+}
+
+impl ProtocolFee {
+    pub fn split(&self, amount: U256) -> FeeSplit {
+        FeeSplit::new(amount, self.bps)
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidProtocolFee> {
+        if self.bps > MAX_FEE_BPS {
+            return Err(InvalidProtocolFee::TooHigh(self.bps));
+        }
+        if self.bps > 0 && self.treasury.is_zero() {
+            return Err(InvalidProtocolFee::NoTreasury(self.bps));
+        }
+        Ok(())
+    }
+}
+
+/// Amount split into the operator payout and the protocol commission, the commission is rounded down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSplit {
+    pub operator_payout: U256,
+    pub protocol_fee: U256,
+}
+
+impl FeeSplit {
+    /// Fees over [`MAX_FEE_BPS`] take the whole amount
+    pub fn new(amount: U256, bps: u16) -> Self {
+        let bps = bps.min(MAX_FEE_BPS);
+        let protocol_fee = amount.full_mul(U256::from(bps)) / U256::from(MAX_FEE_BPS);
+        // The fee is at most the amount, so it fits back
+        let protocol_fee = U256::try_from(protocol_fee).unwrap_or(amount);
+        Self {
+            operator_payout: amount - protocol_fee,
+            protocol_fee,
+        }
+    }
+}
+
+/// Price of a proof request with the protocol commission it includes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeQuote {
+    /// Amount reserved from the requester's deposit
+    pub price: U256,
+    pub fee_bps: u16,
+    #[serde(flatten)]
+    pub split: FeeSplit,
+}
+
+impl FeeQuote {
+    pub fn new(price: U256, fee_bps: u16) -> Self {
+        Self {
+            price,
+            fee_bps,
+            split: FeeSplit::new(price, fee_bps),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let split = FeeSplit::new(U256::from(1_000), 250);
+        assert_eq!(split.protocol_fee, U256::from(25));
+        assert_eq!(split.operator_payout, U256::from(975));
+
+        // Rounded down in favour of the operator
+        let split = FeeSplit::new(U256::from(39), 250);
+        assert_eq!(split.protocol_fee, U256::zero());
+        assert_eq!(split.operator_payout, U256::from(39));
+
+        let split = FeeSplit::new(U256::MAX, MAX_FEE_BPS + 1);
+        assert_eq!(split.protocol_fee, U256::MAX);
+        assert_eq!(split.operator_payout, U256::zero());
+
+        let quote = serde_json::to_value(FeeQuote::new(U256::from(1_000), 250)).unwrap();
+        assert_eq!(quote["feeBps"], 250);
+        assert!(quote.get("protocolFee").is_some());
+    }
+
+    #[test]
+    fn test_protocol_fee_validation() {
+        assert_eq!(ProtocolFee::default().validate(), Ok(()));
+
+        let mut fee = ProtocolFee {
+            bps: 250,
+            treasury: Address::zero(),
+        };
+        assert_eq!(fee.validate(), Err(InvalidProtocolFee::NoTreasury(250)));
+        fee.treasury = Address::from_low_u64_be(1);
+        assert_eq!(fee.validate(), Ok(()));
+        fee.bps = MAX_FEE_BPS + 1;
+        assert_eq!(
+            fee.validate(),
+            Err(InvalidProtocolFee::TooHigh(MAX_FEE_BPS + 1))
+        );
+    }
+}
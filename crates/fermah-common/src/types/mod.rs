@@ -1,2 +1,15 @@
+pub mod audit;
+pub mod balance;
+pub mod block;
+pub mod budget;
+pub mod delegation;
+pub mod fee;
 pub mod network;
+pub mod payment;
 pub mod quorum;
+pub mod reconciliation;
+pub mod region;
+pub mod shard;
+pub mod stats;
+pub mod webhook;
+pub mod withdrawal;
@@ -27,6 +27,15 @@ impl Network {
         }
     }
 
+    /// Chain the network is deployed to, also binds EIP-712 signatures to the network
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Network::Local => 31337,
+            Network::Dev => 17000,
+            Network::Main => 1,
+        }
+    }
+
     pub fn to_mm_p2p(&self) -> Connection {
         match self {
             Network::Local => Connection::try_from_str("127.0.0.1:8888").unwrap(),
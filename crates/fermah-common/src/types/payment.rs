@@ -0,0 +1,176 @@
+use std::{borrow::Cow, fmt, num::ParseIntError, str::FromStr};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{hash::Hashable, proof::request::ProofRequestId};
+
+/// Most payment events returned at once
+pub const MAX_PAYMENT_EVENTS_PAGE: u32 = 1000;
+
+/// Movement of a requester's funds, as accounting systems book it
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum PaymentEventKind {
+    /// Funds held for a request from the requester's balance
+    Reserve,
+    /// Part of a reservation given back to the requester, e.g. when a cancelled request pays its operator only a fee
+    Release,
+    /// Operator paid for a request, without the protocol fee
+    Pay,
+    /// Unspent part of a reservation returned to the requester
+    Refund,
+    /// Protocol fee of a paid request
+    Fee,
+}
+
+/// Position in the stream of payment events: the transaction an event was recorded in, then its sequence number.
+/// Events are only streamed once every transaction which could still commit one before them is done, so paging by the
+/// cursor never skips an event committed late. Written as `xact.seq`, `0.0` from the start.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentCursor {
+    pub xact: i64,
+    pub seq: i64,
+}
+
+impl fmt::Display for PaymentCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.xact, self.seq)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentCursorError {
+    #[error("cursor is not written as xact.seq")]
+    Format,
+    #[error("invalid cursor number: {0}")]
+    Number(#[from] ParseIntError),
+}
+
+impl FromStr for PaymentCursor {
+    type Err = PaymentCursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (xact, seq) = s.split_once('.').ok_or(PaymentCursorError::Format)?;
+        Ok(Self {
+            xact: xact.parse()?,
+            seq: seq.parse()?,
+        })
+    }
+}
+
+/// Payment event of the ledger, derived from the payment transitions of the requests
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentEvent {
+    /// Stable across exports: the sequence number of the transition, suffixed with `-fee` for the fee of a payment
+    pub id: String,
+    /// Position in the stream, the events after it are queried with it as the cursor
+    pub cursor: PaymentCursor,
+    pub kind: PaymentEventKind,
+    pub requester: Address,
+    pub proof_request_id: ProofRequestId,
+    pub amount: U256,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl PaymentEvent {
+    pub const CSV_HEADER: &'static str =
+        "id,cursor,kind,requester,proof_request_id,amount,recorded_at";
+
+    /// Row of the event in a CSV export, none of the fields needs quoting
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{:?},{},{},{}",
+            self.id,
+            self.cursor,
+            self.kind,
+            self.requester,
+            self.proof_request_id,
+            self.amount,
+            self.recorded_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+    }
+}
+
+/// Payment events after a cursor, signed by the requester they're of, or by an admin for every requester
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentEventQuery {
+    /// Every requester's events if `None`, admin only
+    pub requester: Option<Address>,
+    /// Cursor of the last event already seen, the default from the start
+    pub after: PaymentCursor,
+    /// Up to [`MAX_PAYMENT_EVENTS_PAGE`]
+    pub limit: u32,
+}
+
+impl Hashable for PaymentEventQuery {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Page of payment events, oldest first
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentEventPage {
+    pub events: Vec<PaymentEvent>,
+    /// Cursor to query the next page with. It can be past the last event, the transitions which aren't booked
+    /// don't have to be scanned again.
+    pub next_cursor: PaymentCursor,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_row() {
+        let event = PaymentEvent {
+            id: "42-fee".to_string(),
+            cursor: PaymentCursor { xact: 7, seq: 42 },
+            kind: PaymentEventKind::Fee,
+            requester: Address::from_low_u64_be(1),
+            proof_request_id: ProofRequestId::from([1; 32]),
+            amount: U256::from(25),
+            recorded_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        };
+        let row = event.to_csv_row();
+        assert_eq!(
+            row.split(',').count(),
+            PaymentEvent::CSV_HEADER.split(',').count()
+        );
+        assert!(row.starts_with("42-fee,7.42,fee,0x0000000000000000000000000000000000000001,0x"));
+        assert!(row.ends_with(",25,2023-11-14T22:13:20Z"));
+    }
+
+    #[test]
+    fn test_cursor() {
+        let cursor = PaymentCursor { xact: 7, seq: 42 };
+        assert_eq!(cursor.to_string().parse::<PaymentCursor>().unwrap(), cursor);
+        assert_eq!(
+            "0.0".parse::<PaymentCursor>().unwrap(),
+            PaymentCursor::default()
+        );
+        assert!("42".parse::<PaymentCursor>().is_err());
+        // Ordered by the transaction first
+        assert!(PaymentCursor { xact: 8, seq: 1 } > cursor);
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+/// How the vault balance of an address disagrees with the matchmaker's books
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum DiscrepancyKind {
+    /// The deposit cached for the requester isn't its vault balance, requests are admitted against a wrong balance
+    StaleDeposit,
+    /// The requests and withdrawals of the requester lock more than the vault holds, its payouts would fail
+    Undercollateralized,
+    /// The last `BalanceUpdated` event of the address reports another balance than the vault holds at the same block
+    EventMismatch,
+}
+
+/// Vault balance of an address which disagrees with the amount the matchmaker booked for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDiscrepancy {
+    pub address: Address,
+    pub kind: DiscrepancyKind,
+    pub vault_balance: U256,
+    /// Cached deposit, locked funds or event balance, depending on the kind
+    pub booked: U256,
+}
+
+/// Funds of a requester as the matchmaker books them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookedBalance {
+    /// Deposit cached from the vault, if it was ever read
+    pub deposit: Option<U256>,
+    /// Funds locked by proof requests and pending withdrawals
+    pub locked: U256,
+}
+
+impl VaultDiscrepancy {
+    /// Compares the vault balance of the address at a block with its books, and with the balance its last
+    /// `BalanceUpdated` event up to that block reports if there is one
+    pub fn find(
+        address: Address,
+        vault_balance: U256,
+        booked: &BookedBalance,
+        event_balance: Option<U256>,
+    ) -> Vec<Self> {
+        let discrepancy = |kind, booked| {
+            Self {
+                address,
+                kind,
+                vault_balance,
+                booked,
+            }
+        };
+        let mut found = vec![];
+        if let Some(deposit) = booked.deposit.filter(|deposit| *deposit != vault_balance) {
+            found.push(discrepancy(DiscrepancyKind::StaleDeposit, deposit));
+        }
+        if booked.locked > vault_balance {
+            found.push(discrepancy(
+                DiscrepancyKind::Undercollateralized,
+                booked.locked,
+            ));
+        }
+        if let Some(event_balance) = event_balance.filter(|balance| *balance != vault_balance) {
+            found.push(discrepancy(DiscrepancyKind::EventMismatch, event_balance));
+        }
+        found
+    }
+}
+
+/// Report of a run comparing the books of the matchmaker with the vault
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultReconciliation {
+    /// Block the vault balances were read at
+    pub block: u64,
+    /// Addresses compared, the requesters with books and the ones the vault emitted events for since the last run
+    pub checked: u64,
+    pub discrepancies: Vec<VaultDiscrepancy>,
+    pub reconciled_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_discrepancies() {
+        let address = Address::random();
+        let balance = U256::from(100);
+        let booked = BookedBalance {
+            deposit: Some(balance),
+            locked: U256::from(60),
+        };
+        assert!(VaultDiscrepancy::find(address, balance, &booked, Some(balance)).is_empty());
+        // Never read, nor touched by an event
+        assert!(
+            VaultDiscrepancy::find(address, balance, &BookedBalance::default(), None).is_empty()
+        );
+
+        let kinds = |vault_balance: u64, event_balance: Option<u64>| {
+            VaultDiscrepancy::find(
+                address,
+                U256::from(vault_balance),
+                &booked,
+                event_balance.map(U256::from),
+            )
+            .into_iter()
+            .map(|discrepancy| (discrepancy.kind, discrepancy.booked.as_u64()))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds(50, Some(50)),
+            vec![
+                (DiscrepancyKind::StaleDeposit, 100),
+                (DiscrepancyKind::Undercollateralized, 60),
+            ]
+        );
+        assert_eq!(
+            kinds(100, Some(90)),
+            vec![(DiscrepancyKind::EventMismatch, 90)]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Longest region name, zone included
+pub const MAX_REGION_LEN: usize = 64;
+
+/// Most regions a request can prefer
+pub const MAX_PREFERRED_REGIONS: usize = 16;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    #[error("region name is empty")]
+    Empty,
+
+    #[error("region name is longer than {MAX_REGION_LEN} characters")]
+    TooLong,
+
+    #[error("region {0:?} has characters other than lowercase letters, digits, '-' and '/'")]
+    InvalidCharacter(String),
+
+    #[error("at most {MAX_PREFERRED_REGIONS} regions can be preferred")]
+    TooMany,
+}
+
+/// Where an operator runs, a region optionally followed by its zone, e.g. `eu-west` or `eu-west/1a`. Names are
+/// lowercased, the network doesn't define them, operators and requesters agree on them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Region(String);
+
+impl Region {
+    pub fn new(name: &str) -> Result<Self, RegionError> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err(RegionError::Empty);
+        }
+        if name.len() > MAX_REGION_LEN {
+            return Err(RegionError::TooLong);
+        }
+        let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '/';
+        if !name.chars().all(valid) || name.starts_with('/') || name.ends_with('/') {
+            return Err(RegionError::InvalidCharacter(name));
+        }
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether `other` is this region or one of its zones, `eu-west` contains `eu-west/1a` but not `eu-west-2`
+    pub fn contains(&self, other: &Region) -> bool {
+        other.0 == self.0
+            || other
+                .0
+                .strip_prefix(self.0.as_str())
+                .is_some_and(|zone| zone.starts_with('/'))
+    }
+}
+
+impl TryFrom<String> for Region {
+    type Error = RegionError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+impl From<Region> for String {
+    fn from(value: Region) -> Self {
+        value.0
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl Display for Region {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Regions the requester wants its request proven in, e.g. close to where its inputs are streamed from. Operators in
+/// one of the regions, or in one of their zones, are matched first. If `required`, the request waits for such an
+/// operator instead of going elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredRegions {
+    pub regions: Vec<Region>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl PreferredRegions {
+    pub fn validate(&self) -> Result<(), RegionError> {
+        if self.regions.is_empty() {
+            return Err(RegionError::Empty);
+        }
+        if self.regions.len() > MAX_PREFERRED_REGIONS {
+            return Err(RegionError::TooMany);
+        }
+        Ok(())
+    }
+
+    /// Whether an operator in `region` is in one of the preferred regions, operators which didn't report one never are
+    pub fn matches(&self, region: Option<&Region>) -> bool {
+        region.is_some_and(|region| self.regions.iter().any(|r| r.contains(region)))
+    }
+
+    /// Whether an operator in `region` can be assigned the request at all
+    pub fn allows(&self, region: Option<&Region>) -> bool {
+        !self.required || self.matches(region)
+    }
+
+    pub(crate) fn collect(&self) -> Vec<u8> {
+        let names: Vec<&str> = self.regions.iter().map(Region::as_str).collect();
+        [&[self.required as u8][..], names.join(",").as_bytes()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(name: &str) -> Region {
+        Region::new(name).unwrap()
+    }
+
+    #[test]
+    fn test_region_names() {
+        assert_eq!(region(" EU-West/1a ").as_str(), "eu-west/1a");
+        assert_eq!(Region::new(""), Err(RegionError::Empty));
+        assert_eq!(
+            Region::new(&"a".repeat(MAX_REGION_LEN + 1)),
+            Err(RegionError::TooLong)
+        );
+        assert!(Region::new("eu west").is_err());
+        assert!(Region::new("eu-west/").is_err());
+
+        // Validated when deserialized too
+        assert!(serde_json::from_str::<Region>("\"us_east\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<Region>("\"US-EAST\"").unwrap(),
+            region("us-east")
+        );
+    }
+
+    #[test]
+    fn test_matching() {
+        assert!(region("eu-west").contains(&region("eu-west/1a")));
+        assert!(region("eu-west").contains(&region("eu-west")));
+        assert!(!region("eu-west").contains(&region("eu-west-2")));
+        assert!(!region("eu-west/1a").contains(&region("eu-west")));
+
+        let preferred = PreferredRegions {
+            regions: vec![region("eu-west"), region("us-east/1b")],
+            required: false,
+        };
+        assert!(preferred.matches(Some(&region("eu-west/1c"))));
+        assert!(!preferred.matches(Some(&region("us-east/1a"))));
+        assert!(!preferred.matches(None));
+        assert!(preferred.allows(None));
+
+        let required = PreferredRegions {
+            required: true,
+            ..preferred
+        };
+        assert!(!required.allows(None));
+        assert!(required.allows(Some(&region("us-east/1b"))));
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::proof::request::ProofRequestId;
+
+/// Position on the hash ring, the first 4 bytes of a proof request id read big endian
+pub type RingPosition = u32;
+
+/// Points each instance is placed at on the ring, more spread the load more evenly
+pub const VIRTUAL_NODES: u32 = 64;
+
+pub fn ring_position(proof_request_id: &ProofRequestId) -> RingPosition {
+    let bytes = proof_request_id.as_32_bytes();
+    RingPosition::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Range of ring positions owned by one matchmaker instance, `end` is exclusive and `None` up to the end of the ring
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardRange {
+    pub instance_id: String,
+    pub start: RingPosition,
+    pub end: Option<RingPosition>,
+}
+
+impl ShardRange {
+    pub fn contains(&self, position: RingPosition) -> bool {
+        position >= self.start && self.end.map_or(true, |end| position < end)
+    }
+}
+
+/// Consistent hashing of proof requests over matchmaker instances. A request is owned by the instance of the last
+/// point at or before its position, wrapping around the ring, so an instance joining or leaving only moves the
+/// requests next to its own points.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashRing {
+    /// Sorted by position
+    points: Vec<(RingPosition, String)>,
+}
+
+impl HashRing {
+    pub fn new<I: AsRef<str>>(instances: impl IntoIterator<Item = I>) -> Self {
+        let mut points: Vec<(RingPosition, String)> = instances
+            .into_iter()
+            .flat_map(|instance| {
+                let instance = instance.as_ref().to_string();
+                (0..VIRTUAL_NODES).map(move |node| (Self::point(&instance, node), instance.clone()))
+            })
+            .collect();
+        // Ties go to the smallest instance id, so every instance computes the same ring
+        points.sort();
+        points.dedup_by_key(|(position, _)| *position);
+        Self { points }
+    }
+
+    fn point(instance: &str, node: u32) -> RingPosition {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(instance.as_bytes());
+        hasher.update(&node.to_be_bytes());
+        let hash = hasher.finalize();
+        let bytes = hash.as_bytes();
+        RingPosition::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn owner(&self, proof_request_id: &ProofRequestId) -> Option<&str> {
+        let position = ring_position(proof_request_id);
+        let index = self.points.partition_point(|(point, _)| *point <= position);
+        let (_, instance) = match index {
+            0 => self.points.last()?,
+            _ => &self.points[index - 1],
+        };
+        Some(instance)
+    }
+
+    /// Ranges covering the whole ring, adjacent points of the same instance are merged
+    pub fn ranges(&self) -> Vec<ShardRange> {
+        let Some((_, last)) = self.points.last() else {
+            return vec![];
+        };
+
+        let mut ranges: Vec<ShardRange> = vec![];
+        // Positions before the first point wrap around to the last one
+        if self.points[0].0 > 0 {
+            ranges.push(ShardRange {
+                instance_id: last.clone(),
+                start: 0,
+                end: Some(self.points[0].0),
+            });
+        }
+        for (i, (start, instance)) in self.points.iter().enumerate() {
+            let end = self.points.get(i + 1).map(|(point, _)| *point);
+            match ranges.last_mut() {
+                Some(previous) if previous.instance_id == *instance => previous.end = end,
+                _ => {
+                    ranges.push(ShardRange {
+                        instance_id: instance.clone(),
+                        start: *start,
+                        end,
+                    })
+                }
+            }
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn ids(n: usize) -> Vec<ProofRequestId> {
+        let mut rng = StdRng::seed_from_u64(0);
+        (0..n)
+            .map(|_| ProofRequestId::from(rng.gen::<[u8; 32]>()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ranges_cover_the_ring() {
+        let ring = HashRing::new(["mm-a", "mm-b", "mm-c"]);
+        let ranges = ring.ranges();
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges.last().unwrap().end, None);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, Some(pair[1].start));
+        }
+
+        for id in ids(1_000) {
+            let owner = ring.owner(&id).unwrap();
+            let range = ranges
+                .iter()
+                .find(|range| range.contains(ring_position(&id)))
+                .unwrap();
+            assert_eq!(range.instance_id, owner);
+        }
+        assert!(HashRing::new(Vec::<String>::new())
+            .owner(&ids(1)[0])
+            .is_none());
+    }
+
+    #[test]
+    fn test_join_moves_only_to_the_new_instance() {
+        let before = HashRing::new(["mm-a", "mm-b"]);
+        let after = HashRing::new(["mm-a", "mm-b", "mm-c"]);
+
+        let mut moved = 0;
+        for id in ids(3_000) {
+            let (from, to) = (before.owner(&id).unwrap(), after.owner(&id).unwrap());
+            if from != to {
+                assert_eq!(to, "mm-c");
+                moved += 1;
+            }
+        }
+        // About a third of the requests
+        assert!((600..1_400).contains(&moved), "{moved} moved");
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::operator::{liveness::Liveness, OperatorId};
+
+/// Times a proof request entered each status of its lifecycle, the latest one if it entered a status more than once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofLifecycle {
+    pub created: Option<DateTime<Utc>>,
+    pub accepted: Option<DateTime<Utc>>,
+    pub assigned: Option<DateTime<Utc>>,
+    pub acknowledged: Option<DateTime<Utc>>,
+    pub tested: Option<DateTime<Utc>>,
+    pub proven: Option<DateTime<Utc>>,
+}
+
+impl ProofLifecycle {
+    fn between(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Option<Duration> {
+        let elapsed = to? - from?;
+        (elapsed >= Duration::zero()).then_some(elapsed)
+    }
+
+    /// From being accepted to being assigned to the operator
+    pub fn assignment_latency(&self) -> Option<Duration> {
+        Self::between(self.accepted, self.assigned)
+    }
+
+    /// From the operator acknowledging the assignment to it delivering the proof
+    pub fn proving_duration(&self) -> Option<Duration> {
+        Self::between(self.acknowledged, self.tested)
+    }
+
+    /// From the proof being delivered to it being verified
+    pub fn verification_duration(&self) -> Option<Duration> {
+        Self::between(self.tested, self.proven)
+    }
+
+    /// From being submitted to being proven
+    pub fn time_to_proof(&self) -> Option<Duration> {
+        Self::between(self.created, self.proven)
+    }
+}
+
+/// Distribution of a lifecycle phase duration, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationStats {
+    pub count: u64,
+    pub mean_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl DurationStats {
+    pub fn from_durations(durations: impl IntoIterator<Item = Duration>) -> Self {
+        let mut ms: Vec<u64> = durations
+            .into_iter()
+            .map(|d| d.num_milliseconds().max(0) as u64)
+            .collect();
+        if ms.is_empty() {
+            return Self::default();
+        }
+        ms.sort_unstable();
+
+        // Nearest-rank percentile
+        let percentile = |p: usize| ms[(ms.len() * p).div_ceil(100).max(1) - 1];
+
+        Self {
+            count: ms.len() as u64,
+            mean_ms: ms.iter().sum::<u64>() / ms.len() as u64,
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: ms[ms.len() - 1],
+        }
+    }
+}
+
+/// Lifecycle aggregates of the requests proven in a time window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleStats {
+    pub since: Option<DateTime<Utc>>,
+    pub proven: u64,
+    pub assignment_latency: DurationStats,
+    pub proving_duration: DurationStats,
+    pub verification_duration: DurationStats,
+    pub time_to_proof: DurationStats,
+}
+
+impl LifecycleStats {
+    pub fn new(since: Option<DateTime<Utc>>, lifecycles: &[ProofLifecycle]) -> Self {
+        let stats = |phase: fn(&ProofLifecycle) -> Option<Duration>| {
+            DurationStats::from_durations(lifecycles.iter().filter_map(phase))
+        };
+
+        Self {
+            since,
+            proven: lifecycles.iter().filter(|l| l.proven.is_some()).count() as u64,
+            assignment_latency: stats(ProofLifecycle::assignment_latency),
+            proving_duration: stats(ProofLifecycle::proving_duration),
+            verification_duration: stats(ProofLifecycle::verification_duration),
+            time_to_proof: stats(ProofLifecycle::time_to_proof),
+        }
+    }
+}
+
+/// Earnings and utilization of an operator over all the requests assigned to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorStats {
+    pub operator: OperatorId,
+    /// Requests proven by the operator
+    pub completed: u64,
+    /// Requests rejected while assigned to the operator
+    pub rejected: u64,
+    /// Sum of the payments for the requests proven by the operator
+    pub earned: U256,
+    /// Mean time from acknowledging an assignment to delivering the proof, `None` before the first delivery
+    pub avg_proving_ms: Option<u64>,
+    /// Current reputation, `None` if the operator never connected to the matchmaker
+    pub reputation: Option<i64>,
+    /// Requests assigned to the operator, redundant copies included
+    #[serde(default)]
+    pub assignments: u64,
+}
+
+/// Operators known to the matchmaker, archived ones aren't counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorCounts {
+    pub total: u64,
+    /// Operators heard from within the heartbeat timeout
+    pub online: u64,
+    /// Operators registered as online, but not heard from within the heartbeat timeout
+    pub temporary_offline: u64,
+    /// Thresholds the operators were counted with
+    pub liveness: Liveness,
+}
+
+/// Requests proven on a day, in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyProofs {
+    pub date: NaiveDate,
+    pub proven: u64,
+}
+
+/// Network-wide totals, free of requester and operator identities
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStats {
+    /// Requests proven on each of the last days, oldest first, including days without any
+    pub proofs_per_day: Vec<DailyProofs>,
+    /// Requests proven since the start of the network
+    pub proven_total: u64,
+    /// Operators currently online
+    pub active_operators: u64,
+    /// Sum of the payments settled to the operators
+    pub total_settled: U256,
+    /// Time the totals were computed at
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_stats() {
+        assert_eq!(DurationStats::from_durations([]), DurationStats::default());
+
+        let stats = DurationStats::from_durations((1..=20).rev().map(Duration::seconds));
+        assert_eq!(
+            stats,
+            DurationStats {
+                count: 20,
+                mean_ms: 10_500,
+                p50_ms: 10_000,
+                p95_ms: 19_000,
+                max_ms: 20_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_phases() {
+        let at = |s| Some(DateTime::UNIX_EPOCH + Duration::seconds(s));
+        let lifecycle = ProofLifecycle {
+            created: at(0),
+            accepted: at(1),
+            assigned: at(3),
+            acknowledged: at(4),
+            tested: at(64),
+            proven: at(70),
+        };
+
+        assert_eq!(lifecycle.assignment_latency(), Some(Duration::seconds(2)));
+        assert_eq!(lifecycle.proving_duration(), Some(Duration::seconds(60)));
+        assert_eq!(
+            lifecycle.verification_duration(),
+            Some(Duration::seconds(6))
+        );
+        assert_eq!(lifecycle.time_to_proof(), Some(Duration::seconds(70)));
+
+        // Phases which didn't complete are left out
+        let pending = ProofLifecycle {
+            tested: None,
+            proven: None,
+            ..lifecycle
+        };
+        let stats = LifecycleStats::new(None, &[lifecycle, pending]);
+        assert_eq!(stats.proven, 1);
+        assert_eq!(stats.assignment_latency.count, 2);
+        assert_eq!(stats.proving_duration.count, 1);
+    }
+}
@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{hash::Hashable, types::budget::BudgetCap};
+
+pub type WebhookId = i64;
+
+/// Minimal length of a webhook secret, in bytes
+pub const MIN_WEBHOOK_SECRET_LEN: usize = 16;
+
+/// Endpoint a requester registers for push notifications. Registering the same URL again replaces its settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookRegistration {
+    pub url: Url,
+    /// Hex encoded key the deliveries are HMAC-SHA256 signed with
+    pub secret: String,
+    /// Notifies when the spendable balance drops below the amount, once per crossing
+    pub low_balance_threshold: Option<U256>,
+}
+
+impl Hashable for WebhookRegistration {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+/// Removes the requester's webhook with the URL
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookRemoval {
+    pub url: Url,
+}
+
+impl Hashable for WebhookRemoval {
+    fn collect(&self) -> Cow<[u8]> {
+        self.url.as_str().as_bytes().into()
+    }
+}
+
+/// Webhook as listed back to the requester, without its secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookInfo {
+    pub id: WebhookId,
+    pub url: Url,
+    pub low_balance_threshold: Option<U256>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Event pushed to the requester's webhooks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WebhookEvent {
+    /// The spendable balance dropped below the webhook's threshold
+    LowBalance {
+        requester: Address,
+        spendable: U256,
+        threshold: U256,
+    },
+    /// Unspent funds were returned to the requester
+    RefundSettled { requester: Address, amount: U256 },
+    /// A withdrawal from the vault to the requester was mined
+    WithdrawalSettled {
+        requester: Address,
+        amount: U256,
+        tx_hash: H256,
+    },
+    /// The spending reached the alert percentage of a budget cap
+    BudgetAlert {
+        requester: Address,
+        cap: BudgetCap,
+        spent: U256,
+        limit: U256,
+    },
+}
+
+/// JSON body of a webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_forged_registration() {
+        let registration = fixtures::signed(
+            WebhookRegistration {
+                url: "https://hooks.example.com/fermah".parse().unwrap(),
+                secret: "00".repeat(MIN_WEBHOOK_SECRET_LEN),
+                low_balance_threshold: None,
+            },
+            &fixtures::requester(),
+        );
+        assert!(registration.verify_payload().is_ok());
+
+        // The requester's signature paired with another endpoint
+        let mut forged = registration;
+        forged.payload.url = "https://attacker.example.com/".parse().unwrap();
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+    }
+
+    #[test]
+    fn test_event_format() {
+        let event = WebhookEvent::WithdrawalSettled {
+            requester: Address::zero(),
+            amount: U256::from(10),
+            tx_hash: H256::zero(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "type": "withdrawalSettled",
+                "requester": "0x0000000000000000000000000000000000000000",
+                "amount": "0xa",
+                "txHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            })
+        );
+    }
+}
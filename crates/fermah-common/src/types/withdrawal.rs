@@ -0,0 +1,346 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use ethers::types::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    hash::{
+        blake3::{Blake3Hash, Blake3Hasher},
+        Hashable,
+    },
+};
+
+/// Id of a withdrawal, the hash of the signed withdrawal request
+pub type WithdrawalId = Blake3Hash;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidWithdrawal {
+    #[error("withdrawal amount is zero")]
+    ZeroAmount,
+    #[error("withdrawal recipient is the zero address")]
+    ZeroRecipient,
+    #[error("withdrawal deadline passed at {0}")]
+    Expired(DateTime<Utc>),
+}
+
+/// Withdrawal of part of the requester's vault deposit, signed by the requester. It is only executed before the
+/// deadline, so a request stuck in the queue can't be replayed long after the requester gave up on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalRequest {
+    pub to: Address,
+    pub amount: U256,
+    pub deadline: DateTime<Utc>,
+}
+
+impl WithdrawalRequest {
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), InvalidWithdrawal> {
+        if self.amount.is_zero() {
+            return Err(InvalidWithdrawal::ZeroAmount);
+        }
+        if self.to.is_zero() {
+            return Err(InvalidWithdrawal::ZeroRecipient);
+        }
+        if self.deadline <= now {
+            return Err(InvalidWithdrawal::Expired(self.deadline));
+        }
+        Ok(())
+    }
+}
+
+impl Hashable for WithdrawalRequest {
+    fn collect(&self) -> Cow<[u8]> {
+        serde_json::to_vec(self).unwrap().into()
+    }
+}
+
+impl SignedData<WithdrawalRequest, EcdsaSigner> {
+    /// Hash of the payload, whichever hash it came signed with
+    pub fn id(&self) -> WithdrawalId {
+        self.payload.hash::<Blake3Hasher>()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WithdrawalStatus {
+    /// Above the approval threshold, waiting for the approvals of the approvers before it's sent to the vault. The
+    /// amount is held from the requester's balance meanwhile
+    AwaitingApproval,
+    /// Waiting to be sent to the vault, the amount is held from the requester's balance meanwhile
+    Pending,
+    /// Sent to the vault in the AVS transaction with the nonce, the amount is held until it's mined
+    Submitted { nonce: u64 },
+    /// Mined in the transaction
+    Settled(H256),
+    /// Not executed, the amount is released
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    pub id: WithdrawalId,
+    pub requester: Address,
+    pub request: WithdrawalRequest,
+    pub status: WithdrawalStatus,
+    pub requested_at: DateTime<Utc>,
+    /// Approvers which approved the withdrawal, empty for the withdrawals below the approval threshold
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approvals: Vec<Address>,
+}
+
+/// Approval of a withdrawal awaiting approval, signed by one of the approvers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalApproval {
+    pub withdrawal_id: WithdrawalId,
+}
+
+impl WithdrawalApproval {
+    /// Domain tag of the hash, the cancellations are encoded the same
+    pub const DOMAIN: &'static [u8] = b"fermah:approveWithdrawal:";
+}
+
+impl Hashable for WithdrawalApproval {
+    fn collect(&self) -> Cow<[u8]> {
+        [Self::DOMAIN, &serde_json::to_vec(self).unwrap()]
+            .concat()
+            .into()
+    }
+}
+
+/// Cancellation of a withdrawal which isn't sent to the vault yet, signed by its requester
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalCancellation {
+    pub withdrawal_id: WithdrawalId,
+}
+
+impl WithdrawalCancellation {
+    /// Domain tag of the hash, the approvals are encoded the same
+    pub const DOMAIN: &'static [u8] = b"fermah:cancelWithdrawal:";
+}
+
+impl Hashable for WithdrawalCancellation {
+    fn collect(&self) -> Cow<[u8]> {
+        [Self::DOMAIN, &serde_json::to_vec(self).unwrap()]
+            .concat()
+            .into()
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidApprovalPolicy {
+    #[error("withdrawals need at least one approval")]
+    NoApprovals,
+    #[error("{required} approvals are needed, but there are only {approvers} approvers")]
+    TooFewApprovers { required: u32, approvers: usize },
+}
+
+/// Withdrawals large enough to need the approval of other keys than the requester's, m of the n approvers, before
+/// the matchmaker sends them to the vault. The withdrawals of a requester are summed over a rolling window, so a large
+/// withdrawal can't be split under the threshold.
+#[derive(Args, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WithdrawalApprovalPolicy {
+    /// Amount the withdrawals of a requester within the window may sum to before they wait for approvals, none does
+    /// if unset
+    #[arg(long = "withdrawal-approval-threshold", value_parser = U256::from_dec_str)]
+    pub threshold: Option<U256>,
+    /// Seconds the withdrawals of a requester are summed over against the threshold
+    #[arg(long = "withdrawal-approval-window-secs", default_value_t = 86400)]
+    pub window_secs: u64,
+    /// Approvals a withdrawal above the threshold needs
+    #[arg(long = "withdrawal-approvals", default_value_t = 2)]
+    pub required: u32,
+    /// Keys allowed to approve the withdrawals, the requester's own approval doesn't count
+    #[arg(long = "withdrawal-approver")]
+    pub approvers: Vec<Address>,
+}
+
+impl Default for WithdrawalApprovalPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            window_secs: 86400,
+            required: 2,
+            approvers: vec![],
+        }
+    }
+}
+
+impl WithdrawalApprovalPolicy {
+    pub fn validate(&self) -> Result<(), InvalidApprovalPolicy> {
+        if self.threshold.is_none() {
+            return Ok(());
+        }
+        if self.required == 0 {
+            return Err(InvalidApprovalPolicy::NoApprovals);
+        }
+        if self.approvers.len() < self.required as usize {
+            return Err(InvalidApprovalPolicy::TooFewApprovers {
+                required: self.required,
+                approvers: self.approvers.len(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::seconds(self.window_secs.min(i64::MAX as u64 / 1000) as i64)
+    }
+
+    /// Whether a withdrawal of the amount waits for approvals, the requester having withdrawn `recent` within the
+    /// window
+    pub fn requires_approval(&self, amount: U256, recent: U256) -> bool {
+        self.threshold
+            .is_some_and(|threshold| recent.saturating_add(amount) > threshold)
+    }
+
+    pub fn is_approver(&self, key: &Address) -> bool {
+        self.approvers.contains(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_validate() {
+        let now = Utc::now();
+        let request = WithdrawalRequest {
+            to: Address::random(),
+            amount: U256::from(100),
+            deadline: now + Duration::minutes(10),
+        };
+        assert_eq!(request.validate(now), Ok(()));
+
+        assert_eq!(
+            WithdrawalRequest {
+                amount: U256::zero(),
+                ..request.clone()
+            }
+            .validate(now),
+            Err(InvalidWithdrawal::ZeroAmount)
+        );
+        assert_eq!(
+            WithdrawalRequest {
+                to: Address::zero(),
+                ..request.clone()
+            }
+            .validate(now),
+            Err(InvalidWithdrawal::ZeroRecipient)
+        );
+        assert_eq!(
+            request.validate(request.deadline),
+            Err(InvalidWithdrawal::Expired(request.deadline))
+        );
+    }
+
+    #[test]
+    fn test_forged_withdrawal() {
+        let signed = fixtures::signed(
+            WithdrawalRequest {
+                to: Address::random(),
+                amount: U256::from(100),
+                deadline: Utc::now() + Duration::minutes(10),
+            },
+            &fixtures::requester(),
+        );
+        assert!(signed.verify_payload().is_ok());
+        assert_eq!(signed.id(), signed.hash);
+
+        // The requester's signature paired with another recipient and amount
+        let mut forged = signed.clone();
+        forged.payload.to = Address::random();
+        forged.payload.amount = U256::from(1_000_000);
+        assert!(forged.verify().is_ok());
+        assert!(forged.verify_payload().is_err());
+        assert_ne!(forged.id(), signed.id());
+    }
+
+    #[test]
+    fn test_forged_approval_and_cancellation() {
+        let approver = fixtures::signer(7);
+        let approval = fixtures::signed(
+            WithdrawalApproval {
+                withdrawal_id: [1; 32].into(),
+            },
+            &approver,
+        );
+        assert!(approval.verify_payload().is_ok());
+
+        // The approver's signature paired with the approval of another withdrawal
+        let mut forged = approval.clone();
+        forged.payload.withdrawal_id = [2; 32].into();
+        assert!(forged.verify_payload().is_err());
+
+        // Nor is a cancellation of the same withdrawal an approval
+        let cancellation = fixtures::signed(
+            WithdrawalCancellation {
+                withdrawal_id: approval.payload.withdrawal_id,
+            },
+            &approver,
+        );
+        assert!(cancellation.verify_payload().is_ok());
+        let as_approval = SignedData {
+            hash: cancellation.hash,
+            payload: approval.payload.clone(),
+            public_key: cancellation.public_key,
+            signature: cancellation.signature,
+        };
+        assert!(as_approval.verify_payload().is_err());
+    }
+
+    #[test]
+    fn test_approval_policy() {
+        let approvers = vec![Address::random(), Address::random()];
+        let policy = WithdrawalApprovalPolicy {
+            threshold: Some(U256::from(1000)),
+            required: 2,
+            approvers: approvers.clone(),
+            ..Default::default()
+        };
+        assert_eq!(policy.validate(), Ok(()));
+        assert!(!policy.requires_approval(U256::from(1000), U256::zero()));
+        assert!(policy.requires_approval(U256::from(1001), U256::zero()));
+        // Split under the threshold, the withdrawals within the window still add up
+        assert!(!policy.requires_approval(U256::from(400), U256::from(600)));
+        assert!(policy.requires_approval(U256::from(401), U256::from(600)));
+        assert!(policy.is_approver(&approvers[1]));
+        assert!(!policy.is_approver(&Address::random()));
+
+        assert_eq!(
+            WithdrawalApprovalPolicy {
+                required: 3,
+                ..policy.clone()
+            }
+            .validate(),
+            Err(InvalidApprovalPolicy::TooFewApprovers {
+                required: 3,
+                approvers: 2
+            })
+        );
+        assert_eq!(
+            WithdrawalApprovalPolicy {
+                required: 0,
+                ..policy
+            }
+            .validate(),
+            Err(InvalidApprovalPolicy::NoApprovals)
+        );
+
+        // Nothing waits for approvals without a threshold
+        let default = WithdrawalApprovalPolicy::default();
+        assert_eq!(default.validate(), Ok(()));
+        assert!(!default.requires_approval(U256::MAX, U256::MAX));
+    }
+}
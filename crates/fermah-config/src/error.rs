@@ -20,4 +20,6 @@ pub enum Error {
     NonUtf8Path(std::path::PathBuf),
     #[error("failed to merge config for profile: {profile:?}")]
     Merge { profile: ProfileKey },
+    #[error("invalid override {0}: {1}")]
+    InvalidOverride(String, String),
 }
@@ -1,6 +1,10 @@
-use std::{io, io::Read, path::Path};
+use std::{
+    io,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueHint};
 use const_hex::{traits::FromHex, ToHexExt};
 use fermah_common::{
     cli::{
@@ -11,7 +15,8 @@ use fermah_common::{
     crypto::{
         cipher::{aes128ctr::Aes128CtrCipher, Cipher},
         kdf::scrypt::ScryptKdf,
-        keystore::{KeystoreCipher, KeystoreFile, KEYS_DIR},
+        keystore::{KeystoreCipher, KeystoreFile, Web3Keystore, KEYS_DIR},
+        secret::SecretBytes,
         signer::{bls::BlsSigner, ecdsa::EcdsaSigner, Signer, SignerType},
     },
     fs::{self, ensure_dir, json::Json},
@@ -33,6 +38,20 @@ pub enum KeyCommands {
         #[arg(long)]
         name: String,
     },
+    /// Import an ECDSA keystore in the Web3 Secret Storage format (geth, clef, eth-keystore)
+    ImportKeystore {
+        /// Path to the keystore JSON file
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        keystore: PathBuf,
+        /// File containing the passphrase of the imported keystore, if not provided it will be prompted
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        keystore_password_file: Option<PathBuf>,
+        #[command(flatten)]
+        pw: PasswordArgs,
+        /// A name for the key, will be used as its ID
+        #[arg(long)]
+        name: String,
+    },
     /// Generate a key pair
     Gen {
         #[command(flatten)]
@@ -61,8 +80,9 @@ impl KeyCommands {
 
                 match args.key_type {
                     SignerType::ECDSA => {
-                        let (address, private_key) =
-                            Self::get_keypair::<EcdsaSigner>(Vec::from_hex(key_data.trim())?)?;
+                        let (address, private_key) = Self::get_keypair::<EcdsaSigner>(
+                            Vec::from_hex(key_data.trim())?.into(),
+                        )?;
                         Self::save_keys(
                             name,
                             &keys_dir,
@@ -75,7 +95,7 @@ impl KeyCommands {
                     }
                     SignerType::BLS => {
                         let (address, private_key) =
-                            Self::get_keypair::<BlsSigner>(Vec::from_hex(key_data.trim())?)?;
+                            Self::get_keypair::<BlsSigner>(Vec::from_hex(key_data.trim())?.into())?;
                         Self::save_keys(
                             name,
                             &keys_dir,
@@ -90,6 +110,28 @@ impl KeyCommands {
 
                 Ok(())
             }
+            KeyCommands::ImportKeystore {
+                keystore,
+                keystore_password_file,
+                pw,
+                name,
+            } => {
+                info!(?keystore, "importing keystore");
+
+                let mut web3_keystore = Web3Keystore::from_json_path(keystore).await?;
+                info!(kdf = web3_keystore.crypto.kdf_name(), "decrypting keystore");
+
+                let password = match keystore_password_file {
+                    Some(pw_file) => tokio::fs::read_to_string(pw_file).await?.trim().to_string(),
+                    None => cli::prompts::prompt_for_password_unlock(&keystore.to_string_lossy())?,
+                };
+                let private_key = web3_keystore.decrypt_private_key(password.as_bytes())?;
+
+                let (address, private_key) = Self::get_keypair::<EcdsaSigner>(private_key)?;
+                Self::save_keys(name, &keys_dir, private_key, address, pw, pw.fast).await?;
+
+                Ok(())
+            }
             KeyCommands::Gen { pw, key_type, name } => {
                 info!(?key_type, "generating");
 
@@ -109,15 +151,15 @@ impl KeyCommands {
         }
     }
 
-    fn get_keypair<S: Signer>(private_key: Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), Error>
+    fn get_keypair<S: Signer>(private_key: SecretBytes) -> Result<(Vec<u8>, SecretBytes), Error>
     where
         Error: From<<S as Signer>::SignerError>,
     {
-        let signer = S::from_bytes(private_key.as_slice())?;
+        let signer = S::from_bytes(&private_key)?;
         Ok((signer.public_address(), private_key))
     }
 
-    fn get_random_keypair<S: Signer>() -> Result<(Vec<u8>, Vec<u8>), Error>
+    fn get_random_keypair<S: Signer>() -> Result<(Vec<u8>, SecretBytes), Error>
     where
         Error: From<<S as Signer>::SignerError>,
     {
@@ -128,7 +170,7 @@ impl KeyCommands {
     async fn save_keys(
         name: &str,
         keys_dir: &Path,
-        private_key: Vec<u8>,
+        private_key: SecretBytes,
         address: Vec<u8>,
         pw_args: &PasswordArgs,
         fast: bool,
@@ -155,7 +197,8 @@ impl KeyCommands {
             }
         };
 
-        let mut cipher = Aes128CtrCipher::<ScryptKdf>::from_data(private_key, fast);
+        let mut cipher =
+            Aes128CtrCipher::<ScryptKdf>::from_data(private_key.expose_secret().to_vec(), fast);
 
         let spinner = Spinner::new(1, "🔒 Encrypting", SpinnerTemplate::Default);
 
@@ -168,6 +211,13 @@ impl KeyCommands {
 
         KeystoreFile { cipher }.to_json_path(&key_file).await?;
 
+        #[cfg(feature = "keychain")]
+        if pw_args.keychain {
+            fermah_common::crypto::keystore::keychain::store_password(name, &password)
+                .map_err(fermah_common::crypto::keystore::KeystoreFileError::Keychain)?;
+            info!(name, "stored password in the keychain");
+        }
+
         print_var("file", key_file.display());
         print_var("address", address.encode_hex_with_prefix());
         Ok(())
@@ -15,6 +15,10 @@ pub struct PasswordArgs {
     /// Do not set a password
     #[arg(long)]
     pub no_password: bool,
+    /// Store the password in the OS keychain, to unlock the key with `--keychain`
+    #[cfg(feature = "keychain")]
+    #[arg(long, conflicts_with = "no_password")]
+    pub keychain: bool,
     /// Enable fast cipher mode (!INSECURE!)
     #[arg(long)]
     pub fast: bool,
@@ -44,13 +48,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_signer_from_keystore() {
-        let key = "test".to_string();
+        let mut key = KeystoreConfig::new("test");
+        key.password.no_password = true;
 
-        let mut ksfile = KeystoreFile::from_config(&KeystoreConfig { key })
-            .await
-            .unwrap();
+        let mut ksfile = KeystoreFile::from_config(&key).await.unwrap();
 
-        let ecdsa = ksfile.to_signer::<EcdsaSigner>().await.unwrap();
+        let ecdsa = ksfile.to_signer::<EcdsaSigner>(&key).await.unwrap();
         assert_eq!(
             ecdsa.public_address().encode_hex_with_prefix(),
             "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
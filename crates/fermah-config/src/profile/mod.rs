@@ -13,6 +13,7 @@ use tracing::info;
 
 pub mod command;
 pub mod key;
+pub mod overrides;
 
 use crate::{error::Error, profile::key::ProfileKey};
 
@@ -0,0 +1,262 @@
+use std::{fmt::Display, path::Path, str::FromStr};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Override of a single value of a profile config, written as `path=value`,
+/// e.g. `prover.in_mounts[0].target=/data`.
+///
+/// Keys may be given in snake_case and match camelCase fields. The value is parsed as JSON, and taken as a string
+/// when it isn't valid JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Override {
+    pub path: Vec<PathSegment>,
+    pub value: Value,
+}
+
+/// Placeholder in override string values, substituted with the member index of a job array
+pub const INDEX_PLACEHOLDER: &str = "{index}";
+
+impl Override {
+    pub fn new(path: &str, value: Value) -> Result<Self, Error> {
+        Ok(Self {
+            path: Self::parse_path(path)?,
+            value,
+        })
+    }
+
+    /// Copy of the override with [`INDEX_PLACEHOLDER`] substituted by `index` in all string values
+    pub fn with_index(&self, index: usize) -> Self {
+        fn substitute(value: &mut Value, index: &str) {
+            match value {
+                Value::String(s) => *s = s.replace(INDEX_PLACEHOLDER, index),
+                Value::Array(values) => values.iter_mut().for_each(|v| substitute(v, index)),
+                Value::Object(map) => map.values_mut().for_each(|v| substitute(v, index)),
+                _ => {}
+            }
+        }
+
+        let mut value = self.value.clone();
+        substitute(&mut value, &index.to_string());
+        Self {
+            path: self.path.clone(),
+            value,
+        }
+    }
+
+    fn parse_path(path: &str) -> Result<Vec<PathSegment>, Error> {
+        let invalid = |reason: &str| Error::InvalidOverride(path.to_string(), reason.to_string());
+
+        let mut segments = vec![];
+        for part in path.split('.') {
+            let (key, mut indices) = match part.find('[') {
+                Some(i) => (&part[..i], &part[i..]),
+                None => (part, ""),
+            };
+
+            if key.is_empty() {
+                return Err(invalid("empty key"));
+            }
+            segments.push(PathSegment::Key(key.to_string()));
+
+            while !indices.is_empty() {
+                let end = indices.find(']').ok_or_else(|| invalid("unclosed ["))?;
+                let index = indices[1..end]
+                    .parse()
+                    .map_err(|_| invalid("index is not a number"))?;
+                segments.push(PathSegment::Index(index));
+
+                indices = &indices[end + 1..];
+                if !indices.is_empty() && !indices.starts_with('[') {
+                    return Err(invalid("unexpected characters after ]"));
+                }
+            }
+        }
+
+        Ok(segments)
+    }
+
+    /// Sets the value in the JSON tree. Array indices must exist, or be equal to the array length to append.
+    fn apply_to(&self, mut target: &mut Value) -> Result<(), Error> {
+        let invalid = |reason: String| Error::InvalidOverride(self.to_string(), reason);
+
+        for segment in &self.path {
+            target = match segment {
+                PathSegment::Key(key) => {
+                    let object = target
+                        .as_object_mut()
+                        .ok_or_else(|| invalid(format!("{key} is not in an object")))?;
+                    let key = Self::resolve_key(object, key);
+                    object.entry(key).or_insert(Value::Null)
+                }
+                PathSegment::Index(index) => {
+                    let array = target
+                        .as_array_mut()
+                        .ok_or_else(|| invalid(format!("[{index}] is not in an array")))?;
+                    if *index == array.len() {
+                        array.push(Value::Null);
+                    }
+                    let len = array.len();
+                    array
+                        .get_mut(*index)
+                        .ok_or_else(|| invalid(format!("index {index} out of {len}")))?
+                }
+            };
+        }
+
+        *target = self.value.clone();
+        Ok(())
+    }
+
+    /// Existing key of the object matching the given one, either as is or in camelCase
+    fn resolve_key(object: &Map<String, Value>, key: &str) -> String {
+        if object.contains_key(key) {
+            return key.to_string();
+        }
+
+        let camel_case = Self::to_camel_case(key);
+        if object.contains_key(&camel_case) {
+            camel_case
+        } else {
+            key.to_string()
+        }
+    }
+
+    fn to_camel_case(key: &str) -> String {
+        let mut parts = key.split('_');
+        let first = parts.next().unwrap_or_default().to_string();
+        parts.fold(first, |mut camel, part| {
+            let mut chars = part.chars();
+            if let Some(c) = chars.next() {
+                camel.extend(c.to_uppercase());
+                camel.push_str(chars.as_str());
+            }
+            camel
+        })
+    }
+}
+
+impl FromStr for Override {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, value) = s
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidOverride(s.to_string(), "expected path=value".into()))?;
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.into()));
+
+        Self::new(path, value)
+    }
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{key}")?,
+                PathSegment::Key(key) => write!(f, ".{key}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, "={}", self.value)
+    }
+}
+
+/// Reads an inputs manifest: a JSON object mapping override paths to values, e.g.
+/// `{ "prover.in_mounts[0].source": { "file": { ... } }, "nonce": 7 }`
+pub async fn overrides_from_manifest(path: &Path) -> Result<Vec<Override>, Error> {
+    let manifest: Map<String, Value> = serde_json::from_slice(&tokio::fs::read(path).await?)?;
+
+    manifest
+        .into_iter()
+        .map(|(path, value)| Override::new(&path, value))
+        .collect()
+}
+
+/// Applies the overrides in order to the config, which is then deserialized back
+pub fn apply_overrides<T: Serialize + DeserializeOwned>(
+    config: T,
+    overrides: &[Override],
+) -> Result<T, Error> {
+    if overrides.is_empty() {
+        return Ok(config);
+    }
+
+    let mut value = serde_json::to_value(config)?;
+    for o in overrides {
+        o.apply_to(&mut value)?;
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Mount {
+        target: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct TestConfig {
+        in_mounts: Vec<Mount>,
+        nonce: u64,
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let config = TestConfig {
+            in_mounts: vec![Mount {
+                target: "/in".to_string(),
+            }],
+            nonce: 0,
+        };
+
+        let overrides: Vec<Override> = [
+            "in_mounts[0].target=/data",
+            "inMounts[1]={\"target\": \"/more\"}",
+            "nonce=7",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+
+        assert_eq!(
+            apply_overrides(config, &overrides).unwrap(),
+            TestConfig {
+                in_mounts: vec![
+                    Mount {
+                        target: "/data".to_string()
+                    },
+                    Mount {
+                        target: "/more".to_string()
+                    }
+                ],
+                nonce: 7,
+            }
+        );
+
+        assert!("in_mounts[x].target=/data".parse::<Override>().is_err());
+        assert!("nonce".parse::<Override>().is_err());
+
+        let indexed: Override = "in_mounts[0].target=/data/{index}.bin".parse().unwrap();
+        assert_eq!(
+            indexed.with_index(3).value,
+            Value::String("/data/3.bin".to_string())
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::HashSet, net::SocketAddr};
 use anyhow::{Context, Result};
 use diesel::{dsl::insert_into, prelude::*, update};
 use ethers::types::U256;
-use fermah_common::operator::OperatorId;
+use fermah_common::{operator::OperatorId, types::block::ObservedBlock};
 
 use crate::{
     models::{EthAddress, EthU256},
@@ -155,27 +155,44 @@ impl Database {
         &self,
         operator: &OperatorId,
         registered_till_block_: U256,
+    ) -> Result<()> {
+        self.set_operator_registered_till_at(operator, registered_till_block_, None)
+    }
+
+    /// Caches registeredTillBlock along with the block it was read at, so that it can be re-queried after a reorg
+    pub fn set_operator_registered_till_at(
+        &self,
+        operator: &OperatorId,
+        registered_till_block_: U256,
+        observed: Option<ObservedBlock>,
     ) -> Result<()> {
         use schema::avs_operators::dsl::*;
 
         let mut conn = self
             .pool
             .get()
-            .context("set_operator_registered_till: failed to connect to the database")?;
+            .context("set_operator_registered_till_at: failed to connect to the database")?;
+
+        let observed_block_ = observed.map(|b| b.number as i64);
+        let observed_block_hash_ = observed.map(|b| b.hash.as_bytes().to_vec());
 
         insert_into(avs_operators)
             .values((
                 id.eq(EthAddress::from(*operator)),
                 registered_till_block.eq(EthU256::from(registered_till_block_)),
+                observed_block.eq(observed_block_),
+                observed_block_hash.eq(observed_block_hash_.clone()),
             ))
             .on_conflict(id)
             .do_update()
             .set((
                 id.eq(EthAddress::from(*operator)),
                 registered_till_block.eq(EthU256::from(registered_till_block_)),
+                observed_block.eq(observed_block_),
+                observed_block_hash.eq(observed_block_hash_),
             ))
             .execute(&mut conn)
-            .context("queryset_operator_registered_till failed")?;
+            .context("query set_operator_registered_till_at failed")?;
 
         Ok(())
     }
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use diesel::{dsl::insert_into, prelude::*};
 use ethers::types::{Address, U256};
+use fermah_common::types::block::ObservedBlock;
 
 use crate::{
     models::{EthAddress, EthU256},
@@ -46,23 +47,47 @@ impl Database {
         &self,
         proof_requester: &Address,
         deposit_: U256,
+    ) -> Result<()> {
+        self.set_proof_requester_deposit_at(proof_requester, deposit_, None)
+    }
+
+    /// Caches the deposit along with the block it was read at, so that it can be re-queried after a reorg
+    pub fn set_proof_requester_deposit_at(
+        &self,
+        proof_requester: &Address,
+        deposit_: U256,
+        observed: Option<ObservedBlock>,
     ) -> Result<()> {
         use schema::avs_proof_requesters::dsl::*;
         let mut conn = self
             .pool
             .get()
-            .context("set_proof_requester_deposit: failed to connect to the database")?;
+            .context("set_proof_requester_deposit_at: failed to connect to the database")?;
 
         let proof_requester = EthAddress::from(*proof_requester);
         let deposit_ = EthU256::from(deposit_);
+        let observed_block_ = observed.map(|b| b.number as i64);
+        let observed_block_hash_ = observed.map(|b| b.hash.as_bytes().to_vec());
 
         insert_into(avs_proof_requesters)
-            .values((id.eq(proof_requester), deposit.eq(deposit_)))
+            .values((
+                id.eq(proof_requester),
+                deposit.eq(deposit_),
+                observed_block.eq(observed_block_),
+                observed_block_hash.eq(observed_block_hash_.clone()),
+            ))
             .on_conflict(id)
             .do_update()
-            .set((id.eq(proof_requester), deposit.eq(deposit_)))
+            .set((
+                id.eq(proof_requester),
+                deposit.eq(deposit_),
+                observed_block.eq(observed_block_),
+                observed_block_hash.eq(observed_block_hash_),
+            ))
             .execute(&mut conn)
-            .context("query set_proof_requester_deposit failed")?;
+            .context("query set_proof_requester_deposit_at failed")?;
+
+        self.try_check_low_balance(&proof_requester.into());
 
         Ok(())
     }
@@ -0,0 +1,85 @@
+//! Recomputes the reputations and the aggregates of the operators from the request history and reports how they
+//! differ from the stored ones, `cargo run -p fermah-database --features backfill --bin fermah-backfill`. With
+//! `--backfill resources`, rewrites the operator resources stored in a legacy layout instead, to run once migrated, and
+//! with `--backfill proof-requests` the signed proof requests.
+
+use anyhow::Context;
+use clap::{Parser, ValueEnum};
+use fermah_common::operator::reputation::ReputationPolicy;
+use fermah_database::Database;
+use fermah_telemetry::{stdout::StdoutTelemetry, Telemetry};
+
+/// Operator backfill
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// Postgres URL of the matchmaker database
+    #[arg(long)]
+    database_url: String,
+    #[arg(long, value_enum, default_value_t = Backfill::Reputation)]
+    backfill: Backfill,
+    /// Name the progress of the job is recorded under, the name of the backfill by default
+    #[arg(long)]
+    job: Option<String>,
+    /// Operators or proof requests rewritten in one transaction
+    #[arg(long, default_value_t = 500)]
+    batch: i64,
+    /// Only report the differences, without writing them or recording progress
+    #[arg(long)]
+    dry_run: bool,
+    /// Discard the progress of an interrupted job and start from the first record
+    #[arg(long)]
+    restart: bool,
+    #[command(flatten)]
+    policy: ReputationPolicy,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Backfill {
+    /// Reputations and aggregates of the operators
+    Reputation,
+    /// Operator resources stored before GPUs had a vendor and a driver
+    Resources,
+    /// Signed proof requests stored before the fields appended since their first release
+    ProofRequests,
+}
+
+fn main() -> anyhow::Result<()> {
+    StdoutTelemetry::default().init();
+    let cli = Cli::parse();
+
+    let db = Database::connect_to_database(&cli.database_url)?;
+    let job = cli.job.clone().unwrap_or_else(|| {
+        match cli.backfill {
+            Backfill::Reputation => "reputation".to_string(),
+            Backfill::Resources => "resources".to_string(),
+            Backfill::ProofRequests => "proof-requests".to_string(),
+        }
+    });
+    if cli.restart {
+        db.reset_backfill(&job)?;
+    }
+
+    let report = match cli.backfill {
+        Backfill::Reputation => {
+            serde_json::to_string_pretty(&db.backfill_operators(
+                &job,
+                &cli.policy,
+                cli.batch,
+                !cli.dry_run,
+            )?)
+        }
+        Backfill::Resources => {
+            serde_json::to_string_pretty(&db.backfill_resources(&job, cli.batch, !cli.dry_run)?)
+        }
+        Backfill::ProofRequests => {
+            serde_json::to_string_pretty(&db.backfill_proof_requests(
+                &job,
+                cli.batch,
+                !cli.dry_run,
+            )?)
+        }
+    };
+    println!("{}", report.context("failed to serialize the report")?);
+    Ok(())
+}
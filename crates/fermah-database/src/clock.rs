@@ -0,0 +1,63 @@
+use std::fmt::Debug;
+#[cfg(any(test, feature = "database_test"))]
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+use chrono::{DateTime, Utc};
+
+/// Time the timeouts are evaluated at: reassignments, liveness, restrictions, dispute windows and leases
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall clock of the host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Wall clock moved forward by the tests, so timeouts expire without sleeping. The rows written meanwhile keep the
+/// wall clock time, as they would have been written that long ago. Clones share the time.
+#[cfg(any(test, feature = "database_test"))]
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    offset_ms: Arc<AtomicI64>,
+}
+
+#[cfg(any(test, feature = "database_test"))]
+impl MockClock {
+    pub fn advance(&self, by: chrono::Duration) {
+        self.offset_ms
+            .fetch_add(by.num_milliseconds(), Ordering::SeqCst);
+    }
+}
+
+#[cfg(any(test, feature = "database_test"))]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now() + chrono::Duration::milliseconds(self.offset_ms.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::default();
+        let shared = clock.clone();
+        shared.advance(Duration::hours(1));
+
+        let ahead = clock.now() - Utc::now();
+        assert!(ahead > Duration::minutes(59) && ahead <= Duration::hours(1));
+    }
+}
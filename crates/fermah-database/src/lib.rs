@@ -1,18 +1,72 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::{Context, Result};
 use diesel::{
-    pg::PgConnection,
+    pg::{Pg, PgConnection},
+    query_builder::QueryFragment,
+    query_dsl::LoadQuery,
     r2d2::{ConnectionManager, Pool},
+    RunQueryDsl,
 };
 use ethers::types::U256;
+use fermah_common::{
+    operator::{assignment::OperatorOrdering, liveness::Liveness, restriction::GreylistPolicy},
+    proof::{cancellation::CancellationFees, scheduling::AssignmentScheduling},
+    types::withdrawal::WithdrawalApprovalPolicy,
+};
+use clock::{Clock, SystemClock};
+use operator_cache::{OperatorCache, OperatorCacheStats};
+use scheduler::AssignmentScheduler;
 
+pub mod avs_chain_cache;
 pub mod avs_operators;
 pub mod avs_proof_requesters;
+pub mod avs_transactions;
+#[cfg(any(test, feature = "bench"))]
+pub mod bench_data;
+pub mod clock;
+pub mod mm_assignments;
+pub mod mm_audit_log;
+pub mod mm_backfill;
+pub mod mm_cancellations;
+pub mod mm_container_policies;
+pub mod mm_corrupt_records;
 pub mod mm_deadlines;
+pub mod mm_delegations;
+pub mod mm_dependencies;
+pub mod mm_disputes;
+pub mod mm_expanded_requirements;
+pub mod mm_image_registry;
+pub mod mm_image_validations;
+pub mod mm_job_arrays;
+pub mod mm_lifecycle;
+pub mod mm_operator_attestations;
+pub mod mm_operator_offenses;
+pub mod mm_operator_readiness;
+pub mod mm_operator_restrictions;
 pub mod mm_operators;
+pub mod mm_outbox;
+pub mod mm_payment_events;
+pub mod mm_prefetch;
+pub mod mm_proof_receipts;
+pub mod mm_proof_retention;
 pub mod mm_proof_requests;
+pub mod mm_redundant_proofs;
+pub mod mm_request_events;
+pub mod mm_requester_balances;
+pub mod mm_requester_budgets;
+pub mod mm_resource_usage;
+pub mod mm_shards;
+pub mod mm_signed_nonces;
+pub mod mm_stats;
+pub mod mm_vault_reconciliations;
+pub mod mm_webhooks;
+pub mod mm_withdrawals;
 pub mod models;
+pub mod operator_cache;
+#[cfg(test)]
+mod query_plans;
+pub mod scheduler;
 pub mod schema;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -27,9 +81,40 @@ pub struct OperatorParams {
 pub(crate) type DbConnection =
     diesel::r2d2::PooledConnection<diesel::r2d2::ConnectionManager<PgConnection>>;
 
+/// Query loading rows of `U`, built apart from the method running it so the query plan tests can explain it
+pub(crate) trait PlannedQuery<U>:
+    QueryFragment<Pg> + RunQueryDsl<PgConnection> + for<'a> LoadQuery<'a, PgConnection, U>
+{
+}
+
+impl<T, U> PlannedQuery<U> for T where
+    T: QueryFragment<Pg> + RunQueryDsl<PgConnection> + for<'a> LoadQuery<'a, PgConnection, U>
+{
+}
+
 #[derive(Clone, Debug)]
 pub struct Database {
     pool: Pool<ConnectionManager<PgConnection>>,
+    /// Protocol commission, in basis points, split off the amounts reserved from now on
+    protocol_fee_bps: u16,
+    /// Time the requester has to dispute a proof before the prover is paid
+    dispute_window: chrono::Duration,
+    /// How recently the operators must have been heard from to be assigned and counted as online
+    liveness: Liveness,
+    /// Share of the reservation paid to the operator of a request cancelled in flight
+    cancellation_fees: CancellationFees,
+    /// Active operators kept in memory between the reads, if enabled
+    operator_cache: Option<OperatorCache>,
+    /// Failed proofs in a row the operators are greylisted after
+    greylist_policy: GreylistPolicy,
+    /// Withdrawals which wait for the approvals of other keys before they are executed
+    withdrawal_approval: WithdrawalApprovalPolicy,
+    /// Order the available operators are returned in
+    operator_ordering: OperatorOrdering,
+    /// Order the requests waiting for an operator are returned in
+    scheduler: AssignmentScheduler,
+    /// Time the timeouts are evaluated at, the timestamps written are taken from the wall clock
+    clock: Arc<dyn Clock>,
 }
 impl Database {
     /// Connect to the database with the provided URL
@@ -39,7 +124,95 @@ impl Database {
             .test_on_check_out(true)
             .build(manager)
             .context(": failed to connect to the database")?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            protocol_fee_bps: 0,
+            dispute_window: chrono::Duration::zero(),
+            liveness: Liveness::default(),
+            cancellation_fees: CancellationFees::default(),
+            operator_cache: None,
+            greylist_policy: GreylistPolicy::default(),
+            withdrawal_approval: WithdrawalApprovalPolicy::default(),
+            operator_ordering: OperatorOrdering::default(),
+            scheduler: AssignmentScheduler::new(AssignmentScheduling::default()),
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// Takes the protocol commission out of the reservations, the amounts already reserved keep their split
+    pub fn with_protocol_fee_bps(mut self, protocol_fee_bps: u16) -> Self {
+        self.protocol_fee_bps = protocol_fee_bps;
+        self
+    }
+
+    /// Holds the payment of the proven requests for the window, the requester can dispute the proof meanwhile
+    pub fn with_dispute_window(mut self, dispute_window: chrono::Duration) -> Self {
+        self.dispute_window = dispute_window;
+        self
+    }
+
+    /// Evaluates whether the operators are online with the thresholds of the network
+    pub fn with_liveness(mut self, liveness: Liveness) -> Self {
+        self.liveness = liveness;
+        self
+    }
+
+    pub fn liveness(&self) -> &Liveness {
+        &self.liveness
+    }
+
+    /// Pays the operator a share of the reservation when the request it was working on is cancelled
+    pub fn with_cancellation_fees(mut self, cancellation_fees: CancellationFees) -> Self {
+        self.cancellation_fees = cancellation_fees;
+        self
+    }
+
+    /// Keeps the active operators in memory, reloading them all once the `ttl` elapsed and the ones written by this
+    /// process on the next read. Operators changed by other processes may be read stale for up to the `ttl`.
+    pub fn with_operator_cache(mut self, ttl: std::time::Duration) -> Self {
+        self.operator_cache = Some(OperatorCache::new(ttl));
+        self
+    }
+
+    /// Greylists the operators failing as many proofs in a row as the policy tolerates
+    pub fn with_greylist_policy(mut self, greylist_policy: GreylistPolicy) -> Self {
+        self.greylist_policy = greylist_policy;
+        self
+    }
+
+    /// Returns the available operators in the order, e.g. the least recently assigned first so the work spreads
+    /// across them
+    pub fn with_operator_ordering(mut self, operator_ordering: OperatorOrdering) -> Self {
+        self.operator_ordering = operator_ordering;
+        self
+    }
+
+    /// Returns the requests waiting for an operator by the scheduling, e.g. the earliest deadline first
+    pub fn with_assignment_scheduling(mut self, scheduling: AssignmentScheduling) -> Self {
+        self.scheduler = AssignmentScheduler::new(scheduling);
+        self
+    }
+
+    /// Deadlines missed by the requests waiting for an operator since the scheduling was set
+    pub fn deadline_misses(&self) -> u64 {
+        self.scheduler.deadline_misses()
+    }
+
+    /// Holds the withdrawals above the threshold of the policy until enough approvers approved them
+    pub fn with_withdrawal_approval(mut self, withdrawal_approval: WithdrawalApprovalPolicy) -> Self {
+        self.withdrawal_approval = withdrawal_approval;
+        self
+    }
+
+    /// Evaluates the timeouts at the time of the clock, tests move a `MockClock` instead of sleeping
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Hits and misses of the operator cache, `None` if it isn't enabled
+    pub fn operator_cache_stats(&self) -> Option<OperatorCacheStats> {
+        self.operator_cache.as_ref().map(OperatorCache::stats)
     }
 }
 
@@ -3,7 +3,7 @@ use diesel::{
     dsl::{delete, insert_into},
     prelude::*,
 };
-use fermah_common::hash::blake3::Blake3Hash;
+use fermah_common::proof::request::ProofRequestId;
 use thiserror::Error;
 
 use crate::{schema::mm_deadlines::dsl::*, Database};
@@ -14,10 +14,12 @@ pub enum DeadlineDbError {
     FailedConnect(&'static str),
     #[error("query {0} failed")]
     QueryFailed(&'static str),
+    #[error("{0}: invalid proof request id")]
+    InvalidId(&'static str),
 }
 
 impl Database {
-    pub fn get_nearest(&self) -> Result<Option<(Blake3Hash, DateTime<Utc>)>, DeadlineDbError> {
+    pub fn get_nearest(&self) -> Result<Option<(ProofRequestId, DateTime<Utc>)>, DeadlineDbError> {
         let mut conn = self
             .pool
             .get()
@@ -30,15 +32,18 @@ impl Database {
             .optional()
             .map_err(|_| DeadlineDbError::QueryFailed("get_nearest"))?;
 
-        let maybe_nearest =
-            maybe_nearest.map(|(id, deadline_)| (Blake3Hash::from(id), deadline_.and_utc()));
-
-        Ok(maybe_nearest)
+        maybe_nearest
+            .map(|(id, deadline_)| {
+                let id = ProofRequestId::try_from(id)
+                    .map_err(|_| DeadlineDbError::InvalidId("get_nearest"))?;
+                Ok((id, deadline_.and_utc()))
+            })
+            .transpose()
     }
 
     pub fn add(
         &self,
-        proof_request_id: Blake3Hash,
+        proof_request_id: ProofRequestId,
         deadline_: DateTime<Utc>,
     ) -> Result<(), DeadlineDbError> {
         let mut conn = self
@@ -61,7 +66,7 @@ impl Database {
 
     pub fn remove(
         &self,
-        proof_request_id: &Blake3Hash,
+        proof_request_id: &ProofRequestId,
     ) -> Result<Option<DateTime<Utc>>, DeadlineDbError> {
         let mut conn = self
             .pool
@@ -96,8 +101,10 @@ mod tests {
         )
         .unwrap();
 
-        let proof_request_id = Blake3Hash(hash("check_add_remove".as_bytes()));
-        let unknown_proof_request_id = Blake3Hash(hash("unknown_proof_request_id".as_bytes()));
+        let proof_request_id =
+            ProofRequestId::from(*hash("check_add_remove".as_bytes()).as_bytes());
+        let unknown_proof_request_id =
+            ProofRequestId::from(*hash("unknown_proof_request_id".as_bytes()).as_bytes());
         let now = Utc::now();
         assert!(db.add(proof_request_id, now).is_ok());
         assert!(matches!(db.remove(&unknown_proof_request_id), Ok(None)));
@@ -125,7 +132,9 @@ mod tests {
         let test_inputs = (0..10)
             .map(|i| {
                 (
-                    Blake3Hash(hash(format!("check_get_nearest-{i}").as_bytes())),
+                    ProofRequestId::from(
+                        *hash(format!("check_get_nearest-{i}").as_bytes()).as_bytes(),
+                    ),
                     now.checked_add_days(Days::new(i)).unwrap(),
                 )
             })
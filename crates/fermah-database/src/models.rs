@@ -10,8 +10,22 @@ use diesel::{
     serialize::{IsNull, Output, ToSql},
     sql_types::{Bytea, Numeric},
 };
-use ethers::types::{Address, U256};
-use fermah_common::{operator::OperatorId, proof::status::ProofStatus};
+use ethers::types::{Address, Bytes, U256};
+use fermah_common::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    operator::{
+        offense::Offense,
+        restriction::{OperatorRestriction, RestrictionKind},
+        OperatorId,
+    },
+    proof::{
+        cancellation::CancellationReason,
+        legacy::decode_signed_proof_request,
+        request::ProofRequest,
+        status::ProofStatus,
+    },
+    types::region::Region,
+};
 use tracing::{error, warn};
 
 use crate::{
@@ -94,7 +108,7 @@ impl FromSql<Numeric, Pg> for EthU256 {
         bytes: <Pg as diesel::backend::Backend>::RawValue<'_>,
     ) -> diesel::deserialize::Result<Self> {
         let n = <bigdecimal::BigDecimal as FromSql<Numeric, Pg>>::from_sql(bytes)?.to_string();
-        Ok(Self(U256::from_dec_str(&n).unwrap()))
+        Ok(Self(U256::from_dec_str(&n)?))
     }
 }
 
@@ -108,7 +122,7 @@ impl ToSql<Numeric, Pg> for EthU256 {
     }
 }
 
-#[derive(Debug, AsExpression, FromSqlRow)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
 #[diesel(sql_type = crate::schema::sql_types::PrStatus)]
 pub enum PrStatus {
     Created,
@@ -168,7 +182,7 @@ impl FromSql<crate::schema::sql_types::PrStatus, Pg> for PrStatus {
     }
 }
 
-#[derive(Debug, AsExpression, FromSqlRow)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
 #[diesel(sql_type = crate::schema::sql_types::PrPayment)]
 pub enum PrPayment {
     Nothing,
@@ -220,6 +234,24 @@ impl FromSql<crate::schema::sql_types::PrPayment, Pg> for PrPayment {
     }
 }
 
+impl From<(PrPayment, Option<EthU256>)> for Payment {
+    fn from((payment, amount): (PrPayment, Option<EthU256>)) -> Self {
+        if payment != PrPayment::Nothing && amount.is_none() {
+            warn!("empty amount")
+        }
+        let amount = amount.unwrap_or_default().into();
+
+        match payment {
+            PrPayment::Nothing => Payment::Nothing,
+            PrPayment::ToReserve => Payment::ToReserve(amount),
+            PrPayment::Reserved => Payment::Reserved(amount),
+            PrPayment::ReadyToPay => Payment::ReadyToPay(amount),
+            PrPayment::Paid => Payment::Paid(amount),
+            PrPayment::Refund => Payment::Refund(amount),
+        }
+    }
+}
+
 #[derive(Queryable, Selectable)]
 #[diesel(table_name = crate::schema::mm_proof_requests)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -239,15 +271,73 @@ pub struct MmProofRequest {
     pub public_key: Vec<u8>,
     pub payload: Vec<u8>,
     pub signature: Vec<u8>,
-    pub requester: Option<EthAddress>,
+    pub requester: EthAddress,
     pub status: PrStatus,
     pub rejection_message: Option<String>,
     pub operator_id: Option<EthAddress>,
     pub proof: Option<Vec<u8>>,
+    pub cancellation_reason: Option<String>,
+    pub proof_deleted_at: Option<NaiveDateTime>,
 }
 
-impl From<MmProofRequest> for ProofRequestParams {
-    fn from(value: MmProofRequest) -> Self {
+/// Column of a record which can't be decoded
+#[derive(Debug, thiserror::Error)]
+#[error("corrupt {table}.{column} of {}: {source}", Bytes::from(.id.clone()))]
+pub struct DecodeError {
+    pub table: &'static str,
+    pub column: &'static str,
+    /// Primary key of the record
+    pub id: Vec<u8>,
+    pub source: bincode::Error,
+}
+
+impl DecodeError {
+    pub(crate) fn decode<T: serde::de::DeserializeOwned>(
+        table: &'static str,
+        column: &'static str,
+        id: &[u8],
+        bytes: &[u8],
+    ) -> Result<T, Self> {
+        bincode::deserialize(bytes).map_err(|source| {
+            Self {
+                table,
+                column,
+                id: id.to_vec(),
+                source,
+            }
+        })
+    }
+
+    /// Signed proof request of the `mm_proof_requests.payload` column, of the current layout or of the legacy one
+    /// stored before the fields appended since
+    pub(crate) fn decode_proof_request(
+        id: &[u8],
+        bytes: &[u8],
+    ) -> Result<SignedData<ProofRequest, EcdsaSigner>, Self> {
+        decode_signed_proof_request(bytes).map_err(|source| {
+            Self {
+                table: "mm_proof_requests",
+                column: "payload",
+                id: id.to_vec(),
+                source,
+            }
+        })
+    }
+}
+
+impl TryFrom<MmProofRequest> for ProofRequestParams {
+    type Error = DecodeError;
+
+    fn try_from(value: MmProofRequest) -> Result<Self, Self::Error> {
+        let decode_proof = |proof: &Option<Vec<u8>>| {
+            DecodeError::decode(
+                "mm_proof_requests",
+                "proof",
+                &value.hash,
+                proof.as_deref().unwrap_or_default(),
+            )
+        };
+
         let status = match value.status {
             PrStatus::Created => ProofStatus::Created,
             PrStatus::Accepted => ProofStatus::Accepted,
@@ -274,58 +364,135 @@ impl From<MmProofRequest> for ProofRequestParams {
                 if value.proof.is_none() {
                     error!("empty proof");
                 }
-                ProofStatus::ProofBeingTested(
-                    bincode::deserialize(&value.proof.unwrap_or_default()).unwrap(),
-                )
+                ProofStatus::ProofBeingTested(decode_proof(&value.proof)?)
             }
             PrStatus::Proven => {
                 if value.proof.is_none() {
                     error!("empty proof");
                 }
-                ProofStatus::Proven(bincode::deserialize(&value.proof.unwrap_or_default()).unwrap())
+                ProofStatus::Proven(decode_proof(&value.proof)?)
             }
         };
 
-        let payment = match value.payment {
-            PrPayment::Nothing => Payment::Nothing,
-            PrPayment::ToReserve => {
-                if value.amount.is_none() {
-                    warn!("empty amount")
-                }
-                Payment::ToReserve(value.amount.unwrap_or_default().into())
-            }
-            PrPayment::Reserved => {
-                if value.amount.is_none() {
-                    warn!("empty amount")
-                }
-                Payment::Reserved(value.amount.unwrap_or_default().into())
-            }
-            PrPayment::ReadyToPay => {
-                if value.amount.is_none() {
-                    warn!("empty amount")
-                }
-                Payment::ReadyToPay(value.amount.unwrap_or_default().into())
-            }
-            PrPayment::Paid => {
-                if value.amount.is_none() {
-                    warn!("empty amount")
-                }
-                Payment::Paid(value.amount.unwrap_or_default().into())
-            }
-            PrPayment::Refund => {
-                if value.amount.is_none() {
-                    warn!("empty amount")
-                }
-                Payment::Refund(value.amount.unwrap_or_default().into())
-            }
-        };
+        let payment = Payment::from((value.payment, value.amount));
+        let cancellation_reason = value.cancellation_reason.as_deref().and_then(|reason| {
+            CancellationReason::from_str(reason)
+                .inspect_err(|_| warn!(reason, "unknown cancellation_reason"))
+                .ok()
+        });
 
-        Self {
-            signed_payload: bincode::deserialize(&value.payload).unwrap(),
+        Ok(Self {
+            signed_payload: DecodeError::decode_proof_request(&value.hash, &value.payload)?,
             assigned: value.assigned.map(|oid| oid.into()),
             status,
             last_status_update: value.last_status_update.and_utc(),
             payment,
+            cancellation_reason,
+            proof_deleted_at: value.proof_deleted_at.map(|at| at.and_utc()),
+        })
+    }
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::mm_requester_balances)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MmRequesterBalance {
+    pub id: EthAddress,
+    pub reserved: EthU256,
+    pub ready_to_pay: EthU256,
+    pub paid: EthU256,
+    pub last_update: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = crate::schema::sql_types::AvsTxStatus)]
+pub enum AvsTxStatus {
+    Pending,
+    Confirmed,
+    Reverted,
+    Dropped,
+}
+
+impl ToSql<crate::schema::sql_types::AvsTxStatus, Pg> for AvsTxStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        match *self {
+            AvsTxStatus::Pending => out.write_all(b"Pending")?,
+            AvsTxStatus::Confirmed => out.write_all(b"Confirmed")?,
+            AvsTxStatus::Reverted => out.write_all(b"Reverted")?,
+            AvsTxStatus::Dropped => out.write_all(b"Dropped")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<crate::schema::sql_types::AvsTxStatus, Pg> for AvsTxStatus {
+    fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"Pending" => Ok(AvsTxStatus::Pending),
+            b"Confirmed" => Ok(AvsTxStatus::Confirmed),
+            b"Reverted" => Ok(AvsTxStatus::Reverted),
+            b"Dropped" => Ok(AvsTxStatus::Dropped),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::avs_transactions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AvsTransaction {
+    pub sender: EthAddress,
+    pub nonce: i64,
+    pub request: String,
+    pub tx_hashes: Vec<Vec<u8>>,
+    pub max_fee_per_gas: EthU256,
+    pub max_priority_fee_per_gas: EthU256,
+    pub bumps: i32,
+    pub status: AvsTxStatus,
+    pub submitted_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = crate::schema::sql_types::OperatorOffense)]
+pub enum OperatorOffense {
+    MissedDeadlineAfterAck,
+    InvalidProof,
+}
+
+impl From<Offense> for OperatorOffense {
+    fn from(value: Offense) -> Self {
+        match value {
+            Offense::MissedDeadlineAfterAck => OperatorOffense::MissedDeadlineAfterAck,
+            Offense::InvalidProof => OperatorOffense::InvalidProof,
+        }
+    }
+}
+
+impl From<OperatorOffense> for Offense {
+    fn from(value: OperatorOffense) -> Self {
+        match value {
+            OperatorOffense::MissedDeadlineAfterAck => Offense::MissedDeadlineAfterAck,
+            OperatorOffense::InvalidProof => Offense::InvalidProof,
+        }
+    }
+}
+
+impl ToSql<crate::schema::sql_types::OperatorOffense, Pg> for OperatorOffense {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        match *self {
+            OperatorOffense::MissedDeadlineAfterAck => out.write_all(b"MissedDeadlineAfterAck")?,
+            OperatorOffense::InvalidProof => out.write_all(b"InvalidProof")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<crate::schema::sql_types::OperatorOffense, Pg> for OperatorOffense {
+    fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"MissedDeadlineAfterAck" => Ok(OperatorOffense::MissedDeadlineAfterAck),
+            b"InvalidProof" => Ok(OperatorOffense::InvalidProof),
+            _ => Err("Unrecognized enum variant".into()),
         }
     }
 }
@@ -340,17 +507,55 @@ pub struct MmOperator {
     pub resource: Vec<u8>,
     pub reputation: i64,
     pub online: bool,
+    pub max_concurrent_jobs: i32,
+    pub region: Option<String>,
+    pub runtime: Option<Vec<u8>>,
+    pub restriction: Option<String>,
+    pub restriction_reason: Option<String>,
+    pub restricted_at: Option<NaiveDateTime>,
+    pub restricted_until: Option<NaiveDateTime>,
+    pub assignments: i64,
 }
 
-impl From<MmOperator> for OperatorInfo {
-    fn from(value: MmOperator) -> Self {
-        Self {
+impl TryFrom<MmOperator> for OperatorInfo {
+    type Error = DecodeError;
+
+    fn try_from(value: MmOperator) -> Result<Self, Self::Error> {
+        Ok(Self {
             operator_id: value.id.into(),
-            resource: bincode::deserialize(&value.resource).unwrap(),
+            resource: DecodeError::decode(
+                "mm_operators",
+                "resource",
+                value.id.0.as_bytes(),
+                &value.resource,
+            )?,
             reputation: value.reputation,
             last_interaction: value.last_interaction.and_utc(),
             online: value.online,
             last_assignment: value.last_assignment.and_utc(),
-        }
+            assignments: value.assignments.max(0) as u64,
+            max_concurrent_jobs: value.max_concurrent_jobs.max(1) as u32,
+            active_jobs: 0,
+            // Checked when reported
+            region: value.region.and_then(|region| Region::new(&region).ok()),
+            runtime: value
+                .runtime
+                .map(|runtime| {
+                    DecodeError::decode("mm_operators", "runtime", value.id.0.as_bytes(), &runtime)
+                })
+                .transpose()?
+                .unwrap_or_default(),
+            restriction: value.restriction.and_then(|kind| {
+                Some(OperatorRestriction {
+                    kind: kind
+                        .parse::<RestrictionKind>()
+                        .inspect_err(|err| warn!(%err, %kind, "unknown operator restriction"))
+                        .ok()?,
+                    reason: value.restriction_reason.unwrap_or_default(),
+                    since: value.restricted_at.unwrap_or_default().and_utc(),
+                    until: value.restricted_until.map(|until| until.and_utc()),
+                })
+            }),
+        })
     }
 }
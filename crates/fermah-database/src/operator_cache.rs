@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use fermah_common::operator::OperatorId;
+use opentelemetry::{global::meter, metrics::Counter};
+
+use crate::mm_operators::OperatorInfo;
+
+/// Hits and misses of the [`OperatorCache`] since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperatorCacheStats {
+    /// Reads served from the cache, including those which reloaded a few changed operators
+    pub hits: u64,
+    /// Reads which loaded all the operators, because the cache was empty or expired
+    pub misses: u64,
+    /// Operators reloaded one by one after they changed
+    pub reloads: u64,
+}
+
+impl OperatorCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let reads = self.hits + self.misses;
+        if reads == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / reads as f64
+    }
+}
+
+#[derive(Default)]
+struct CachedOperators {
+    operators: HashMap<OperatorId, OperatorInfo>,
+    /// Operators which changed since they were loaded, reloaded on the next read
+    stale: HashSet<OperatorId>,
+    loaded_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    reloads: AtomicU64,
+}
+
+struct Metrics {
+    hits: Counter<u64>,
+    misses: Counter<u64>,
+    reloads: Counter<u64>,
+}
+
+impl Metrics {
+    fn init() -> Self {
+        let m = meter("database metrics");
+        let counter = |name: &'static str, description: &'static str| {
+            m.u64_counter(name).with_description(description).init()
+        };
+        Self {
+            hits: counter(
+                "operator_cache_hits",
+                "Operator reads served from the in-process cache",
+            ),
+            misses: counter(
+                "operator_cache_misses",
+                "Operator reads which loaded all the operators from the database",
+            ),
+            reloads: counter(
+                "operator_cache_reloads",
+                "Operators reloaded from the database after they changed",
+            ),
+        }
+    }
+}
+
+/// Decoded infos of the active operators, shared by the clones of a [`Database`](crate::Database), so assigning
+/// requests and counting nodes don't load and decode every operator on each call. The database marks an operator
+/// stale whenever it writes it, and the next read reloads only the stale ones. The TTL bounds how long changes made
+/// by other processes go unnoticed.
+#[derive(Clone)]
+pub struct OperatorCache {
+    ttl: Duration,
+    cached: Arc<RwLock<CachedOperators>>,
+    counters: Arc<Counters>,
+    metrics: Arc<Metrics>,
+}
+
+impl fmt::Debug for OperatorCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperatorCache")
+            .field("ttl", &self.ttl)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl OperatorCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Arc::default(),
+            counters: Arc::default(),
+            metrics: Arc::new(Metrics::init()),
+        }
+    }
+
+    pub fn stats(&self) -> OperatorCacheStats {
+        OperatorCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            reloads: self.counters.reloads.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The operator is reloaded on the next read
+    pub fn invalidate(&self, operator_id: &OperatorId) {
+        let mut cached = self.cached.write().unwrap_or_else(|err| err.into_inner());
+        if cached.loaded_at.is_some() {
+            cached.stale.insert(*operator_id);
+        }
+    }
+
+    /// All the operators are loaded again on the next read
+    pub fn clear(&self) {
+        let mut cached = self.cached.write().unwrap_or_else(|err| err.into_inner());
+        *cached = CachedOperators::default();
+    }
+
+    /// Active operators, from the cache if it's fresh. `load` loads the given operators, or all of them with `None`,
+    /// leaving out those which are gone or no longer active.
+    pub(crate) fn get_or_load(
+        &self,
+        load: impl FnOnce(Option<&[OperatorId]>) -> Result<Vec<OperatorInfo>>,
+    ) -> Result<Vec<OperatorInfo>> {
+        {
+            let cached = self.cached.read().unwrap_or_else(|err| err.into_inner());
+            if self.is_fresh(&cached) && cached.stale.is_empty() {
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                self.metrics.hits.add(1, &[]);
+                return Ok(cached.operators.values().cloned().collect());
+            }
+        }
+
+        // Concurrent readers wait for the one reloading, instead of all hitting the database
+        let mut cached = self.cached.write().unwrap_or_else(|err| err.into_inner());
+        if !self.is_fresh(&cached) {
+            let operators = load(None)?;
+            cached.operators = operators
+                .into_iter()
+                .map(|operator| (operator.operator_id, operator))
+                .collect();
+            cached.stale.clear();
+            cached.loaded_at = Some(Instant::now());
+            self.counters.misses.fetch_add(1, Ordering::Relaxed);
+            self.metrics.misses.add(1, &[]);
+        } else {
+            if !cached.stale.is_empty() {
+                let stale: Vec<OperatorId> = cached.stale.iter().copied().collect();
+                let reloaded = load(Some(&stale))?;
+                for operator_id in &stale {
+                    cached.operators.remove(operator_id);
+                }
+                for operator in reloaded {
+                    cached.operators.insert(operator.operator_id, operator);
+                }
+                cached.stale.clear();
+                self.counters
+                    .reloads
+                    .fetch_add(stale.len() as u64, Ordering::Relaxed);
+                self.metrics.reloads.add(stale.len() as u64, &[]);
+            }
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            self.metrics.hits.add(1, &[]);
+        }
+
+        Ok(cached.operators.values().cloned().collect())
+    }
+
+    fn is_fresh(&self, cached: &CachedOperators) -> bool {
+        cached
+            .loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() < self.ttl)
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use fermah_common::{
+    crypto::signer::{ecdsa::EcdsaSigner, SignedData},
+    proof::{
+        request::ProofRequest,
+        scheduling::{AssignmentScheduling, RequestOrdering},
+    },
+};
+use opentelemetry::{global::meter, metrics::Counter};
+use tracing::warn;
+
+/// Orders the requests waiting for an operator, and counts the deadlines passing while they wait. Shared by the
+/// clones of a [`Database`](crate::Database), so a missed deadline is counted once by the process.
+#[derive(Clone)]
+pub struct AssignmentScheduler {
+    scheduling: AssignmentScheduling,
+    /// Time the waiting requests were last scheduled at, the deadlines passed since are missed
+    last_scheduled: Arc<Mutex<Option<DateTime<Utc>>>>,
+    deadline_misses: Arc<AtomicU64>,
+    metrics: Arc<Counter<u64>>,
+}
+
+impl fmt::Debug for AssignmentScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssignmentScheduler")
+            .field("scheduling", &self.scheduling)
+            .field("deadline_misses", &self.deadline_misses())
+            .finish()
+    }
+}
+
+impl AssignmentScheduler {
+    pub fn new(scheduling: AssignmentScheduling) -> Self {
+        Self {
+            scheduling,
+            last_scheduled: Arc::default(),
+            deadline_misses: Arc::default(),
+            metrics: Arc::new(
+                meter("database metrics")
+                    .u64_counter("proof_requests_deadline_missed")
+                    .with_description(
+                        "Proof requests whose deadline passed while they waited for an operator",
+                    )
+                    .init(),
+            ),
+        }
+    }
+
+    pub fn scheduling(&self) -> AssignmentScheduling {
+        self.scheduling
+    }
+
+    /// Deadlines missed by the waiting requests since the scheduler was created
+    pub fn deadline_misses(&self) -> u64 {
+        self.deadline_misses.load(Ordering::Relaxed)
+    }
+
+    /// Orders the waiting requests, each with the time it was accepted at, by the configured ordering. The deadlines
+    /// passed since the previous call are counted as missed, see [`Database::proven_after_deadline`] for the requests
+    /// proven late.
+    ///
+    /// [`Database::proven_after_deadline`]: crate::Database::proven_after_deadline
+    pub(crate) fn schedule(
+        &self,
+        now: DateTime<Utc>,
+        mut waiting: Vec<(SignedData<ProofRequest, EcdsaSigner>, DateTime<Utc>)>,
+    ) -> Vec<SignedData<ProofRequest, EcdsaSigner>> {
+        let since = self
+            .last_scheduled
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .replace(now);
+        let missed = waiting
+            .iter()
+            .filter_map(|(request, _)| request.payload.deadline)
+            .filter(|deadline| *deadline <= now && since.map_or(true, |since| since < *deadline))
+            .count() as u64;
+        if missed > 0 {
+            warn!(
+                count = missed,
+                "proof requests missed their deadline waiting for an operator"
+            );
+            self.deadline_misses.fetch_add(missed, Ordering::Relaxed);
+            self.metrics.add(missed, &[]);
+        }
+
+        match self.scheduling.request_ordering {
+            RequestOrdering::Unordered => {}
+            RequestOrdering::EarliestDeadlineFirst => {
+                waiting.sort_by_cached_key(|(request, accepted)| {
+                    self.scheduling
+                        .priority(request.payload.deadline, *accepted, now)
+                })
+            }
+        }
+        waiting.into_iter().map(|(request, _)| request).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use fermah_common::fixtures;
+
+    use super::*;
+
+    #[test]
+    fn test_earliest_deadline_first() {
+        let scheduler = AssignmentScheduler::new(AssignmentScheduling {
+            request_ordering: RequestOrdering::EarliestDeadlineFirst,
+            ..Default::default()
+        });
+        let now = Utc::now();
+        let request = |deadline: Option<DateTime<Utc>>, nonce: u64| {
+            let mut request = fixtures::signed_proof_request();
+            request.payload.deadline = deadline;
+            request.payload.nonce = nonce;
+            request
+        };
+        let waiting = vec![
+            (request(Some(now + Duration::hours(1)), 0), now),
+            // Starving, due before the later deadline
+            (request(None, 1), now - Duration::hours(1)),
+            (request(Some(now + Duration::minutes(1)), 2), now),
+            (request(None, 3), now),
+            // Missed, it goes last
+            (request(Some(now - Duration::minutes(1)), 4), now),
+        ];
+
+        let nonces = |scheduled: Vec<SignedData<ProofRequest, EcdsaSigner>>| {
+            scheduled
+                .iter()
+                .map(|request| request.payload.nonce)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            nonces(scheduler.schedule(now, waiting.clone())),
+            vec![1, 2, 3, 0, 4]
+        );
+        assert_eq!(scheduler.deadline_misses(), 1);
+
+        // Counted once, until another deadline passes
+        scheduler.schedule(now + Duration::seconds(30), waiting.clone());
+        assert_eq!(scheduler.deadline_misses(), 1);
+        scheduler.schedule(now + Duration::minutes(2), waiting.clone());
+        assert_eq!(scheduler.deadline_misses(), 2);
+
+        let unordered = AssignmentScheduler::new(AssignmentScheduling::default());
+        assert_eq!(
+            nonces(unordered.schedule(now, waiting)),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+}
@@ -1,6 +1,14 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "avs_tx_status"))]
+    pub struct AvsTxStatus;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "operator_offense"))]
+    pub struct OperatorOffense;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "pr_payment"))]
     pub struct PrPayment;
@@ -16,6 +24,8 @@ diesel::table! {
         socket -> Nullable<Text>,
         is_el_registered -> Bool,
         registered_till_block -> Numeric,
+        observed_block -> Nullable<Int8>,
+        observed_block_hash -> Nullable<Bytea>,
     }
 }
 
@@ -23,6 +33,80 @@ diesel::table! {
     avs_proof_requesters (id) {
         id -> Bytea,
         deposit -> Numeric,
+        observed_block -> Nullable<Int8>,
+        observed_block_hash -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AvsTxStatus;
+
+    avs_transactions (sender, nonce) {
+        sender -> Bytea,
+        nonce -> Int8,
+        request -> Text,
+        tx_hashes -> Array<Bytea>,
+        max_fee_per_gas -> Numeric,
+        max_priority_fee_per_gas -> Numeric,
+        bumps -> Int4,
+        status -> AvsTxStatus,
+        submitted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_audit_log (seq) {
+        seq -> Int8,
+        actor -> Bytea,
+        action -> Text,
+        amount -> Nullable<Numeric>,
+        tx_hash -> Nullable<Bytea>,
+        pr_ids -> Array<Bytea>,
+        details -> Nullable<Text>,
+        recorded_at -> Timestamp,
+        prev_hash -> Bytea,
+        hash -> Bytea,
+    }
+}
+
+diesel::table! {
+    mm_backfill_checkpoints (job) {
+        job -> Text,
+        last_key -> Nullable<Bytea>,
+        scanned -> Int8,
+        started_at -> Timestamp,
+        updated_at -> Timestamp,
+        finished_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mm_cancellations (pr_id, operator_id) {
+        pr_id -> Bytea,
+        operator_id -> Bytea,
+        reason -> Text,
+        created_at -> Timestamp,
+        acknowledged_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mm_container_policies (pr_id) {
+        pr_id -> Bytea,
+        privileged -> Text,
+        network -> Text,
+        decided_at -> Timestamp,
+        docker -> Text,
+    }
+}
+
+diesel::table! {
+    mm_corrupt_records (table_name, record_id) {
+        table_name -> Text,
+        record_id -> Bytea,
+        error -> Text,
+        detected_at -> Timestamp,
     }
 }
 
@@ -33,6 +117,122 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mm_delegations (requester, submitter) {
+        requester -> Bytea,
+        submitter -> Bytea,
+        expires_at -> Timestamp,
+        spend_cap -> Nullable<Numeric>,
+        max_requests -> Nullable<Int8>,
+        spent -> Numeric,
+        requests -> Int8,
+        delegated_at -> Timestamp,
+        nonce -> Int8,
+    }
+}
+
+diesel::table! {
+    mm_disputes (pr_id) {
+        pr_id -> Bytea,
+        requester -> Bytea,
+        reason -> Text,
+        status -> Text,
+        verifier -> Nullable<Bytea>,
+        created_at -> Timestamp,
+        assigned_at -> Nullable<Timestamp>,
+        resolved_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mm_expanded_requirements (pr_id) {
+        pr_id -> Bytea,
+        requirement -> Bytea,
+        expanded_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_image_names (name) {
+        name -> Text,
+        publisher -> Bytea,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_image_records (name, version) {
+        name -> Text,
+        version -> Text,
+        image_hash -> Bytea,
+        record -> Text,
+        published_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_image_validations (pr_id) {
+        pr_id -> Bytea,
+        images_total -> Int4,
+        images_verified -> Int4,
+        attempts -> Int4,
+        created_at -> Timestamp,
+        locked_until -> Nullable<Timestamp>,
+        finished_at -> Nullable<Timestamp>,
+        passed -> Nullable<Bool>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    mm_job_arrays (pr_id) {
+        pr_id -> Bytea,
+        array_id -> Bytea,
+        array_index -> Int4,
+        requester -> Bytea,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_operator_attestations (operator_id) {
+        operator_id -> Bytea,
+        tee -> Text,
+        quote -> Bytea,
+        reported_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_operator_images (operator_id, image_hash) {
+        operator_id -> Bytea,
+        image_hash -> Bytea,
+        reported_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::OperatorOffense;
+
+    mm_operator_offenses (operator_id, pr_id, offense) {
+        operator_id -> Bytea,
+        pr_id -> Bytea,
+        offense -> OperatorOffense,
+        recorded_at -> Timestamp,
+        slashing_tx -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    mm_operator_readiness (operator_id) {
+        operator_id -> Bytea,
+        ready -> Bool,
+        report -> Text,
+        submitted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     mm_operators (id) {
         id -> Bytea,
@@ -41,6 +241,54 @@ diesel::table! {
         reputation -> Int8,
         online -> Bool,
         last_assignment -> Timestamp,
+        max_concurrent_jobs -> Int4,
+        region -> Nullable<Text>,
+        runtime -> Nullable<Bytea>,
+        archived_at -> Nullable<Timestamp>,
+        restriction -> Nullable<Text>,
+        restriction_reason -> Nullable<Text>,
+        restricted_at -> Nullable<Timestamp>,
+        restricted_until -> Nullable<Timestamp>,
+        failure_streak -> Int4,
+        assignments -> Int8,
+    }
+}
+
+diesel::table! {
+    mm_outbox (id) {
+        id -> Int8,
+        kind -> Text,
+        payload -> Bytea,
+        created_at -> Timestamp,
+        attempts -> Int4,
+        locked_until -> Nullable<Timestamp>,
+        processed_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    mm_prefetch_hints (operator_id, image_hash) {
+        operator_id -> Bytea,
+        image_hash -> Bytea,
+        url -> Text,
+        pr_id -> Bytea,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_proof_receipts (pr_id) {
+        pr_id -> Bytea,
+        receipt -> Bytea,
+        issued_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_proof_request_dependencies (pr_id, depends_on) {
+        pr_id -> Bytea,
+        depends_on -> Bytea,
     }
 }
 
@@ -59,18 +307,220 @@ diesel::table! {
         public_key -> Bytea,
         payload -> Bytea,
         signature -> Bytea,
-        requester -> Nullable<Bytea>,
+        requester -> Bytea,
         status -> PrStatus,
         rejection_message -> Nullable<Varchar>,
         operator_id -> Nullable<Bytea>,
         proof -> Nullable<Bytea>,
+        created_at -> Nullable<Timestamp>,
+        accepted_at -> Nullable<Timestamp>,
+        assigned_at -> Nullable<Timestamp>,
+        acknowledged_at -> Nullable<Timestamp>,
+        tested_at -> Nullable<Timestamp>,
+        proven_at -> Nullable<Timestamp>,
+        peak_ram -> Nullable<Int8>,
+        peak_vram -> Nullable<Int8>,
+        wall_time_ms -> Nullable<Int8>,
+        requirement_fit -> Nullable<Text>,
+        protocol_fee -> Nullable<Numeric>,
+        retention_days -> Nullable<Int4>,
+        retain_until_retrieved -> Bool,
+        retrieved_at -> Nullable<Timestamp>,
+        proof_deleted_at -> Nullable<Timestamp>,
+        cancellation_reason -> Nullable<Text>,
+        deadline -> Nullable<Timestamp>,
+        sealed -> Bool,
     }
 }
 
+diesel::table! {
+    mm_redundant_proofs (pr_id, operator_id) {
+        pr_id -> Bytea,
+        operator_id -> Bytea,
+        proof -> Nullable<Bytea>,
+        proof_digest -> Nullable<Bytea>,
+        agrees -> Nullable<Bool>,
+        assigned_at -> Timestamp,
+        submitted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    mm_request_events (seq) {
+        seq -> Int8,
+        pr_id -> Bytea,
+        kind -> Text,
+        value -> Text,
+        operator_id -> Nullable<Bytea>,
+        amount -> Nullable<Numeric>,
+        details -> Nullable<Text>,
+        recorded_at -> Timestamp,
+        xact -> Int8,
+    }
+}
+
+diesel::table! {
+    mm_requester_balances (id) {
+        id -> Bytea,
+        reserved -> Numeric,
+        ready_to_pay -> Numeric,
+        paid -> Numeric,
+        last_update -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_requester_budgets (requester) {
+        requester -> Bytea,
+        daily_cap -> Nullable<Numeric>,
+        total_cap -> Nullable<Numeric>,
+        alert_percent -> Nullable<Int4>,
+        day -> Date,
+        spent_today -> Numeric,
+        spent_total -> Numeric,
+        daily_alerted -> Bool,
+        total_alerted -> Bool,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_shard_instances (instance_id) {
+        instance_id -> Text,
+        joined_at -> Timestamp,
+        heartbeat_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_shard_ranges (range_start) {
+        range_start -> Bytea,
+        range_end -> Bytea,
+        instance_id -> Text,
+    }
+}
+
+diesel::table! {
+    mm_signed_nonces (scope, signer) {
+        scope -> Text,
+        signer -> Bytea,
+        nonce -> Int8,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_vault_discrepancies (reconciliation_id, address, kind) {
+        reconciliation_id -> Int8,
+        address -> Bytea,
+        kind -> Text,
+        vault_balance -> Numeric,
+        booked -> Numeric,
+    }
+}
+
+diesel::table! {
+    mm_vault_reconciliations (id) {
+        id -> Int8,
+        block -> Int8,
+        checked -> Int8,
+        reconciled_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_webhook_deliveries (id) {
+        id -> Int8,
+        webhook_id -> Int8,
+        body -> Text,
+        created_at -> Timestamp,
+        attempts -> Int4,
+        next_attempt_at -> Nullable<Timestamp>,
+        delivered_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    mm_webhooks (id) {
+        id -> Int8,
+        requester -> Bytea,
+        url -> Text,
+        secret -> Bytea,
+        low_balance_threshold -> Nullable<Numeric>,
+        below_threshold -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_withdrawal_approvals (withdrawal_id, approver) {
+        withdrawal_id -> Bytea,
+        approver -> Bytea,
+        approved_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mm_withdrawals (id) {
+        id -> Bytea,
+        requester -> Bytea,
+        recipient -> Bytea,
+        amount -> Numeric,
+        deadline -> Timestamp,
+        status -> Text,
+        tx_hash -> Nullable<Bytea>,
+        error -> Nullable<Text>,
+        requested_at -> Timestamp,
+        resolved_at -> Nullable<Timestamp>,
+        tx_nonce -> Nullable<Int8>,
+        submitted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::joinable!(mm_image_records -> mm_image_names (name));
+diesel::joinable!(mm_vault_discrepancies -> mm_vault_reconciliations (reconciliation_id));
+diesel::joinable!(mm_webhook_deliveries -> mm_webhooks (webhook_id));
+diesel::joinable!(mm_withdrawal_approvals -> mm_withdrawals (withdrawal_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
     avs_operators,
     avs_proof_requesters,
+    avs_transactions,
+    mm_audit_log,
+    mm_backfill_checkpoints,
+    mm_cancellations,
+    mm_container_policies,
+    mm_corrupt_records,
     mm_deadlines,
+    mm_delegations,
+    mm_disputes,
+    mm_expanded_requirements,
+    mm_image_names,
+    mm_image_records,
+    mm_image_validations,
+    mm_job_arrays,
+    mm_operator_attestations,
+    mm_operator_images,
+    mm_operator_offenses,
+    mm_operator_readiness,
     mm_operators,
+    mm_outbox,
+    mm_prefetch_hints,
+    mm_proof_receipts,
+    mm_proof_request_dependencies,
     mm_proof_requests,
+    mm_redundant_proofs,
+    mm_request_events,
+    mm_requester_balances,
+    mm_requester_budgets,
+    mm_shard_instances,
+    mm_shard_ranges,
+    mm_signed_nonces,
+    mm_vault_discrepancies,
+    mm_vault_reconciliations,
+    mm_webhook_deliveries,
+    mm_webhooks,
+    mm_withdrawal_approvals,
+    mm_withdrawals,
 );
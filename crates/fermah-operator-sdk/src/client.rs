@@ -0,0 +1,245 @@
+use fermah_common::crypto::signer::Signer;
+use fermah_rpc::rpc_client::RpcClient;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::{
+    runner::ExecutableRunner,
+    types::{
+        Assignment,
+        Cancellation,
+        Deregistration,
+        DetectError,
+        DisputeStatus,
+        DisputeVerdict,
+        DisputeVerification,
+        EcdsaSigner,
+        OperatorExit,
+        OperatorHeartbeat,
+        OperatorId,
+        OperatorRegistration,
+        OperatorStats,
+        PrefetchHint,
+        ProofDelivery,
+        ProofRequestId,
+        ProofStatus,
+        ReadinessReport,
+        Region,
+        RetryPolicy,
+        RpcClientError,
+        RpcConfig,
+        TeeAttestation,
+        VerificationVerdict,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("failed to detect the resources of the host: {0}")]
+    Detect(#[from] DetectError),
+
+    #[error(transparent)]
+    Rpc(#[from] RpcClientError),
+}
+
+/// What the matchmaker has for the operator since it last polled
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperatorWork {
+    /// Requests assigned to the operator, to acknowledge and prove, or to verify for the verification-only ones
+    pub assignments: Vec<Assignment>,
+    /// Assignments taken back, the operator should stop proving them and acknowledge them
+    pub cancellations: Vec<Cancellation>,
+    /// Images of the requests the operator will likely be assigned, to download ahead
+    pub prefetch_hints: Vec<PrefetchHint>,
+    /// Disputed proofs the operator has to verify again
+    pub dispute_verifications: Vec<DisputeVerification>,
+}
+
+impl OperatorWork {
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+            && self.cancellations.is_empty()
+            && self.prefetch_hints.is_empty()
+            && self.dispute_verifications.is_empty()
+    }
+}
+
+/// Calls of the operator to the matchmaker RPC, signed with the operator key. An operator keeps itself online with
+/// [`Self::heartbeat`], [`Self::poll`]s for its work, acknowledges its assignments and submits their proofs, or the
+/// verdicts on the provided proofs of the verification-only requests.
+pub struct OperatorClient {
+    rpc: RpcClient,
+}
+
+impl OperatorClient {
+    pub async fn connect(config: RpcConfig, signer: EcdsaSigner) -> Result<Self, RpcClientError> {
+        Self::connect_with_retry(config, signer, RetryPolicy::default()).await
+    }
+
+    pub async fn connect_with_retry(
+        config: RpcConfig,
+        signer: EcdsaSigner,
+        retry: RetryPolicy,
+    ) -> Result<Self, RpcClientError> {
+        let rpc = RpcClient::from_config_with_retry(config, signer, retry).await?;
+        Ok(Self { rpc })
+    }
+
+    /// Operator the client signs for
+    pub fn operator_id(&self) -> OperatorId {
+        self.rpc.signer.verifying_key().into()
+    }
+
+    /// Underlying RPC client, for the calls the SDK doesn't wrap
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Registers the operator with the resources the runner detects on the host and its runtime, and makes the
+    /// matchmaker read the registration of the operator from the chain again, after it registered or extended its
+    /// registration
+    pub async fn sync_registration<R: ExecutableRunner>(
+        &self,
+        runner: &R,
+        max_concurrent_jobs: u32,
+        region: Option<Region>,
+    ) -> Result<(), SyncError> {
+        let registration = OperatorRegistration {
+            resource: runner.resource()?,
+            max_concurrent_jobs,
+            region,
+            runtime: runner.runtime(),
+        };
+        debug!(?registration, "registering the operator");
+        self.rpc.register_operator(registration).await?;
+        Ok(self.rpc.update_registered_till_block().await?)
+    }
+
+    /// Everything the matchmaker has for the operator, asked for at once
+    pub async fn poll(&self) -> Result<OperatorWork, RpcClientError> {
+        let (assignments, cancellations, prefetch_hints, dispute_verifications) = tokio::try_join!(
+            self.rpc.get_assignments(),
+            self.rpc.get_cancellations(),
+            self.rpc.get_prefetch_hints(),
+            self.rpc.get_dispute_verifications(),
+        )?;
+        let work = OperatorWork {
+            assignments,
+            cancellations,
+            prefetch_hints,
+            dispute_verifications,
+        };
+        if !work.is_empty() {
+            debug!(
+                operator=?self.operator_id(),
+                assignments = work.assignments.len(),
+                cancellations = work.cancellations.len(),
+                prefetch_hints = work.prefetch_hints.len(),
+                dispute_verifications = work.dispute_verifications.len(),
+                "polled operator work"
+            );
+        }
+        Ok(work)
+    }
+
+    /// Keeps the operator online, with the number of requests it proves at once, the images it has downloaded and its
+    /// region. Sent more often than the heartbeat timeout of the network.
+    pub async fn heartbeat(&self, heartbeat: OperatorHeartbeat) -> Result<(), RpcClientError> {
+        self.rpc.heartbeat(heartbeat).await
+    }
+
+    /// Confirms the operator took on the assignment, so it isn't given to another operator. Returns `false` if it was
+    /// acknowledged already.
+    pub async fn acknowledge_assignment(
+        &self,
+        proof_request_id: ProofRequestId,
+    ) -> Result<bool, RpcClientError> {
+        self.rpc.acknowledge_assignment(proof_request_id).await
+    }
+
+    /// Submits the proof of an assignment, from [`crate::runner::prove`]. Returns the status of the request once the
+    /// proof is recorded.
+    pub async fn submit_proof(
+        &self,
+        proof_request_id: ProofRequestId,
+        proof: Vec<u8>,
+    ) -> Result<ProofStatus, RpcClientError> {
+        self.rpc
+            .submit_proof(ProofDelivery {
+                proof_request_id,
+                proof,
+            })
+            .await
+    }
+
+    /// Confirms the operator stopped proving the cancelled assignment, it's no longer considered busy with it.
+    /// Returns `false` if there was no such cancellation.
+    pub async fn acknowledge_cancellation(
+        &self,
+        proof_request_id: ProofRequestId,
+    ) -> Result<bool, RpcClientError> {
+        self.rpc.acknowledge_cancellation(proof_request_id).await
+    }
+
+    /// Replaces the TEE attestation of the operator
+    pub async fn submit_attestation(
+        &self,
+        attestation: TeeAttestation,
+    ) -> Result<(), RpcClientError> {
+        self.rpc.submit_attestation(attestation).await
+    }
+
+    /// Submits the report of [`crate::self_test::self_test`], usually right after [`Self::sync_registration`]
+    pub async fn submit_readiness(&self, report: ReadinessReport) -> Result<(), RpcClientError> {
+        self.rpc.submit_readiness_report(report).await
+    }
+
+    /// Outcome of verifying a disputed proof again, from [`OperatorWork::dispute_verifications`]
+    pub async fn submit_dispute_verdict(
+        &self,
+        proof_request_id: ProofRequestId,
+        proof_valid: bool,
+    ) -> Result<DisputeStatus, RpcClientError> {
+        self.rpc
+            .submit_dispute_verdict(DisputeVerdict {
+                proof_request_id,
+                proof_valid,
+            })
+            .await
+    }
+
+    /// Outcome of verifying the proof provided with a verification-only request assigned to the operator, from
+    /// [`crate::runner::verify_provided`]. Returns the status the request ends in, proven with the provided proof or
+    /// rejected.
+    pub async fn submit_verification(
+        &self,
+        proof_request_id: ProofRequestId,
+        proof_valid: bool,
+    ) -> Result<ProofStatus, RpcClientError> {
+        self.rpc
+            .submit_verification(VerificationVerdict {
+                proof_request_id,
+                proof_valid,
+            })
+            .await
+    }
+
+    /// Requests proven by the operator and what it earned
+    pub async fn stats(&self) -> Result<OperatorStats, RpcClientError> {
+        self.rpc.get_operator_stats().await
+    }
+
+    /// Withdraws what the operator earned from the vault
+    pub async fn withdraw(&self) -> Result<(), RpcClientError> {
+        self.rpc.withdraw().await
+    }
+
+    /// Stops the operator for good: it's taken offline, its in-flight requests are assigned to other operators and it's
+    /// paid out for its proven requests. It leaves the AVS too if the deregistration says so.
+    pub async fn deregister(
+        &self,
+        deregistration: Deregistration,
+    ) -> Result<OperatorExit, RpcClientError> {
+        self.rpc.deregister_operator(deregistration).await
+    }
+}
@@ -0,0 +1,17 @@
+//! Client library for the operators, the prover nodes proving the matched requests.
+//!
+//! [`client::OperatorClient`] makes the operator's calls to the matchmaker RPC, signed with the operator key.
+//! [`runner::ExecutableRunner`] is what a prover node implements to run the provers and verifiers of the requests,
+//! and [`usage::UsageMeter`] measures what a job consumed, to report it with the proof. [`self_test::self_test`] runs
+//! them on a reference request, to check a prover node can serve requests before it registers, and
+//! [`self_test::SelfTestArgs`] is the self-test command a prover node adds to its CLI. The wire types are
+//! re-exported from [`types`], so a prover node doesn't have to depend on the matchmaker crates.
+
+pub mod client;
+pub mod runner;
+pub mod self_test;
+pub mod types;
+pub mod usage;
+
+/// Version of the SDK, which doesn't follow the versions of the matchmaker crates
+pub const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,405 @@
+use async_trait::async_trait;
+use fermah_common::crypto::sealed;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::{
+    types::{
+        detect_resource,
+        ContainerPolicyDecision,
+        DetectError,
+        DownloadError,
+        Executable,
+        ExecutableError,
+        ExtractedResult,
+        OperatorRuntime,
+        Proof,
+        ProofRequest,
+        ProofSubmission,
+        Resource,
+        ResourceOverrides,
+        SealError,
+        TemplateVars,
+        UnsupportedFeatures,
+        UrlPolicy,
+    },
+    usage::UsageMeter,
+};
+
+/// Runs the executables of the requests, the part of a prover node the SDK leaves to it. A run pulls the image or
+/// downloads the wasm module, mounts the in-mounts, injects the input, and extracts the result as the executable's
+/// [`ResultExtractor`](crate::types::ResultExtractor) tells.
+#[async_trait]
+pub trait ExecutableRunner: Send + Sync {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Runtime the executables are run with, advertised when the operator registers
+    fn runtime(&self) -> OperatorRuntime;
+
+    /// Resources claimed when the operator registers, the ones detected on the host unless the runner overrides
+    /// some of them from its config
+    fn resource(&self) -> Result<Resource, DetectError> {
+        detect_resource(&ResourceOverrides::default())
+    }
+
+    /// Runs the executable to completion. `injected` is written where the executable's injector points, e.g. the
+    /// proof for a verifier. The usage meter is sampled while the executable runs.
+    async fn run(
+        &self,
+        executable: &Executable,
+        injected: Option<&[u8]>,
+        meter: &mut UsageMeter,
+    ) -> Result<ExtractedResult, Self::Error>;
+}
+
+#[derive(Error, Debug)]
+pub enum RunError<E> {
+    #[error("invalid executable: {0}")]
+    Invalid(#[from] ExecutableError),
+
+    #[error(transparent)]
+    Unsupported(#[from] UnsupportedFeatures),
+
+    #[error("executable failed: {0}")]
+    Runner(#[source] E),
+
+    #[error("executable didn't produce a proof")]
+    NoProof,
+
+    #[error("verifier exited with a code it doesn't define a result for")]
+    NoVerdict,
+
+    #[error("verifier rejected the proof, it wasn't sealed")]
+    Rejected,
+
+    #[error("failed to seal the proof: {0}")]
+    Seal(#[from] SealError),
+
+    #[error(
+        "request is verification-only, the proof it provides is verified instead of proving it"
+    )]
+    VerificationOnly,
+
+    #[error("request provides no proof to verify")]
+    NoProvidedProof,
+
+    #[error("failed to download the provided proof: {0}")]
+    Download(#[from] DownloadError),
+}
+
+/// Checks the executable can run on the runner, then runs it with its placeholders substituted and the accesses the
+/// container policy of the matchmaker left it
+async fn run_checked<R: ExecutableRunner>(
+    runner: &R,
+    executable: &Executable,
+    vars: &TemplateVars,
+    decision: &ContainerPolicyDecision,
+    injected: Option<&[u8]>,
+    meter: &mut UsageMeter,
+) -> Result<ExtractedResult, RunError<R::Error>> {
+    let mut executable = executable
+        .resolve_templates(vars)
+        .map_err(ExecutableError::from)?;
+    decision.apply(&mut executable);
+    executable.validate()?;
+    runner.runtime().check(&executable)?;
+
+    runner
+        .run(&executable, injected, meter)
+        .await
+        .map_err(RunError::Runner)
+}
+
+/// Runs the prover of the request, the proof of the operator of the `vars` is submitted with the usage of the run.
+/// The proof of a request with a result key is sealed to it, nobody else can verify it then, so the operator runs
+/// the verifier on it first. Verification-only requests aren't proven, see [`verify_provided`].
+pub async fn prove<R: ExecutableRunner>(
+    runner: &R,
+    vars: &TemplateVars,
+    proof_request: &ProofRequest,
+    decision: &ContainerPolicyDecision,
+) -> Result<ProofSubmission, RunError<R::Error>> {
+    if proof_request.is_verification_only() {
+        return Err(RunError::VerificationOnly);
+    }
+    let mut meter = UsageMeter::start();
+    let result = run_checked(
+        runner,
+        &proof_request.prover,
+        vars,
+        decision,
+        None,
+        &mut meter,
+    )
+    .await?;
+    let ExtractedResult::Bytes(proof) = result else {
+        debug!(?result, "prover result isn't a proof");
+        return Err(RunError::NoProof);
+    };
+    let mut proof = Proof::new(proof, vars.operator_id);
+
+    if let Some(result_key) = &proof_request.result_key {
+        if !verify(runner, vars, proof_request, &proof, decision).await? {
+            return Err(RunError::Rejected);
+        }
+        proof.proof = sealed::seal(result_key, &proof.proof)?;
+    }
+
+    Ok(ProofSubmission {
+        proof,
+        usage: Some(meter.finish()),
+    })
+}
+
+/// Runs the verifier of the request on the proof, e.g. of a disputed proof. Returns whether the proof is valid.
+pub async fn verify<R: ExecutableRunner>(
+    runner: &R,
+    vars: &TemplateVars,
+    proof_request: &ProofRequest,
+    proof: &Proof,
+    decision: &ContainerPolicyDecision,
+) -> Result<bool, RunError<R::Error>> {
+    let mut meter = UsageMeter::start();
+    let result = run_checked(
+        runner,
+        &proof_request.verifier,
+        vars,
+        decision,
+        Some(&proof.proof),
+        &mut meter,
+    )
+    .await?;
+
+    match result {
+        ExtractedResult::Success | ExtractedResult::Bytes(_) => Ok(true),
+        ExtractedResult::NegativeResult => Ok(false),
+        ExtractedResult::ZipDirectory(_) => Err(RunError::NoVerdict),
+    }
+}
+
+/// Runs the verifier of the verification-only request on the proof it provides, downloaded first if it's remote.
+/// Returns whether the proof is valid, the verdict the operator submits.
+pub async fn verify_provided<R: ExecutableRunner>(
+    runner: &R,
+    vars: &TemplateVars,
+    proof_request: &ProofRequest,
+    decision: &ContainerPolicyDecision,
+    policy: &UrlPolicy,
+) -> Result<bool, RunError<R::Error>> {
+    let Some(provided) = &proof_request.proof else {
+        return Err(RunError::NoProvidedProof);
+    };
+    let proof = Proof::new(provided.fetch(policy).await?, vars.operator_id);
+    verify(runner, vars, proof_request, &proof, decision).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use ethers::types::Address;
+    use fermah_common::{fixtures, proof::container_policy::AccessDecision};
+
+    use super::*;
+    use crate::types::{ContainerRuntime, OperatorId, ProvidedProof, RuntimeFeature};
+
+    /// Runner returning the scripted result, remembering what it was asked to run
+    struct ScriptedRunner {
+        runtime: OperatorRuntime,
+        result: ExtractedResult,
+        /// Result of the runs the proof is injected into, the verifiers, if it isn't `result`
+        verdict: Option<ExtractedResult>,
+        ran: Mutex<Vec<(Executable, Option<Vec<u8>>)>>,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("scripted failure")]
+    struct ScriptedError;
+
+    #[async_trait]
+    impl ExecutableRunner for ScriptedRunner {
+        type Error = ScriptedError;
+
+        fn runtime(&self) -> OperatorRuntime {
+            self.runtime.clone()
+        }
+
+        async fn run(
+            &self,
+            executable: &Executable,
+            injected: Option<&[u8]>,
+            meter: &mut UsageMeter,
+        ) -> Result<ExtractedResult, ScriptedError> {
+            meter.sample_ram(1024);
+            self.ran
+                .lock()
+                .unwrap()
+                .push((executable.clone(), injected.map(<[u8]>::to_vec)));
+            match (injected, &self.verdict) {
+                (Some(_), Some(verdict)) => Ok(verdict.clone()),
+                _ => Ok(self.result.clone()),
+            }
+        }
+    }
+
+    fn vars(operator_id: OperatorId) -> TemplateVars {
+        TemplateVars {
+            request_id: fixtures::signed_proof_request().id(),
+            operator_id,
+            block: 7,
+        }
+    }
+
+    fn runner(result: ExtractedResult) -> ScriptedRunner {
+        ScriptedRunner {
+            runtime: OperatorRuntime::default(),
+            result,
+            verdict: None,
+            ran: Mutex::new(vec![]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prove() {
+        let operator_id = OperatorId::from(Address::random());
+        let mut proof_request = fixtures::proof_request();
+        proof_request.prover.network_enabled = true;
+        proof_request.prover.cmd = vec!["--block=${FERMAH_BLOCK}".to_string()];
+        let decision = ContainerPolicyDecision {
+            network: AccessDecision::Downgraded,
+            ..ContainerPolicyDecision::default()
+        };
+
+        let prover = runner(ExtractedResult::Bytes(vec![1, 2, 3]));
+        let submission = prove(&prover, &vars(operator_id), &proof_request, &decision)
+            .await
+            .unwrap();
+        assert_eq!(submission.proof, Proof::new(vec![1, 2, 3], operator_id));
+        assert_eq!(submission.usage.unwrap().peak_ram, Some(1024));
+        // The downgraded access is taken away from the run
+        assert!(!prover.ran.lock().unwrap()[0].0.network_enabled);
+        // The placeholders are substituted for the run
+        assert_eq!(prover.ran.lock().unwrap()[0].0.cmd, vec!["--block=7"]);
+
+        let no_proof = runner(ExtractedResult::Success);
+        assert!(matches!(
+            prove(&no_proof, &vars(operator_id), &proof_request, &decision).await,
+            Err(RunError::NoProof)
+        ));
+
+        // Executables asking for features the runtime lacks aren't run
+        proof_request.prover.docker_access = true;
+        let offline = ScriptedRunner {
+            runtime: OperatorRuntime {
+                runtime: ContainerRuntime::Podman,
+                features: vec![RuntimeFeature::Privileged],
+            },
+            ..runner(ExtractedResult::Bytes(vec![1]))
+        };
+        assert!(matches!(
+            prove(
+                &offline,
+                &vars(operator_id),
+                &proof_request,
+                &ContainerPolicyDecision::default()
+            )
+            .await,
+            Err(RunError::Unsupported(_))
+        ));
+        assert!(offline.ran.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prove_sealed() {
+        let requester = fixtures::requester();
+        let operator_id = OperatorId::from(Address::random());
+        let mut proof_request = fixtures::proof_request();
+        proof_request.result_key = Some(requester.result_key());
+        let decision = ContainerPolicyDecision::default();
+
+        let prover = runner(ExtractedResult::Bytes(vec![1, 2, 3]));
+        let submission = prove(&prover, &vars(operator_id), &proof_request, &decision)
+            .await
+            .unwrap();
+        // Only the requester reads the proof, which the verifier checked before it was sealed
+        assert_ne!(submission.proof.proof, vec![1, 2, 3]);
+        assert_eq!(
+            requester.open_result(&submission.proof.proof),
+            Ok(vec![1, 2, 3])
+        );
+        assert_eq!(prover.ran.lock().unwrap()[1].1, Some(vec![1, 2, 3]));
+
+        let rejecting = ScriptedRunner {
+            verdict: Some(ExtractedResult::NegativeResult),
+            ..runner(ExtractedResult::Bytes(vec![1]))
+        };
+        assert!(matches!(
+            prove(&rejecting, &vars(operator_id), &proof_request, &decision).await,
+            Err(RunError::Rejected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let proof_request = fixtures::proof_request();
+        let proof = Proof::new(vec![4, 5, 6], OperatorId::from(Address::random()));
+        let vars = vars(OperatorId::from(Address::random()));
+        let decision = ContainerPolicyDecision::default();
+
+        let valid = runner(ExtractedResult::Success);
+        assert!(verify(&valid, &vars, &proof_request, &proof, &decision)
+            .await
+            .unwrap());
+        // The proof is injected into the verifier
+        assert_eq!(valid.ran.lock().unwrap()[0].1, Some(vec![4, 5, 6]));
+
+        let invalid = runner(ExtractedResult::NegativeResult);
+        assert!(!verify(&invalid, &vars, &proof_request, &proof, &decision)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verify_provided() {
+        let mut proof_request = fixtures::proof_request();
+        proof_request.prover = proof_request.verifier.clone();
+        proof_request.proof = Some(ProvidedProof::Inline(vec![7, 8]));
+        let vars = vars(OperatorId::from(Address::random()));
+        let decision = ContainerPolicyDecision::default();
+        let policy = UrlPolicy::default();
+
+        // Only the verifier runs, on the provided proof
+        let valid = runner(ExtractedResult::Success);
+        assert!(matches!(
+            prove(&valid, &vars, &proof_request, &decision).await,
+            Err(RunError::VerificationOnly)
+        ));
+        assert!(valid.ran.lock().unwrap().is_empty());
+        assert!(
+            verify_provided(&valid, &vars, &proof_request, &decision, &policy)
+                .await
+                .unwrap()
+        );
+        assert_eq!(valid.ran.lock().unwrap().len(), 1);
+        assert_eq!(valid.ran.lock().unwrap()[0].1, Some(vec![7, 8]));
+
+        let invalid = runner(ExtractedResult::NegativeResult);
+        assert!(
+            !verify_provided(&invalid, &vars, &proof_request, &decision, &policy)
+                .await
+                .unwrap()
+        );
+
+        assert!(matches!(
+            verify_provided(
+                &valid,
+                &vars,
+                &fixtures::proof_request(),
+                &decision,
+                &policy
+            )
+            .await,
+            Err(RunError::NoProvidedProof)
+        ));
+    }
+}
@@ -0,0 +1,373 @@
+use std::{collections::HashMap, path::PathBuf, time::Instant};
+
+use chrono::Utc;
+use clap::Args;
+use fermah_common::{
+    crypto::{
+        keystore::{KeystoreConfig, KeystoreFile},
+        signer::Signer,
+    },
+    resource::requirement::ResourceRequirement,
+};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::{
+    runner::{prove, verify, ExecutableRunner},
+    types::{
+        ContainerPolicyDecision,
+        EcdsaSigner,
+        Executable,
+        Image,
+        Injector,
+        OperatorId,
+        ProofRequest,
+        ProofRequestId,
+        ReadinessCheck,
+        ReadinessCheckKind,
+        ReadinessReport,
+        ResultExtractor,
+        SignedData,
+        TemplateVars,
+    },
+    SDK_VERSION,
+};
+
+/// Image of the reference request, published with each release of the SDK under its version
+pub const REFERENCE_IMAGE: &str = concat!("fermah/self-test:", env!("CARGO_PKG_VERSION"));
+/// File the reference prover writes its proof to, and the reference verifier reads it from
+const REFERENCE_STATE: &str = "/output/state.bin";
+/// Exit code of the reference verifier rejecting the proof
+const REFERENCE_REJECTION_CODE: i64 = 58;
+
+fn millis(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+fn reference_executable(entrypoint: &str) -> Executable {
+    Executable {
+        image: Image::Docker(REFERENCE_IMAGE.to_string()),
+        platform: None,
+        in_mounts: vec![],
+        result_extractor: None,
+        injector: None,
+        entrypoint: vec![entrypoint.to_string()],
+        cmd: vec![],
+        env_vars: Some(HashMap::from([(
+            "STATE_LOCATION".to_string(),
+            REFERENCE_STATE.to_string(),
+        )])),
+        network_enabled: false,
+        privileged: false,
+        docker_access: false,
+    }
+}
+
+/// Reference request the self-test runs by default, pinned to the [`REFERENCE_IMAGE`] of this SDK version. Its
+/// prover makes a small groth16 proof in seconds on a single core, so the self-test mostly times the image pull.
+pub fn reference_request() -> ProofRequest {
+    ProofRequest {
+        requester: None,
+        prover: Executable {
+            result_extractor: Some(ResultExtractor::File(REFERENCE_STATE.into())),
+            ..reference_executable("/bin/prove")
+        },
+        verifier: Executable {
+            result_extractor: Some(ResultExtractor::NegativeExitCode(REFERENCE_REJECTION_CODE)),
+            injector: Some(Injector::File(REFERENCE_STATE.into())),
+            ..reference_executable("/bin/verify")
+        },
+        resource_requirement: ResourceRequirement {
+            min_cpu_cores: Some(1),
+            ..Default::default()
+        },
+        callback_url: None,
+        deadline: None,
+        nonce: 0,
+        depends_on: vec![],
+        redundancy: None,
+        preferred_regions: None,
+        proof: None,
+        result_key: None,
+        retention: None,
+        result_validator: None,
+    }
+}
+
+/// Self-test command of a prover node, e.g. `prover self-test`. The node flattens the arguments into its own CLI and
+/// calls [`SelfTestArgs::run`] with its runner.
+#[derive(Args, Debug, Clone)]
+pub struct SelfTestArgs {
+    /// Keystore of the operator key, it's decrypted as the node would when it starts
+    #[command(flatten)]
+    pub key: KeystoreConfig,
+
+    /// JSON file of the request to test with, the pinned reference request of the SDK if not set
+    #[arg(long)]
+    pub reference: Option<PathBuf>,
+}
+
+#[derive(Error, Debug)]
+pub enum SelfTestError {
+    #[error("failed to read the keystore: {0}")]
+    Keystore(#[from] fermah_common::fs::error::Error),
+    #[error("failed to decrypt the keystore: {0}")]
+    Decryption(#[from] fermah_common::crypto::keystore::KeystoreFileError),
+    #[error("failed to read the reference request {0}: {1}")]
+    Reference(PathBuf, String),
+}
+
+impl SelfTestArgs {
+    /// Decrypts the operator keystore, then runs [`self_test`] with the key on the reference request. The
+    /// decryption is the first check of the report. Nothing can be checked without the key, so a keystore which
+    /// can't be decrypted fails the command instead.
+    pub async fn run<R: ExecutableRunner>(
+        &self,
+        runner: &R,
+    ) -> Result<ReadinessReport, SelfTestError> {
+        let reference = match &self.reference {
+            Some(path) => {
+                let json = tokio::fs::read(path)
+                    .await
+                    .map_err(|err| SelfTestError::Reference(path.clone(), err.to_string()))?;
+                serde_json::from_slice(&json)
+                    .map_err(|err| SelfTestError::Reference(path.clone(), err.to_string()))?
+            }
+            None => reference_request(),
+        };
+
+        let started = Instant::now();
+        let signer = KeystoreFile::from_config(&self.key)
+            .await?
+            .to_signer::<EcdsaSigner>(&self.key)
+            .await?;
+        let decrypted = ReadinessCheck::passed(ReadinessCheckKind::Keystore, millis(started));
+
+        let mut report = self_test(runner, &signer, &reference).await;
+        report.checks.insert(0, decrypted);
+        Ok(report)
+    }
+}
+
+/// Checks the prover node can serve requests before it registers: the operator key signs, the resources of the host
+/// are detected, and the prover and verifier of the reference request run on the runner as an assignment of it would,
+/// e.g. a small image of the network's proof profiles. The image is pulled by the first run, which is timed with it.
+/// Failed checks don't stop the self-test, the report lists all of them.
+pub async fn self_test<R: ExecutableRunner>(
+    runner: &R,
+    signer: &EcdsaSigner,
+    reference: &ProofRequest,
+) -> ReadinessReport {
+    let operator_id = OperatorId::from(signer.verifying_key());
+    let mut checks = vec![];
+
+    let started = Instant::now();
+    let signed = SignedData::new(reference.clone(), signer)
+        .map_err(|err| err.to_string())
+        .and_then(|signed| {
+            signed
+                .verify()
+                .map(|_| signed)
+                .map_err(|err| err.to_string())
+        });
+    checks.push(match &signed {
+        Ok(_) => ReadinessCheck::passed(ReadinessCheckKind::Key, millis(started)),
+        Err(err) => ReadinessCheck::failed(ReadinessCheckKind::Key, err, millis(started)),
+    });
+
+    let started = Instant::now();
+    let resource = match runner.resource() {
+        Ok(resource) => {
+            checks.push(ReadinessCheck::passed(
+                ReadinessCheckKind::Resource,
+                millis(started),
+            ));
+            Some(resource)
+        }
+        Err(err) => {
+            checks.push(ReadinessCheck::failed(
+                ReadinessCheckKind::Resource,
+                err,
+                millis(started),
+            ));
+            None
+        }
+    };
+
+    // An assignment's id, unless the key can't sign the reference
+    let vars = TemplateVars {
+        request_id: signed
+            .as_ref()
+            .map(|signed| signed.id())
+            .unwrap_or_else(|_| ProofRequestId::from([0; 32])),
+        operator_id,
+        block: 0,
+    };
+    let decision = ContainerPolicyDecision::default();
+    let started = Instant::now();
+    match prove(runner, &vars, reference, &decision).await {
+        Ok(submission) => {
+            checks.push(ReadinessCheck::passed(
+                ReadinessCheckKind::Prover,
+                millis(started),
+            ));
+
+            let started = Instant::now();
+            checks.push(
+                match verify(runner, &vars, reference, &submission.proof, &decision).await {
+                    Ok(true) => {
+                        ReadinessCheck::passed(ReadinessCheckKind::Verifier, millis(started))
+                    }
+                    Ok(false) => {
+                        ReadinessCheck::failed(
+                            ReadinessCheckKind::Verifier,
+                            "verifier rejected the proof of the prover",
+                            millis(started),
+                        )
+                    }
+                    Err(err) => {
+                        ReadinessCheck::failed(ReadinessCheckKind::Verifier, err, millis(started))
+                    }
+                },
+            );
+        }
+        Err(err) => {
+            checks.push(ReadinessCheck::failed(
+                ReadinessCheckKind::Prover,
+                err,
+                millis(started),
+            ));
+            checks.push(ReadinessCheck::failed(
+                ReadinessCheckKind::Verifier,
+                "no proof to verify",
+                0,
+            ));
+        }
+    }
+
+    let report = ReadinessReport {
+        operator_id,
+        sdk_version: SDK_VERSION.to_string(),
+        runtime: runner.runtime(),
+        resource,
+        checks,
+        tested_at: Utc::now(),
+    };
+    if report.is_ready() {
+        info!(operator=?operator_id, "self-test passed");
+    } else {
+        warn!(operator=?operator_id, "self-test failed");
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use fermah_common::fixtures;
+    use thiserror::Error;
+
+    use super::*;
+    use crate::{
+        types::{DetectError, Executable, ExtractedResult, OperatorRuntime, Resource},
+        usage::UsageMeter,
+    };
+
+    /// Runner whose provers return a proof and whose verifiers return the verdict
+    struct ReferenceRunner {
+        verdict: ExtractedResult,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("image pull failed")]
+    struct PullError;
+
+    #[async_trait]
+    impl ExecutableRunner for ReferenceRunner {
+        type Error = PullError;
+
+        fn runtime(&self) -> OperatorRuntime {
+            OperatorRuntime::default()
+        }
+
+        fn resource(&self) -> Result<Resource, DetectError> {
+            Ok(Resource::default())
+        }
+
+        async fn run(
+            &self,
+            _executable: &Executable,
+            injected: Option<&[u8]>,
+            _meter: &mut UsageMeter,
+        ) -> Result<ExtractedResult, PullError> {
+            match (injected, &self.verdict) {
+                (_, ExtractedResult::ZipDirectory(_)) => Err(PullError),
+                (None, _) => Ok(ExtractedResult::Bytes(vec![0, 9, 6, 0])),
+                (Some(_), verdict) => Ok(verdict.clone()),
+            }
+        }
+    }
+
+    fn kinds(report: &ReadinessReport) -> Vec<(ReadinessCheckKind, bool)> {
+        report
+            .checks
+            .iter()
+            .map(|check| (check.kind, check.passed))
+            .collect()
+    }
+
+    #[test]
+    fn test_reference_request() {
+        let reference = reference_request();
+        assert_eq!(reference.prover.validate(), Ok(()));
+        assert_eq!(reference.verifier.validate(), Ok(()));
+        assert_eq!(reference.prover.image.name(), REFERENCE_IMAGE);
+        assert!(REFERENCE_IMAGE.ends_with(crate::SDK_VERSION));
+    }
+
+    #[tokio::test]
+    async fn test_self_test() {
+        let signer = fixtures::requester();
+        let reference = fixtures::proof_request();
+
+        let ready = ReferenceRunner {
+            verdict: ExtractedResult::Success,
+        };
+        let report = self_test(&ready, &signer, &reference).await;
+        assert!(report.is_ready(), "{report}");
+        assert_eq!(report.operator_id, OperatorId::from(signer.verifying_key()));
+        assert_eq!(report.resource, Some(Resource::default()));
+
+        let rejecting = ReferenceRunner {
+            verdict: ExtractedResult::NegativeResult,
+        };
+        let report = self_test(&rejecting, &signer, &reference).await;
+        assert!(!report.is_ready());
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ReadinessCheckKind::Key, true),
+                (ReadinessCheckKind::Resource, true),
+                (ReadinessCheckKind::Prover, true),
+                (ReadinessCheckKind::Verifier, false),
+            ]
+        );
+
+        // Nothing runs, e.g. without a docker daemon
+        let broken = ReferenceRunner {
+            verdict: ExtractedResult::ZipDirectory(vec![]),
+        };
+        let report = self_test(&broken, &signer, &reference).await;
+        assert_eq!(
+            kinds(&report)[2..],
+            [
+                (ReadinessCheckKind::Prover, false),
+                (ReadinessCheckKind::Verifier, false),
+            ]
+        );
+        assert_eq!(
+            report.checks[2].detail.as_deref(),
+            Some("executable failed: image pull failed")
+        );
+    }
+}
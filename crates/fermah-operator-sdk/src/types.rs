@@ -0,0 +1,51 @@
+//! Types exchanged with the matchmaker, as the SDK calls expect and return them
+
+pub use fermah_common::{
+    crypto::{
+        sealed::{ResultKey, SealError},
+        signer::{ecdsa::EcdsaSigner, SignedData},
+    },
+    executable::{
+        Executable,
+        ExecutableError,
+        ExtractedResult,
+        Image,
+        InMount,
+        Injector,
+        ResultExtractor,
+        Source,
+    },
+    http::url_policy::UrlPolicy,
+    operator::{
+        assignment::{Assignment, ProofDelivery},
+        deregistration::{Deregistration, OperatorExit},
+        liveness::OperatorHeartbeat,
+        readiness::{OperatorReadiness, ReadinessCheck, ReadinessCheckKind, ReadinessReport},
+        registration::OperatorRegistration,
+        runtime::{ContainerRuntime, OperatorRuntime, RuntimeFeature, UnsupportedFeatures},
+        OperatorId,
+    },
+    proof::{
+        cancellation::Cancellation,
+        container_policy::ContainerPolicyDecision,
+        dispute::{DisputeStatus, DisputeVerdict, DisputeVerification},
+        prefetch::PrefetchHint,
+        request::{ProofRequest, ProofRequestId, ProvidedProof, VerificationVerdict},
+        status::ProofStatus,
+        template::{Placeholder, TemplateError, TemplateVars},
+        Proof,
+        ProofSubmission,
+    },
+    resource::{
+        detect::{detect_resource, DetectError, ResourceOverrides},
+        tee::TeeAttestation,
+        usage::ResourceUsage,
+        Resource,
+    },
+    resources::DownloadError,
+    types::{network::Connection, region::Region, stats::OperatorStats},
+};
+pub use fermah_rpc::{
+    rpc_client::{RetryPolicy, RpcClientError},
+    RpcConfig,
+};
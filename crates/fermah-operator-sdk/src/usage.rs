@@ -0,0 +1,61 @@
+use std::time::Instant;
+
+use crate::types::{Proof, ProofSubmission, ResourceUsage};
+
+/// Measures a job while it runs: its wall time, and the peaks of the memory samples the node takes meanwhile
+#[derive(Debug, Clone)]
+pub struct UsageMeter {
+    started: Instant,
+    peak_ram: Option<u64>,
+    peak_vram: Option<u64>,
+}
+
+impl UsageMeter {
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            peak_ram: None,
+            peak_vram: None,
+        }
+    }
+
+    /// RAM the job uses now, in bytes
+    pub fn sample_ram(&mut self, bytes: u64) {
+        self.peak_ram = self.peak_ram.max(Some(bytes));
+    }
+
+    /// VRAM the job uses now over all the GPUs, in bytes
+    pub fn sample_vram(&mut self, bytes: u64) {
+        self.peak_vram = self.peak_vram.max(Some(bytes));
+    }
+
+    /// Usage of the job, which ends now. Peaks the node didn't sample are left out.
+    pub fn finish(self) -> ResourceUsage {
+        ResourceUsage {
+            peak_ram: self.peak_ram,
+            peak_vram: self.peak_vram,
+            wall_time_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Proof delivered with what its job consumed, `None` if the node doesn't measure it
+pub fn submission(proof: Proof, usage: Option<ResourceUsage>) -> ProofSubmission {
+    ProofSubmission { proof, usage }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_meter() {
+        let mut meter = UsageMeter::start();
+        for ram in [100, 300, 200] {
+            meter.sample_ram(ram);
+        }
+        let usage = meter.finish();
+        assert_eq!(usage.peak_ram, Some(300));
+        assert_eq!(usage.peak_vram, None);
+    }
+}
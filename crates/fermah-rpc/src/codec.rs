@@ -0,0 +1,434 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use base64::{engine::general_purpose, Engine};
+use ciborium::Value as CborValue;
+use http::{header, HeaderValue};
+use jsonrpsee::{
+    core::http_helpers::read_body,
+    server::{HttpBody, HttpRequest, HttpResponse},
+};
+use serde_json::Value as JsonValue;
+use tower::{Layer, Service};
+use tracing::debug;
+
+use crate::RpcConfig;
+
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// Same limit as the jsonrpsee request body
+const MAX_REQUEST_BODY_SIZE: u32 = 10 * 1024 * 1024;
+/// Byte strings grow by a third as base64, so a CBOR body up to this size still fits the request body limit once it's
+/// transcoded to JSON. Larger ones are refused before they're decoded.
+const MAX_CBOR_BODY_SIZE: u32 = MAX_REQUEST_BODY_SIZE / 4 * 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    #[error("invalid CBOR: {0}")]
+    Decode(String),
+
+    #[error("CBOR value has no JSON equivalent: {0}")]
+    Unsupported(&'static str),
+
+    #[error("failed to encode CBOR: {0}")]
+    Encode(String),
+
+    #[error("transcoded request is larger than {MAX_REQUEST_BODY_SIZE} bytes")]
+    TooLarge,
+}
+
+/// Lets HTTP clients send the JSON-RPC requests as CBOR with `Content-Type: application/cbor`, and get CBOR responses
+/// with `Accept: application/cbor`. The requests are transcoded to JSON for the RPC methods, CBOR byte strings
+/// become the base64 strings the methods expect, so proofs and other binary fields can be sent raw instead of
+/// base64-encoded. Binary fields of the responses stay base64 strings. JSON stays the default and websocket
+/// connections aren't affected.
+#[derive(Debug, Clone, Copy)]
+pub struct CborCodecLayer;
+
+impl CborCodecLayer {
+    /// `None` if the CBOR codec is disabled
+    pub fn from_config(config: &RpcConfig) -> Option<Self> {
+        config.cbor.then_some(Self)
+    }
+}
+
+impl<S> Layer<S> for CborCodecLayer {
+    type Service = CborCodec<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CborCodec { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CborCodec<S> {
+    inner: S,
+}
+
+fn has_cbor(value: Option<&HeaderValue>) -> bool {
+    value
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains(CBOR_CONTENT_TYPE))
+}
+
+impl<S> Service<HttpRequest<HttpBody>> for CborCodec<S>
+where
+    S: Service<HttpRequest<HttpBody>, Response = HttpResponse> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<HttpBody>) -> Self::Future {
+        let cbor_request = has_cbor(request.headers().get(header::CONTENT_TYPE));
+        let cbor_response = has_cbor(request.headers().get(header::ACCEPT));
+        if !cbor_request && !cbor_response {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // The ready service is taken, the clone is left for the next call
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let body = if cbor_request {
+                let json = match read_body(&parts.headers, body, MAX_CBOR_BODY_SIZE).await {
+                    Ok((bytes, _)) => cbor_to_json_bytes(&bytes),
+                    Err(err) => Err(CodecError::Decode(err.to_string())),
+                };
+                match json {
+                    Ok(json) => {
+                        parts.headers.insert(
+                            header::CONTENT_TYPE,
+                            HeaderValue::from_static("application/json"),
+                        );
+                        parts.headers.remove(header::CONTENT_LENGTH);
+                        HttpBody::from(json)
+                    }
+                    Err(err) => {
+                        debug!(%err, "rejecting CBOR request");
+                        return Ok(parse_error(&err, cbor_response));
+                    }
+                }
+            } else {
+                body
+            };
+
+            let response = inner.call(HttpRequest::from_parts(parts, body)).await?;
+            if !cbor_response {
+                return Ok(response);
+            }
+            Ok(encode_response(response).await)
+        })
+    }
+}
+
+/// Re-encodes a JSON response as CBOR, other responses are passed through
+async fn encode_response(response: HttpResponse) -> HttpResponse {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let cbor = match read_body(&parts.headers, body, u32::MAX).await {
+        Ok((bytes, _)) => json_to_cbor_bytes(&bytes),
+        Err(err) => Err(CodecError::Encode(err.to_string())),
+    };
+    match cbor {
+        Ok(cbor) => {
+            parts.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(CBOR_CONTENT_TYPE),
+            );
+            parts.headers.remove(header::CONTENT_LENGTH);
+            HttpResponse::from_parts(parts, HttpBody::from(cbor))
+        }
+        Err(err) => {
+            debug!(%err, "failed to encode CBOR response");
+            HttpResponse::builder()
+                .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+                .body(HttpBody::empty())
+                .expect("valid response")
+        }
+    }
+}
+
+/// JSON-RPC parse error, in the encoding the client asked for
+fn parse_error(err: &CodecError, cbor: bool) -> HttpResponse {
+    let error = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {"code": -32700, "message": err.to_string()},
+    });
+    let (content_type, body) = match cbor
+        .then(|| json_to_cbor_bytes(error.to_string().as_bytes()).ok())
+        .flatten()
+    {
+        Some(body) => (CBOR_CONTENT_TYPE, body),
+        None => ("application/json", error.to_string().into_bytes()),
+    };
+    HttpResponse::builder()
+        .status(http::StatusCode::BAD_REQUEST)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(HttpBody::from(body))
+        .expect("valid response")
+}
+
+fn cbor_to_json_bytes(bytes: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let value: CborValue =
+        ciborium::from_reader(bytes).map_err(|err| CodecError::Decode(err.to_string()))?;
+    let json = serde_json::to_vec(&cbor_to_json(value)?)
+        .map_err(|err| CodecError::Decode(err.to_string()))?;
+    // Keys and small values may grow more than the byte strings
+    if json.len() > MAX_REQUEST_BODY_SIZE as usize {
+        return Err(CodecError::TooLarge);
+    }
+    Ok(json)
+}
+
+fn json_to_cbor_bytes(bytes: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let value: JsonValue =
+        serde_json::from_slice(bytes).map_err(|err| CodecError::Encode(err.to_string()))?;
+    let mut buf = Vec::with_capacity(bytes.len());
+    ciborium::into_writer(&value, &mut buf).map_err(|err| CodecError::Encode(err.to_string()))?;
+    Ok(buf)
+}
+
+/// Byte strings become base64 strings, which is how the RPC types encode binary fields in JSON
+pub fn cbor_to_json(value: CborValue) -> Result<JsonValue, CodecError> {
+    Ok(match value {
+        CborValue::Null => JsonValue::Null,
+        CborValue::Bool(b) => JsonValue::Bool(b),
+        CborValue::Integer(i) => {
+            let i = i128::from(i);
+            if let Ok(u) = u64::try_from(i) {
+                u.into()
+            } else if let Ok(i) = i64::try_from(i) {
+                i.into()
+            } else {
+                return Err(CodecError::Unsupported("integer out of range"));
+            }
+        }
+        CborValue::Float(f) => {
+            serde_json::Number::from_f64(f)
+                .map(JsonValue::Number)
+                .ok_or(CodecError::Unsupported("non-finite float"))?
+        }
+        CborValue::Text(s) => JsonValue::String(s),
+        CborValue::Bytes(b) => JsonValue::String(general_purpose::STANDARD.encode(b)),
+        CborValue::Array(values) => {
+            JsonValue::Array(
+                values
+                    .into_iter()
+                    .map(cbor_to_json)
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        CborValue::Map(entries) => {
+            JsonValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let CborValue::Text(key) = key else {
+                            return Err(CodecError::Unsupported("non-string map key"));
+                        };
+                        Ok((key, cbor_to_json(value)?))
+                    })
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        CborValue::Tag(_, value) => cbor_to_json(*value)?,
+        _ => return Err(CodecError::Unsupported("unknown CBOR value")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::HeaderMap;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// Proof sized payload
+    const PROOF_SIZE: usize = 1024 * 1024;
+
+    fn proof() -> Vec<u8> {
+        (0..PROOF_SIZE).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    /// Submission carrying the proof as base64, the JSON way
+    fn json_request(proof: &[u8]) -> JsonValue {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "submitProof",
+            "params": [{"proof": general_purpose::STANDARD.encode(proof), "requestId": "0x2a"}],
+        })
+    }
+
+    /// Same submission with the raw proof, the CBOR way
+    fn cbor_request(proof: &[u8]) -> CborValue {
+        let text = |s: &str| CborValue::Text(s.to_string());
+        CborValue::Map(vec![
+            (text("jsonrpc"), text("2.0")),
+            (text("id"), CborValue::Integer(1.into())),
+            (text("method"), text("submitProof")),
+            (
+                text("params"),
+                CborValue::Array(vec![CborValue::Map(vec![
+                    (text("proof"), CborValue::Bytes(proof.to_vec())),
+                    (text("requestId"), text("0x2a")),
+                ])]),
+            ),
+        ])
+    }
+
+    /// Echoes the request body back as a JSON response, and reports it
+    #[derive(Clone)]
+    struct Echo(mpsc::UnboundedSender<Vec<u8>>);
+
+    impl Service<HttpRequest<HttpBody>> for Echo {
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, Infallible>> + Send>>;
+        type Response = HttpResponse;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: HttpRequest<HttpBody>) -> Self::Future {
+            let tx = self.0.clone();
+            Box::pin(async move {
+                let (body, _) = read_body(&HeaderMap::new(), request.into_body(), u32::MAX)
+                    .await
+                    .unwrap();
+                tx.send(body.clone()).unwrap();
+                let mut response = HttpResponse::new(HttpBody::from(body));
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+                Ok(response)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn cbor_request_size() {
+        let proof = proof();
+        let json = serde_json::to_vec(&json_request(&proof)).unwrap();
+        let mut cbor = vec![];
+        ciborium::into_writer(&cbor_request(&proof), &mut cbor).unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut service = CborCodecLayer.layer(Echo(tx));
+
+        let response = service
+            .call(
+                HttpRequest::builder()
+                    .method("POST")
+                    .header(header::CONTENT_TYPE, CBOR_CONTENT_TYPE)
+                    .header(header::ACCEPT, CBOR_CONTENT_TYPE)
+                    .body(HttpBody::from(cbor.clone()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // The methods get the same JSON as from a JSON client
+        let forwarded: JsonValue = serde_json::from_slice(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(forwarded, json_request(&proof));
+
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            CBOR_CONTENT_TYPE
+        );
+        let (body, _) = read_body(&HeaderMap::new(), response.into_body(), u32::MAX)
+            .await
+            .unwrap();
+        let decoded: JsonValue = ciborium::from_reader(body.as_slice()).unwrap();
+        assert_eq!(decoded, forwarded);
+
+        // Base64 adds a third
+        assert!(cbor.len() * 4 < json.len() * 3 + 1024);
+    }
+
+    #[tokio::test]
+    async fn json_passthrough() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut service = CborCodecLayer.layer(Echo(tx));
+        let json = br#"{"jsonrpc":"2.0","id":1,"method":"networkStats","params":[]}"#.to_vec();
+
+        let response = service
+            .call(
+                HttpRequest::builder()
+                    .method("POST")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(HttpBody::from(json.clone()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.unwrap(), json);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_cbor() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let response = CborCodecLayer
+            .layer(Echo(tx))
+            .call(
+                HttpRequest::builder()
+                    .method("POST")
+                    .header(header::CONTENT_TYPE, CBOR_CONTENT_TYPE)
+                    .body(HttpBody::from(vec![0xff, 0x00]))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn oversized_cbor() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut cbor = vec![];
+        ciborium::into_writer(
+            &cbor_request(&vec![0; MAX_CBOR_BODY_SIZE as usize]),
+            &mut cbor,
+        )
+        .unwrap();
+
+        let response = CborCodecLayer
+            .layer(Echo(tx))
+            .call(
+                HttpRequest::builder()
+                    .method("POST")
+                    .header(header::CONTENT_TYPE, CBOR_CONTENT_TYPE)
+                    .body(HttpBody::from(cbor))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use jsonrpsee::server::HttpBody;
+use tower::Layer;
+use tower_http::{
+    compression::{Compression, CompressionBody, CompressionLayer as ResponseCompressionLayer},
+    decompression::{DecompressionBody, RequestDecompression, RequestDecompressionLayer},
+    map_request_body::MapRequestBody,
+    map_response_body::MapResponseBody,
+};
+
+use crate::RpcConfig;
+
+type RequestBodyFn = fn(DecompressionBody<HttpBody>) -> HttpBody;
+type ResponseBodyFn = fn(CompressionBody<HttpBody>) -> HttpBody;
+
+/// Compresses the HTTP responses with gzip or zstd, and decompresses the requests, as negotiated by the
+/// `Accept-Encoding` and `Content-Encoding` headers. Clients which don't ask for it get plain responses. Websocket
+/// connections aren't affected.
+///
+/// The verbose JSON of signed proof requests and proofs shrinks several times, as the `compression_ratio` test checks.
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionLayer;
+
+impl CompressionLayer {
+    /// `None` if compression is disabled
+    pub fn from_config(config: &RpcConfig) -> Option<Self> {
+        config.compression.then_some(Self)
+    }
+}
+
+impl<S> Layer<S> for CompressionLayer {
+    type Service = MapResponseBody<
+        Compression<RequestDecompression<MapRequestBody<S, RequestBodyFn>>>,
+        ResponseBodyFn,
+    >;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        // The rest of the middleware stack works with jsonrpsee bodies
+        let inner = MapRequestBody::new(inner, HttpBody::new as RequestBodyFn);
+        let inner = RequestDecompressionLayer::new()
+            .gzip(true)
+            .zstd(true)
+            .layer(inner);
+        let inner = ResponseCompressionLayer::new()
+            .gzip(true)
+            .zstd(true)
+            .layer(inner);
+        MapResponseBody::new(inner, HttpBody::new as ResponseBodyFn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use fermah_common::{
+        executable::{InMount, Source},
+        fixtures,
+        resources::{ImageHash, RemoteResource},
+    };
+    use http::{header, HeaderMap};
+    use jsonrpsee::{
+        core::http_helpers::read_body,
+        server::{HttpRequest, HttpResponse},
+    };
+    use tower::Service;
+
+    use super::*;
+
+    /// Body carrying a signed proof request with a big env map and many mounts
+    fn payload() -> String {
+        let mut proof_request = fixtures::proof_request();
+        proof_request.prover.env_vars = Some(
+            (0..200)
+                .map(|i| (format!("ENV_VAR_{i}"), format!("/data/inputs/{i}.bin")))
+                .collect(),
+        );
+        proof_request.prover.in_mounts = (0..50)
+            .map(|i| {
+                InMount {
+                    source: Source::File(RemoteResource {
+                        url: format!("https://inputs.example.com/requests/{i}.bin")
+                            .parse()
+                            .unwrap(),
+                        hash: ImageHash::from([i as u8; 32]),
+                        delta: None,
+                    }),
+                    target: format!("/data/inputs/{i}.bin").into(),
+                    temporary: false,
+                }
+            })
+            .collect();
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "params": [fixtures::signed(proof_request, &fixtures::requester())],
+        })
+        .to_string()
+    }
+
+    #[derive(Clone)]
+    struct Payload;
+
+    impl Service<HttpRequest<HttpBody>> for Payload {
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, Infallible>> + Send>>;
+        type Response = HttpResponse;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: HttpRequest<HttpBody>) -> Self::Future {
+            Box::pin(async {
+                let mut response = HttpResponse::new(HttpBody::from(payload()));
+                response
+                    .headers_mut()
+                    .insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+                Ok(response)
+            })
+        }
+    }
+
+    async fn response_size(accept_encoding: Option<&str>) -> (usize, Option<String>) {
+        let mut request = HttpRequest::builder().method("POST");
+        if let Some(encoding) = accept_encoding {
+            request = request.header(header::ACCEPT_ENCODING, encoding);
+        }
+        let request = request.body(HttpBody::empty()).unwrap();
+
+        let response = CompressionLayer.layer(Payload).call(request).await.unwrap();
+        let encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|e| e.to_str().unwrap().to_string());
+        let (body, _) = read_body(&HeaderMap::new(), response.into_body(), u32::MAX)
+            .await
+            .unwrap();
+        (body.len(), encoding)
+    }
+
+    #[tokio::test]
+    async fn compression_ratio() {
+        let (plain, encoding) = response_size(None).await;
+        assert_eq!(plain, payload().len());
+        assert_eq!(encoding, None);
+
+        for algorithm in ["gzip", "zstd"] {
+            let (compressed, encoding) = response_size(Some(algorithm)).await;
+            assert_eq!(encoding.as_deref(), Some(algorithm));
+            assert!(compressed * 4 < plain);
+        }
+    }
+}
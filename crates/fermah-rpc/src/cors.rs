@@ -0,0 +1,233 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http::{header, HeaderValue, Method, StatusCode};
+use jsonrpsee::server::{HttpBody, HttpRequest, HttpResponse};
+use tower::{Layer, Service};
+
+use crate::RpcConfig;
+
+/// How long browsers may cache a preflight response, in seconds
+const PREFLIGHT_MAX_AGE: &str = "600";
+
+#[derive(Debug)]
+struct CorsPolicy {
+    /// `None` allows any origin
+    origins: Option<Vec<HeaderValue>>,
+    methods: HeaderValue,
+    headers: HeaderValue,
+}
+
+impl CorsPolicy {
+    /// Value of `Access-Control-Allow-Origin` for the request origin, `None` if it isn't allowed
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        match &self.origins {
+            None => Some(HeaderValue::from_static("*")),
+            Some(origins) => origins.contains(origin).then(|| origin.clone()),
+        }
+    }
+}
+
+/// Adds the CORS headers to the responses and answers the preflight requests of the allowed origins
+#[derive(Debug, Clone)]
+pub struct CorsLayer {
+    policy: Arc<CorsPolicy>,
+}
+
+impl CorsLayer {
+    /// `None` if CORS isn't configured. Origins, methods and headers which aren't valid header values are skipped.
+    pub fn from_config(config: &RpcConfig) -> Option<Self> {
+        if config.cors_origins.is_empty() {
+            return None;
+        }
+
+        let origins = (!config.cors_origins.iter().any(|origin| origin == "*")).then(|| {
+            config
+                .cors_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok())
+                .collect()
+        });
+        let join = |values: &[String]| {
+            HeaderValue::from_str(&values.join(", ")).unwrap_or(HeaderValue::from_static(""))
+        };
+
+        Some(Self {
+            policy: Arc::new(CorsPolicy {
+                origins,
+                methods: join(&config.cors_methods),
+                headers: join(&config.cors_headers),
+            }),
+        })
+    }
+}
+
+impl<S> Layer<S> for CorsLayer {
+    type Service = Cors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Cors {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cors<S> {
+    inner: S,
+    policy: Arc<CorsPolicy>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for Cors<S>
+where
+    S: Service<HttpRequest<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<B>) -> Self::Future {
+        // Not a browser request, or not from an allowed origin, the browser blocks the latter itself
+        let Some(allow_origin) = request
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|origin| self.policy.allow_origin(origin))
+        else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let is_preflight = request.method() == Method::OPTIONS
+            && request
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+        if is_preflight {
+            let response = HttpResponse::builder()
+                .status(StatusCode::NO_CONTENT)
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+                .header(header::ACCESS_CONTROL_ALLOW_METHODS, &self.policy.methods)
+                .header(header::ACCESS_CONTROL_ALLOW_HEADERS, &self.policy.headers)
+                .header(header::ACCESS_CONTROL_MAX_AGE, PREFLIGHT_MAX_AGE)
+                .header(header::VARY, "origin")
+                .body(HttpBody::empty())
+                .expect("valid response");
+            return Box::pin(async { Ok(response) });
+        }
+
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let mut response = response.await?;
+            let headers = response.headers_mut();
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            headers.append(header::VARY, HeaderValue::from_static("origin"));
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Ok200;
+
+    impl Service<HttpRequest<HttpBody>> for Ok200 {
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, Infallible>> + Send>>;
+        type Response = HttpResponse;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: HttpRequest<HttpBody>) -> Self::Future {
+            Box::pin(async { Ok(HttpResponse::new(HttpBody::empty())) })
+        }
+    }
+
+    fn service(origins: &[&str]) -> Cors<Ok200> {
+        let config = RpcConfig {
+            cors_origins: origins.iter().map(|o| o.to_string()).collect(),
+            ..RpcConfig::new(Default::default())
+        };
+        CorsLayer::from_config(&config).unwrap().layer(Ok200)
+    }
+
+    fn request(method: Method, origin: &str) -> HttpRequest<HttpBody> {
+        let mut request = HttpRequest::builder()
+            .method(method.clone())
+            .header(header::ORIGIN, origin);
+        if method == Method::OPTIONS {
+            request = request.header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST");
+        }
+        request.body(HttpBody::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_disabled_without_origins() {
+        assert!(CorsLayer::from_config(&RpcConfig::new(Default::default())).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preflight() {
+        let response = service(&["https://dashboard.fermah.xyz/"])
+            .call(request(Method::OPTIONS, "https://dashboard.fermah.xyz"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://dashboard.fermah.xyz"
+        );
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+            "POST, OPTIONS"
+        );
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "Content-Type, Authorization"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allowed_origins() {
+        let response = service(&["https://dashboard.fermah.xyz"])
+            .call(request(Method::POST, "https://dashboard.fermah.xyz"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://dashboard.fermah.xyz"
+        );
+
+        // Disallowed origins are passed through without the headers
+        let response = service(&["https://dashboard.fermah.xyz"])
+            .call(request(Method::OPTIONS, "https://evil.example"))
+            .await
+            .unwrap();
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let response = service(&["*"])
+            .call(request(Method::POST, "https://evil.example"))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+}
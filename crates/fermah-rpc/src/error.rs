@@ -0,0 +1,176 @@
+use std::fmt::{self, Display};
+
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Stable codes of the errors returned by the server. Clients tell the errors apart by their code, the messages are
+/// meant for humans and may change. Codes are never reused for another meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum RpcErrorCode {
+    /// The request can't be handled now, it can be retried later
+    ServerBusy = -32009,
+    /// The requester can't afford the quoted price of a submission, or the amount of a withdrawal exceeds the
+    /// spendable balance. The data of a refused submission is an [`InsufficientFunds`](crate::InsufficientFunds)
+    InsufficientFunds = -32010,
+    /// A different proof request with the same id was already submitted
+    ProofRequestConflict = -32011,
+    /// The quoted price doesn't fit in the requester's budget, the data is a
+    /// [`BudgetExceeded`](fermah_common::types::budget::BudgetExceeded)
+    BudgetExceeded = -32012,
+    /// The signature doesn't verify, or the signed hash isn't the hash of the payload
+    InvalidSignature = -32013,
+    /// The signer isn't allowed to send the request, e.g. it isn't the requester, the operator or an admin
+    Unauthorized = -32014,
+    /// The request refers to a proof request, dispute or operator the server doesn't know
+    NotFound = -32015,
+    /// The request isn't allowed in the current state, e.g. cancelling a proven request or publishing a version
+    /// again with another image
+    InvalidState = -32016,
+    /// The cached vault deposit of the requester is below the minimum the server accepts requests from, the data is
+    /// a [`BelowMinimumDeposit`](crate::BelowMinimumDeposit)
+    BelowMinimumDeposit = -32017,
+    /// The method isn't served, e.g. exports by a server without a signing key
+    MethodNotFound = -32601,
+    /// The parameters are invalid, the request shouldn't be retried as is
+    InvalidParams = -32602,
+    /// The server failed to handle the request, retrying may succeed
+    Internal = -32603,
+}
+
+impl RpcErrorCode {
+    pub const ALL: [Self; 12] = [
+        Self::ServerBusy,
+        Self::InsufficientFunds,
+        Self::ProofRequestConflict,
+        Self::BudgetExceeded,
+        Self::InvalidSignature,
+        Self::Unauthorized,
+        Self::NotFound,
+        Self::InvalidState,
+        Self::BelowMinimumDeposit,
+        Self::MethodNotFound,
+        Self::InvalidParams,
+        Self::Internal,
+    ];
+
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// `None` for the codes outside the catalog, e.g. returned by the transport
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|known| known.code() == code)
+    }
+
+    pub fn error(self, message: impl Into<String>) -> ErrorObjectOwned {
+        ErrorObject::owned(self.code(), message, None::<()>)
+    }
+
+    pub fn error_with_data<D: Serialize>(
+        self,
+        message: impl Into<String>,
+        data: D,
+    ) -> ErrorObjectOwned {
+        ErrorObject::owned(self.code(), message, Some(data))
+    }
+}
+
+impl Display for RpcErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::ServerBusy => "server busy",
+            Self::InsufficientFunds => "insufficient funds",
+            Self::ProofRequestConflict => "proof request conflict",
+            Self::BudgetExceeded => "budget exceeded",
+            Self::InvalidSignature => "invalid signature",
+            Self::Unauthorized => "unauthorized",
+            Self::NotFound => "not found",
+            Self::InvalidState => "invalid state",
+            Self::BelowMinimumDeposit => "below minimum deposit",
+            Self::MethodNotFound => "method not found",
+            Self::InvalidParams => "invalid params",
+            Self::Internal => "internal error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Error returned by the server, as received by a client
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message} ({code})")]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+    /// Raw JSON of the data attached to the error
+    pub data: Option<String>,
+}
+
+impl RpcError {
+    /// Code of the error in the catalog, `None` for the codes outside of it
+    pub fn kind(&self) -> Option<RpcErrorCode> {
+        RpcErrorCode::from_code(self.code)
+    }
+
+    /// Data attached to the error, `None` if there's none or it isn't a `D`
+    pub fn data<D: DeserializeOwned>(&self) -> Option<D> {
+        self.data
+            .as_deref()
+            .and_then(|data| serde_json::from_str(data).ok())
+    }
+}
+
+impl From<&ErrorObjectOwned> for RpcError {
+    fn from(err: &ErrorObjectOwned) -> Self {
+        Self {
+            code: err.code(),
+            message: err.message().to_string(),
+            data: err.data().map(|data| data.get().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::{Address, U256};
+
+    use super::*;
+    use crate::{BelowMinimumDeposit, InsufficientFunds};
+
+    #[test]
+    fn test_error_codes() {
+        for code in RpcErrorCode::ALL {
+            assert_eq!(RpcErrorCode::from_code(code.code()), Some(code));
+        }
+        assert_eq!(RpcErrorCode::from_code(-32700), None);
+
+        let insufficient = InsufficientFunds {
+            quoted: U256::from(100),
+            spendable: U256::from(40),
+            top_up: U256::from(60),
+            protocol_fee_bps: 0,
+            protocol_fee: U256::zero(),
+        };
+        let err = RpcError::from(
+            &RpcErrorCode::InsufficientFunds.error_with_data("insufficient funds", insufficient),
+        );
+        assert_eq!(err.kind(), Some(RpcErrorCode::InsufficientFunds));
+        assert_eq!(err.data(), Some(insufficient));
+        assert_eq!(err.to_string(), "insufficient funds (-32010)");
+
+        let below = BelowMinimumDeposit {
+            requester: Address::repeat_byte(1),
+            deposit: U256::zero(),
+            min_deposit: U256::from(500),
+            deposit_address: Some(Address::repeat_byte(2)),
+        };
+        let err = RpcError::from(
+            &RpcErrorCode::BelowMinimumDeposit.error_with_data(below.to_string(), below),
+        );
+        assert_eq!(err.kind(), Some(RpcErrorCode::BelowMinimumDeposit));
+        assert_eq!(err.data(), Some(below));
+        assert!(err
+            .message
+            .contains("deposit at least 500 more to the vault at 0x0202"));
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use fermah_common::supervisor::{RestartPolicy, Supervisor};
+use fermah_database::{mm_request_events::RequestChange, Database};
+use tokio::{sync::broadcast, task::JoinSet};
+use tracing::{debug, info, warn};
+
+#[derive(Debug, Clone, Copy)]
+pub struct EventBusConfig {
+    /// Changes a subscriber can lag behind before it misses some
+    pub capacity: usize,
+    /// How often the notifications the listening connection received are read, no query is sent
+    pub poll_interval: Duration,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            poll_interval: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Broadcasts the status and payment changes of the requests, as notified by the database when they're committed,
+/// so components react to them instead of polling the requests. Subscribers which lag behind by more than the
+/// capacity miss changes, they have to catch up from the database then.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    db: Database,
+    tx: broadcast::Sender<RequestChange>,
+    config: EventBusConfig,
+}
+
+impl EventBus {
+    pub fn new(db: Database, config: EventBusConfig) -> Self {
+        let (tx, _) = broadcast::channel(config.capacity);
+        Self { db, tx, config }
+    }
+
+    /// Changes committed from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<RequestChange> {
+        self.tx.subscribe()
+    }
+
+    /// Listens to the database until shutdown. The listening connection is opened again when the task restarts after
+    /// losing it, changes committed meanwhile are missed.
+    pub fn start(&self, supervisor: &Supervisor, tasks: &mut JoinSet<Result<()>>) {
+        let bus = self.clone();
+        supervisor.spawn(
+            tasks,
+            "event_bus",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let bus = bus.clone();
+                async move {
+                    let db = bus.db.clone();
+                    let mut listener =
+                        tokio::task::spawn_blocking(move || db.listen_request_events()).await??;
+                    info!("Listening to request changes");
+
+                    let mut interval = tokio::time::interval(bus.config.poll_interval);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Event bus stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => {
+                                // Only reads what the connection already received, it doesn't block
+                                for change in listener.try_recv()? {
+                                    debug!(
+                                        seq = change.seq,
+                                        proof_request_id = ?change.proof_request_id,
+                                        value = %change.value,
+                                        "request change"
+                                    );
+                                    // Nobody may be subscribed yet
+                                    let _ = bus.tx.send(change);
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+}
+
+/// Waits for the next change, `None` once the bus is gone. Lagging subscribers skip the changes they missed.
+pub async fn next_change(rx: &mut broadcast::Receiver<RequestChange>) -> Option<RequestChange> {
+    loop {
+        match rx.recv().await {
+            Ok(change) => return Some(change),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!(missed, "request change subscriber lagged behind");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use fermah_common::{
+    http::url_policy::UrlPolicy,
+    resources::{DownloadError, RemoteResource},
+    supervisor::{RestartPolicy, Supervisor},
+};
+use fermah_database::{mm_image_validations::ClaimedImageValidation, Database};
+use futures_util::future::join_all;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImageValidatorConfig {
+    /// Requests validated at once
+    pub batch_size: i64,
+    /// Time a validation has to finish before it's claimed again
+    pub lease: Duration,
+    pub poll_interval: Duration,
+    /// Attempts before an image which can't be downloaded is considered unavailable
+    pub max_attempts: u32,
+    /// Operators hinted to prefetch the images of an accepted request
+    pub prefetch_operators: usize,
+    /// Downloaded images kept on disk, the least recently validated ones are deleted past it
+    pub max_cached_images: usize,
+}
+
+impl Default for ImageValidatorConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 16,
+            lease: Duration::from_secs(300),
+            poll_interval: Duration::from_secs(1),
+            max_attempts: 3,
+            prefetch_operators: 3,
+            max_cached_images: 64,
+        }
+    }
+}
+
+/// Downloads the remote prover and verifier images of the created requests off the RPC path, and accepts the
+/// requests whose images match their hashes or rejects them otherwise. Operators are hinted to prefetch the images of
+/// the accepted requests.
+#[derive(Debug, Clone)]
+pub struct ImageValidator {
+    db: Database,
+    config: ImageValidatorConfig,
+    /// Where the images can be downloaded from, the policy the URLs of the requests were checked with
+    url_policy: UrlPolicy,
+    cache: Arc<Mutex<ImageCache>>,
+}
+
+impl ImageValidator {
+    pub fn new(db: Database, config: ImageValidatorConfig, url_policy: UrlPolicy) -> Self {
+        Self {
+            db,
+            config,
+            url_policy,
+            cache: Arc::new(Mutex::new(ImageCache::new(config.max_cached_images))),
+        }
+    }
+
+    /// Validates the claimed requests until shutdown
+    pub fn start(&self, supervisor: &Supervisor, tasks: &mut JoinSet<Result<()>>) {
+        let validator = self.clone();
+        supervisor.spawn(
+            tasks,
+            "image_validation",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let validator = validator.clone();
+                async move {
+                    let mut interval = tokio::time::interval(validator.config.poll_interval);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Image validator stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => validator.validate_batch().await,
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    async fn validate_batch(&self) {
+        let db = self.db.clone();
+        let (limit, lease) = (self.config.batch_size, self.config.lease);
+        let validations = match tokio::task::spawn_blocking(move || {
+            db.claim_image_validations(limit, chrono::Duration::from_std(lease).unwrap_or_default())
+        })
+        .await
+        {
+            Ok(Ok(validations)) => validations,
+            Ok(Err(err)) => {
+                error!(?err, "failed to claim image validations");
+                return;
+            }
+            Err(err) => {
+                error!(?err, "image validation claim task panicked");
+                return;
+            }
+        };
+
+        join_all(validations.into_iter().map(|v| self.validate(v))).await;
+    }
+
+    async fn validate(&self, validation: ClaimedImageValidation) {
+        let id = validation.proof_request.id();
+        debug!(
+            ?id,
+            attempts = validation.attempts,
+            "validating proof request images"
+        );
+
+        let mut failure = None;
+        for image in validation.proof_request.payload.remote_images() {
+            match image.download(&self.url_policy, None).await {
+                Ok(location) => {
+                    debug!(?id, url=%image.url, ?location, "image hashes match");
+                    let evicted = self.cache.lock().unwrap().touch(location.local());
+                    for path in evicted {
+                        if let Err(err) = tokio::fs::remove_file(&path).await {
+                            warn!(?err, ?path, "failed to delete the evicted image");
+                        }
+                    }
+                    let db = self.db.clone();
+                    self.blocking(move || db.record_image_verified(&id)).await;
+                }
+                Err(err) => {
+                    failure = Some(err);
+                    break;
+                }
+            }
+        }
+
+        let db = self.db.clone();
+        match failure {
+            None => {
+                let images: Vec<RemoteResource> = validation
+                    .proof_request
+                    .payload
+                    .remote_images()
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let operators = self.config.prefetch_operators;
+                self.blocking(move || {
+                    db.finish_image_validation(&id, Ok(()))?;
+                    // The images are known to be fine, the likely provers can start downloading them
+                    db.hint_prefetch(&id, &images.iter().collect::<Vec<_>>(), operators)?;
+                    Ok(())
+                })
+                .await
+            }
+            // Retrying doesn't help once the image is missing, isn't the one the request was signed with, or is hosted
+            // where the policy doesn't allow fetching from
+            Some(
+                err @ (DownloadError::NotFound(_)
+                | DownloadError::HashMismatch { .. }
+                | DownloadError::Refused(_)),
+            ) => {
+                warn!(?id, %err, "proof request image unavailable");
+                let reason = err.to_string();
+                self.blocking(move || db.finish_image_validation(&id, Err(reason)))
+                    .await
+            }
+            Some(err) if validation.attempts >= self.config.max_attempts => {
+                warn!(?id, %err, attempts = validation.attempts, "proof request image unavailable");
+                let reason = err.to_string();
+                self.blocking(move || db.finish_image_validation(&id, Err(reason)))
+                    .await
+            }
+            Some(err) => {
+                debug!(?id, %err, attempts = validation.attempts, "image download failed, retrying");
+                let reason = err.to_string();
+                self.blocking(move || db.retry_image_validation(&id, &reason))
+                    .await
+            }
+        }
+    }
+
+    async fn blocking<F>(&self, f: F)
+    where
+        F: FnOnce() -> Result<()> + Send + 'static,
+    {
+        match tokio::task::spawn_blocking(f).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => error!(?err, "failed to update image validation"),
+            Err(err) => error!(?err, "image validation task panicked"),
+        }
+    }
+}
+
+/// Images downloaded by the validator, the least recently validated are evicted past the capacity
+#[derive(Debug)]
+struct ImageCache {
+    capacity: usize,
+    images: VecDeque<PathBuf>,
+}
+
+impl ImageCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            images: VecDeque::new(),
+        }
+    }
+
+    /// Marks the image as the most recently validated, returns the images evicted to stay within the capacity
+    fn touch(&mut self, image: PathBuf) -> Vec<PathBuf> {
+        self.images.retain(|cached| *cached != image);
+        self.images.push_back(image);
+        let excess = self.images.len().saturating_sub(self.capacity);
+        self.images.drain(..excess).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_cache_evicts_least_recent() {
+        let mut cache = ImageCache::new(2);
+        assert!(cache.touch("a".into()).is_empty());
+        assert!(cache.touch("b".into()).is_empty());
+        // Validated again, "b" is now the least recent
+        assert!(cache.touch("a".into()).is_empty());
+        assert_eq!(cache.touch("c".into()), vec![PathBuf::from("b")]);
+        assert_eq!(cache.touch("d".into()), vec![PathBuf::from("a")]);
+
+        // Nothing is kept without a capacity
+        let mut cache = ImageCache::new(0);
+        assert_eq!(cache.touch("a".into()), vec![PathBuf::from("a")]);
+    }
+}
@@ -1,11 +1,59 @@
+use std::{fmt::Display, path::PathBuf};
+
+use chrono::{DateTime, Utc};
 use clap::{self, Parser};
-use ethers::types::Address;
+use ethers::types::{Address, U256};
 use fermah_common::{
     crypto::signer::{ecdsa::EcdsaSigner, SignedData},
     hash::blake3::Blake3Hasher,
-    proof::{request::ProofRequest, status::ProofStatus},
+    image_registry::{ImageRecord, PublishedImage},
+    operator::{
+        assignment::{Assignment, OperatorAssignments, ProofDelivery},
+        deregistration::{Deregistration, OperatorExit},
+        liveness::OperatorHeartbeat,
+        readiness::{OperatorReadiness, ReadinessReport},
+        registration::OperatorRegistration,
+        restriction::{RestrictedOperator, RestrictionRemoval, RestrictionRequest},
+    },
+    proof::{
+        cancellation::{CancelRequest, Cancellation},
+        container_policy::ContainerPolicyDecision,
+        dispute::{Dispute, DisputeInfo, DisputeStatus, DisputeVerdict, DisputeVerification},
+        export::RequestExport,
+        image_validation::ImageValidation,
+        job_array::{JobArrayId, JobArrayStatus},
+        prefetch::PrefetchHint,
+        receipt::ProofReceipt,
+        redundancy::RedundancyStatus,
+        request::{ProofRequest, ProofRequestId, VerificationVerdict},
+        retention::ProofRetrieval,
+        status::{ProofStatus, StatusReport},
+        submission::ProofSubmission,
+    },
+    resource::{
+        class::ResourceClasses,
+        tee::{OperatorAttestation, TeeAttestation},
+    },
     serialization::hash::SerializableHash,
-    types::network::Connection,
+    supervisor::HealthReport,
+    types::{
+        balance::RequesterBalance,
+        budget::{BudgetStatus, BudgetUpdate},
+        delegation::{Delegation, DelegationRevocation, DelegationStatus},
+        fee::FeeQuote,
+        network::Connection,
+        payment::{PaymentEventPage, PaymentEventQuery},
+        reconciliation::VaultReconciliation,
+        stats::{LifecycleStats, NetworkStats, OperatorCounts, OperatorStats},
+        webhook::{WebhookId, WebhookInfo, WebhookRegistration, WebhookRemoval},
+        withdrawal::{
+            Withdrawal,
+            WithdrawalApproval,
+            WithdrawalCancellation,
+            WithdrawalId,
+            WithdrawalRequest,
+        },
+    },
 };
 use jsonrpsee::{
     core::{RpcResult, Serialize},
@@ -13,36 +61,406 @@ use jsonrpsee::{
 };
 use serde::Deserialize;
 
+#[cfg(feature = "server")]
+pub mod codec;
+#[cfg(feature = "server")]
+pub mod compression;
+#[cfg(feature = "server")]
+pub mod cors;
+pub mod error;
+#[cfg(feature = "db")]
+pub mod event_bus;
+#[cfg(feature = "db")]
+pub mod image_validation;
 #[cfg(feature = "server")]
 pub mod metrics;
+#[cfg(feature = "db")]
+pub mod outbox;
+pub mod policy;
 #[cfg(feature = "client")]
 pub mod rpc_client;
 #[cfg(feature = "server")]
 pub mod rpc_server;
+#[cfg(feature = "server")]
+pub mod transport;
 pub mod upstream;
+#[cfg(feature = "server")]
+pub mod webhooks;
 
-#[derive(Serialize, Deserialize, Parser, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Parser, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcConfig {
     /// Connection settings for RPC
     #[arg(long, value_parser = Connection::try_from_str, default_value = "127.0.0.1:8080")]
     pub connection: Connection,
+
+    /// PEM certificate chain the server terminates TLS with, the server listens in plaintext if not set
+    #[arg(long, requires = "tls_key")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key of the TLS certificate
+    #[arg(long, requires = "tls_cert")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM CA certificates the client certificates must be issued by, enables mutual TLS
+    #[arg(long, requires = "tls_cert")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_ca: Option<PathBuf>,
+
+    /// Token the clients must send as `Authorization: Bearer <token>`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+
+    /// Origins browsers may call the server from, `*` allows any. CORS is disabled if empty
+    #[arg(long = "cors-origin")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cors_origins: Vec<String>,
+
+    /// Methods allowed in the CORS preflight
+    #[arg(long = "cors-method", default_values_t = default_cors_methods())]
+    #[serde(default = "default_cors_methods")]
+    pub cors_methods: Vec<String>,
+
+    /// Request headers allowed in the CORS preflight
+    #[arg(long = "cors-header", default_values_t = default_cors_headers())]
+    #[serde(default = "default_cors_headers")]
+    pub cors_headers: Vec<String>,
+
+    /// Compresses the HTTP responses with gzip or zstd for the clients asking for it, and accepts compressed requests
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    #[serde(default = "default_compression")]
+    pub compression: bool,
+
+    /// Accepts CBOR requests and sends CBOR responses to the HTTP clients asking for it, JSON stays the default
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    #[serde(default = "default_cbor")]
+    pub cbor: bool,
+}
+
+fn default_compression() -> bool {
+    true
+}
+
+fn default_cbor() -> bool {
+    true
+}
+
+fn default_cors_methods() -> Vec<String> {
+    vec!["POST".to_string(), "OPTIONS".to_string()]
+}
+
+fn default_cors_headers() -> Vec<String> {
+    vec!["Content-Type".to_string(), "Authorization".to_string()]
+}
+
+impl RpcConfig {
+    /// Plaintext connection without transport auth
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            tls_cert: None,
+            tls_key: None,
+            tls_client_ca: None,
+            auth_token: None,
+            cors_origins: vec![],
+            cors_methods: default_cors_methods(),
+            cors_headers: default_cors_headers(),
+            compression: default_compression(),
+            cbor: default_cbor(),
+        }
+    }
+
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+}
+
+/// Maximal number of proven requests `getStats` aggregates
+pub const MAX_STATS_REQUESTS: i64 = 10_000;
+
+/// Days `networkStats` reports the proven requests of
+pub const NETWORK_STATS_DAYS: u32 = 30;
+
+/// Maximal number of proof requests in a job array
+pub const MAX_JOB_ARRAY_LEN: usize = 1000;
+
+/// Data attached to an [`InsufficientFunds`](error::RpcErrorCode::InsufficientFunds) error of a submission
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InsufficientFunds {
+    /// Price quoted for the proof request
+    pub quoted: U256,
+    /// Deposit left after reservations of the requester
+    pub spendable: U256,
+    /// Minimal amount to deposit to the vault for the request to be admitted
+    pub top_up: U256,
+    /// Protocol commission included in the quoted price, in basis points
+    #[serde(default)]
+    pub protocol_fee_bps: u16,
+    /// Part of the quoted price going to the protocol treasury
+    #[serde(default)]
+    pub protocol_fee: U256,
+}
+
+/// Data attached to a [`BelowMinimumDeposit`](error::RpcErrorCode::BelowMinimumDeposit) error of a submission
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BelowMinimumDeposit {
+    /// Address which has to deposit, the requester of the refused requests
+    pub requester: Address,
+    /// Cached vault deposit of the requester
+    pub deposit: U256,
+    /// Deposit required before any request is accepted
+    pub min_deposit: U256,
+    /// Vault to deposit to, if the server is configured with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit_address: Option<Address>,
+}
+
+impl Display for BelowMinimumDeposit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "deposit of {:?} is {}, below the minimum of {}, deposit at least {} more",
+            self.requester,
+            self.deposit,
+            self.min_deposit,
+            self.min_deposit.saturating_sub(self.deposit)
+        )?;
+        if let Some(vault) = self.deposit_address {
+            write!(f, " to the vault at {vault:?}")?;
+        }
+        Ok(())
+    }
 }
 
 #[rpc(server, client)]
 pub(crate) trait RpcApi {
+    /// Price of the proof request with the protocol commission it includes and the operator payout
+    #[method(name = "quoteProofRequest")]
+    async fn quote_proof_request(&self, proof_request: ProofRequest) -> RpcResult<FeeQuote>;
+
+    /// Resource classes the requests may name instead of their requirement, with their prices
+    #[method(name = "resourceClasses")]
+    async fn resource_classes(&self) -> RpcResult<ResourceClasses>;
+
     #[method(name = "submitProofRequest")]
     async fn submit_proof_request(
         &self,
         proof_request: SignedData<ProofRequest, EcdsaSigner>,
     ) -> RpcResult<()>;
 
+    /// Same as `submitProofRequest`, and reports the id and status of the request. Resubmitting the identical request
+    /// succeeds with its current status.
+    #[method(name = "submitProofRequestWithStatus")]
+    async fn submit_proof_request_with_status(
+        &self,
+        proof_request: SignedData<ProofRequest, EcdsaSigner>,
+    ) -> RpcResult<ProofSubmission>;
+
+    /// Submits the proof requests, signed by the same requester, as one job array tracked together
+    #[method(name = "submitJobArray")]
+    async fn submit_job_array(
+        &self,
+        proof_requests: Vec<SignedData<ProofRequest, EcdsaSigner>>,
+    ) -> RpcResult<JobArrayId>;
+
+    /// Aggregated status of the job array members, `None` for an unknown array
+    #[method(name = "getJobArrayStatus")]
+    async fn get_job_array_status(
+        &self,
+        array_id: SignedData<SerializableHash<Blake3Hasher>, EcdsaSigner>,
+    ) -> RpcResult<Option<JobArrayStatus>>;
+
+    /// Status of the request, with why it was cancelled if it's cancelled
     #[method(name = "checkRequestStatus")]
     async fn check_request_status(
         &self,
-        request_status: SignedData<SerializableHash<Blake3Hasher>, EcdsaSigner>,
+        request_status: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<StatusReport>;
+
+    /// Progress of the image checks the request goes through while it's `Created`, `None` for requests created
+    /// before the images were validated
+    #[method(name = "getImageValidation")]
+    async fn get_image_validation(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<Option<ImageValidation>>;
+
+    /// Privileged and network access the request's executables are run with, `None` for requests submitted before
+    /// the container policy was applied
+    #[method(name = "getContainerPolicy")]
+    async fn get_container_policy(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<Option<ContainerPolicyDecision>>;
+
+    /// Everything recorded about the request, signed by the matchmaker, to replay what happened to it. Admin only,
+    /// `None` if the request is unknown.
+    #[method(name = "exportProofRequest")]
+    async fn export_proof_request(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<Option<SignedData<RequestExport, EcdsaSigner>>>;
+
+    /// Receipt signed by the matchmaker for a proven request, `None` while the request is not proven
+    #[method(name = "getReceipt")]
+    async fn get_receipt(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<Option<SignedData<ProofReceipt, EcdsaSigner>>>;
+
+    /// Cancels the request, signed by its requester or an admin, which is the recorded reason. Requests already being
+    /// tested can't be cancelled.
+    #[method(name = "cancelProofRequest")]
+    async fn cancel_proof_request(
+        &self,
+        cancel: SignedData<CancelRequest, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Assignments taken back from the operator, signed by the operator
+    #[method(name = "getCancellations")]
+    async fn get_cancellations(
+        &self,
+        operator: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<Cancellation>>;
+
+    /// Images the operator should download ahead of likely assignments, signed by the operator
+    #[method(name = "getPrefetchHints")]
+    async fn get_prefetch_hints(
+        &self,
+        operator: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<PrefetchHint>>;
+
+    /// Replaces the attestation of the operator's TEE, signed by the operator
+    #[method(name = "submitAttestation")]
+    async fn submit_attestation(
+        &self,
+        attestation: SignedData<TeeAttestation, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Latest attestation of the operator's TEE, `None` if it never reported one. The quote is returned unverified, for
+    /// requesters to verify against the vendor's certificate chain
+    #[method(name = "getOperatorAttestation")]
+    async fn get_operator_attestation(
+        &self,
+        operator: Address,
+    ) -> RpcResult<Option<OperatorAttestation>>;
+
+    /// Replaces the self-test report of the operator, signed by the operator
+    #[method(name = "submitReadinessReport")]
+    async fn submit_readiness_report(
+        &self,
+        report: SignedData<ReadinessReport, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Latest self-test report of the operator, `None` if it never submitted one
+    #[method(name = "getOperatorReadiness")]
+    async fn get_operator_readiness(
+        &self,
+        operator: Address,
+    ) -> RpcResult<Option<OperatorReadiness>>;
+
+    /// The operator stops for good, signed by the operator. It's taken offline at once, its in-flight requests are
+    /// assigned to other operators and its pending payments settled.
+    #[method(name = "deregisterOperator")]
+    async fn deregister_operator(
+        &self,
+        deregistration: SignedData<Deregistration, EcdsaSigner>,
+    ) -> RpcResult<OperatorExit>;
+
+    /// The operator stopped proving the cancelled request, signed by the operator
+    #[method(name = "acknowledgeCancellation")]
+    async fn acknowledge_cancellation(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Requests assigned to the operator and not proven yet, signed by the operator
+    #[method(name = "getAssignments")]
+    async fn get_assignments(
+        &self,
+        operator: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<Assignment>>;
+
+    /// The operator confirmed the assignment, signed by the operator. Returns `false` if it was acknowledged already.
+    #[method(name = "acknowledgeAssignment")]
+    async fn acknowledge_assignment(
+        &self,
+        request_id: SignedData<ProofRequestId, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Proof of a request assigned to the operator, signed by the operator. Returns the status of the request once
+    /// the proof is recorded.
+    #[method(name = "submitProof")]
+    async fn submit_proof(
+        &self,
+        delivery: SignedData<ProofDelivery, EcdsaSigner>,
+    ) -> RpcResult<ProofStatus>;
+
+    /// Registers the operator with the resources it claims, or replaces its claim, signed by the operator
+    #[method(name = "registerOperator")]
+    async fn register_operator(
+        &self,
+        registration: SignedData<OperatorRegistration, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Keeps the operator online and declares what it can take on, signed by the operator
+    #[method(name = "heartbeat")]
+    async fn heartbeat(
+        &self,
+        heartbeat: SignedData<OperatorHeartbeat, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Disputes the proof of a proven request within the dispute window, signed by the requester. The prover isn't
+    /// paid until an independent operator verified the proof again.
+    #[method(name = "disputeProof")]
+    async fn dispute_proof(&self, dispute: SignedData<Dispute, EcdsaSigner>) -> RpcResult<()>;
+
+    #[method(name = "getDispute")]
+    async fn get_dispute(&self, request_id: ProofRequestId) -> RpcResult<Option<DisputeInfo>>;
+
+    /// Disputed proofs the operator has to verify again, signed by the operator
+    #[method(name = "getDisputeVerifications")]
+    async fn get_dispute_verifications(
+        &self,
+        operator: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<DisputeVerification>>;
+
+    /// Verdict on a disputed proof, signed by the operator the dispute is assigned to
+    #[method(name = "submitDisputeVerdict")]
+    async fn submit_dispute_verdict(
+        &self,
+        verdict: SignedData<DisputeVerdict, EcdsaSigner>,
+    ) -> RpcResult<DisputeStatus>;
+
+    /// Verdict on the proof provided with a verification-only request, signed by the operator who acknowledged its
+    /// assignment
+    #[method(name = "submitVerification")]
+    async fn submit_verification(
+        &self,
+        verdict: SignedData<VerificationVerdict, EcdsaSigner>,
     ) -> RpcResult<ProofStatus>;
 
+    /// The requester retrieved the proof of its proven request, signed by the requester. Proofs retained until they
+    /// are retrieved are deleted afterwards.
+    #[method(name = "acknowledgeProof")]
+    async fn acknowledge_proof(
+        &self,
+        retrieval: SignedData<ProofRetrieval, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Copies of a redundantly proven request, `None` if it's unknown or proven by one operator
+    #[method(name = "getRedundancyStatus")]
+    async fn get_redundancy_status(
+        &self,
+        request_id: ProofRequestId,
+    ) -> RpcResult<Option<RedundancyStatus>>;
+
     #[method(name = "updateBalance")]
     async fn update_balance(&self, someone: SignedData<Address, EcdsaSigner>) -> RpcResult<()>;
 
@@ -55,15 +473,187 @@ pub(crate) trait RpcApi {
     #[method(name = "returnUnspent")]
     async fn return_unspent(&self, someone: SignedData<Address, EcdsaSigner>) -> RpcResult<()>;
 
+    #[method(name = "getBalance")]
+    async fn get_balance(
+        &self,
+        someone: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<RequesterBalance>;
+
+    /// Payment events of the ledger after the cursor, for accounting systems. Signed by the requester they're of, or by
+    /// an admin for any or every requester.
+    #[method(name = "getPaymentEvents")]
+    async fn get_payment_events(
+        &self,
+        query: SignedData<PaymentEventQuery, EcdsaSigner>,
+    ) -> RpcResult<PaymentEventPage>;
+
+    /// Report of the last run comparing the books with the vault balances, signed by an admin. `None` until the first
+    /// run.
+    #[method(name = "getVaultReconciliation")]
+    async fn get_vault_reconciliation(
+        &self,
+        admin: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Option<VaultReconciliation>>;
+
     // Withdraw to operator
     #[method(name = "withdraw")]
     async fn withdraw(&self, someone: SignedData<Address, EcdsaSigner>) -> RpcResult<()>;
 
+    /// Withdraws part of the requester's deposit to the recipient before the deadline, signed by the requester. The
+    /// amount is held from the spendable balance until the withdrawal is executed, `getWithdrawal` reports the
+    /// transaction it's settled in. Withdrawals above the approval threshold aren't executed until approved with
+    /// `approveWithdrawal`.
+    #[method(name = "withdrawAmount")]
+    async fn withdraw_amount(
+        &self,
+        withdrawal: SignedData<WithdrawalRequest, EcdsaSigner>,
+    ) -> RpcResult<WithdrawalId>;
+
+    /// Approves a withdrawal awaiting approval, signed by one of the approvers. The withdrawal is executed once it has
+    /// the approvals the policy requires, it's returned with the approval.
+    #[method(name = "approveWithdrawal")]
+    async fn approve_withdrawal(
+        &self,
+        approval: SignedData<WithdrawalApproval, EcdsaSigner>,
+    ) -> RpcResult<Withdrawal>;
+
+    /// Cancels a withdrawal awaiting approval or not sent to the vault yet, signed by its requester. Its amount is
+    /// released, it's returned cancelled.
+    #[method(name = "cancelWithdrawal")]
+    async fn cancel_withdrawal(
+        &self,
+        cancellation: SignedData<WithdrawalCancellation, EcdsaSigner>,
+    ) -> RpcResult<Withdrawal>;
+
+    /// Withdrawal requested with `withdrawAmount`, `None` if it's unknown
+    #[method(name = "getWithdrawal")]
+    async fn get_withdrawal(&self, id: WithdrawalId) -> RpcResult<Option<Withdrawal>>;
+
+    /// Lifecycle aggregates of the requests proven since the time, the last 24 hours by default
+    #[method(name = "getStats")]
+    async fn get_stats(&self, since: Option<DateTime<Utc>>) -> RpcResult<LifecycleStats>;
+
+    /// Earnings and utilization of the operator, signed by the operator
+    #[method(name = "getOperatorStats")]
+    async fn get_operator_stats(
+        &self,
+        operator: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<OperatorStats>;
+
+    /// Network-wide totals for explorers, cached on the server
+    #[method(name = "networkStats")]
+    async fn network_stats(&self) -> RpcResult<NetworkStats>;
+
+    /// Operators online and temporarily offline, with the thresholds of the network they were counted with
+    #[method(name = "operatorCounts")]
+    async fn operator_counts(&self) -> RpcResult<OperatorCounts>;
+
+    /// Requests assigned to each operator and when it was last assigned one, the most assigned first
+    #[method(name = "operatorAssignments")]
+    async fn operator_assignments(&self) -> RpcResult<Vec<OperatorAssignments>>;
+
+    /// Bans or greylists the operator, it isn't assigned requests until the restriction expires or is cleared. Admin
+    /// only, `false` if the operator is unknown.
+    #[method(name = "restrictOperator")]
+    async fn restrict_operator(
+        &self,
+        request: SignedData<RestrictionRequest, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Lifts the ban or greylisting of the operator and forgets its failed proofs. Admin only, `false` if the
+    /// operator is unknown.
+    #[method(name = "clearOperatorRestriction")]
+    async fn clear_operator_restriction(
+        &self,
+        removal: SignedData<RestrictionRemoval, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Operators currently banned or greylisted
+    #[method(name = "restrictedOperators")]
+    async fn restricted_operators(&self) -> RpcResult<Vec<RestrictedOperator>>;
+
+    /// Registers a webhook of the signer, or updates the one with the same URL
+    #[method(name = "registerWebhook")]
+    async fn register_webhook(
+        &self,
+        registration: SignedData<WebhookRegistration, EcdsaSigner>,
+    ) -> RpcResult<WebhookId>;
+
+    /// Removes the signer's webhook with the URL, returns `false` if there is none
+    #[method(name = "removeWebhook")]
+    async fn remove_webhook(
+        &self,
+        removal: SignedData<WebhookRemoval, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Webhooks of the requester, signed by the requester
+    #[method(name = "listWebhooks")]
+    async fn list_webhooks(
+        &self,
+        someone: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<WebhookInfo>>;
+
+    /// Sets the spend caps of the signer, the reservations over a cap are refused. Refused past its deadline or with
+    /// a nonce at or below the last accepted one
+    #[method(name = "setBudget")]
+    async fn set_budget(&self, budget: SignedData<BudgetUpdate, EcdsaSigner>) -> RpcResult<()>;
+
+    /// Budget of the requester with what it already consumed, signed by the requester
+    #[method(name = "getBudget")]
+    async fn get_budget(
+        &self,
+        someone: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Option<BudgetStatus>>;
+
+    /// Delegates the submitter to submit requests paid by the signer, until the delegation expires and within its
+    /// limits. Delegating the submitter again replaces its limits, what it already spent is kept. Refused with a nonce
+    /// at or below the one of the last delegation or revocation of the submitter.
+    #[method(name = "setDelegation")]
+    async fn set_delegation(
+        &self,
+        delegation: SignedData<Delegation, EcdsaSigner>,
+    ) -> RpcResult<()>;
+
+    /// Revokes the delegation of the submitter by the signer, `false` if it wasn't delegated
+    #[method(name = "revokeDelegation")]
+    async fn revoke_delegation(
+        &self,
+        revocation: SignedData<DelegationRevocation, EcdsaSigner>,
+    ) -> RpcResult<bool>;
+
+    /// Delegations of the requester with what their submitters already spent, signed by the requester
+    #[method(name = "getDelegations")]
+    async fn get_delegations(
+        &self,
+        someone: SignedData<Address, EcdsaSigner>,
+    ) -> RpcResult<Vec<DelegationStatus>>;
+
+    /// Publishes a version of an image, signed by its publisher. A name belongs to its first publisher and a version
+    /// can't change once published, `false` if the version was published with the same image before.
+    #[method(name = "publishImage")]
+    async fn publish_image(&self, record: SignedData<ImageRecord, EcdsaSigner>) -> RpcResult<bool>;
+
+    /// Signed record of the version of the image, of its latest version if none is given. `None` if it's unknown
+    #[method(name = "resolveImage")]
+    async fn resolve_image(
+        &self,
+        name: String,
+        version: Option<String>,
+    ) -> RpcResult<Option<PublishedImage>>;
+
+    /// Published versions of the image, the most recent first
+    #[method(name = "imageVersions")]
+    async fn image_versions(&self, name: String) -> RpcResult<Vec<PublishedImage>>;
+
     // Health endpoint
     #[method(name = "health")]
     async fn health(&self) -> RpcResult<String>;
 
-    // Nodes Health endpoint
+    /// Health of the background tasks of the server, restarted or given up after failing
+    #[method(name = "healthDetails")]
+    async fn health_details(&self) -> RpcResult<HealthReport>;
+
+    // Nodes Health endpoint, counts the operators online and with a free slot
     #[method(name = "nodes")]
     async fn nodes(&self) -> RpcResult<usize>;
 }
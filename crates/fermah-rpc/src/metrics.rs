@@ -1,17 +1,70 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
 use ethers::types::Address;
-use opentelemetry::{global::meter, metrics::Counter, KeyValue};
+use fermah_common::types::stats::ProofLifecycle;
+use opentelemetry::{
+    global::meter,
+    metrics::{Counter, Histogram},
+    KeyValue,
+};
 
 #[derive(Clone)]
 pub struct Metrics {
     proof_requests: Counter<u64>,
+    expired_requests: Counter<u64>,
+    collected_proofs: Counter<u64>,
+    below_min_deposit: Counter<u64>,
+    /// Requests refused for the minimum deposit since the start, the counter can't be read back
+    below_min_deposit_total: Arc<AtomicU64>,
+    assignment_latency: Histogram<f64>,
+    proving_duration: Histogram<f64>,
+    verification_duration: Histogram<f64>,
+    time_to_proof: Histogram<f64>,
 }
 
 impl Metrics {
     pub fn init() -> Self {
         let m = meter("rpc metrics");
         let proof_requests = m.u64_counter("proof_requests").init();
+        let histogram = |name: &'static str, description: &'static str| {
+            m.f64_histogram(name).with_description(description).init()
+        };
 
-        Self { proof_requests }
+        Self {
+            proof_requests,
+            expired_requests: m
+                .u64_counter("proof_requests_expired")
+                .with_description("Proof requests rejected for staying in Created too long")
+                .init(),
+            collected_proofs: m
+                .u64_counter("proofs_collected")
+                .with_description("Proofs deleted once their retention passed")
+                .init(),
+            below_min_deposit: m
+                .u64_counter("proof_requests_below_min_deposit")
+                .with_description("Proof requests rejected for a deposit below the minimum")
+                .init(),
+            below_min_deposit_total: Arc::new(AtomicU64::new(0)),
+            assignment_latency: histogram(
+                "proof_assignment_latency_seconds",
+                "Time from a proof request being accepted to being assigned",
+            ),
+            proving_duration: histogram(
+                "proof_proving_duration_seconds",
+                "Time from an assignment being acknowledged to the proof being delivered",
+            ),
+            verification_duration: histogram(
+                "proof_verification_duration_seconds",
+                "Time from a proof being delivered to being verified",
+            ),
+            time_to_proof: histogram(
+                "proof_time_to_proof_seconds",
+                "Time from a proof request being submitted to being proven",
+            ),
+        }
     }
 
     pub fn inc_proof_requests(&self, seeker: Address, valid: bool) {
@@ -23,4 +76,39 @@ impl Metrics {
             ],
         )
     }
+
+    pub fn inc_expired_requests(&self, count: u64) {
+        self.expired_requests.add(count, &[])
+    }
+
+    pub fn inc_collected_proofs(&self, count: u64) {
+        self.collected_proofs.add(count, &[])
+    }
+
+    pub fn inc_below_min_deposit(&self, count: u64) {
+        self.below_min_deposit.add(count, &[]);
+        self.below_min_deposit_total
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn below_min_deposit_total(&self) -> u64 {
+        self.below_min_deposit_total.load(Ordering::Relaxed)
+    }
+
+    /// Records the phase durations of a proven request
+    pub fn record_proof_lifecycle(&self, lifecycle: &ProofLifecycle) {
+        for (histogram, duration) in [
+            (&self.assignment_latency, lifecycle.assignment_latency()),
+            (&self.proving_duration, lifecycle.proving_duration()),
+            (
+                &self.verification_duration,
+                lifecycle.verification_duration(),
+            ),
+            (&self.time_to_proof, lifecycle.time_to_proof()),
+        ] {
+            if let Some(duration) = duration {
+                histogram.record(duration.num_milliseconds() as f64 / 1000., &[]);
+            }
+        }
+    }
 }
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use fermah_common::supervisor::{RestartPolicy, Supervisor};
+use fermah_database::{
+    mm_outbox::{OutboxEntry, OutboxId},
+    Database,
+};
+use tokio::{sync::mpsc::Sender, task::JoinSet};
+use tracing::{debug, error, info, warn};
+
+use crate::upstream::UpstreamEvent;
+
+/// Upstream event read from the outbox. It's delivered again after the lease expires, until it's completed.
+#[derive(Debug)]
+pub struct OutboxDelivery {
+    pub id: OutboxId,
+    /// Deliveries so far, including this one
+    pub attempts: u32,
+    pub event: UpstreamEvent,
+}
+
+impl OutboxDelivery {
+    /// Acknowledges the event once it's processed
+    pub fn complete(&self, db: &Database) -> Result<()> {
+        db.complete_outbox_event(self.id)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxConfig {
+    /// Events claimed at once
+    pub batch_size: i64,
+    /// Time the consumer has to complete an event before it's delivered again
+    pub lease: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            lease: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Drains the outbox into the match maker channel
+#[derive(Debug, Clone)]
+pub struct OutboxRelay {
+    db: Database,
+    tx: Sender<OutboxDelivery>,
+    config: OutboxConfig,
+}
+
+impl OutboxRelay {
+    pub fn new(db: Database, tx: Sender<OutboxDelivery>, config: OutboxConfig) -> Self {
+        Self { db, tx, config }
+    }
+
+    /// Polls the outbox until shutdown, or until the consumer is gone
+    pub fn start(&self, supervisor: &Supervisor, tasks: &mut JoinSet<Result<()>>) {
+        let relay = self.clone();
+        supervisor.spawn(
+            tasks,
+            "outbox",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let relay = relay.clone();
+                async move {
+                    let mut interval = tokio::time::interval(relay.config.poll_interval);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Outbox relay stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => {
+                                if !relay.relay_batch().await {
+                                    warn!("Outbox consumer is gone, relay stopped");
+                                    return Ok(())
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    /// Claims and sends a batch of events, returns `false` if the consumer is gone
+    async fn relay_batch(&self) -> bool {
+        let db = self.db.clone();
+        let (limit, lease) = (self.config.batch_size, self.config.lease);
+        let entries = match tokio::task::spawn_blocking(move || {
+            db.claim_outbox_events(limit, chrono::Duration::from_std(lease).unwrap_or_default())
+        })
+        .await
+        {
+            Ok(Ok(entries)) => entries,
+            Ok(Err(err)) => {
+                error!(?err, "failed to claim outbox events");
+                return true;
+            }
+            Err(err) => {
+                error!(?err, "outbox claim task panicked");
+                return true;
+            }
+        };
+
+        for entry in entries {
+            let Some(delivery) = self.decode(entry) else {
+                continue;
+            };
+            debug!(
+                id = delivery.id,
+                attempts = delivery.attempts,
+                "relaying outbox event"
+            );
+            if self.tx.send(delivery).await.is_err() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Events which can't be decoded are failed, so they aren't delivered again
+    fn decode(&self, entry: OutboxEntry) -> Option<OutboxDelivery> {
+        match UpstreamEvent::decode(&entry.payload) {
+            Ok(event) => {
+                Some(OutboxDelivery {
+                    id: entry.id,
+                    attempts: entry.attempts,
+                    event,
+                })
+            }
+            Err(err) => {
+                error!(
+                    ?err,
+                    id = entry.id,
+                    kind = %entry.kind,
+                    "undecodable outbox event"
+                );
+                if let Err(err) = self.db.fail_outbox_event(entry.id, &err.to_string()) {
+                    error!(?err, id = entry.id, "failed to mark outbox event as failed");
+                }
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use ethers::types::{Address, U256};
+use fermah_common::{
+    http::url_policy::UrlPolicy,
+    operator::{assignment::OperatorOrdering, liveness::Liveness, restriction::GreylistPolicy},
+    proof::{
+        cancellation::CancellationFees,
+        container_policy::ContainerPolicy,
+        dispute::DisputePolicy,
+        limits::PayloadLimits,
+        scheduling::AssignmentScheduling,
+    },
+    resource::class::ResourceClasses,
+    types::{fee::ProtocolFee, withdrawal::WithdrawalApprovalPolicy},
+};
+use serde::{Deserialize, Serialize};
+
+/// How the matchmaker serving the RPC admits, prices, assigns and settles the requests. Only the server reads it, the
+/// clients connect with the [`RpcConfig`](crate::RpcConfig) alone.
+#[derive(Serialize, Deserialize, Args, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerPolicy {
+    /// Seconds the `networkStats` totals are cached for
+    #[arg(long, default_value_t = default_network_stats_refresh_secs())]
+    #[serde(default = "default_network_stats_refresh_secs")]
+    pub network_stats_refresh_secs: u64,
+
+    /// Protocol commission disclosed in the quotes, the matchmaker takes it out of the reservations
+    #[command(flatten)]
+    #[serde(default)]
+    pub protocol_fee: ProtocolFee,
+
+    /// Share of the reservation the operators are paid for the requests cancelled while assigned to them
+    #[command(flatten)]
+    #[serde(default)]
+    pub cancellation_fees: CancellationFees,
+
+    /// Window the proofs can be disputed in before their prover is paid, and the time the verifiers have
+    #[command(flatten)]
+    #[serde(default)]
+    pub disputes: DisputePolicy,
+
+    /// Size limits of the submitted proof requests
+    #[command(flatten)]
+    #[serde(default)]
+    pub payload_limits: PayloadLimits,
+
+    /// URLs the proof requests may name, the images are downloaded from them. The image validator is given the same
+    /// policy, so it downloads from what was checked only
+    #[command(flatten)]
+    #[serde(default)]
+    pub url_policy: UrlPolicy,
+
+    /// Privileged and network access the proof requests may ask for
+    #[command(flatten)]
+    #[serde(default)]
+    pub container_policy: ContainerPolicy,
+
+    /// Keys allowed to call the admin methods, e.g. `exportProofRequest`
+    #[arg(long = "admin-key")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub admin_keys: Vec<Address>,
+
+    /// How recently the operators must have been heard from to be counted by `nodes` and `operatorCounts`
+    #[command(flatten)]
+    #[serde(default)]
+    pub liveness: Liveness,
+
+    /// Seconds the operators read by `nodes` and `operatorCounts` are kept in memory for, not cached if unset
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator_cache_ttl_secs: Option<u64>,
+
+    /// Minutes after which the requests still in Created are cancelled as expired, they never expire if unset
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_expiry_minutes: Option<u64>,
+
+    /// Minutes the operators have to deliver their copy of a redundant request, after which it's released to be
+    /// assigned to another operator. Never released if unset
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replica_timeout_minutes: Option<u64>,
+
+    /// Days the proofs of the requests without a retention are kept for once proven, kept for good if unset
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_retention_days: Option<u64>,
+
+    /// Failed proofs in a row the operators are greylisted after
+    #[command(flatten)]
+    #[serde(default)]
+    pub greylist_policy: GreylistPolicy,
+
+    /// Order the available operators are offered to the matching in, the least recently assigned first spreads the
+    /// work across them
+    #[arg(long, value_enum, default_value_t)]
+    #[serde(default)]
+    pub operator_ordering: OperatorOrdering,
+
+    /// Order the requests waiting for an operator are assigned in, e.g. the earliest deadline first
+    #[command(flatten)]
+    #[serde(default)]
+    pub assignment_scheduling: AssignmentScheduling,
+
+    /// Withdrawals which wait for the approval of the approvers before they are executed
+    #[command(flatten)]
+    #[serde(default)]
+    pub withdrawal_approval: WithdrawalApprovalPolicy,
+
+    /// Resource classes the requests may name instead of their requirement, and their prices. Only set from the
+    /// config file
+    #[arg(skip)]
+    #[serde(default)]
+    pub resource_classes: ResourceClasses,
+
+    /// Cached vault deposit the requesters need before any of their requests is accepted, so empty addresses can't
+    /// fill the database. No minimum if unset
+    #[arg(long, value_parser = U256::from_dec_str)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_deposit: Option<U256>,
+
+    /// Time until which the requests hashed by the clients built before the hash covered every executable field are
+    /// accepted. Their `cmd` isn't signed, so they are refused if unset, and should only be accepted while the
+    /// clients are upgraded
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legacy_hash_until: Option<DateTime<Utc>>,
+}
+
+fn default_network_stats_refresh_secs() -> u64 {
+    60
+}
+
+impl ServerPolicy {
+    /// Time after which the requests still in Created expire, fails if it's past the range of the timestamps
+    pub fn created_expiry(&self) -> Result<Option<chrono::Duration>> {
+        self.created_expiry_minutes
+            .map(|minutes| {
+                i64::try_from(minutes)
+                    .ok()
+                    .and_then(chrono::Duration::try_minutes)
+                    .with_context(|| format!("created expiry of {minutes} minutes is out of range"))
+            })
+            .transpose()
+    }
+
+    /// Time after which the undelivered copies of the redundant requests are released, fails if it's past the range
+    /// of the timestamps
+    pub fn replica_timeout(&self) -> Result<Option<chrono::Duration>> {
+        self.replica_timeout_minutes
+            .map(|minutes| {
+                i64::try_from(minutes)
+                    .ok()
+                    .and_then(chrono::Duration::try_minutes)
+                    .with_context(|| {
+                        format!("replica timeout of {minutes} minutes is out of range")
+                    })
+            })
+            .transpose()
+    }
+
+    /// Time the proofs of the requests without a retention are kept for, fails if it's past the range of the
+    /// timestamps
+    pub fn proof_retention(&self) -> Result<Option<chrono::Duration>> {
+        self.proof_retention_days
+            .map(|days| {
+                i64::try_from(days)
+                    .ok()
+                    .and_then(chrono::Duration::try_days)
+                    .with_context(|| format!("proof retention of {days} days is out of range"))
+            })
+            .transpose()
+    }
+}
+
+impl Default for ServerPolicy {
+    fn default() -> Self {
+        Self {
+            network_stats_refresh_secs: default_network_stats_refresh_secs(),
+            protocol_fee: ProtocolFee::default(),
+            cancellation_fees: CancellationFees::default(),
+            disputes: DisputePolicy::default(),
+            payload_limits: PayloadLimits::default(),
+            url_policy: UrlPolicy::default(),
+            container_policy: ContainerPolicy::default(),
+            admin_keys: vec![],
+            liveness: Liveness::default(),
+            operator_cache_ttl_secs: None,
+            created_expiry_minutes: None,
+            replica_timeout_minutes: None,
+            proof_retention_days: None,
+            greylist_policy: GreylistPolicy::default(),
+            operator_ordering: OperatorOrdering::default(),
+            assignment_scheduling: AssignmentScheduling::default(),
+            withdrawal_approval: WithdrawalApprovalPolicy::default(),
+            resource_classes: ResourceClasses::default(),
+            min_deposit: None,
+            legacy_hash_until: None,
+        }
+    }
+}
@@ -1,19 +1,84 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
 use fermah_common::{
-    crypto::signer::{ecdsa::EcdsaSigner, SignedData, Signer},
-    hash::blake3::{Blake3Hash, Blake3Hasher},
+    crypto::signer::{ecdsa::EcdsaSigner, freshness::Freshness, SignedData, Signer},
+    hash::blake3::Blake3Hasher,
+    image_registry::{ImageRecord, PublishedImage},
+    operator::{
+        assignment::{Assignment, OperatorAssignments, ProofDelivery},
+        deregistration::{Deregistration, OperatorExit},
+        liveness::OperatorHeartbeat,
+        readiness::{OperatorReadiness, ReadinessReport},
+        registration::OperatorRegistration,
+        restriction::{RestrictedOperator, RestrictionRemoval, RestrictionRequest},
+        OperatorId,
+    },
     proof,
-    proof::request::ProofRequest,
+    proof::{
+        builder::{ProofRequestBuilder, SignedProofRequest},
+        cancellation::{CancelRequest, Cancellation},
+        container_policy::ContainerPolicyDecision,
+        dispute::{Dispute, DisputeInfo, DisputeStatus, DisputeVerdict, DisputeVerification},
+        export::RequestExport,
+        image_validation::ImageValidation,
+        job_array::{JobArrayId, JobArrayStatus},
+        prefetch::PrefetchHint,
+        receipt::ProofReceipt,
+        redundancy::RedundancyStatus,
+        request::{ProofRequest, ProofRequestId, VerificationVerdict},
+        retention::ProofRetrieval,
+        submission::ProofSubmission,
+    },
+    resource::{
+        class::ResourceClasses,
+        tee::{OperatorAttestation, TeeAttestation},
+    },
     serialization::hash::SerializableHash,
+    supervisor::HealthReport,
+    types::{
+        balance::RequesterBalance,
+        budget::{BudgetConfig, BudgetExceeded, BudgetStatus, BudgetUpdate},
+        delegation::{Delegation, DelegationRevocation, DelegationStatus},
+        fee::FeeQuote,
+        network::ConnectionProtocol,
+        payment::{PaymentEventPage, PaymentEventQuery},
+        reconciliation::VaultReconciliation,
+        stats::{LifecycleStats, NetworkStats, OperatorCounts, OperatorStats},
+        webhook::{WebhookId, WebhookInfo, WebhookRegistration, WebhookRemoval},
+        withdrawal::{
+            Withdrawal,
+            WithdrawalApproval,
+            WithdrawalCancellation,
+            WithdrawalId,
+            WithdrawalRequest,
+        },
+    },
 };
 use jsonrpsee::{
-    async_client::{Client, ClientBuilder},
+    async_client::{Client, ClientBuilder, PingConfig},
     client_transport::ws::WsTransportClientBuilder,
+    core::ClientError,
+    http_client::{transport::HttpBackend, HeaderMap, HeaderValue, HttpClient, HttpClientBuilder},
 };
-use tracing::error;
+use reqwest::Url;
+use tokio::sync::Mutex;
+use tower_http::decompression::{Decompression, DecompressionLayer};
+use tracing::{error, info, warn};
 
-use crate::{RpcApiClient, RpcConfig};
+use crate::{
+    error::{RpcError, RpcErrorCode},
+    BelowMinimumDeposit,
+    InsufficientFunds,
+    RpcApiClient,
+    RpcConfig,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum RpcClientError {
@@ -21,30 +86,177 @@ pub enum RpcClientError {
     Fs(#[from] fermah_common::fs::error::Error),
 
     #[error("RPC client error: {0}")]
-    Rpc(#[from] jsonrpsee::core::ClientError),
+    Rpc(jsonrpsee::core::ClientError),
+
+    #[error("RPC server error: {0}")]
+    Server(RpcError),
 
     #[error("RPC client handshake error: {0}")]
     RpcHandshake(#[from] jsonrpsee::client_transport::ws::WsHandshakeError),
 
+    #[error(
+        "insufficient funds: quoted {} including a protocol fee of {}, spendable {}, deposit at least {} more",
+        .0.quoted,
+        .0.protocol_fee,
+        .0.spendable,
+        .0.top_up
+    )]
+    InsufficientFunds(InsufficientFunds),
+
+    #[error("a different proof request with the same id was already submitted")]
+    ProofRequestConflict,
+
+    #[error("{0}")]
+    BudgetExceeded(BudgetExceeded),
+
+    #[error("{0}")]
+    BelowMinimumDeposit(BelowMinimumDeposit),
+
+    #[error("auth token is not a valid header value")]
+    InvalidAuthToken,
+
     #[error("proof requester address does not match private key")]
     InvalidRequesterAddress,
 
+    #[error("wallet error: {0}")]
+    Wallet(#[from] ethers::signers::WalletError),
+
     #[error("ecdsa signer error: {0}")]
     EcdsaSigner(#[from] fermah_common::crypto::signer::ecdsa::EcdsaSignerError),
 
     #[error("keystore file error: {0}")]
     KeystoreError(#[from] fermah_common::crypto::keystore::KeystoreFileError),
+
+    #[error("invalid proof request: {0}")]
+    ProofRequest(#[from] fermah_common::proof::builder::ProofRequestBuilderError),
+}
+
+impl RpcClientError {
+    /// Code of the error returned by the server, `None` if the call didn't get an answer from the server or its
+    /// code isn't in the catalog
+    pub fn code(&self) -> Option<RpcErrorCode> {
+        match self {
+            Self::InsufficientFunds(_) => Some(RpcErrorCode::InsufficientFunds),
+            Self::ProofRequestConflict => Some(RpcErrorCode::ProofRequestConflict),
+            Self::BudgetExceeded(_) => Some(RpcErrorCode::BudgetExceeded),
+            Self::BelowMinimumDeposit(_) => Some(RpcErrorCode::BelowMinimumDeposit),
+            Self::Server(err) => err.kind(),
+            _ => None,
+        }
+    }
+}
+
+/// The errors returned by the server are typed by their code, the admission rejections of a submission carry their
+/// details
+impl From<ClientError> for RpcClientError {
+    fn from(err: ClientError) -> Self {
+        let ClientError::Call(call) = &err else {
+            return Self::Rpc(err);
+        };
+        let err = RpcError::from(call);
+        match err.kind() {
+            Some(RpcErrorCode::ProofRequestConflict) => Self::ProofRequestConflict,
+            Some(RpcErrorCode::InsufficientFunds) => {
+                match err.data() {
+                    Some(insufficient) => Self::InsufficientFunds(insufficient),
+                    None => Self::Server(err),
+                }
+            }
+            Some(RpcErrorCode::BudgetExceeded) => {
+                match err.data() {
+                    Some(exceeded) => Self::BudgetExceeded(exceeded),
+                    None => Self::Server(err),
+                }
+            }
+            Some(RpcErrorCode::BelowMinimumDeposit) => {
+                match err.data() {
+                    Some(below) => Self::BelowMinimumDeposit(below),
+                    None => Self::Server(err),
+                }
+            }
+            _ => Self::Server(err),
+        }
+    }
+}
+
+/// HTTP client decompressing the responses, see [`RpcClient::compressed_http_client`]
+pub type CompressedHttpClient = HttpClient<Decompression<HttpBackend>>;
+
+/// How [`RpcClient`] keeps its connection alive and reconnects when it's lost
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Reconnections tried for one call before its error is returned, retries forever if not set
+    pub max_retries: Option<u32>,
+    /// Delay before the first reconnection, doubled on every further one
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// Interval the server is pinged at, a connection missing the pongs is closed and reconnected by the next call.
+    /// Not pinged if not set
+    pub ping_interval: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: Some(5),
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            ping_interval: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the `retries`-th reconnection
+    fn backoff(&self, retries: u32) -> Duration {
+        self.backoff
+            .saturating_mul(1 << retries.saturating_sub(1).min(16))
+            .min(self.max_backoff)
+    }
+
+    fn allows(&self, retries: u32) -> bool {
+        self.max_retries
+            .map_or(true, |max_retries| retries < max_retries)
+    }
+}
+
+/// Calls the [`RpcApiClient`] method through [`RpcClient::call_with_retry`], the arguments are cloned for every
+/// attempt. Only for the methods which are safe to repeat
+macro_rules! with_retry {
+    ($self:ident, $method:ident($($arg:ident),*)) => {
+        $self.call_with_retry(|client| {
+            $(let $arg = $arg.clone();)*
+            async move { RpcApiClient::$method(&*client, $($arg),*).await }
+        })
+    };
+}
+
+/// Calls the [`RpcApiClient`] method through [`RpcClient::call_once`], for the methods which aren't safe to repeat
+macro_rules! call_once {
+    ($self:ident, $method:ident($($arg:ident),*)) => {
+        $self.call_once(|client| async move { RpcApiClient::$method(&*client, $($arg),*).await })
+    };
 }
 
 pub struct RpcClient {
-    /// JSON-RPC HTTP client.
-    pub client: Client,
+    /// JSON-RPC websocket client, replaced on reconnection. Read it with [`RpcClient::client`]
+    pub client: RwLock<Arc<Client>>,
+
+    /// Held while reconnecting, so concurrent calls losing the connection reconnect once
+    reconnecting: Mutex<()>,
 
     /// RPC Configuration
     pub config: RpcConfig,
 
+    /// Reconnection and keep-alive of the connection
+    pub retry: RetryPolicy,
+
     /// Client's signer
     pub signer: EcdsaSigner,
+
+    /// HTTP client the methods returning proofs are called with, so they're sent compressed. `None` if the config
+    /// disables compression
+    compressed: Option<CompressedHttpClient>,
 }
 
 impl RpcClient {
@@ -52,66 +264,697 @@ impl RpcClient {
         config: RpcConfig,
         signer: EcdsaSigner,
     ) -> Result<Self, RpcClientError> {
-        let (tx, rx) = WsTransportClientBuilder::default()
-            .build(config.connection.into())
-            .await
-            .inspect_err(|_| error!("failed to connect to RPC server: {}", config.connection))?;
+        Self::from_config_with_retry(config, signer, RetryPolicy::default()).await
+    }
+
+    pub async fn from_config_with_retry(
+        config: RpcConfig,
+        signer: EcdsaSigner,
+        retry: RetryPolicy,
+    ) -> Result<Self, RpcClientError> {
+        let client = Self::connect(&config, &retry).await?;
+        let compressed = config
+            .compression
+            .then(|| Self::build_compressed_http_client(&config))
+            .transpose()?;
 
         Ok(Self {
-            client: ClientBuilder::default().build_with_tokio(tx, rx),
+            client: RwLock::new(Arc::new(client)),
+            reconnecting: Mutex::new(()),
             config,
+            retry,
             signer,
+            compressed,
         })
     }
 
+    async fn connect(config: &RpcConfig, retry: &RetryPolicy) -> Result<Client, RpcClientError> {
+        let (tx, rx) = WsTransportClientBuilder::default()
+            .set_headers(Self::headers(config)?)
+            .build(config.connection.into())
+            .await
+            .inspect_err(|_| error!("failed to connect to RPC server: {}", config.connection))?;
+
+        let mut builder = ClientBuilder::default();
+        if let Some(interval) = retry.ping_interval {
+            builder = builder.enable_ws_ping(
+                PingConfig::new()
+                    .ping_interval(interval)
+                    .inactive_limit(interval * 2),
+            );
+        }
+        Ok(builder.build_with_tokio(tx, rx))
+    }
+
+    /// Current connection, it may be closed
+    pub fn client(&self) -> Arc<Client> {
+        self.client.read().unwrap().clone()
+    }
+
+    /// Calls the server, reconnecting by the [`RetryPolicy`] when the connection is lost or the call times out. The
+    /// errors returned by the server aren't retried. The call may be sent more than once, see
+    /// [`RpcClient::call_once`] for the calls which aren't safe to repeat.
+    pub async fn call_with_retry<T, F, Fut>(&self, call: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<Client>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut retries = 0;
+        loop {
+            let client = self.client();
+            let err = match call(client.clone()).await {
+                Err(err) if is_connection_error(&err) && self.retry.allows(retries) => err,
+                result => return result,
+            };
+
+            retries += 1;
+            warn!(%err, retries, "RPC call failed, retrying");
+            tokio::time::sleep(self.retry.backoff(retries)).await;
+            if let Err(err) = self.reconnect(&client).await {
+                warn!(%err, "failed to reconnect to RPC server");
+            }
+        }
+    }
+
+    /// Calls the server once. A connection found closed is replaced before the call, but the call isn't retried once
+    /// it was sent, as the server may have applied it without its answer getting back.
+    pub async fn call_once<T, F, Fut>(&self, call: F) -> Result<T, ClientError>
+    where
+        F: FnOnce(Arc<Client>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let client = self.client();
+        if !client.is_connected() {
+            if let Err(err) = self.reconnect(&client).await {
+                warn!(%err, "failed to reconnect to RPC server");
+            }
+        }
+        call(self.client()).await
+    }
+
+    /// Replaces the connection if `failed` is still the current one and it's closed
+    async fn reconnect(&self, failed: &Arc<Client>) -> Result<(), RpcClientError> {
+        let _reconnecting = self.reconnecting.lock().await;
+        if !Arc::ptr_eq(failed, &self.client()) || failed.is_connected() {
+            return Ok(());
+        }
+
+        let client = Self::connect(&self.config, &self.retry).await?;
+        *self.client.write().unwrap() = Arc::new(client);
+        info!("reconnected to RPC server: {}", self.config.connection);
+        Ok(())
+    }
+
+    fn headers(config: &RpcConfig) -> Result<HeaderMap, RpcClientError> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = &config.auth_token {
+            headers.insert(
+                "Authorization",
+                HeaderValue::from_str(&format!("Bearer {token}"))
+                    .map_err(|_| RpcClientError::InvalidAuthToken)?,
+            );
+        }
+        Ok(headers)
+    }
+
+    /// HTTP client to the same server asking for gzip or zstd compressed responses, for the methods returning large
+    /// payloads. The websocket connection of [`RpcClient::client`] isn't compressed.
+    pub fn compressed_http_client(&self) -> Result<CompressedHttpClient, RpcClientError> {
+        Self::build_compressed_http_client(&self.config)
+    }
+
+    fn build_compressed_http_client(
+        config: &RpcConfig,
+    ) -> Result<CompressedHttpClient, RpcClientError> {
+        let mut connection = config.connection;
+        connection.proto = Some(match connection.proto.unwrap_or_default() {
+            ConnectionProtocol::Wss | ConnectionProtocol::Https => ConnectionProtocol::Https,
+            ConnectionProtocol::Ws | ConnectionProtocol::Http => ConnectionProtocol::Http,
+        });
+
+        Ok(HttpClientBuilder::default()
+            .set_headers(Self::headers(config)?)
+            .set_http_middleware(
+                tower::ServiceBuilder::new().layer(DecompressionLayer::new().gzip(true).zstd(true)),
+            )
+            .build(Url::from(connection))?)
+    }
+
     pub async fn submit_proof_request(
         &self,
-        mut proof_request: ProofRequest,
-    ) -> Result<Blake3Hash, RpcClientError> {
-        proof_request.requester = Some(self.signer.verifying_key());
+        proof_request: ProofRequest,
+    ) -> Result<ProofRequestId, RpcClientError> {
+        let signed_request = ProofRequestBuilder::from(proof_request).sign(&self.signer)?;
+        Ok(self
+            .submit_signed_proof_request(signed_request)
+            .await?
+            .proof_request_id)
+    }
+
+    /// Signs the proof request as EIP-712 typed data, bound to the chain
+    pub async fn submit_proof_request_eip712(
+        &self,
+        proof_request: ProofRequest,
+        chain_id: u64,
+    ) -> Result<ProofRequestId, RpcClientError> {
+        let signed_request = ProofRequestBuilder::from(proof_request)
+            .sign_eip712(chain_id, &self.signer)
+            .await?;
+        Ok(self
+            .submit_signed_proof_request(signed_request)
+            .await?
+            .proof_request_id)
+    }
 
-        let signed_request = SignedData::new(proof_request, &self.signer)?;
+    /// Submits a request signed with [`ProofRequestBuilder`], e.g. by another signer than the client's. It isn't
+    /// retried once sent, but resubmitting it is safe, the identical request is reported as `existing`.
+    pub async fn submit_signed_proof_request(
+        &self,
+        signed_request: SignedProofRequest,
+    ) -> Result<ProofSubmission, RpcClientError> {
         signed_request.verify()?;
 
-        let proof_request_id = signed_request.hash;
+        Ok(call_once!(self, submit_proof_request_with_status(signed_request)).await?)
+    }
+
+    /// Signs every proof request and submits them as one job array
+    pub async fn submit_job_array(
+        &self,
+        proof_requests: Vec<ProofRequest>,
+    ) -> Result<JobArrayId, RpcClientError> {
+        let signed_requests = proof_requests
+            .into_iter()
+            .map(|proof_request| ProofRequestBuilder::from(proof_request).sign(&self.signer))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(call_once!(self, submit_job_array(signed_requests)).await?)
+    }
 
-        RpcApiClient::submit_proof_request(&self.client, signed_request).await?;
-        Ok(proof_request_id)
+    pub async fn get_job_array_status(
+        &self,
+        array_id: SerializableHash<Blake3Hasher>,
+    ) -> Result<Option<JobArrayStatus>, RpcClientError> {
+        let signed_request = SignedData::new(array_id, &self.signer)?;
+        Ok(with_retry!(self, get_job_array_status(signed_request)).await?)
     }
 
+    /// Status of the request, with why it was cancelled if it's cancelled. The proof of a proven request is fetched
+    /// compressed, unless the config disables compression.
     pub async fn check_request_status(
         &self,
-        request_status: SerializableHash<Blake3Hasher>,
-    ) -> Result<proof::status::ProofStatus, RpcClientError> {
+        request_status: ProofRequestId,
+    ) -> Result<proof::status::StatusReport, RpcClientError> {
         let signed_request = SignedData::new(request_status, &self.signer)?;
-        Ok(RpcApiClient::check_request_status(&self.client, signed_request).await?)
+        if let Some(compressed) = &self.compressed {
+            return Ok(RpcApiClient::check_request_status(compressed, signed_request).await?);
+        }
+        Ok(with_retry!(self, check_request_status(signed_request)).await?)
+    }
+
+    /// Progress of the request's image checks, `None` if its images aren't validated
+    pub async fn get_image_validation(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<ImageValidation>, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, get_image_validation(signed_request)).await?)
+    }
+
+    /// Privileged and network access the request is run with, `None` if the container policy wasn't applied to it
+    pub async fn get_container_policy(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<ContainerPolicyDecision>, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, get_container_policy(signed_request)).await?)
+    }
+
+    /// Everything recorded about the request, signed by the matchmaker. The client's key has to be an admin key of
+    /// the server.
+    pub async fn export_proof_request(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<SignedData<RequestExport, EcdsaSigner>>, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, export_proof_request(signed_request)).await?)
+    }
+
+    /// Cancels the request, the client's key has to be the requester's or an admin key of the server
+    pub async fn cancel_proof_request(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<(), RpcClientError> {
+        let cancel = CancelRequest::new(request_id, Utc::now());
+        let signed_cancel = SignedData::new(cancel, &self.signer)?;
+        Ok(with_retry!(self, cancel_proof_request(signed_cancel)).await?)
+    }
+
+    /// Cancelled assignments of the operator the client signs for
+    pub async fn get_cancellations(&self) -> Result<Vec<Cancellation>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_cancellations(payload)).await?)
+    }
+
+    /// Images the operator the client signs for should prefetch
+    pub async fn get_prefetch_hints(&self) -> Result<Vec<PrefetchHint>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_prefetch_hints(payload)).await?)
+    }
+
+    /// Replaces the attestation of the operator the client signs for
+    pub async fn submit_attestation(
+        &self,
+        attestation: TeeAttestation,
+    ) -> Result<(), RpcClientError> {
+        let signed_attestation = SignedData::new(attestation, &self.signer)?;
+        Ok(with_retry!(self, submit_attestation(signed_attestation)).await?)
+    }
+
+    pub async fn get_operator_attestation(
+        &self,
+        operator: Address,
+    ) -> Result<Option<OperatorAttestation>, RpcClientError> {
+        Ok(with_retry!(self, get_operator_attestation(operator)).await?)
+    }
+
+    /// Replaces the self-test report of the operator the client signs for
+    pub async fn submit_readiness_report(
+        &self,
+        report: ReadinessReport,
+    ) -> Result<(), RpcClientError> {
+        let signed_report = SignedData::new(report, &self.signer)?;
+        Ok(with_retry!(self, submit_readiness_report(signed_report)).await?)
+    }
+
+    pub async fn get_operator_readiness(
+        &self,
+        operator: Address,
+    ) -> Result<Option<OperatorReadiness>, RpcClientError> {
+        Ok(with_retry!(self, get_operator_readiness(operator)).await?)
+    }
+
+    pub async fn acknowledge_cancellation(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<bool, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, acknowledge_cancellation(signed_request)).await?)
+    }
+
+    /// Requests assigned to the operator the client signs for and not proven yet
+    pub async fn get_assignments(&self) -> Result<Vec<Assignment>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_assignments(payload)).await?)
+    }
+
+    pub async fn acknowledge_assignment(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<bool, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, acknowledge_assignment(signed_request)).await?)
+    }
+
+    pub async fn submit_proof(
+        &self,
+        delivery: ProofDelivery,
+    ) -> Result<proof::status::ProofStatus, RpcClientError> {
+        let signed_delivery = SignedData::new(delivery, &self.signer)?;
+        Ok(with_retry!(self, submit_proof(signed_delivery)).await?)
+    }
+
+    /// Registers the operator the client signs for with the resources it claims
+    pub async fn register_operator(
+        &self,
+        registration: OperatorRegistration,
+    ) -> Result<(), RpcClientError> {
+        let signed_registration = SignedData::new(registration, &self.signer)?;
+        Ok(with_retry!(self, register_operator(signed_registration)).await?)
+    }
+
+    pub async fn heartbeat(&self, heartbeat: OperatorHeartbeat) -> Result<(), RpcClientError> {
+        let signed_heartbeat = SignedData::new(heartbeat, &self.signer)?;
+        Ok(with_retry!(self, heartbeat(signed_heartbeat)).await?)
+    }
+
+    /// Takes the operator the client signs for offline for good, the deregistration is accepted until the deadline
+    pub async fn deregister_operator(
+        &self,
+        deregistration: Deregistration,
+    ) -> Result<OperatorExit, RpcClientError> {
+        let signed_deregistration = SignedData::new(deregistration, &self.signer)?;
+        Ok(with_retry!(self, deregister_operator(signed_deregistration)).await?)
+    }
+
+    /// Disputes the proof of the request, the client signs for its requester
+    pub async fn dispute_proof(
+        &self,
+        request_id: ProofRequestId,
+        reason: String,
+    ) -> Result<(), RpcClientError> {
+        let dispute = Dispute {
+            proof_request_id: request_id,
+            reason,
+        };
+        let signed_dispute = SignedData::new(dispute, &self.signer)?;
+        Ok(call_once!(self, dispute_proof(signed_dispute)).await?)
+    }
+
+    /// Acknowledges the retrieval of the request's proof, the client signs for its requester
+    pub async fn acknowledge_proof(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<(), RpcClientError> {
+        let retrieval = ProofRetrieval {
+            proof_request_id: request_id,
+        };
+        let signed_retrieval = SignedData::new(retrieval, &self.signer)?;
+        Ok(with_retry!(self, acknowledge_proof(signed_retrieval)).await?)
+    }
+
+    pub async fn get_dispute(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<DisputeInfo>, RpcClientError> {
+        Ok(with_retry!(self, get_dispute(request_id)).await?)
+    }
+
+    /// Disputed proofs the operator the client signs for has to verify again
+    pub async fn get_dispute_verifications(
+        &self,
+    ) -> Result<Vec<DisputeVerification>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_dispute_verifications(payload)).await?)
+    }
+
+    pub async fn submit_dispute_verdict(
+        &self,
+        verdict: DisputeVerdict,
+    ) -> Result<DisputeStatus, RpcClientError> {
+        let signed_verdict = SignedData::new(verdict, &self.signer)?;
+        Ok(with_retry!(self, submit_dispute_verdict(signed_verdict)).await?)
+    }
+
+    pub async fn submit_verification(
+        &self,
+        verdict: VerificationVerdict,
+    ) -> Result<proof::status::ProofStatus, RpcClientError> {
+        let signed_verdict = SignedData::new(verdict, &self.signer)?;
+        Ok(with_retry!(self, submit_verification(signed_verdict)).await?)
+    }
+
+    pub async fn get_redundancy_status(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<RedundancyStatus>, RpcClientError> {
+        Ok(with_retry!(self, get_redundancy_status(request_id)).await?)
+    }
+
+    pub async fn get_receipt(
+        &self,
+        request_id: ProofRequestId,
+    ) -> Result<Option<SignedData<ProofReceipt, EcdsaSigner>>, RpcClientError> {
+        let signed_request = SignedData::new(request_id, &self.signer)?;
+        Ok(with_retry!(self, get_receipt(signed_request)).await?)
     }
 
     pub async fn update_balance(&self) -> Result<(), RpcClientError> {
         let address = self.signer.verifying_key();
         let payload = SignedData::new(address, &self.signer)?;
-        Ok(RpcApiClient::update_balance(&self.client, payload).await?)
+        Ok(with_retry!(self, update_balance(payload)).await?)
     }
 
     pub async fn update_registered_till_block(&self) -> Result<(), RpcClientError> {
         let address = self.signer.verifying_key();
         let payload = SignedData::new(address, &self.signer)?;
-        Ok(RpcApiClient::update_registered_till_block(&self.client, payload).await?)
+        Ok(with_retry!(self, update_registered_till_block(payload)).await?)
     }
 
     pub async fn return_unspent(&self) -> Result<(), RpcClientError> {
         let address = self.signer.verifying_key();
         let payload = SignedData::new(address, &self.signer)?;
-        Ok(RpcApiClient::return_unspent(&self.client, payload).await?)
+        Ok(call_once!(self, return_unspent(payload)).await?)
+    }
+
+    pub async fn get_balance(&self) -> Result<RequesterBalance, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_balance(payload)).await?)
+    }
+
+    /// Page of payment events after the cursor, of the signer unless it's an admin
+    pub async fn get_payment_events(
+        &self,
+        query: PaymentEventQuery,
+    ) -> Result<PaymentEventPage, RpcClientError> {
+        let signed_query = SignedData::new(query, &self.signer)?;
+        Ok(with_retry!(self, get_payment_events(signed_query)).await?)
+    }
+
+    /// Report of the last vault reconciliation, the client must sign for an admin
+    pub async fn get_vault_reconciliation(
+        &self,
+    ) -> Result<Option<VaultReconciliation>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_vault_reconciliation(payload)).await?)
     }
 
     pub async fn withdraw(&self) -> Result<(), RpcClientError> {
         let address = self.signer.verifying_key();
         let payload = SignedData::new(address, &self.signer)?;
-        Ok(RpcApiClient::withdraw(&self.client, payload).await?)
+        Ok(call_once!(self, withdraw(payload)).await?)
+    }
+
+    /// Withdraws part of the deposit of the signer, the withdrawal is executed asynchronously
+    pub async fn withdraw_amount(
+        &self,
+        withdrawal: WithdrawalRequest,
+    ) -> Result<WithdrawalId, RpcClientError> {
+        let signed_withdrawal = SignedData::new(withdrawal, &self.signer)?;
+        Ok(call_once!(self, withdraw_amount(signed_withdrawal)).await?)
+    }
+
+    /// Approves the withdrawal as one of the approvers
+    pub async fn approve_withdrawal(
+        &self,
+        withdrawal_id: WithdrawalId,
+    ) -> Result<Withdrawal, RpcClientError> {
+        let signed_approval = SignedData::new(WithdrawalApproval { withdrawal_id }, &self.signer)?;
+        Ok(with_retry!(self, approve_withdrawal(signed_approval)).await?)
+    }
+
+    /// Cancels the withdrawal of the signer, if it isn't sent to the vault yet
+    pub async fn cancel_withdrawal(
+        &self,
+        withdrawal_id: WithdrawalId,
+    ) -> Result<Withdrawal, RpcClientError> {
+        let signed_cancellation =
+            SignedData::new(WithdrawalCancellation { withdrawal_id }, &self.signer)?;
+        Ok(call_once!(self, cancel_withdrawal(signed_cancellation)).await?)
+    }
+
+    pub async fn get_withdrawal(
+        &self,
+        id: WithdrawalId,
+    ) -> Result<Option<Withdrawal>, RpcClientError> {
+        Ok(with_retry!(self, get_withdrawal(id)).await?)
+    }
+
+    pub async fn get_stats(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<LifecycleStats, RpcClientError> {
+        Ok(with_retry!(self, get_stats(since)).await?)
+    }
+
+    /// Stats of the operator the client signs for
+    pub async fn get_operator_stats(&self) -> Result<OperatorStats, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_operator_stats(payload)).await?)
+    }
+
+    /// Price of the proof request with the protocol commission it includes
+    pub async fn quote_proof_request(
+        &self,
+        proof_request: ProofRequest,
+    ) -> Result<FeeQuote, RpcClientError> {
+        Ok(with_retry!(self, quote_proof_request(proof_request)).await?)
+    }
+
+    pub async fn resource_classes(&self) -> Result<ResourceClasses, RpcClientError> {
+        Ok(with_retry!(self, resource_classes()).await?)
+    }
+
+    pub async fn network_stats(&self) -> Result<NetworkStats, RpcClientError> {
+        Ok(with_retry!(self, network_stats()).await?)
+    }
+
+    pub async fn operator_counts(&self) -> Result<OperatorCounts, RpcClientError> {
+        Ok(with_retry!(self, operator_counts()).await?)
+    }
+
+    pub async fn operator_assignments(&self) -> Result<Vec<OperatorAssignments>, RpcClientError> {
+        Ok(with_retry!(self, operator_assignments()).await?)
+    }
+
+    /// Bans or greylists the operator, the client must sign with an admin key. The request needs a fresh
+    /// [`Freshness`], e.g. `Freshness::new(Utc::now())`
+    pub async fn restrict_operator(
+        &self,
+        request: RestrictionRequest,
+    ) -> Result<bool, RpcClientError> {
+        let signed_request = SignedData::new(request, &self.signer)?;
+        Ok(call_once!(self, restrict_operator(signed_request)).await?)
+    }
+
+    /// Lifts the restriction of the operator, the client must sign with an admin key
+    pub async fn clear_operator_restriction(
+        &self,
+        operator_id: OperatorId,
+    ) -> Result<bool, RpcClientError> {
+        let removal = RestrictionRemoval {
+            operator_id,
+            freshness: Freshness::new(Utc::now()),
+        };
+        let signed_removal = SignedData::new(removal, &self.signer)?;
+        Ok(call_once!(self, clear_operator_restriction(signed_removal)).await?)
+    }
+
+    pub async fn restricted_operators(&self) -> Result<Vec<RestrictedOperator>, RpcClientError> {
+        Ok(with_retry!(self, restricted_operators()).await?)
+    }
+
+    pub async fn register_webhook(
+        &self,
+        registration: WebhookRegistration,
+    ) -> Result<WebhookId, RpcClientError> {
+        let payload = SignedData::new(registration, &self.signer)?;
+        Ok(call_once!(self, register_webhook(payload)).await?)
+    }
+
+    pub async fn remove_webhook(&self, removal: WebhookRemoval) -> Result<bool, RpcClientError> {
+        let payload = SignedData::new(removal, &self.signer)?;
+        Ok(with_retry!(self, remove_webhook(payload)).await?)
+    }
+
+    /// Webhooks of the requester the client signs for
+    pub async fn list_webhooks(&self) -> Result<Vec<WebhookInfo>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, list_webhooks(payload)).await?)
+    }
+
+    /// Sets the spend caps of the requester the client signs for
+    pub async fn set_budget(&self, budget: BudgetConfig) -> Result<(), RpcClientError> {
+        let update = BudgetUpdate {
+            config: budget,
+            freshness: Freshness::new(Utc::now()),
+        };
+        let payload = SignedData::new(update, &self.signer)?;
+        Ok(call_once!(self, set_budget(payload)).await?)
+    }
+
+    /// Budget of the requester the client signs for, `None` if it has no caps
+    pub async fn get_budget(&self) -> Result<Option<BudgetStatus>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_budget(payload)).await?)
+    }
+
+    /// Delegates the submitter to submit requests paid by the requester the client signs for. The submitter signs
+    /// them with [`ProofRequestBuilder::sign_for`]
+    pub async fn set_delegation(&self, delegation: Delegation) -> Result<(), RpcClientError> {
+        let payload = SignedData::new(delegation, &self.signer)?;
+        Ok(call_once!(self, set_delegation(payload)).await?)
+    }
+
+    /// Revokes the delegation of the submitter, `false` if it wasn't delegated
+    pub async fn revoke_delegation(&self, submitter: Address) -> Result<bool, RpcClientError> {
+        let revocation = DelegationRevocation {
+            submitter,
+            nonce: Freshness::nonce_at(Utc::now()),
+        };
+        let payload = SignedData::new(revocation, &self.signer)?;
+        Ok(call_once!(self, revoke_delegation(payload)).await?)
+    }
+
+    /// Delegations of the requester the client signs for
+    pub async fn get_delegations(&self) -> Result<Vec<DelegationStatus>, RpcClientError> {
+        let address = self.signer.verifying_key();
+        let payload = SignedData::new(address, &self.signer)?;
+        Ok(with_retry!(self, get_delegations(payload)).await?)
+    }
+
+    /// Publishes the image version with the client as its publisher, `false` if it was published before
+    pub async fn publish_image(&self, record: ImageRecord) -> Result<bool, RpcClientError> {
+        let payload = SignedData::new(record, &self.signer)?;
+        Ok(with_retry!(self, publish_image(payload)).await?)
+    }
+
+    /// Record of the image version as the server has it, to be checked with [`PublishedImage::verify`]
+    pub async fn resolve_image(
+        &self,
+        name: String,
+        version: Option<String>,
+    ) -> Result<Option<PublishedImage>, RpcClientError> {
+        Ok(with_retry!(self, resolve_image(name, version)).await?)
+    }
+
+    pub async fn image_versions(
+        &self,
+        name: String,
+    ) -> Result<Vec<PublishedImage>, RpcClientError> {
+        Ok(with_retry!(self, image_versions(name)).await?)
     }
 
     pub async fn health(&self) -> Result<String, RpcClientError> {
-        Ok(RpcApiClient::health(&self.client).await?)
+        Ok(with_retry!(self, health()).await?)
+    }
+
+    pub async fn health_details(&self) -> Result<HealthReport, RpcClientError> {
+        Ok(with_retry!(self, health_details()).await?)
+    }
+}
+
+/// Errors of a lost or unresponsive connection, unlike the errors returned by the server
+fn is_connection_error(err: &ClientError) -> bool {
+    matches!(
+        err,
+        ClientError::RestartNeeded(_) | ClientError::Transport(_) | ClientError::RequestTimeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff() {
+        let retry = RetryPolicy {
+            max_retries: Some(3),
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ping_interval: None,
+        };
+        assert_eq!(retry.backoff(1), Duration::from_secs(1));
+        assert_eq!(retry.backoff(2), Duration::from_secs(2));
+        assert_eq!(retry.backoff(3), Duration::from_secs(4));
+        assert_eq!(retry.backoff(4), Duration::from_secs(5));
+        assert!(retry.allows(2));
+        assert!(!retry.allows(3));
+
+        let forever = RetryPolicy {
+            max_retries: None,
+            ..retry
+        };
+        assert!(forever.allows(u32::MAX));
+        assert!(is_connection_error(&ClientError::RequestTimeout));
+        assert!(!is_connection_error(&ClientError::Custom("refused".into())));
     }
 }
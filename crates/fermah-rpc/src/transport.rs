@@ -0,0 +1,245 @@
+use std::{
+    fs::File,
+    future::Future,
+    io::BufReader,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use jsonrpsee::server::{HttpBody, HttpRequest, HttpResponse};
+use rustls::{
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore,
+    ServerConfig,
+};
+use tokio_rustls::TlsAcceptor;
+use tower::{Layer, Service};
+
+use crate::RpcConfig;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    #[error("failed to read {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("no private key in {0}")]
+    NoPrivateKey(String),
+
+    #[error("TLS error: {0}")]
+    Tls(#[from] rustls::Error),
+
+    #[error("client certificate verifier error: {0}")]
+    ClientVerifier(#[from] rustls::server::VerifierBuilderError),
+}
+
+/// TLS acceptor for the configured certificate, `None` if TLS isn't configured. Client certificates are required and
+/// verified against `tls_client_ca` if it's set.
+pub fn tls_acceptor(config: &RpcConfig) -> Result<Option<TlsAcceptor>, TransportError> {
+    let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) else {
+        return Ok(None);
+    };
+
+    let builder =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?;
+
+    let builder = match &config.tls_client_ca {
+        Some(ca) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca)? {
+                roots.add(cert)?;
+            }
+            builder.with_client_cert_verifier(
+                WebPkiClientVerifier::builder_with_provider(
+                    Arc::new(roots),
+                    Arc::new(rustls::crypto::ring::default_provider()),
+                )
+                .build()?,
+            )
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = builder.with_single_cert(load_certs(cert)?, load_key(key)?)?;
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok(Some(TlsAcceptor::from(Arc::new(server_config))))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TransportError> {
+    File::open(path).map(BufReader::new).map_err(|source| {
+        TransportError::Read {
+            path: path.display().to_string(),
+            source,
+        }
+    })
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TransportError> {
+    rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<_, _>>()
+        .map_err(|source| {
+            TransportError::Read {
+                path: path.display().to_string(),
+                source,
+            }
+        })
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, TransportError> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|source| {
+            TransportError::Read {
+                path: path.display().to_string(),
+                source,
+            }
+        })?
+        .ok_or_else(|| TransportError::NoPrivateKey(path.display().to_string()))
+}
+
+/// Rejects HTTP requests and websocket handshakes without the bearer token
+#[derive(Debug, Clone)]
+pub struct BearerAuthLayer {
+    expected: Arc<str>,
+}
+
+impl BearerAuthLayer {
+    pub fn new(token: &str) -> Self {
+        Self {
+            expected: format!("Bearer {token}").into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for BearerAuthLayer {
+    type Service = BearerAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerAuth {
+            inner,
+            expected: self.expected.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BearerAuth<S> {
+    inner: S,
+    expected: Arc<str>,
+}
+
+impl<S> BearerAuth<S> {
+    fn is_authorized<B>(&self, request: &HttpRequest<B>) -> bool {
+        request
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), self.expected.as_bytes()))
+    }
+}
+
+impl<S, B> Service<HttpRequest<B>> for BearerAuth<S>
+where
+    S: Service<HttpRequest<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<B>) -> Self::Future {
+        if !self.is_authorized(&request) {
+            return Box::pin(async {
+                Ok(HttpResponse::builder()
+                    .status(http::StatusCode::UNAUTHORIZED)
+                    .header(http::header::WWW_AUTHENTICATE, "Bearer")
+                    .body(HttpBody::empty())
+                    .expect("valid response"))
+            });
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+/// Compares without short-circuiting, so the token can't be guessed from the response time
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Ok200;
+
+    impl Service<HttpRequest<HttpBody>> for Ok200 {
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, Infallible>> + Send>>;
+        type Response = HttpResponse;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: HttpRequest<HttpBody>) -> Self::Future {
+            Box::pin(async { Ok(HttpResponse::new(HttpBody::empty())) })
+        }
+    }
+
+    async fn status(authorization: Option<&str>) -> http::StatusCode {
+        let mut service = BearerAuthLayer::new("secret").layer(Ok200);
+        let mut request = HttpRequest::builder();
+        if let Some(authorization) = authorization {
+            request = request.header(http::header::AUTHORIZATION, authorization);
+        }
+        service
+            .call(request.body(HttpBody::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_bearer_auth() {
+        assert_eq!(status(Some("Bearer secret")).await, http::StatusCode::OK);
+        assert_eq!(status(None).await, http::StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(Some("Bearer secreT")).await,
+            http::StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status(Some("secret")).await, http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_plaintext_without_tls_config() {
+        let config = RpcConfig::new(Default::default());
+        assert!(tls_acceptor(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_missing_certificate() {
+        let config = RpcConfig {
+            tls_cert: Some("/nonexistent/cert.pem".into()),
+            tls_key: Some("/nonexistent/key.pem".into()),
+            ..RpcConfig::new(Default::default())
+        };
+        assert!(matches!(
+            tls_acceptor(&config),
+            Err(TransportError::Read { .. })
+        ));
+    }
+}
@@ -1,10 +1,17 @@
 use ethers::types::Address;
 use fermah_common::{
     crypto::signer::{ecdsa::EcdsaSigner, SignedData},
-    proof::request::ProofRequest,
+    operator::deregistration::Deregistration,
+    proof::{
+        cancellation::CancellationReason,
+        legacy::decode_legacy_signed_proof_request,
+        request::{ProofRequest, ProofRequestId},
+    },
+    types::withdrawal::WithdrawalId,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[allow(clippy::large_enum_variant)] // TODO remove me
 pub enum UpstreamEvent {
     ProofRequest(SignedData<ProofRequest, EcdsaSigner>),
@@ -12,4 +19,75 @@ pub enum UpstreamEvent {
     UpdateRegisteredTillBlock(Address),
     ReturnUnspent(Address),
     Withdraw(Address),
+    /// Cancelled by the requester or an admin, the assigned operator has to be told to stop
+    CancelProofRequest(ProofRequestId, CancellationReason),
+    /// Disputed by the requester, an independent operator has to verify the proof again
+    DisputeProof(ProofRequestId),
+    /// Withdrawal held from the requester's balance, to be executed through the vault
+    WithdrawAmount(WithdrawalId),
+    /// Operator taken offline for good, to be paid out and ejected from the AVS if it's leaving it
+    DeregisterOperator(SignedData<Deregistration, EcdsaSigner>),
+}
+
+impl UpstreamEvent {
+    /// Name the event is stored under in the outbox
+    pub fn kind(&self) -> &'static str {
+        match self {
+            UpstreamEvent::ProofRequest(_) => "proofRequest",
+            UpstreamEvent::UpdateBalance(_) => "updateBalance",
+            UpstreamEvent::UpdateRegisteredTillBlock(_) => "updateRegisteredTillBlock",
+            UpstreamEvent::ReturnUnspent(_) => "returnUnspent",
+            UpstreamEvent::Withdraw(_) => "withdraw",
+            UpstreamEvent::CancelProofRequest(..) => "cancelProofRequest",
+            UpstreamEvent::DisputeProof(_) => "disputeProof",
+            UpstreamEvent::WithdrawAmount(_) => "withdrawAmount",
+            UpstreamEvent::DeregisterOperator(_) => "deregisterOperator",
+        }
+    }
+
+    /// Decodes an event of the outbox. A proof request enqueued in the legacy layout, before the fields appended since
+    /// its first release, is decoded all the same.
+    pub fn decode(bytes: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(bytes).or_else(|err| {
+            // Variant index first, the proof request is the first variant
+            match bytes.strip_prefix(&0u32.to_le_bytes()[..]) {
+                Some(proof_request) => {
+                    decode_legacy_signed_proof_request(proof_request)
+                        .map(UpstreamEvent::ProofRequest)
+                        .map_err(|_| err)
+                }
+                None => Err(err),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fermah_common::fixtures;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_legacy_proof_request() {
+        let signed = fixtures::signed_proof_request();
+        let event = bincode::serialize(&UpstreamEvent::ProofRequest(signed.clone())).unwrap();
+        assert!(matches!(
+            UpstreamEvent::decode(&event).unwrap(),
+            UpstreamEvent::ProofRequest(decoded) if decoded == signed
+        ));
+
+        // Enqueued before the fields were appended, the vector of the `compat` suite
+        let legacy = const_hex::decode(
+            include_str!("../../fermah-common/compat/legacy/signed_proof_request.bincode.hex")
+                .trim_end(),
+        )
+        .unwrap();
+        let event = [&0u32.to_le_bytes()[..], &legacy].concat();
+        assert!(matches!(
+            UpstreamEvent::decode(&event).unwrap(),
+            UpstreamEvent::ProofRequest(decoded) if decoded.payload.nonce == 217
+        ));
+        assert!(UpstreamEvent::decode(&[1, 2, 3]).is_err());
+    }
 }
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use fermah_common::{
+    http::url_policy::{GetError, UrlPolicy, UrlPolicyError},
+    supervisor::{RestartPolicy, Supervisor},
+};
+use fermah_database::{mm_webhooks::WebhookDelivery, Database};
+use hmac::{Hmac, Mac};
+use reqwest::{Response, Url};
+use sha2::Sha256;
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn};
+
+use crate::event_bus::{next_change, EventBus};
+
+/// Unix time the delivery was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Fermah-Timestamp";
+/// `sha256=<hex>` HMAC of `<timestamp>.<body>`, keyed with the webhook secret
+pub const SIGNATURE_HEADER: &str = "X-Fermah-Signature";
+/// Same for every attempt of a delivery, so receivers can drop duplicates
+pub const DELIVERY_HEADER: &str = "X-Fermah-Delivery";
+
+/// Signature of a delivery body, receivers recompute it with their secret and compare
+pub fn sign(secret: &[u8], timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", const_hex::encode(mac.finalize().into_bytes()))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookConfig {
+    /// Deliveries claimed at once
+    pub batch_size: i64,
+    pub poll_interval: Duration,
+    /// Time a webhook has to answer
+    pub timeout: Duration,
+    /// Attempts before a delivery is given up
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every further one
+    pub retry_backoff: Duration,
+    pub max_retry_backoff: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 50,
+            poll_interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+            max_attempts: 8,
+            retry_backoff: Duration::from_secs(10),
+            max_retry_backoff: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Delay before retrying a delivery which failed for the `attempts`-th time
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+            .min(self.max_retry_backoff)
+    }
+}
+
+/// Posts the queued webhook events to the requesters' endpoints
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    db: Database,
+    config: WebhookConfig,
+    /// Where the webhooks can be posted to, the policy their URLs were registered with
+    url_policy: UrlPolicy,
+    /// Request changes the due events are delivered on, without waiting for the next poll
+    event_bus: Option<EventBus>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: Database, config: WebhookConfig, url_policy: UrlPolicy) -> Self {
+        Self {
+            db,
+            config,
+            url_policy,
+            event_bus: None,
+        }
+    }
+
+    /// Delivers the due events as soon as a request changes, the events queued along with the change are delivered
+    /// right away. The poll still delivers the retries and the events queued apart from a change.
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Delivers the due events until shutdown
+    pub fn start(&self, supervisor: &Supervisor, tasks: &mut JoinSet<Result<()>>) {
+        let dispatcher = self.clone();
+        supervisor.spawn(
+            tasks,
+            "webhooks",
+            RestartPolicy::default(),
+            move |mut shutdown_rx| {
+                let dispatcher = dispatcher.clone();
+                async move {
+                    let mut interval = tokio::time::interval(dispatcher.config.poll_interval);
+                    let mut changes = dispatcher.event_bus.as_ref().map(EventBus::subscribe);
+                    loop {
+                        tokio::select! {
+                            _ = shutdown_rx.changed() => {
+                                info!("Webhook dispatcher stopped");
+                                return Ok(())
+                            }
+
+                            _ = interval.tick() => dispatcher.dispatch_batch().await,
+
+                            Some(change) = async {
+                                match changes.as_mut() {
+                                    Some(rx) => next_change(rx).await,
+                                    None => std::future::pending().await,
+                                }
+                            } => {
+                                debug!(proof_request_id = ?change.proof_request_id, "dispatching webhooks on request change");
+                                // Changes committed together are dispatched at once
+                                if let Some(rx) = changes.as_mut() {
+                                    while rx.try_recv().is_ok() {}
+                                }
+                                dispatcher.dispatch_batch().await;
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    async fn dispatch_batch(&self) {
+        let db = self.db.clone();
+        let limit = self.config.batch_size;
+        // Deliveries still in flight when the lease expires would be sent twice
+        let lease = self.config.timeout * 2;
+        let deliveries = match tokio::task::spawn_blocking(move || {
+            db.claim_webhook_deliveries(
+                limit,
+                chrono::Duration::from_std(lease).unwrap_or_default(),
+            )
+        })
+        .await
+        {
+            Ok(Ok(deliveries)) => deliveries,
+            Ok(Err(err)) => {
+                error!(?err, "failed to claim webhook deliveries");
+                return;
+            }
+            Err(err) => {
+                error!(?err, "webhook claim task panicked");
+                return;
+            }
+        };
+
+        futures_util::future::join_all(deliveries.into_iter().map(|d| self.deliver(d))).await;
+    }
+
+    /// Posts the delivery to the addresses its URL resolves to. They're checked on every delivery, a host can resolve
+    /// to an internal address once its webhook is registered.
+    async fn post(&self, delivery: &WebhookDelivery) -> Result<Response, GetError> {
+        let url =
+            Url::parse(&delivery.url).map_err(|_| UrlPolicyError::Resolve(delivery.url.clone()))?;
+        let addrs = self.url_policy.resolve(&url).await?;
+        let client = reqwest::Client::builder()
+            .timeout(self.config.timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve_to_addrs(url.host_str().unwrap_or_default(), &addrs)
+            .build()?;
+
+        let timestamp = Utc::now().timestamp();
+        let response = client
+            .post(url)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                sign(&delivery.secret, timestamp, &delivery.body),
+            )
+            .header(DELIVERY_HEADER, delivery.id)
+            .body(delivery.body.clone())
+            .send()
+            .await?;
+        Ok(response.error_for_status()?)
+    }
+
+    async fn deliver(&self, delivery: WebhookDelivery) {
+        let result = self.post(&delivery).await;
+
+        let db = self.db.clone();
+        let (id, attempts) = (delivery.id, delivery.attempts);
+        let update = match result {
+            Ok(_) => {
+                debug!(id, attempts, "webhook delivered");
+                tokio::task::spawn_blocking(move || db.complete_webhook_delivery(id)).await
+            }
+            Err(err) => {
+                let retry_at = (attempts < self.config.max_attempts).then(|| {
+                    Utc::now().naive_utc()
+                        + chrono::Duration::from_std(self.config.backoff(attempts))
+                            .unwrap_or_default()
+                });
+                warn!(%err, id, attempts, ?retry_at, url = %delivery.url, "webhook delivery failed");
+                let error = err.to_string();
+                tokio::task::spawn_blocking(move || {
+                    db.reschedule_webhook_delivery(id, &error, retry_at)
+                })
+                .await
+            }
+        };
+
+        match update {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => error!(?err, id, "failed to update webhook delivery"),
+            Err(err) => error!(?err, id, "webhook update task panicked"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            sign(b"secret", 1_700_000_000, "{}"),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = WebhookConfig::default();
+        assert_eq!(config.backoff(1), Duration::from_secs(10));
+        assert_eq!(config.backoff(3), Duration::from_secs(40));
+        assert_eq!(config.backoff(30), Duration::from_secs(3600));
+    }
+}
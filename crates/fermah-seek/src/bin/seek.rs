@@ -1,73 +1,135 @@
-#[cfg(feature = "send_proof_requests")]
-use std::path::PathBuf;
-use std::{ops::Add, path::Path};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Add,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Context;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use const_hex::{traits::FromHex, ToHexExt};
-use fermah_avs::contract::Contracts;
-#[cfg(feature = "mint_vault_token")]
-use fermah_common::crypto::keystore::KeystoreConfig;
+use ethers::{
+    providers::{Http, Provider},
+    types::Address,
+};
+use fermah_avs::contract::{
+    verify::{known_codehashes, verify_contracts},
+    Contracts,
+};
 use fermah_common::{
     cli,
     cli::{
+        completions::{write_completions, CommandTree},
         prompts::print_var,
         spinner::{Spinner, SpinnerLayer, SpinnerTemplate},
     },
-    crypto::{keystore::KeystoreFile, signer::ecdsa::EcdsaSigner},
+    crypto::{
+        keystore::{KeystoreConfig, KeystoreFile},
+        signer::{ecdsa::EcdsaSigner, Signer},
+    },
     executable::Image,
     fs::{app_home_dir, ensure_dir, hash::hash_path, json::Json},
     hash::blake3::Blake3Hasher,
     http::{file_download::FileDownload, file_server::FileServer},
+    image_registry::{ImageRecord, ImageRecordError},
     print_info,
-    proof::{request::ProofRequest, status::ProofStatus},
-    resources::RemoteResource,
+    proof::{
+        builder::ProofRequestBuilder,
+        redundancy::Redundancy,
+        request::{ProofRequest, ProofRequestId},
+        retention::ProofRetention,
+        status::ProofStatus,
+    },
+    resources::{create_patch, ImageDelta, ImageHash, RemoteResource},
     serialization::hash::SerializableHash,
+    types::{
+        network::Connection,
+        payment::{PaymentEvent, PaymentEventQuery, MAX_PAYMENT_EVENTS_PAGE},
+        region::PreferredRegions,
+    },
+};
+use fermah_config::profile::{
+    overrides::{apply_overrides, overrides_from_manifest},
+    FromProfile,
+    Profile,
+    ProfileType,
+    CONFIG_DIR,
+    NONCE_FILE,
 };
 #[cfg(feature = "send_proof_requests")]
-use fermah_config::profile::NONCE_FILE;
-use fermah_config::profile::{FromProfile, Profile, ProfileType, CONFIG_DIR};
-#[cfg(feature = "send_proof_requests")]
-use fermah_rpc::rpc_client::RpcClientError;
+use fermah_rpc::rpc_client::RetryPolicy;
 use fermah_rpc::{rpc_client::RpcClient, RpcConfig};
 use fermah_seek::{
-    command::{ClientCommands, ConfigCommands, ImageCommands, ProofCommands},
+    command::{ClientCommands, ConfigCommands, ContractCommands, ImageCommands, ProofCommands},
     error::Error,
+    estimate::DepositEstimate,
+    init,
+    support::{SupportBundle, SupportCheck},
     IMAGES_DIR,
     PROOFS_DIR,
 };
 use fermah_telemetry::{stdout::StdoutTelemetry, Telemetry};
-#[cfg(feature = "send_proof_requests")]
-use tracing::warn;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use url::Url;
 
 /// Proof Requester CLI
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, arg_required_else_help = true)]
 pub struct Cli {
     /// Commands
     #[command(subcommand)]
-    pub command: ClientCommands,
+    pub command: Option<ClientCommands>,
+
+    /// Print the commands and arguments of the CLI as JSON, for wrapper tooling
+    #[arg(long, hide = true, exclusive = true)]
+    pub dump_cli_json: bool,
+}
+
+impl Cli {
+    /// Whether the output is read by other programs, which the banner would break
+    fn is_machine_readable(&self) -> bool {
+        self.dump_cli_json || matches!(self.command, Some(ClientCommands::Completions { .. }))
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    cli::ascii::print_ascii();
-    print_info!();
+    let args = Cli::parse();
+    if !args.is_machine_readable() {
+        cli::ascii::print_ascii();
+        print_info!();
+    }
 
-    let _ = run().await.inspect_err(|e| {
+    let _ = run(args).await.inspect_err(|e| {
         error!("CLI failed: {e}");
     });
 }
 
-async fn run() -> Result<(), Error> {
+async fn run(cli: Cli) -> Result<(), Error> {
     let t = StdoutTelemetry::default();
-    let cli = Cli::parse();
+
+    if cli.dump_cli_json {
+        let tree = serde_json::to_string_pretty(&CommandTree::of::<Cli>())
+            .context("failed to serialize the command tree")?;
+        println!("{tree}");
+        return Ok(());
+    }
+    let Some(command) = cli.command else {
+        Cli::command().print_help()?;
+        return Ok(());
+    };
 
     let config_dir = app_home_dir().await?.join(CONFIG_DIR);
 
-    match cli.command {
+    match command {
+        ClientCommands::Completions { shell } => {
+            write_completions::<Cli>(shell, &mut std::io::stdout());
+        }
+        ClientCommands::Init { network } => {
+            t.with_filter("warn".into()).init();
+
+            init::run_wizard(network, &config_dir).await?;
+        }
         ClientCommands::Config { configs } => {
             match configs {
                 ConfigCommands::Proof { profiles } => {
@@ -77,7 +139,12 @@ async fn run() -> Result<(), Error> {
         }
         ClientCommands::Image { images } => {
             match images {
-                ImageCommands::Serve { dir, port } => {
+                ImageCommands::Serve {
+                    dir,
+                    port,
+                    zstd,
+                    max_downloads,
+                } => {
                     t.init();
 
                     let d = match dir {
@@ -91,30 +158,66 @@ async fn run() -> Result<(), Error> {
                         }
                     };
 
-                    FileServer::new(port)
-                        .serve_dir("images".to_string(), d.into())
-                        .await;
+                    let mut server = FileServer::new(port).with_compression(zstd);
+                    if let Some(max_downloads) = max_downloads {
+                        server = server.with_max_concurrent_downloads(max_downloads);
+                    }
+                    server.serve_dir("images".to_string(), d.into()).await;
                 }
                 ImageCommands::Download {
                     image_name,
-                    version,
+                    mut version,
                     from,
                     url,
+                    publisher,
+                    rpc,
+                    key,
                     prover,
                     verifier,
                     proof_request_profile,
                 } => {
                     t.init();
 
-                    let from = Url::parse(&from)?;
+                    // Published images are downloaded from where their publisher signed for
+                    let (from, published) = match from {
+                        Some(from) => (Url::parse(&from)?, None),
+                        None => {
+                            let conn =
+                                rpc.unwrap_or_else(|| proof_request_profile.network.to_mm_rpc());
+                            let publisher = publisher.ok_or(Error::NoPublisher)?;
+                            let record =
+                                resolve_image(conn, &key, &image_name, &version, publisher).await?;
+                            version = record.version;
+                            (record.image.url.clone(), Some(record.image))
+                        }
+                    };
+
                     let dir = app_home_dir().await?.join(IMAGES_DIR);
                     ensure_dir(&dir, None).await?;
                     let filepath = dir.join(image_name.as_str());
-                    if !filepath.exists() {
+                    let stale = match &published {
+                        // An earlier version may have been downloaded under the same name
+                        Some(image) if filepath.exists() => {
+                            ImageHash::from(hash_path::<Blake3Hasher>(&filepath).await?)
+                                != image.hash
+                        }
+                        _ => !filepath.exists(),
+                    };
+                    if stale {
                         download_file(&from, &filepath).await?;
                     }
 
-                    let hash = hash_path::<Blake3Hasher>(&filepath).await?;
+                    let hash = ImageHash::from(hash_path::<Blake3Hasher>(&filepath).await?);
+                    if let Some(image) = &published {
+                        if image.hash != hash {
+                            return Err(anyhow::anyhow!(
+                                "downloaded image hash {hash} doesn't match the published hash {}",
+                                image.hash
+                            )
+                            .into());
+                        }
+                    }
+                    let delta = published.and_then(|image| image.delta);
 
                     let mut proof_profile = Profile::<ProofRequest>::from_props(
                         &config_dir,
@@ -135,14 +238,17 @@ async fn run() -> Result<(), Error> {
                             RemoteResource {
                                 url: url.clone(),
                                 hash,
+                                delta: delta.clone(),
                             },
                             image_name.clone().add(&v),
                         ));
                     }
 
                     if verifier {
-                        proof_profile.config.verifier.image =
-                            Image::RemoteDocker((RemoteResource { url, hash }, image_name.add(&v)));
+                        proof_profile.config.verifier.image = Image::RemoteDocker((
+                            RemoteResource { url, hash, delta },
+                            image_name.add(&v),
+                        ));
                     }
 
                     proof_profile.save().await?;
@@ -150,6 +256,96 @@ async fn run() -> Result<(), Error> {
                     print_var("image", filepath.display());
                     print_var("hash", hash);
                 }
+                ImageCommands::Publish {
+                    image_name,
+                    version,
+                    file,
+                    url,
+                    network,
+                    rpc,
+                    key,
+                } => {
+                    t.with_filter("warn".into()).init();
+
+                    let record = ImageRecord {
+                        name: image_name,
+                        version,
+                        image: RemoteResource {
+                            url,
+                            hash: ImageHash::from(hash_path::<Blake3Hasher>(&file).await?),
+                            delta: None,
+                        },
+                    };
+                    record.validate()?;
+
+                    let ecdsa_signer = KeystoreFile::from_config(&key)
+                        .await?
+                        .to_signer::<EcdsaSigner>(&key)
+                        .await?;
+                    let conn = rpc.unwrap_or_else(|| network.to_mm_rpc());
+                    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+                    let published = rpc.publish_image(record.clone()).await?;
+                    print_var("image", record.reference());
+                    print_var("hash", record.image.hash);
+                    print_var(
+                        "status",
+                        if published {
+                            "published"
+                        } else {
+                            "already published"
+                        },
+                    );
+                }
+                ImageCommands::Delta {
+                    base,
+                    image,
+                    out,
+                    url,
+                    proof_request_profile,
+                } => {
+                    t.init();
+
+                    let out = out.unwrap_or_else(|| image.with_extension("delta.zst"));
+                    let delta = ImageDelta {
+                        base: ImageHash::from(hash_path::<Blake3Hasher>(&base).await?),
+                        url,
+                    };
+                    let hash = ImageHash::from(hash_path::<Blake3Hasher>(&image).await?);
+
+                    let mut file = File::create(&out)?;
+                    tokio::task::spawn_blocking(move || create_patch(&base, &image, &mut file))
+                        .await
+                        .map_err(std::io::Error::from)??;
+
+                    let mut proof_profile = Profile::<ProofRequest>::from_props(
+                        &config_dir,
+                        ProfileType::Proof,
+                        &proof_request_profile,
+                    )
+                    .await?;
+                    let mut updated = false;
+                    for executable in [
+                        &mut proof_profile.config.prover,
+                        &mut proof_profile.config.verifier,
+                    ] {
+                        if let Some(resource) = executable.image.remote_mut() {
+                            if resource.hash == hash {
+                                resource.delta = Some(delta.clone());
+                                updated = true;
+                            }
+                        }
+                    }
+                    if updated {
+                        proof_profile.save().await?;
+                    } else {
+                        warn!(%hash, "No image of the proof profile has the hash of the new image");
+                    }
+
+                    print_var("delta", out.display());
+                    print_var("base", delta.base);
+                    print_var("hash", hash);
+                }
             }
         }
         ClientCommands::Key { keys } => {
@@ -163,6 +359,17 @@ async fn run() -> Result<(), Error> {
                     profile_key,
                     rpc,
                     key,
+                    eip712,
+                    inputs,
+                    overrides,
+                    depends_on,
+                    redundancy,
+                    quorum,
+                    regions,
+                    require_region,
+                    seal_result,
+                    retention_days,
+                    delete_after_retrieval,
                 } => {
                     let spinner =
                         Spinner::new(1, "Sending proof request", SpinnerTemplate::Default);
@@ -175,20 +382,66 @@ async fn run() -> Result<(), Error> {
 
                     let ecdsa_signer = KeystoreFile::from_config(&key)
                         .await?
-                        .to_signer::<EcdsaSigner>()
+                        .to_signer::<EcdsaSigner>(&key)
                         .await?;
 
                     let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
 
-                    let rpc = RpcClient::from_config(RpcConfig { connection: conn }, ecdsa_signer)
-                        .await?;
+                    let rpc =
+                        RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer.clone()).await?;
 
                     let proof_request =
                         ProofRequest::from_profile(&config_dir, ProfileType::Proof, &profile_key)
                             .await?;
 
-                    let proof_request_id = rpc
-                        .submit_proof_request(proof_request.clone())
+                    let mut all_overrides = match &inputs {
+                        Some(manifest) => overrides_from_manifest(manifest).await?,
+                        None => vec![],
+                    };
+                    all_overrides.extend(overrides);
+                    let mut builder =
+                        ProofRequestBuilder::from(apply_overrides(proof_request, &all_overrides)?);
+                    for id in depends_on {
+                        let dependency = ProofRequestId::from_hex(id.clone())
+                            .with_context(|| format!("failed to parse proof request ID {id}"))?;
+                        builder = builder.depends_on(dependency);
+                    }
+                    // Checked by the builder
+                    if let Some(copies) = redundancy {
+                        builder = builder.redundancy(Redundancy {
+                            copies,
+                            quorum: quorum.unwrap_or(copies),
+                        });
+                    }
+                    if !regions.is_empty() {
+                        builder = builder.preferred_regions(PreferredRegions {
+                            regions,
+                            required: require_region,
+                        });
+                    }
+                    if seal_result {
+                        builder = builder.result_key(ecdsa_signer.result_key());
+                    }
+                    if let Some(days) = retention_days {
+                        builder = builder.retention(ProofRetention::Days(days));
+                    }
+                    if delete_after_retrieval {
+                        builder = builder.retention(ProofRetention::UntilRetrieved);
+                    }
+
+                    let signed_request = if eip712 {
+                        builder
+                            .sign_eip712(profile_key.network.chain_id(), &ecdsa_signer)
+                            .await
+                    } else {
+                        builder.sign(&ecdsa_signer)
+                    }
+                    .inspect_err(|_| {
+                        spinner.finish("Failed!", false);
+                    })?;
+
+                    let submission = rpc
+                        .submit_signed_proof_request(signed_request)
                         .await
                         .inspect_err(|_| {
                             spinner.finish("Failed!", false);
@@ -196,7 +449,68 @@ async fn run() -> Result<(), Error> {
 
                     spinner.finish("Done!", true);
 
-                    print_var("proof_id", proof_request_id.encode_hex_with_prefix());
+                    print_var(
+                        "proof_id",
+                        submission.proof_request_id.encode_hex_with_prefix(),
+                    );
+                    if submission.existing {
+                        print_var("already_submitted", submission.status);
+                    }
+                }
+                ProofCommands::Estimate {
+                    profile_key,
+                    rpc,
+                    key,
+                    inputs,
+                    overrides,
+                    json,
+                } => {
+                    t.with_filter("warn".into()).init();
+
+                    let ecdsa_signer = KeystoreFile::from_config(&key)
+                        .await?
+                        .to_signer::<EcdsaSigner>(&key)
+                        .await?;
+
+                    let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
+                    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+                    let proof_request =
+                        ProofRequest::from_profile(&config_dir, ProfileType::Proof, &profile_key)
+                            .await?;
+                    let mut all_overrides = match &inputs {
+                        Some(manifest) => overrides_from_manifest(manifest).await?,
+                        None => vec![],
+                    };
+                    all_overrides.extend(overrides);
+                    let proof_request = apply_overrides(proof_request, &all_overrides)?;
+
+                    let quote = rpc.quote_proof_request(proof_request).await?;
+                    // Syncs the deposit with the vault first, so recent deposits are counted
+                    rpc.update_balance().await?;
+                    let balance = rpc.get_balance().await?;
+                    let estimate = DepositEstimate::new(&quote, &balance);
+
+                    if json {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&estimate)
+                                .context("failed to serialize the estimate")?
+                        );
+                    } else {
+                        print_var("price", estimate.price);
+                        print_var("fee_bps", estimate.fee_bps);
+                        print_var("balance", estimate.deposit);
+                        print_var("reserved", estimate.reserved);
+                        print_var("spendable", estimate.spendable);
+                        print_var("top_up", estimate.top_up);
+                        if !estimate.is_affordable() {
+                            warn!(
+                                "Deposit at least {} more into the vault before sending the request",
+                                estimate.top_up
+                            );
+                        }
+                    }
                 }
                 #[cfg(feature = "send_proof_requests")]
                 ProofCommands::SendProofRequests {
@@ -210,14 +524,21 @@ async fn run() -> Result<(), Error> {
 
                     let ecdsa_signer = KeystoreFile::from_config(&key)
                         .await?
-                        .to_signer::<EcdsaSigner>()
+                        .to_signer::<EcdsaSigner>(&key)
                         .await?;
 
                     let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
 
-                    let mut rpc = RpcClient::from_config(
-                        RpcConfig { connection: conn },
-                        ecdsa_signer.clone(),
+                    // Reconnects forever, one pause apart
+                    let rpc = RpcClient::from_config_with_retry(
+                        RpcConfig::new(conn),
+                        ecdsa_signer,
+                        RetryPolicy {
+                            max_retries: None,
+                            backoff: pause,
+                            max_backoff: pause,
+                            ..Default::default()
+                        },
                     )
                     .await?;
 
@@ -247,26 +568,6 @@ async fn run() -> Result<(), Error> {
                             Ok(proof_request_id) => {
                                 info!(id=?proof_request_id.encode_hex_with_prefix(), "Proof request #{nonce} sent!")
                             }
-                            Err(RpcClientError::Rpc(
-                                jsonrpsee::core::ClientError::RestartNeeded(_),
-                            )) => {
-                                warn!("Disconnected from the matchmaker");
-                                // Reconnect to the matchmaker
-                                loop {
-                                    tokio::time::sleep(pause).await;
-                                    let Ok(maybe_rpc) = RpcClient::from_config(
-                                        RpcConfig { connection: conn },
-                                        ecdsa_signer.clone(),
-                                    )
-                                    .await
-                                    else {
-                                        continue;
-                                    };
-                                    info!("Reconnected to the matchmaker");
-                                    rpc = maybe_rpc;
-                                    break;
-                                }
-                            }
                             Err(err) => {
                                 error!(?err, "Failed to send proof request over RPC");
                             }
@@ -275,6 +576,106 @@ async fn run() -> Result<(), Error> {
                         tokio::time::sleep(pause).await;
                     }
                 }
+                ProofCommands::SubmitJobArray {
+                    profile_key,
+                    rpc,
+                    key,
+                    count,
+                    nonce: initial_nonce,
+                    inputs,
+                    overrides,
+                } => {
+                    let spinner = Spinner::new(1, "Sending job array", SpinnerTemplate::Default);
+
+                    t.with_spinner_layer(SpinnerLayer::new(
+                        StdoutTelemetry::default_fmt_layer(),
+                        spinner.clone(),
+                    ))
+                    .init();
+
+                    let ecdsa_signer = KeystoreFile::from_config(&key)
+                        .await?
+                        .to_signer::<EcdsaSigner>(&key)
+                        .await?;
+
+                    let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
+
+                    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+                    let proof_request =
+                        ProofRequest::from_profile(&config_dir, ProfileType::Proof, &profile_key)
+                            .await?;
+
+                    let mut all_overrides = match &inputs {
+                        Some(manifest) => overrides_from_manifest(manifest).await?,
+                        None => vec![],
+                    };
+                    all_overrides.extend(overrides);
+
+                    let nonce_file = config_dir
+                        .join(format!("{}net", profile_key.network))
+                        .join(NONCE_FILE);
+
+                    // Members differ at least by their nonces, so their IDs are unique
+                    let initial_nonce = match initial_nonce {
+                        Some(nonce) => nonce,
+                        None => read_nonce(&nonce_file).await,
+                    };
+
+                    let mut proof_requests = Vec::with_capacity(count);
+                    for index in 0..count {
+                        let member_overrides: Vec<_> =
+                            all_overrides.iter().map(|o| o.with_index(index)).collect();
+                        let mut member = apply_overrides(proof_request.clone(), &member_overrides)?;
+                        member.nonce = initial_nonce + index as u64;
+                        proof_requests.push(member);
+                    }
+
+                    let array_id =
+                        rpc.submit_job_array(proof_requests)
+                            .await
+                            .inspect_err(|_| {
+                                spinner.finish("Failed!", false);
+                            })?;
+                    write_nonce(&nonce_file, initial_nonce + count as u64).await;
+
+                    spinner.finish("Done!", true);
+
+                    print_var("job_array_id", array_id.encode_hex_with_prefix());
+                }
+                ProofCommands::JobArrayStatus {
+                    profile_key,
+                    rpc,
+                    key,
+                    id,
+                } => {
+                    t.with_filter("warn".into()).init();
+
+                    let ecdsa_signer = KeystoreFile::from_config(&key)
+                        .await?
+                        .to_signer::<EcdsaSigner>(&key)
+                        .await?;
+
+                    let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
+                    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+                    let array_id = SerializableHash::from_hex(id.clone())
+                        .with_context(|| format!("failed to parse job array ID {id}"))?;
+
+                    match rpc.get_job_array_status(array_id).await? {
+                        Some(status) => {
+                            print_var("total", status.total);
+                            print_var("proven", status.proven());
+                            print_var("rejected", status.rejected());
+                            print_var("cancelled", status.cancelled());
+                            print_var("pending", status.total - status.completed());
+                            for (reason, n) in &status.failures {
+                                print_var(&format!("failed: {reason}"), n);
+                            }
+                        }
+                        None => print_var("status", "unknown job array"),
+                    }
+                }
                 ProofCommands::CheckProofRequest {
                     profile_key,
                     rpc,
@@ -293,16 +694,17 @@ async fn run() -> Result<(), Error> {
 
                     let ecdsa_signer = KeystoreFile::from_config(&key)
                         .await?
-                        .to_signer::<EcdsaSigner>()
+                        .to_signer::<EcdsaSigner>(&key)
                         .await?;
 
                     let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
-                    let rpc = RpcClient::from_config(RpcConfig { connection: conn }, ecdsa_signer)
-                        .await?;
+                    let rpc =
+                        RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer.clone()).await?;
 
-                    match SerializableHash::from_hex(id.clone()) {
+                    match ProofRequestId::from_hex(id.clone()) {
                         Ok(status_request) => {
-                            let status = rpc.check_request_status(status_request).await?;
+                            let report = rpc.check_request_status(status_request).await?;
+                            let status = report.status;
                             if status.is_final() {
                                 info!("Proof request is final");
                             }
@@ -310,7 +712,24 @@ async fn run() -> Result<(), Error> {
                             print_var("status", status.to_string());
 
                             match status {
-                                ProofStatus::Proven(proof) => {
+                                // Deleted past its retention, only the prover is left
+                                ProofStatus::Proven(_) if report.proof_deleted_at.is_some() => {
+                                    if let Some(deleted_at) = report.proof_deleted_at {
+                                        print_var("deleted", deleted_at);
+                                    }
+                                }
+                                ProofStatus::Proven(mut proof) => {
+                                    // Sealed to the result key of the request, the keystore's if it sent it
+                                    let mut opened = true;
+                                    if report.sealed {
+                                        match ecdsa_signer.open_result(&proof.proof) {
+                                            Ok(plaintext) => proof.proof = plaintext,
+                                            Err(err) => {
+                                                opened = false;
+                                                warn!(%err, "Failed to open the sealed proof, saving it as is")
+                                            }
+                                        }
+                                    }
                                     let dir = out_dir
                                         .map_or(app_home_dir().await?.join(PROOFS_DIR), |d| {
                                             d.into()
@@ -321,14 +740,45 @@ async fn run() -> Result<(), Error> {
                                     proof.to_json_path(&filepath).await?;
 
                                     print_var("proof", filepath.display());
+                                    // Saved in the clear, the matchmaker may delete it if the request asked so
+                                    if opened {
+                                        if let Err(err) =
+                                            rpc.acknowledge_proof(status_request).await
+                                        {
+                                            warn!(%err, "Failed to acknowledge the retrieval of the proof");
+                                        }
+                                    }
                                 }
                                 ProofStatus::Rejected(reason) => {
                                     print_var("reason", reason);
                                 }
+                                ProofStatus::Cancelled => {
+                                    if let Some(reason) = report.cancellation_reason {
+                                        print_var("reason", reason);
+                                    }
+                                }
                                 ProofStatus::AcknowledgedAssignment(op_id)
                                 | ProofStatus::Assigned(op_id) => {
                                     print_var("op_id", op_id.encode_hex_with_prefix());
                                 }
+                                ProofStatus::Created => {
+                                    if let Some(validation) =
+                                        rpc.get_image_validation(status_request).await?
+                                    {
+                                        print_var(
+                                            "images",
+                                            format!(
+                                                "{:?} ({}/{} verified)",
+                                                validation.state,
+                                                validation.verified,
+                                                validation.total
+                                            ),
+                                        );
+                                        if let Some(error) = validation.error {
+                                            print_var("image_error", error);
+                                        }
+                                    }
+                                }
                                 _ => {}
                             }
                         }
@@ -337,9 +787,109 @@ async fn run() -> Result<(), Error> {
                         }
                     }
                 }
+                ProofCommands::Export {
+                    profile_key,
+                    rpc,
+                    key,
+                    id,
+                    out_dir,
+                } => {
+                    t.with_filter("warn".into()).init();
+
+                    let ecdsa_signer = KeystoreFile::from_config(&key)
+                        .await?
+                        .to_signer::<EcdsaSigner>(&key)
+                        .await?;
+
+                    let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
+                    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+                    let proof_request_id = ProofRequestId::from_hex(id.clone())
+                        .with_context(|| format!("failed to parse proof request ID {id}"))?;
+
+                    match rpc.export_proof_request(proof_request_id).await? {
+                        Some(export) => {
+                            // Not saved, a bundle which doesn't match its signature proves nothing
+                            if export.verify_payload().is_err() {
+                                return Err(Error::InvalidExport(id));
+                            }
+
+                            let dir = out_dir
+                                .map_or(app_home_dir().await?.join(PROOFS_DIR), |d| d.into());
+                            ensure_dir(&dir, None).await?;
+
+                            let filepath = dir.join(format!("{}.export.json", id));
+                            export.to_json_path(&filepath).await?;
+
+                            print_var("events", export.payload.events.len());
+                            print_var("signer", export.public_key.encode_hex_with_prefix());
+                            print_var("export", filepath.display());
+                        }
+                        None => print_var("status", "unknown proof request"),
+                    }
+                }
             }
         }
 
+        ClientCommands::Contracts { contracts } => {
+            match contracts {
+                ContractCommands::Verify {
+                    chain_rpc,
+                    avs_profile,
+                    pin,
+                } => {
+                    t.with_filter("warn".into()).init();
+
+                    let mut profile = Profile::<fermah_avs::config::Config>::from_props(
+                        &config_dir,
+                        ProfileType::Avs,
+                        &avs_profile,
+                    )
+                    .await?;
+                    let provider = Provider::<Http>::try_from(chain_rpc.as_str())
+                        .context("failed to create provider")?;
+
+                    // Whatever the profile expected before, the codehashes found are pinned
+                    if pin {
+                        profile.config.codehashes.clear();
+                    }
+                    let verified = verify_contracts(&profile.config, &provider).await?;
+                    for contract in &verified {
+                        match contract.implementation {
+                            Some(implementation) => {
+                                print_var(
+                                    &contract.field,
+                                    format!(
+                                        "{:?} -> {:?} {:?}",
+                                        contract.address, implementation, contract.codehash
+                                    ),
+                                )
+                            }
+                            None => {
+                                print_var(
+                                    &contract.field,
+                                    format!("{:?} {:?}", contract.address, contract.codehash),
+                                )
+                            }
+                        }
+                    }
+
+                    if pin {
+                        // The codehashes known for the network are checked anyway, only the others are pinned
+                        let known = known_codehashes(profile.config.chain_id);
+                        profile.config.codehashes = verified
+                            .into_iter()
+                            .filter(|contract| {
+                                !known.is_some_and(|known| known.contains_key(&contract.field))
+                            })
+                            .map(|contract| (contract.field, contract.codehash))
+                            .collect();
+                        profile.save().await?;
+                        print_var("pinned", profile.config.codehashes.len());
+                    }
+                }
+            }
+        }
         ClientCommands::Deposit {
             chain_rpc,
             rpc,
@@ -361,7 +911,7 @@ async fn run() -> Result<(), Error> {
 
             let ecdsa_signer = KeystoreFile::from_config(&key)
                 .await?
-                .to_signer::<EcdsaSigner>()
+                .to_signer::<EcdsaSigner>(&key)
                 .await?;
 
             let client_contracts =
@@ -369,11 +919,11 @@ async fn run() -> Result<(), Error> {
 
             #[cfg(feature = "mint_vault_token")]
             {
-                let ecdsa_signer_minter =
-                    KeystoreFile::from_config(&KeystoreConfig { key: minter_key })
-                        .await?
-                        .to_signer::<EcdsaSigner>()
-                        .await?;
+                let minter_key = KeystoreConfig::new(minter_key);
+                let ecdsa_signer_minter = KeystoreFile::from_config(&minter_key)
+                    .await?
+                    .to_signer::<EcdsaSigner>(&minter_key)
+                    .await?;
 
                 let minter_contracts =
                     Contracts::from_config(&avs, &chain_rpc, ecdsa_signer_minter).await?;
@@ -413,7 +963,7 @@ async fn run() -> Result<(), Error> {
             };
 
             let conn = rpc.unwrap_or_else(|| avs_profile.network.to_mm_rpc());
-            RpcClient::from_config(RpcConfig { connection: conn }, ecdsa_signer)
+            RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer)
                 .await?
                 .update_balance()
                 .await?;
@@ -428,10 +978,10 @@ async fn run() -> Result<(), Error> {
             let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
 
             RpcClient::from_config(
-                RpcConfig { connection: conn },
+                RpcConfig::new(conn),
                 KeystoreFile::from_config(&key)
                     .await?
-                    .to_signer::<EcdsaSigner>()
+                    .to_signer::<EcdsaSigner>(&key)
                     .await?,
             )
             .await?
@@ -446,21 +996,134 @@ async fn run() -> Result<(), Error> {
             let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
 
             RpcClient::from_config(
-                RpcConfig { connection: conn },
+                RpcConfig::new(conn),
                 KeystoreFile::from_config(&key)
                     .await?
-                    .to_signer::<EcdsaSigner>()
+                    .to_signer::<EcdsaSigner>(&key)
                     .await?,
             )
             .await?
             .return_unspent()
             .await?;
         }
+        ClientCommands::Payments {
+            profile_key,
+            rpc,
+            key,
+            out,
+            after,
+            all,
+        } => {
+            let conn = rpc.unwrap_or_else(|| profile_key.network.to_mm_rpc());
+            let signer = KeystoreFile::from_config(&key)
+                .await?
+                .to_signer::<EcdsaSigner>(&key)
+                .await?;
+            let requester = (!all).then(|| signer.verifying_key());
+            let client = RpcClient::from_config(RpcConfig::new(conn), signer).await?;
+
+            let mut writer: Box<dyn Write> = match &out {
+                Some(out) => Box::new(BufWriter::new(File::create(out)?)),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            writeln!(writer, "{}", PaymentEvent::CSV_HEADER)?;
+
+            let mut cursor = after;
+            let mut exported = 0;
+            loop {
+                let page = client
+                    .get_payment_events(PaymentEventQuery {
+                        requester,
+                        after: cursor,
+                        limit: MAX_PAYMENT_EVENTS_PAGE,
+                    })
+                    .await?;
+                for event in &page.events {
+                    writeln!(writer, "{}", event.to_csv_row())?;
+                }
+                exported += page.events.len();
+                if page.next_cursor == cursor {
+                    break;
+                }
+                cursor = page.next_cursor;
+            }
+            writer.flush()?;
+
+            if let Some(out) = out {
+                print_var("file", out.display());
+            }
+            // Kept for the next export, on stderr not to mix with the printed CSV
+            eprintln!("Exported {exported} payment events, continue with --after {cursor}");
+        }
+        ClientCommands::SupportBundle {
+            network,
+            rpc,
+            chain_rpc,
+            database_url,
+            logs,
+            log_lines,
+            out,
+        } => {
+            t.with_filter("warn".into()).init();
+
+            let mut bundle = SupportBundle::default();
+            bundle.add_profiles(&config_dir).await;
+            for log in &logs {
+                bundle.add_log(log, log_lines);
+            }
+
+            let mut checks = vec![];
+            if let Some(conn) = rpc.or_else(|| network.map(|network| network.to_mm_rpc())) {
+                checks.push(SupportCheck::matchmaker(conn).await);
+            }
+            if let Some(chain_rpc) = &chain_rpc {
+                checks.push(SupportCheck::chain(chain_rpc).await);
+            }
+            if let Some(database_url) = &database_url {
+                checks.push(SupportCheck::database(database_url).await);
+            }
+            for check in &checks {
+                let outcome = if check.ok { "ok" } else { "FAILED" };
+                print_var(&check.name, format!("{outcome} {}", check.detail));
+            }
+
+            let out = bundle.write(checks, &out.unwrap_or_else(SupportBundle::default_path))?;
+            print_var("file", out.display());
+        }
     }
 
     Ok(())
 }
 
+/// Registry record of the image version, of its latest version for `latest`, verified to be signed by the publisher
+async fn resolve_image(
+    conn: Connection,
+    key: &KeystoreConfig,
+    name: &str,
+    version: &str,
+    publisher: Address,
+) -> Result<ImageRecord, Error> {
+    let ecdsa_signer = KeystoreFile::from_config(key)
+        .await?
+        .to_signer::<EcdsaSigner>(key)
+        .await?;
+    let rpc = RpcClient::from_config(RpcConfig::new(conn), ecdsa_signer).await?;
+
+    let reference = format!("{name}:{version}");
+    let version = (version != "latest").then(|| version.to_string());
+    let published = rpc
+        .resolve_image(name.to_string(), version)
+        .await?
+        .ok_or_else(|| Error::UnknownImage(reference))?;
+
+    let record = published.verify(publisher)?.clone();
+    if record.name != name {
+        return Err(ImageRecordError::InvalidName(record.name).into());
+    }
+    print_var("publisher", format!("{publisher:?}"));
+    Ok(record)
+}
+
 async fn download_file(url: &Url, filepath: &Path) -> Result<(), Error> {
     let spinner = Spinner::new(1, "Downloading image", SpinnerTemplate::Progress);
 
@@ -484,14 +1147,12 @@ async fn download_file(url: &Url, filepath: &Path) -> Result<(), Error> {
     Ok(())
 }
 
-#[cfg(feature = "send_proof_requests")]
 async fn write_nonce(nonce_file: &PathBuf, nonce: u64) {
     if let Err(err) = tokio::fs::write(nonce_file, nonce.to_ne_bytes()).await {
         warn!(?err, ?nonce, "failed to update nonce file");
     }
 }
 
-#[cfg(feature = "send_proof_requests")]
 async fn read_nonce(nonce_file: &PathBuf) -> u64 {
     let Ok(maybe_nonce) = tokio::fs::read(nonce_file).await else {
         return <_>::default();
@@ -1,23 +1,32 @@
+use std::path::PathBuf;
 #[cfg(feature = "send_proof_requests")]
 use std::time::Duration;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueHint};
 use ethers::{prelude::U256, types::Address};
 use fermah_common::{
+    cli::completions::Shell,
     crypto::keystore::KeystoreConfig,
     proof::request::ProofRequest,
-    types::network::Connection,
+    types::{
+        network::{Connection, Network},
+        payment::PaymentCursor,
+        region::Region,
+    },
 };
 use fermah_config::{
     keystore::command::KeyCommands,
     profile::{
         command::{MergableArgs, ProfileCommands},
         key::ProfileKey,
+        overrides::Override,
     },
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::support::DEFAULT_LOG_LINES;
+
 /// Arguments for proof request configuration
 #[derive(Serialize, Deserialize, Parser, Debug)]
 pub struct ProofRequestProfileArgs {}
@@ -40,19 +49,43 @@ pub enum ConfigCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ContractCommands {
+    /// Check every contract address of the AVS profile holds the expected contract on the chain
+    Verify {
+        /// Chain RPC connection
+        #[arg(long, default_value = "http://127.0.0.1:8545")]
+        chain_rpc: Url,
+        #[command(flatten)]
+        avs_profile: ProfileKey,
+        /// Record the codehashes found in the profile, the next verifications expect them. Trusted on first use,
+        /// the codehashes known for the network aren't pinned and are checked whatever the profile says.
+        #[arg(long)]
+        pin: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ImageCommands {
     /// Serve images from local directory
     Serve {
         /// File directory, defaults to ~/.fermah/images
-        #[arg(long)]
+        #[arg(long, value_hint = ValueHint::DirPath)]
         dir: Option<String>,
 
         /// Port to serve image on
         #[arg(long, default_value = "3000")]
         port: u16,
+
+        /// Compress the images with zstd for the operators accepting it
+        #[arg(long)]
+        zstd: bool,
+
+        /// Images sent at once, the other operators are asked to retry later. Unlimited if not set
+        #[arg(long)]
+        max_downloads: Option<usize>,
     },
-    /// Download image from remote URL and set it to a proof request
+    /// Download image from remote URL, or as published in the image registry, and set it to a proof request
     Download {
         /// Docker image name
         #[arg(long)]
@@ -62,14 +95,26 @@ pub enum ImageCommands {
         #[arg(long, default_value = "latest")]
         version: String,
 
-        /// URL to download image from
+        /// URL to download image from, the image is resolved from the matchmaker's registry by name and version if
+        /// not set
         #[arg(long)]
-        from: String,
+        from: Option<String>,
 
         /// URL to set image fetch to, optional, defaults to "from" url
         #[arg(long)]
         url: Option<String>,
 
+        /// Publisher the registry record of the image must be signed by, the registry itself isn't trusted
+        #[arg(long, conflicts_with = "from", required_unless_present = "from")]
+        publisher: Option<Address>,
+
+        /// Matchmaker RPC connection of the registry
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+
+        #[command(flatten)]
+        key: KeystoreConfig,
+
         /// Set this image as prover
         #[arg(long, default_value_t = true)]
         prover: bool,
@@ -78,6 +123,58 @@ pub enum ImageCommands {
         #[arg(long, default_value_t = true)]
         verifier: bool,
 
+        #[command(flatten)]
+        proof_request_profile: ProfileKey,
+    },
+    /// Publish a version of an image to the matchmaker's registry, signed by the key. The first publisher of a name
+    /// owns it, and a version can't change once published.
+    Publish {
+        /// Docker image name
+        #[arg(long)]
+        image_name: String,
+
+        /// Docker image version
+        #[arg(long)]
+        version: String,
+
+        /// Image file the hash is computed from
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// URL the image is served from
+        #[arg(long)]
+        url: Url,
+
+        /// Network of the matchmaker
+        #[arg(short = 'k', long)]
+        network: Network,
+
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+
+        #[command(flatten)]
+        key: KeystoreConfig,
+    },
+    /// Make the delta of an image against an earlier version, and set it to the images of a proof request which
+    /// download the new version. Operators having the earlier version download the delta instead.
+    Delta {
+        /// Earlier version of the image
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        base: PathBuf,
+
+        /// New version of the image
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        image: PathBuf,
+
+        /// File to write the delta to, defaults to the image path with a `.delta.zst` extension
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        out: Option<PathBuf>,
+
+        /// URL the delta is served from
+        #[arg(long)]
+        url: Url,
+
         #[command(flatten)]
         proof_request_profile: ProfileKey,
     },
@@ -95,6 +192,60 @@ pub enum ProofCommands {
         rpc: Option<Connection>,
         #[command(flatten)]
         key: KeystoreConfig,
+        /// Sign the request as EIP-712 typed data, as hardware wallets do
+        #[arg(long)]
+        eip712: bool,
+        /// JSON file mapping proof request paths to values, applied to the profile before `--set`
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        inputs: Option<PathBuf>,
+        /// Override a proof request value of the profile, e.g. `--set prover.in_mounts[0].target=/data`.
+        /// The value is parsed as JSON, or taken as a string
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<Override>,
+        /// ID of a proof request which has to be proven first, can be repeated
+        #[arg(long = "depends-on", value_name = "ID")]
+        depends_on: Vec<String>,
+        /// Prove the request independently with this many operators and cross-check their proofs
+        #[arg(long, value_name = "COPIES")]
+        redundancy: Option<u8>,
+        /// Copies which have to deliver the same proof, all of them by default
+        #[arg(long, value_name = "COPIES", requires = "redundancy")]
+        quorum: Option<u8>,
+        /// Prefer operators in this region or zone, e.g. `eu-west` or `eu-west/1a`, can be repeated
+        #[arg(long = "region", value_name = "REGION")]
+        regions: Vec<Region>,
+        /// Wait for an operator in one of the regions instead of falling back to any operator
+        #[arg(long, requires = "regions")]
+        require_region: bool,
+        /// Seal the proof to the key of the requester, only it can read the proof then, and it can't be disputed
+        #[arg(long, conflicts_with = "redundancy")]
+        seal_result: bool,
+        /// Days the matchmaker keeps the proof for once it's proven, its default retention if not set
+        #[arg(long, value_name = "DAYS")]
+        retention_days: Option<u16>,
+        /// Delete the proof from the matchmaker once `check-proof-request` retrieved it
+        #[arg(long, conflicts_with = "retention_days")]
+        delete_after_retrieval: bool,
+    },
+    /// Quote the proof request of the profile and show the deposit it needs, without sending it
+    Estimate {
+        #[command(flatten)]
+        profile_key: ProfileKey,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        #[command(flatten)]
+        key: KeystoreConfig,
+        /// JSON file mapping proof request paths to values, applied to the profile before `--set`
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        inputs: Option<PathBuf>,
+        /// Override a proof request value of the profile, e.g. `--set prover.in_mounts[0].target=/data`.
+        /// The value is parsed as JSON, or taken as a string
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<Override>,
+        /// Print the estimate as JSON
+        #[arg(long)]
+        json: bool,
     },
     #[cfg(feature = "send_proof_requests")]
     /// Send One Proof Request every N seconds
@@ -113,6 +264,44 @@ pub enum ProofCommands {
         #[arg(long, value_parser = humantime::parse_duration, default_value = "30s")]
         pause: Duration,
     },
+    /// Send N proof requests of the profile as one job array
+    #[command(alias = "array")]
+    SubmitJobArray {
+        #[command(flatten)]
+        profile_key: ProfileKey,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        #[command(flatten)]
+        key: KeystoreConfig,
+        /// Number of proof requests in the array
+        #[arg(long)]
+        count: usize,
+        /// Nonce of the first proof request, the following ones are incremented
+        #[arg(long)]
+        nonce: Option<u64>,
+        /// JSON file mapping proof request paths to values, applied to the profile before `--set`
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        inputs: Option<PathBuf>,
+        /// Override a proof request value of the profile, `{index}` in string values is replaced with the member
+        /// index, e.g. `--set prover.in_mounts[0].target=/data/{index}.bin`
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<Override>,
+    },
+    /// Check the aggregated status of a submitted job array
+    #[command(alias = "array-status")]
+    JobArrayStatus {
+        #[command(flatten)]
+        profile_key: ProfileKey,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        #[command(flatten)]
+        key: KeystoreConfig,
+        /// Job array ID
+        #[arg(long)]
+        id: String,
+    },
     /// Check submitted Proof Request's status
     #[command(alias = "check")]
     CheckProofRequest {
@@ -127,13 +316,35 @@ pub enum ProofCommands {
         #[arg(long)]
         id: String,
         /// Output directory
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        out_dir: Option<String>,
+    },
+    /// Export everything the matchmaker recorded about a proof request as a signed JSON bundle, admin keys only
+    Export {
+        #[command(flatten)]
+        profile_key: ProfileKey,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        #[command(flatten)]
+        key: KeystoreConfig,
+        /// Proof request ID
         #[arg(long)]
+        id: String,
+        /// Output directory
+        #[arg(long, value_hint = ValueHint::DirPath)]
         out_dir: Option<String>,
     },
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ClientCommands {
+    /// Set up a key, a proof profile and check the balance, step by step
+    Init {
+        /// Network to set up, prompted for if not set
+        #[arg(short = 'k', long)]
+        network: Option<Network>,
+    },
     /// Setup CLI configuration
     #[command(alias = "cfg")]
     Config {
@@ -154,6 +365,11 @@ pub enum ClientCommands {
         #[command(subcommand)]
         proofs: ProofCommands,
     },
+    /// Chain contracts of the AVS profile
+    Contracts {
+        #[command(subcommand)]
+        contracts: ContractCommands,
+    },
     /// Deposit into the AVS vault
     Deposit {
         /// Matchmaker RPC connection
@@ -202,4 +418,53 @@ pub enum ClientCommands {
         #[command(flatten)]
         key: KeystoreConfig,
     },
+    /// Export the payment events of the ledger as CSV, for accounting
+    Payments {
+        #[command(flatten)]
+        profile_key: ProfileKey,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        #[command(flatten)]
+        key: KeystoreConfig,
+        /// CSV file to write, printed if not set
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        out: Option<PathBuf>,
+        /// Cursor printed by the previous export, to export only the events since
+        #[arg(long, default_value = "0.0")]
+        after: PaymentCursor,
+        /// Events of every requester instead of the signer's, admin only
+        #[arg(long)]
+        all: bool,
+    },
+    /// Gather redacted config profiles, recent logs, version info and connectivity checks into a tarball to attach
+    /// to a support request. Secrets are scrubbed, keystores aren't included
+    SupportBundle {
+        /// Network whose matchmaker is checked, not checked if neither it nor `--rpc` is set
+        #[arg(short = 'k', long)]
+        network: Option<Network>,
+        /// Matchmaker RPC connection
+        #[arg(long, value_parser = Connection::try_from_str)]
+        rpc: Option<Connection>,
+        /// Chain RPC connection, not checked if not set
+        #[arg(long)]
+        chain_rpc: Option<Url>,
+        /// Database URL of a self-hosted matchmaker, not checked if not set
+        #[arg(long)]
+        database_url: Option<Url>,
+        /// Log file to include the last lines of, can be repeated
+        #[arg(long = "log", value_hint = ValueHint::FilePath)]
+        logs: Vec<PathBuf>,
+        /// Lines kept from the end of each log file
+        #[arg(long, default_value_t = DEFAULT_LOG_LINES)]
+        log_lines: usize,
+        /// Tarball to write, defaults to `seek-support-<timestamp>.tar.zst` in the current directory
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        out: Option<PathBuf>,
+    },
+    /// Print the shell completion script, e.g. `seek completions zsh > ~/.zfunc/_seek`
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
 }
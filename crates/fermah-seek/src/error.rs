@@ -16,16 +16,28 @@ pub enum Error {
     RpcClient(#[from] fermah_rpc::rpc_client::RpcClientError),
     #[error("contract error: {0}")]
     Contract(#[from] ethers_contract::ContractError<SignerMiddlewareContract>),
+    #[error("contract verification failed: {0}")]
+    ContractVerification(#[from] fermah_avs::contract::verify::VerifyError),
     #[error("keystore error: {0}")]
     Keystore(#[from] fermah_config::keystore::error::Error),
     #[error("keystore file error: {0}")]
     KeystoreFile(#[from] fermah_common::crypto::keystore::KeystoreFileError),
     #[error("file download error: {0}")]
     FileDownload(#[from] fermah_common::http::file_download::FileDownloadError),
+    #[error("invalid proof request: {0}")]
+    ProofRequest(#[from] fermah_common::proof::builder::ProofRequestBuilderError),
     #[error("file already exists: {0}")]
     FileExists(PathBuf),
     #[error("invalid file url")]
     InvalidFileUrl,
+    #[error("invalid image record: {0}")]
+    ImageRecord(#[from] fermah_common::image_registry::ImageRecordError),
+    #[error("image {0} isn't published in the registry")]
+    UnknownImage(String),
+    #[error("the publisher of a registry image must be given")]
+    NoPublisher,
+    #[error("export of proof request {0} doesn't match its signature")]
+    InvalidExport(String),
 
     #[error("error: {0}")]
     Other(#[from] anyhow::Error),
@@ -0,0 +1,68 @@
+use ethers::types::U256;
+use fermah_common::types::{balance::RequesterBalance, fee::FeeQuote};
+use serde::Serialize;
+
+/// What a proof request would cost against the requester's balance, and what has to be deposited to afford it
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositEstimate {
+    /// Price quoted for the request, commission included
+    pub price: U256,
+    pub fee_bps: u16,
+    /// Vault deposit, as last synced by the matchmaker
+    pub deposit: U256,
+    /// Funds locked by the requester's other proof requests
+    pub reserved: U256,
+    pub spendable: U256,
+    /// Deposit still needed for the price to be reserved, zero if the request is affordable
+    pub top_up: U256,
+}
+
+impl DepositEstimate {
+    pub fn new(quote: &FeeQuote, balance: &RequesterBalance) -> Self {
+        Self {
+            price: quote.price,
+            fee_bps: quote.fee_bps,
+            deposit: balance.deposit,
+            reserved: balance.reserved,
+            spendable: balance.spendable,
+            top_up: quote.price.saturating_sub(balance.spendable),
+        }
+    }
+
+    pub fn is_affordable(&self) -> bool {
+        self.top_up.is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_up() {
+        let quote = FeeQuote::new(U256::from(100), 250);
+
+        let enough = DepositEstimate::new(
+            &quote,
+            &RequesterBalance::new(U256::from(500), U256::from(400)),
+        );
+        assert_eq!(enough.top_up, U256::zero());
+        assert!(enough.is_affordable());
+
+        let short = DepositEstimate::new(
+            &quote,
+            &RequesterBalance::new(U256::from(500), U256::from(460)),
+        );
+        assert_eq!(short.spendable, U256::from(40));
+        assert_eq!(short.top_up, U256::from(60));
+        assert!(!short.is_affordable());
+
+        // Over-reserved balances can't go below zero
+        let over = DepositEstimate::new(
+            &quote,
+            &RequesterBalance::new(U256::from(10), U256::from(20)),
+        );
+        assert_eq!(over.top_up, quote.price);
+    }
+}
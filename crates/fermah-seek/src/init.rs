@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use fermah_common::{
+    cli::{
+        prompts::{
+            print_var,
+            prompt_for_choice,
+            prompt_for_confirmation,
+            prompt_for_input,
+            prompt_for_secret,
+        },
+        spinner::{Spinner, SpinnerTemplate},
+    },
+    crypto::{
+        keystore::{KeystoreConfig, KeystoreFile, KEYS_DIR},
+        signer::{ecdsa::EcdsaSigner, SignerType},
+    },
+    fs::app_home_dir,
+    proof::request::ProofRequest,
+    types::{balance::RequesterBalance, network::Network},
+};
+use fermah_config::{
+    keystore::{command::KeyCommands, KeyArgs, PasswordArgs},
+    profile::{command::ProfileCommands, key::ProfileKey, Profile, ProfileType},
+    Profiles,
+};
+use fermah_rpc::{rpc_client::RpcClient, RpcConfig};
+use tracing::warn;
+
+use crate::{command::ProofRequestProfileArgs, error::Error};
+
+const KEY_SOURCES: [&str; 3] = [
+    "Generate a new key",
+    "Import a hex private key",
+    "Import a Web3 keystore file (geth, clef, eth-keystore)",
+];
+
+/// First-run setup of `seek init`: walks through the key, the network, the default proof profile and the balance
+/// in the order the other commands need them. Steps already done are kept, so it can be run again.
+pub async fn run_wizard(network: Option<Network>, config_dir: &Path) -> Result<(), Error> {
+    println!("Welcome to seek! Let's get you ready to send proof requests.");
+
+    let network = match network {
+        Some(network) => network,
+        None => {
+            let networks = Network::value_variants();
+            let i = prompt_for_choice("Which network do you want to use?", networks)?;
+            networks[i].clone()
+        }
+    };
+    print_var("network", &network);
+
+    let key = setup_key().await?;
+    let profile = setup_profile(&network, config_dir).await?;
+
+    if prompt_for_confirmation(
+        &format!("Check the balance of the key on the {network} matchmaker?"),
+        true,
+    )? {
+        check_balance(&network, &key).await?;
+    }
+
+    println!("\nAll set! Send a proof request with:");
+    println!(
+        "  seek proof send -k {network} -n {} --key {}",
+        profile.name, key.key
+    );
+    Ok(())
+}
+
+/// Picks an existing key, or creates it from one of the [`KEY_SOURCES`]
+async fn setup_key() -> Result<KeystoreConfig, Error> {
+    let name = prompt_for_input("Name of your key", "default")?;
+    let key_file = app_home_dir()
+        .await?
+        .join(KEYS_DIR)
+        .join(format!("{name}.key.json"));
+    if key_file.exists() {
+        print_var("key", key_file.display());
+        return Ok(KeystoreConfig::new(name));
+    }
+
+    let pw = PasswordArgs {
+        password_stdin: false,
+        no_pw_confirm: false,
+        no_password: false,
+        #[cfg(feature = "keychain")]
+        keychain: false,
+        fast: false,
+    };
+    let command = match prompt_for_choice(
+        &format!("There is no key named {name}, how do you want to create it?"),
+        &KEY_SOURCES,
+    )? {
+        0 => {
+            KeyCommands::Gen {
+                pw,
+                key_type: SignerType::ECDSA,
+                name: name.clone(),
+            }
+        }
+        1 => {
+            KeyCommands::Import {
+                key: KeyArgs {
+                    private_key: prompt_for_secret(
+                        "Enter the hex private key, or the path of a file containing it",
+                    )?,
+                    key_type: SignerType::ECDSA,
+                    pw,
+                },
+                name: name.clone(),
+            }
+        }
+        _ => {
+            KeyCommands::ImportKeystore {
+                keystore: prompt_for_input("Path of the keystore JSON file", "keystore.json")?
+                    .into(),
+                keystore_password_file: None,
+                pw,
+                name: name.clone(),
+            }
+        }
+    };
+    command.run().await?;
+
+    Ok(KeystoreConfig::new(name))
+}
+
+/// Creates the proof profile from one of the templates of the network, unless it exists already
+async fn setup_profile(network: &Network, config_dir: &Path) -> Result<ProfileKey, Error> {
+    let profile = ProfileKey {
+        network: network.clone(),
+        name: prompt_for_input("Name of your proof profile", "default")?,
+    };
+    if let Ok(existing) =
+        Profile::<ProofRequest>::from_props(config_dir, ProfileType::Proof, &profile).await
+    {
+        print_var("profile", &existing);
+        return Ok(profile);
+    }
+
+    let templates =
+        match Profiles::<ProofRequest>::from_dir(config_dir, network, &ProfileType::Proof).await {
+            Ok(profiles) => {
+                let mut templates: Vec<String> =
+                    profiles.index.into_keys().map(|key| key.name).collect();
+                templates.sort();
+                templates
+            }
+            Err(err) => {
+                warn!(%err, "failed to load the proof profile templates");
+                vec![]
+            }
+        };
+    if templates.is_empty() {
+        warn!("No proof profile template for {network}, create one with `seek config proof set`");
+        return Ok(profile);
+    }
+
+    let i = prompt_for_choice(
+        &format!(
+            "Which template should the {} profile start from?",
+            profile.name
+        ),
+        &templates,
+    )?;
+    ProfileCommands::Set {
+        template: templates[i].clone(),
+        profile: profile.clone(),
+        config: ProofRequestProfileArgs {},
+    }
+    .run(ProfileType::Proof, config_dir)
+    .await?;
+
+    Ok(profile)
+}
+
+/// Prints the deposit of the key, synced with the vault first, and how to fund it when nothing can be spent
+async fn check_balance(network: &Network, key: &KeystoreConfig) -> Result<(), Error> {
+    let signer = KeystoreFile::from_config(key)
+        .await?
+        .to_signer::<EcdsaSigner>(key)
+        .await?;
+
+    let spinner = Spinner::new(1, "Checking balance", SpinnerTemplate::Default);
+    let balance = fetch_balance(network, signer).await;
+    spinner.finish(
+        if balance.is_ok() { "Done!" } else { "Failed" },
+        balance.is_ok(),
+    );
+
+    match balance {
+        Ok(balance) => {
+            print_var("deposit", balance.deposit);
+            print_var("reserved", balance.reserved);
+            print_var("spendable", balance.spendable);
+            if balance.spendable.is_zero() {
+                println!(
+                    "\nNothing to spend yet, deposit into the vault with:\n  seek deposit -k {network} --key {} --amount <AMOUNT>",
+                    key.key
+                );
+            }
+        }
+        // The setup is done anyway, the matchmaker may just be unreachable for now
+        Err(err) => warn!(%err, "failed to check the balance"),
+    }
+    Ok(())
+}
+
+async fn fetch_balance(network: &Network, signer: EcdsaSigner) -> Result<RequesterBalance, Error> {
+    let rpc = RpcClient::from_config(RpcConfig::new(network.to_mm_rpc()), signer).await?;
+    rpc.update_balance().await?;
+    Ok(rpc.get_balance().await?)
+}
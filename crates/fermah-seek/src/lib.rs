@@ -1,5 +1,8 @@
 pub mod command;
 pub mod error;
+pub mod estimate;
+pub mod init;
+pub mod support;
 
 pub const IMAGES_DIR: &str = "images";
 pub const PROOFS_DIR: &str = "proofs";
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Clock of the simulation, it only moves when advanced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FakeClock {
+    now: DateTime<Utc>,
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new(DateTime::UNIX_EPOCH)
+    }
+}
+
+impl FakeClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: start }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    pub fn advance(&mut self, by: Duration) {
+        self.now += by;
+    }
+
+    /// Moves the clock to `at`, the clock never goes backwards
+    pub fn advance_to(&mut self, at: DateTime<Utc>) {
+        self.now = self.now.max(at);
+    }
+}
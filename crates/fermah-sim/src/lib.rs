@@ -0,0 +1,12 @@
+//! Deterministic simulation of proof request matching.
+//!
+//! Proof requests are matched to in-memory operator agents, which acknowledge, prove or fail their jobs as scripted,
+//! while a fake clock drives assignment timeouts. No database, chain or real operators are involved, so matching
+//! strategies and timeout policies can be checked end-to-end with [`scenario::Scenario`].
+
+pub mod clock;
+pub mod operator;
+pub mod policy;
+pub mod scenario;
+pub mod simulation;
+pub mod strategy;
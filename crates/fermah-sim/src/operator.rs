@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use chrono::Duration;
+use fermah_common::{operator::OperatorId, types::region::Region};
+
+/// What an operator does with an assigned job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobBehavior {
+    /// Acknowledges after `ack_after` and delivers a valid proof `prove_after` later
+    Complete {
+        ack_after: Duration,
+        prove_after: Duration,
+    },
+    /// Acknowledges after `ack_after` and delivers a proof failing verification `prove_after` later
+    InvalidProof {
+        ack_after: Duration,
+        prove_after: Duration,
+    },
+    /// Declines the assignment after `after`
+    Decline { after: Duration },
+    /// Acknowledges after `ack_after`, but never delivers
+    Vanish { ack_after: Duration },
+    /// Never answers
+    Ignore,
+}
+
+/// In-memory operator, working on its jobs as scripted
+#[derive(Debug, Clone)]
+pub struct OperatorAgent {
+    pub id: OperatorId,
+    /// Jobs worked on at once
+    pub capacity: usize,
+    /// Region the operator reports, if any
+    pub region: Option<Region>,
+    /// Behaviours for the next assigned jobs, in order
+    script: VecDeque<JobBehavior>,
+    /// Behaviour once the script is exhausted
+    fallback: JobBehavior,
+    active: usize,
+}
+
+impl OperatorAgent {
+    pub fn new(id: OperatorId, fallback: JobBehavior) -> Self {
+        Self {
+            id,
+            capacity: 1,
+            region: None,
+            script: VecDeque::new(),
+            fallback,
+            active: 0,
+        }
+    }
+
+    /// Operator which completes every job
+    pub fn honest(id: OperatorId, ack_after: Duration, prove_after: Duration) -> Self {
+        Self::new(
+            id,
+            JobBehavior::Complete {
+                ack_after,
+                prove_after,
+            },
+        )
+    }
+
+    pub fn with_script(mut self, script: impl IntoIterator<Item = JobBehavior>) -> Self {
+        self.script.extend(script);
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_region(mut self, region: Region) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn active_jobs(&self) -> usize {
+        self.active
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.active < self.capacity
+    }
+
+    /// Takes a job and returns what the operator does with it
+    pub(crate) fn take_job(&mut self) -> JobBehavior {
+        self.active += 1;
+        self.script.pop_front().unwrap_or(self.fallback)
+    }
+
+    pub(crate) fn release_job(&mut self) {
+        self.active = self.active.saturating_sub(1);
+    }
+}
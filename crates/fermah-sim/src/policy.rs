@@ -0,0 +1,22 @@
+use chrono::Duration;
+
+/// How long operators are given before their assignment is taken back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutPolicy {
+    /// Time to acknowledge an assignment, an unacknowledged assignment is reassigned without an offense
+    pub ack_timeout: Duration,
+    /// Time to deliver a proof after the acknowledgement, missing it is an offense
+    pub proving_timeout: Duration,
+    /// Assignments of a request before it is rejected
+    pub max_attempts: usize,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            ack_timeout: Duration::seconds(30),
+            proving_timeout: Duration::minutes(10),
+            max_attempts: 3,
+        }
+    }
+}
@@ -0,0 +1,464 @@
+use chrono::Duration;
+use ethers::types::U256;
+use fermah_common::{
+    operator::{offense::Offense, OperatorId},
+    proof::{request::ProofRequestId, status::ProofStatus},
+};
+use thiserror::Error;
+
+use crate::{
+    operator::OperatorAgent,
+    policy::TimeoutPolicy,
+    simulation::{SimRequest, Simulation},
+    strategy::MatchingStrategy,
+};
+
+/// Expected state of the simulation once all events are handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// The request ends in the status, compared by its name, e.g. `Proven`
+    Status {
+        id: ProofRequestId,
+        status: &'static str,
+    },
+    /// The request is proven by the operator
+    ProvenBy {
+        id: ProofRequestId,
+        operator: OperatorId,
+    },
+    /// The request was assigned to exactly these operators, in order
+    Assignments {
+        id: ProofRequestId,
+        operators: Vec<OperatorId>,
+    },
+    /// The request reached a final status no later than `within` after the simulation start
+    FinalWithin {
+        id: ProofRequestId,
+        within: Duration,
+    },
+    /// The operator was paid exactly the amount
+    Paid { operator: OperatorId, amount: U256 },
+    /// The operator committed exactly these offenses, in order
+    Offenses {
+        operator: OperatorId,
+        offenses: Vec<Offense>,
+    },
+}
+
+#[derive(Error, Debug)]
+#[error("scenario \"{scenario}\" failed:\n{}", .failures.join("\n"))]
+pub struct ScenarioError {
+    pub scenario: String,
+    pub failures: Vec<String>,
+}
+
+/// Operators, requests and expectations of a simulation run
+pub struct Scenario<S> {
+    name: String,
+    simulation: Simulation<S>,
+    expectations: Vec<Expectation>,
+}
+
+impl<S: MatchingStrategy> Scenario<S> {
+    pub fn new(name: &str, strategy: S, policy: TimeoutPolicy) -> Self {
+        Self {
+            name: name.to_string(),
+            simulation: Simulation::new(strategy, policy),
+            expectations: vec![],
+        }
+    }
+
+    pub fn operator(mut self, operator: OperatorAgent) -> Self {
+        self.simulation.add_operator(operator);
+        self
+    }
+
+    /// Submits the request `after` the simulation start
+    pub fn request(mut self, request: SimRequest, after: Duration) -> Self {
+        self.simulation.submit(request, after);
+        self
+    }
+
+    pub fn expect(mut self, expectation: Expectation) -> Self {
+        self.expectations.push(expectation);
+        self
+    }
+
+    /// Runs the simulation to the end and checks all expectations, returning the simulation for further inspection
+    pub fn run(mut self) -> Result<Simulation<S>, ScenarioError> {
+        let start = self.simulation.now();
+        self.simulation.run();
+
+        let sim = &self.simulation;
+        let failures: Vec<String> = self
+            .expectations
+            .iter()
+            .filter_map(|expectation| {
+                match expectation {
+                    Expectation::Status { id, status } => {
+                        let actual = sim.status(id).map(ProofStatus::to_const_str);
+                        (actual != Some(*status))
+                            .then(|| format!("{id}: expected status {status}, got {actual:?}"))
+                    }
+                    Expectation::ProvenBy { id, operator } => {
+                        match sim.status(id) {
+                            Some(ProofStatus::Proven(proof)) if proof.prover == *operator => None,
+                            actual => {
+                                Some(format!(
+                                    "{id}: expected to be proven by {operator}, got {actual:?}"
+                                ))
+                            }
+                        }
+                    }
+                    Expectation::Assignments { id, operators } => {
+                        let actual = sim.job(id).map(|job| job.assignments.clone());
+                        (actual.as_ref() != Some(operators)).then(|| {
+                            format!("{id}: expected assignments {operators:?}, got {actual:?}")
+                        })
+                    }
+                    Expectation::FinalWithin { id, within } => {
+                        let finalized = sim.job(id).and_then(|job| {
+                            job.history
+                                .iter()
+                                .find(|(_, status)| status.is_final())
+                                .map(|(at, _)| *at - start)
+                        });
+                        match finalized {
+                            Some(after) if after <= *within => None,
+                            _ => {
+                                Some(format!(
+                                    "{id}: expected to be final within {within}, got {finalized:?}"
+                                ))
+                            }
+                        }
+                    }
+                    Expectation::Paid { operator, amount } => {
+                        let actual = sim.paid(operator);
+                        (actual != *amount).then(|| {
+                            format!("{operator}: expected to be paid {amount}, got {actual}")
+                        })
+                    }
+                    Expectation::Offenses { operator, offenses } => {
+                        let actual = sim.offenses(operator);
+                        (actual != *offenses).then(|| {
+                            format!("{operator}: expected offenses {offenses:?}, got {actual:?}")
+                        })
+                    }
+                }
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(self.simulation)
+        } else {
+            Err(ScenarioError {
+                scenario: self.name,
+                failures,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use ethers::types::Address;
+    use fermah_common::types::region::{PreferredRegions, Region};
+
+    use super::*;
+    use crate::{
+        operator::JobBehavior,
+        strategy::{LeastLoaded, RegionAware, RoundRobin},
+    };
+
+    fn request(n: u8) -> SimRequest {
+        SimRequest::new([n; 32].into(), U256::from(100))
+    }
+
+    fn operator_id() -> OperatorId {
+        Address::random().into()
+    }
+
+    #[test]
+    fn test_honest_operator_is_paid() {
+        let op = operator_id();
+        let pr = request(1);
+
+        Scenario::new("honest", RoundRobin::default(), TimeoutPolicy::default())
+            .operator(OperatorAgent::honest(
+                op,
+                Duration::seconds(1),
+                Duration::minutes(1),
+            ))
+            .request(pr.clone(), Duration::zero())
+            .expect(Expectation::ProvenBy {
+                id: pr.id,
+                operator: op,
+            })
+            .expect(Expectation::FinalWithin {
+                id: pr.id,
+                within: Duration::seconds(61),
+            })
+            .expect(Expectation::Paid {
+                operator: op,
+                amount: U256::from(100),
+            })
+            .expect(Expectation::Offenses {
+                operator: op,
+                offenses: vec![],
+            })
+            .run()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reassignment() {
+        let (ignoring, vanishing, cheating, honest) =
+            (operator_id(), operator_id(), operator_id(), operator_id());
+        let pr = request(2);
+        let policy = TimeoutPolicy {
+            max_attempts: 4,
+            ..Default::default()
+        };
+
+        // Candidates are equally loaded, so the first one which didn't fail the request is picked
+        let sim = Scenario::new("reassignment", LeastLoaded, policy)
+            .operator(OperatorAgent::new(ignoring, JobBehavior::Ignore))
+            .operator(OperatorAgent::new(
+                vanishing,
+                JobBehavior::Vanish {
+                    ack_after: Duration::seconds(1),
+                },
+            ))
+            .operator(OperatorAgent::new(
+                cheating,
+                JobBehavior::InvalidProof {
+                    ack_after: Duration::seconds(1),
+                    prove_after: Duration::seconds(1),
+                },
+            ))
+            .operator(OperatorAgent::honest(
+                honest,
+                Duration::seconds(1),
+                Duration::seconds(1),
+            ))
+            .request(pr.clone(), Duration::zero())
+            .expect(Expectation::Assignments {
+                id: pr.id,
+                operators: vec![ignoring, vanishing, cheating, honest],
+            })
+            .expect(Expectation::ProvenBy {
+                id: pr.id,
+                operator: honest,
+            })
+            // Not acknowledging isn't an offense, the assignment is just taken back
+            .expect(Expectation::Offenses {
+                operator: ignoring,
+                offenses: vec![],
+            })
+            .expect(Expectation::Offenses {
+                operator: vanishing,
+                offenses: vec![Offense::MissedDeadlineAfterAck],
+            })
+            .expect(Expectation::Offenses {
+                operator: cheating,
+                offenses: vec![Offense::InvalidProof],
+            })
+            .expect(Expectation::Paid {
+                operator: cheating,
+                amount: U256::zero(),
+            })
+            .run()
+            .unwrap();
+
+        for op in [ignoring, vanishing, cheating, honest] {
+            assert_eq!(sim.operator(&op).unwrap().active_jobs(), 0);
+        }
+    }
+
+    #[test]
+    fn test_rejected_after_max_attempts() {
+        let (a, b) = (operator_id(), operator_id());
+        let pr = request(3);
+        let policy = TimeoutPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        };
+
+        Scenario::new("max attempts", RoundRobin::default(), policy)
+            .operator(OperatorAgent::new(
+                a,
+                JobBehavior::Decline {
+                    after: Duration::seconds(1),
+                },
+            ))
+            .operator(OperatorAgent::new(b, JobBehavior::Ignore))
+            .request(pr.clone(), Duration::zero())
+            .expect(Expectation::Status {
+                id: pr.id,
+                status: "Rejected",
+            })
+            .expect(Expectation::FinalWithin {
+                id: pr.id,
+                within: Duration::seconds(31),
+            })
+            .run()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deadline() {
+        let op = operator_id();
+        let pr = request(4).with_deadline(DateTime::UNIX_EPOCH + Duration::minutes(5));
+
+        Scenario::new("deadline", RoundRobin::default(), TimeoutPolicy::default())
+            .operator(OperatorAgent::honest(
+                op,
+                Duration::seconds(1),
+                Duration::minutes(6),
+            ))
+            .request(pr.clone(), Duration::zero())
+            .expect(Expectation::Status {
+                id: pr.id,
+                status: "Rejected",
+            })
+            .expect(Expectation::Paid {
+                operator: op,
+                amount: U256::zero(),
+            })
+            .run()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_strategies() {
+        let (busy, idle) = (operator_id(), operator_id());
+        let (first, second) = (request(5), request(6));
+
+        let scenario = |name| {
+            Scenario::new(name, LeastLoaded, TimeoutPolicy::default())
+                .operator(
+                    OperatorAgent::honest(busy, Duration::seconds(1), Duration::minutes(1))
+                        .with_capacity(2),
+                )
+                .operator(OperatorAgent::honest(
+                    idle,
+                    Duration::seconds(1),
+                    Duration::minutes(1),
+                ))
+                .request(first.clone(), Duration::zero())
+                .request(second.clone(), Duration::seconds(1))
+        };
+
+        // The first request loads `busy`, so the second one goes to `idle`
+        scenario("least loaded")
+            .expect(Expectation::ProvenBy {
+                id: first.id,
+                operator: busy,
+            })
+            .expect(Expectation::ProvenBy {
+                id: second.id,
+                operator: idle,
+            })
+            .run()
+            .unwrap();
+
+        // A failing expectation reports every mismatch
+        let err = scenario("least loaded")
+            .expect(Expectation::ProvenBy {
+                id: second.id,
+                operator: busy,
+            })
+            .expect(Expectation::Paid {
+                operator: busy,
+                amount: U256::from(200),
+            })
+            .run()
+            .err()
+            .unwrap();
+        assert_eq!(err.failures.len(), 2);
+    }
+
+    #[test]
+    fn test_pending_until_operator_is_free() {
+        let op = operator_id();
+        let (first, second) = (request(7), request(8));
+
+        let sim = Scenario::new("queueing", RoundRobin::default(), TimeoutPolicy::default())
+            .operator(OperatorAgent::honest(
+                op,
+                Duration::seconds(1),
+                Duration::minutes(1),
+            ))
+            .request(first.clone(), Duration::zero())
+            .request(second.clone(), Duration::zero())
+            .expect(Expectation::Paid {
+                operator: op,
+                amount: U256::from(200),
+            })
+            .run()
+            .unwrap();
+
+        let second_assigned = sim
+            .job(&second.id)
+            .unwrap()
+            .history
+            .iter()
+            .find(|(_, status)| matches!(status, ProofStatus::Assigned(_)))
+            .map(|(at, _)| *at);
+        assert_eq!(
+            second_assigned,
+            Some(DateTime::UNIX_EPOCH + Duration::seconds(61))
+        );
+    }
+
+    #[test]
+    fn test_region_aware() {
+        let (us, eu) = (operator_id(), operator_id());
+        let region = |name| Region::new(name).unwrap();
+        let preferring = |name, required| {
+            PreferredRegions {
+                regions: vec![region(name)],
+                required,
+            }
+        };
+        let (local, anywhere, nowhere) = (
+            request(9).with_preferred_regions(preferring("eu-west", false)),
+            request(10).with_preferred_regions(preferring("ap-south", false)),
+            request(11).with_preferred_regions(preferring("ap-south", true)),
+        );
+
+        Scenario::new(
+            "regions",
+            RegionAware::new(LeastLoaded),
+            TimeoutPolicy::default(),
+        )
+        .operator(
+            OperatorAgent::honest(us, Duration::seconds(1), Duration::minutes(1))
+                .with_region(region("us-east/1a")),
+        )
+        .operator(
+            OperatorAgent::honest(eu, Duration::seconds(1), Duration::minutes(1))
+                .with_region(region("eu-west/1b")),
+        )
+        .request(local.clone(), Duration::zero())
+        .request(anywhere.clone(), Duration::seconds(1))
+        .request(nowhere.clone(), Duration::seconds(2))
+        // Picked over the less loaded operator of the first registered region
+        .expect(Expectation::ProvenBy {
+            id: local.id,
+            operator: eu,
+        })
+        // No operator in the region, falls back to the least loaded one
+        .expect(Expectation::ProvenBy {
+            id: anywhere.id,
+            operator: us,
+        })
+        .expect(Expectation::Assignments {
+            id: nowhere.id,
+            operators: vec![],
+        })
+        .run()
+        .unwrap();
+    }
+}
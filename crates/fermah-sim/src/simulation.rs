@@ -0,0 +1,449 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use fermah_common::{
+    hash::{blake3::Blake3Hasher, Hashable},
+    operator::{offense::Offense, OperatorId},
+    proof::{
+        request::{ProofRequest, ProofRequestId},
+        status::ProofStatus,
+        Proof,
+    },
+    types::region::PreferredRegions,
+};
+use tracing::debug;
+
+use crate::{
+    clock::FakeClock,
+    operator::{JobBehavior, OperatorAgent},
+    policy::TimeoutPolicy,
+    strategy::{Candidate, MatchingStrategy},
+};
+
+/// The parts of a proof request the matching depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimRequest {
+    pub id: ProofRequestId,
+    pub quote: U256,
+    pub deadline: Option<DateTime<Utc>>,
+    pub preferred_regions: Option<PreferredRegions>,
+}
+
+impl SimRequest {
+    pub fn new(id: ProofRequestId, quote: U256) -> Self {
+        Self {
+            id,
+            quote,
+            deadline: None,
+            preferred_regions: None,
+        }
+    }
+
+    pub fn with_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_preferred_regions(mut self, preferred_regions: PreferredRegions) -> Self {
+        self.preferred_regions = Some(preferred_regions);
+        self
+    }
+}
+
+impl From<&ProofRequest> for SimRequest {
+    fn from(value: &ProofRequest) -> Self {
+        Self {
+            id: value.hash::<Blake3Hasher>().into(),
+            quote: value.quote(),
+            deadline: value.deadline,
+            preferred_regions: value.preferred_regions.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Submit,
+    Ack { attempt: usize },
+    Decline { attempt: usize },
+    Deliver { attempt: usize, valid: bool },
+    AckTimeout { attempt: usize },
+    ProvingTimeout { attempt: usize },
+    Deadline,
+}
+
+/// Event due at `at`, events due at the same time are handled in the order they were scheduled
+#[derive(Debug)]
+struct Scheduled {
+    at: DateTime<Utc>,
+    seq: u64,
+    id: ProofRequestId,
+    event: Event,
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        (self.at, self.seq) == (other.at, other.seq)
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scheduled {
+    // Reversed, so the binary heap pops the earliest event first
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.at, other.seq).cmp(&(self.at, self.seq))
+    }
+}
+
+/// Proof request tracked by the simulation
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub request: SimRequest,
+    pub status: ProofStatus,
+    /// Status changes with the time they happened at
+    pub history: Vec<(DateTime<Utc>, ProofStatus)>,
+    /// Operators the request was assigned to, in order
+    pub assignments: Vec<OperatorId>,
+}
+
+impl Job {
+    fn attempt(&self) -> usize {
+        self.assignments.len()
+    }
+
+    fn set_status(&mut self, at: DateTime<Utc>, status: ProofStatus) {
+        debug!(id=?self.request.id, %status, "status change");
+        self.history.push((at, status.clone()));
+        self.status = status;
+    }
+}
+
+/// Matchmaker running on a fake clock against in-memory operators
+pub struct Simulation<S> {
+    clock: FakeClock,
+    strategy: S,
+    policy: TimeoutPolicy,
+    operators: Vec<OperatorAgent>,
+    jobs: HashMap<ProofRequestId, Job>,
+    /// Accepted requests waiting for an available operator, in arrival order
+    pending: VecDeque<ProofRequestId>,
+    queue: BinaryHeap<Scheduled>,
+    seq: u64,
+    payments: HashMap<OperatorId, U256>,
+    offenses: Vec<(OperatorId, Offense, ProofRequestId)>,
+}
+
+impl<S: MatchingStrategy> Simulation<S> {
+    pub fn new(strategy: S, policy: TimeoutPolicy) -> Self {
+        Self {
+            clock: FakeClock::default(),
+            strategy,
+            policy,
+            operators: vec![],
+            jobs: HashMap::new(),
+            pending: VecDeque::new(),
+            queue: BinaryHeap::new(),
+            seq: 0,
+            payments: HashMap::new(),
+            offenses: vec![],
+        }
+    }
+
+    pub fn with_clock(mut self, clock: FakeClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn add_operator(&mut self, operator: OperatorAgent) {
+        self.operators.push(operator);
+    }
+
+    /// Submits the request `after` the current time
+    pub fn submit(&mut self, request: SimRequest, after: Duration) {
+        let id = request.id;
+        let at = self.now() + after;
+        if let Some(deadline) = request.deadline {
+            self.schedule(deadline, id, Event::Deadline);
+        }
+        self.jobs.insert(
+            id,
+            Job {
+                request,
+                status: ProofStatus::Created,
+                history: vec![(at, ProofStatus::Created)],
+                assignments: vec![],
+            },
+        );
+        self.schedule(at, id, Event::Submit);
+    }
+
+    /// Handles the next event, returns `false` if there are none left
+    pub fn step(&mut self) -> bool {
+        let Some(Scheduled { at, id, event, .. }) = self.queue.pop() else {
+            return false;
+        };
+        self.clock.advance_to(at);
+        self.handle(id, event);
+        true
+    }
+
+    /// Runs until there are no events left
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Runs the events due until `until`, and moves the clock there
+    pub fn run_until(&mut self, until: DateTime<Utc>) {
+        while self.queue.peek().is_some_and(|next| next.at <= until) {
+            self.step();
+        }
+        self.clock.advance_to(until);
+    }
+
+    pub fn job(&self, id: &ProofRequestId) -> Option<&Job> {
+        self.jobs.get(id)
+    }
+
+    pub fn status(&self, id: &ProofRequestId) -> Option<&ProofStatus> {
+        self.jobs.get(id).map(|job| &job.status)
+    }
+
+    pub fn operator(&self, id: &OperatorId) -> Option<&OperatorAgent> {
+        self.operators.iter().find(|op| op.id == *id)
+    }
+
+    /// Total paid to the operator for proven requests
+    pub fn paid(&self, operator: &OperatorId) -> U256 {
+        self.payments.get(operator).copied().unwrap_or_default()
+    }
+
+    pub fn offenses(&self, operator: &OperatorId) -> Vec<Offense> {
+        self.offenses
+            .iter()
+            .filter(|(op, ..)| op == operator)
+            .map(|(_, offense, _)| *offense)
+            .collect()
+    }
+
+    fn schedule(&mut self, at: DateTime<Utc>, id: ProofRequestId, event: Event) {
+        self.seq += 1;
+        self.queue.push(Scheduled {
+            at,
+            seq: self.seq,
+            id,
+            event,
+        });
+    }
+
+    fn handle(&mut self, id: ProofRequestId, event: Event) {
+        let now = self.now();
+        let Some(job) = self.jobs.get_mut(&id) else {
+            return;
+        };
+        if job.status.is_final() {
+            return;
+        }
+
+        match event {
+            Event::Submit => {
+                job.set_status(now, ProofStatus::Accepted);
+                self.pending.push_back(id);
+                self.match_pending();
+            }
+            Event::Ack { attempt } => {
+                if attempt != job.attempt() {
+                    return;
+                }
+                if let ProofStatus::Assigned(op) = job.status {
+                    job.set_status(now, ProofStatus::AcknowledgedAssignment(op));
+                    self.schedule(
+                        now + self.policy.proving_timeout,
+                        id,
+                        Event::ProvingTimeout { attempt },
+                    );
+                }
+            }
+            Event::Decline { attempt } => {
+                if attempt != job.attempt() {
+                    return;
+                }
+                if let ProofStatus::Assigned(op) = job.status {
+                    self.reassign(id, op, None);
+                }
+            }
+            Event::Deliver { attempt, valid } => {
+                if attempt != job.attempt() {
+                    return;
+                }
+                if let ProofStatus::AcknowledgedAssignment(op) = job.status {
+                    if valid {
+                        let quote = job.request.quote;
+                        job.set_status(now, ProofStatus::Proven(Proof::new(vec![], op)));
+                        *self.payments.entry(op).or_default() += quote;
+                        self.release(op);
+                        self.match_pending();
+                    } else {
+                        self.reassign(id, op, Some(Offense::InvalidProof));
+                    }
+                }
+            }
+            Event::AckTimeout { attempt } => {
+                if attempt != job.attempt() {
+                    return;
+                }
+                if let ProofStatus::Assigned(op) = job.status {
+                    self.reassign(id, op, None);
+                }
+            }
+            Event::ProvingTimeout { attempt } => {
+                if attempt != job.attempt() {
+                    return;
+                }
+                if let ProofStatus::AcknowledgedAssignment(op) = job.status {
+                    self.reassign(id, op, Some(Offense::MissedDeadlineAfterAck));
+                }
+            }
+            Event::Deadline => {
+                let assigned = match job.status {
+                    ProofStatus::Assigned(op) | ProofStatus::AcknowledgedAssignment(op) => Some(op),
+                    _ => None,
+                };
+                job.set_status(now, ProofStatus::reject("deadline passed"));
+                self.pending.retain(|pending| *pending != id);
+                if let Some(op) = assigned {
+                    self.release(op);
+                    self.match_pending();
+                }
+            }
+        }
+    }
+
+    /// Takes the request back from the operator and queues it for another one
+    fn reassign(&mut self, id: ProofRequestId, operator: OperatorId, offense: Option<Offense>) {
+        let now = self.now();
+        if let Some(offense) = offense {
+            debug!(?id, ?offense, "operator offense");
+            self.offenses.push((operator, offense, id));
+        }
+        self.release(operator);
+
+        let max_attempts = self.policy.max_attempts;
+        if let Some(job) = self.jobs.get_mut(&id) {
+            if job.attempt() >= max_attempts {
+                job.set_status(
+                    now,
+                    ProofStatus::reject(format!("not proven after {max_attempts} assignments")),
+                );
+            } else {
+                job.set_status(now, ProofStatus::Accepted);
+                self.pending.push_front(id);
+            }
+        }
+        self.match_pending();
+    }
+
+    fn release(&mut self, operator: OperatorId) {
+        if let Some(op) = self.operators.iter_mut().find(|op| op.id == operator) {
+            op.release_job();
+        }
+    }
+
+    /// Assigns the pending requests, in order, while there are operators to take them
+    fn match_pending(&mut self) {
+        let mut waiting = VecDeque::new();
+
+        while let Some(id) = self.pending.pop_front() {
+            let Some(job) = self.jobs.get(&id) else {
+                continue;
+            };
+
+            let candidates: Vec<Candidate> = self
+                .operators
+                .iter()
+                .filter(|op| op.is_available() && !job.assignments.contains(&op.id))
+                .map(|op| {
+                    Candidate {
+                        id: op.id,
+                        active_jobs: op.active_jobs(),
+                        capacity: op.capacity,
+                        offenses: self.offenses.iter().filter(|(o, ..)| *o == op.id).count(),
+                        region: op.region.clone(),
+                    }
+                })
+                .collect();
+
+            match self.strategy.select(&job.request, &candidates) {
+                Some(operator) => self.assign(id, operator),
+                None => waiting.push_back(id),
+            }
+        }
+
+        self.pending = waiting;
+    }
+
+    fn assign(&mut self, id: ProofRequestId, operator: OperatorId) {
+        let now = self.now();
+        let Some(behavior) = self
+            .operators
+            .iter_mut()
+            .find(|op| op.id == operator)
+            .map(|op| op.take_job())
+        else {
+            return;
+        };
+        let Some(job) = self.jobs.get_mut(&id) else {
+            return;
+        };
+
+        job.assignments.push(operator);
+        job.set_status(now, ProofStatus::Assigned(operator));
+        let attempt = job.attempt();
+
+        // Operator events are scheduled first, so they win ties with the timeouts
+        match behavior {
+            JobBehavior::Complete {
+                ack_after,
+                prove_after,
+            }
+            | JobBehavior::InvalidProof {
+                ack_after,
+                prove_after,
+            } => {
+                let valid = matches!(behavior, JobBehavior::Complete { .. });
+                self.schedule(now + ack_after, id, Event::Ack { attempt });
+                self.schedule(
+                    now + ack_after + prove_after,
+                    id,
+                    Event::Deliver { attempt, valid },
+                );
+            }
+            JobBehavior::Decline { after } => {
+                self.schedule(now + after, id, Event::Decline { attempt })
+            }
+            JobBehavior::Vanish { ack_after } => {
+                self.schedule(now + ack_after, id, Event::Ack { attempt })
+            }
+            JobBehavior::Ignore => {}
+        }
+        self.schedule(
+            now + self.policy.ack_timeout,
+            id,
+            Event::AckTimeout { attempt },
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use fermah_common::{operator::OperatorId, types::region::Region};
+
+use crate::simulation::SimRequest;
+
+/// Operator a strategy can choose from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub id: OperatorId,
+    pub active_jobs: usize,
+    pub capacity: usize,
+    /// Offenses committed during the simulation
+    pub offenses: usize,
+    pub region: Option<Region>,
+}
+
+/// Picks the operator for a proof request
+pub trait MatchingStrategy {
+    /// `candidates` are the available operators, in registration order, which didn't fail the request before
+    fn select(&mut self, request: &SimRequest, candidates: &[Candidate]) -> Option<OperatorId>;
+}
+
+/// Rotates through the candidates
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+impl MatchingStrategy for RoundRobin {
+    fn select(&mut self, _request: &SimRequest, candidates: &[Candidate]) -> Option<OperatorId> {
+        if candidates.is_empty() {
+            return None;
+        }
+        let candidate = &candidates[self.next % candidates.len()];
+        self.next += 1;
+        Some(candidate.id)
+    }
+}
+
+/// Prefers the candidate with the fewest active jobs, then with the fewest offenses
+#[derive(Debug, Default)]
+pub struct LeastLoaded;
+
+impl MatchingStrategy for LeastLoaded {
+    fn select(&mut self, _request: &SimRequest, candidates: &[Candidate]) -> Option<OperatorId> {
+        candidates
+            .iter()
+            .min_by_key(|c| (c.active_jobs, c.offenses))
+            .map(|c| c.id)
+    }
+}
+
+/// Leaves the choice to the wrapped strategy among the candidates in the preferred regions of the request, or among
+/// all of them if none is there and the regions aren't required, as the matchmaker does
+#[derive(Debug, Default)]
+pub struct RegionAware<S> {
+    inner: S,
+}
+
+impl<S> RegionAware<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: MatchingStrategy> MatchingStrategy for RegionAware<S> {
+    fn select(&mut self, request: &SimRequest, candidates: &[Candidate]) -> Option<OperatorId> {
+        let Some(preferred) = &request.preferred_regions else {
+            return self.inner.select(request, candidates);
+        };
+
+        let local: Vec<Candidate> = candidates
+            .iter()
+            .filter(|c| preferred.matches(c.region.as_ref()))
+            .cloned()
+            .collect();
+        if !local.is_empty() || preferred.required {
+            self.inner.select(request, &local)
+        } else {
+            self.inner.select(request, candidates)
+        }
+    }
+}
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TABLE mm_requester_balances (
+    id BYTEA PRIMARY KEY,
+    reserved NUMERIC NOT NULL DEFAULT 0,
+    ready_to_pay NUMERIC NOT NULL DEFAULT 0,
+    paid NUMERIC NOT NULL DEFAULT 0,
+    last_update TIMESTAMP NOT NULL
+);
+
+-- Seed the ledger from the existing proof requests
+INSERT INTO
+    mm_requester_balances (id, reserved, ready_to_pay, paid, last_update)
+SELECT
+    public_key,
+    COALESCE(SUM(amount) FILTER (WHERE payment = 'Reserved'), 0),
+    COALESCE(SUM(amount) FILTER (WHERE payment = 'ReadyToPay'), 0),
+    COALESCE(SUM(amount) FILTER (WHERE payment = 'Paid'), 0),
+    NOW()
+FROM
+    mm_proof_requests
+GROUP BY
+    public_key;
\ No newline at end of file
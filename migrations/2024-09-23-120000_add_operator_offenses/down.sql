@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_operator_offenses;
+DROP TYPE operator_offense;
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TYPE operator_offense AS ENUM (
+    'MissedDeadlineAfterAck',
+    'InvalidProof'
+);
+
+CREATE TABLE mm_operator_offenses (
+    operator_id BYTEA NOT NULL,
+    pr_id BYTEA NOT NULL,
+    offense operator_offense NOT NULL,
+    recorded_at TIMESTAMP NOT NULL,
+    -- outcome, set once a slashing transaction covering the offense was mined
+    slashing_tx BYTEA NULL DEFAULT NULL,
+    PRIMARY KEY (operator_id, pr_id, offense)
+);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE
+    avs_proof_requesters DROP COLUMN observed_block,
+    DROP COLUMN observed_block_hash;
+
+ALTER TABLE
+    avs_operators DROP COLUMN observed_block,
+    DROP COLUMN observed_block_hash;
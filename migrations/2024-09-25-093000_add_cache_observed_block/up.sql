@@ -0,0 +1,14 @@
+-- Your SQL goes here
+ALTER TABLE
+    avs_proof_requesters
+ADD
+    observed_block BIGINT NULL DEFAULT NULL,
+ADD
+    observed_block_hash BYTEA NULL DEFAULT NULL;
+
+ALTER TABLE
+    avs_operators
+ADD
+    observed_block BIGINT NULL DEFAULT NULL,
+ADD
+    observed_block_hash BYTEA NULL DEFAULT NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE avs_transactions;
+DROP TYPE avs_tx_status;
\ No newline at end of file
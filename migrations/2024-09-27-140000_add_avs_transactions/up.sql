@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TYPE avs_tx_status AS ENUM (
+    'Pending',
+    'Confirmed',
+    'Reverted',
+    'Dropped'
+);
+
+CREATE TABLE avs_transactions (
+    sender BYTEA NOT NULL,
+    nonce BIGINT NOT NULL,
+    -- JSON encoded transaction request, kept to re-submit it with bumped fees
+    request TEXT NOT NULL,
+    -- all hashes the nonce was submitted with, the latest one last
+    tx_hashes BYTEA[] NOT NULL,
+    max_fee_per_gas NUMERIC NOT NULL,
+    max_priority_fee_per_gas NUMERIC NOT NULL,
+    bumps INTEGER NOT NULL DEFAULT 0,
+    status avs_tx_status NOT NULL DEFAULT 'Pending',
+    submitted_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (sender, nonce)
+);
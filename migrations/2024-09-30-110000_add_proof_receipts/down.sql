@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_proof_receipts;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+CREATE TABLE mm_proof_receipts (
+    pr_id BYTEA PRIMARY KEY,
+    -- bincode encoded receipt signed by the matchmaker
+    receipt BYTEA NOT NULL,
+    issued_at TIMESTAMP NOT NULL
+);
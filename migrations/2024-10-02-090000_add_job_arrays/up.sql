@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE mm_job_arrays (
+    pr_id BYTEA PRIMARY KEY,
+    array_id BYTEA NOT NULL,
+    array_index INT NOT NULL,
+    requester BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX mm_job_arrays_array_id ON mm_job_arrays (array_id);
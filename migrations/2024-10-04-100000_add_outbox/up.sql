@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE mm_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    locked_until TIMESTAMP,
+    processed_at TIMESTAMP,
+    last_error TEXT
+);
+
+CREATE INDEX mm_outbox_unprocessed ON mm_outbox (id) WHERE processed_at IS NULL;
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX mm_proof_requests_proven_at;
+
+ALTER TABLE
+    mm_proof_requests DROP COLUMN created_at,
+    DROP COLUMN accepted_at,
+    DROP COLUMN assigned_at,
+    DROP COLUMN acknowledged_at,
+    DROP COLUMN tested_at,
+    DROP COLUMN proven_at;
\ No newline at end of file
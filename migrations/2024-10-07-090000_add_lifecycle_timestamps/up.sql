@@ -0,0 +1,17 @@
+-- Your SQL goes here
+ALTER TABLE
+    mm_proof_requests
+ADD
+    created_at TIMESTAMP,
+ADD
+    accepted_at TIMESTAMP,
+ADD
+    assigned_at TIMESTAMP,
+ADD
+    acknowledged_at TIMESTAMP,
+ADD
+    tested_at TIMESTAMP,
+ADD
+    proven_at TIMESTAMP;
+
+CREATE INDEX mm_proof_requests_proven_at ON mm_proof_requests (proven_at);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_webhook_deliveries;
+
+DROP TABLE mm_webhooks;
\ No newline at end of file
@@ -0,0 +1,24 @@
+-- Your SQL goes here
+CREATE TABLE mm_webhooks (
+    id BIGSERIAL PRIMARY KEY,
+    requester BYTEA NOT NULL,
+    url TEXT NOT NULL,
+    secret BYTEA NOT NULL,
+    low_balance_threshold NUMERIC,
+    below_threshold BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL,
+    UNIQUE (requester, url)
+);
+
+CREATE TABLE mm_webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id BIGINT NOT NULL REFERENCES mm_webhooks (id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP,
+    delivered_at TIMESTAMP,
+    last_error TEXT
+);
+
+CREATE INDEX mm_webhook_deliveries_due ON mm_webhook_deliveries (next_attempt_at) WHERE delivered_at IS NULL;
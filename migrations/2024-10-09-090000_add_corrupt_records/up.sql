@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE mm_corrupt_records (
+    table_name TEXT NOT NULL,
+    record_id BYTEA NOT NULL,
+    error TEXT NOT NULL,
+    detected_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (table_name, record_id)
+);
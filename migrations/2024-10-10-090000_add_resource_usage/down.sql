@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE
+    mm_proof_requests DROP COLUMN peak_ram,
+    DROP COLUMN peak_vram,
+    DROP COLUMN wall_time_ms,
+    DROP COLUMN requirement_fit;
\ No newline at end of file
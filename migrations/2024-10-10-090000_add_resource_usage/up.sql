@@ -0,0 +1,11 @@
+-- Your SQL goes here
+ALTER TABLE
+    mm_proof_requests
+ADD
+    peak_ram BIGINT,
+ADD
+    peak_vram BIGINT,
+ADD
+    wall_time_ms BIGINT,
+ADD
+    requirement_fit TEXT;
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE mm_cancellations (
+    pr_id BYTEA NOT NULL,
+    operator_id BYTEA NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    acknowledged_at TIMESTAMP,
+    PRIMARY KEY (pr_id, operator_id)
+);
+
+CREATE INDEX mm_cancellations_pending ON mm_cancellations (operator_id) WHERE acknowledged_at IS NULL;
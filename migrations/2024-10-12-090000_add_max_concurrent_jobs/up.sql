@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE
+    mm_operators
+ADD
+    max_concurrent_jobs INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE mm_proof_request_dependencies (
+    pr_id BYTEA NOT NULL,
+    depends_on BYTEA NOT NULL,
+    PRIMARY KEY (pr_id, depends_on)
+);
+
+CREATE INDEX mm_proof_request_dependencies_dependents ON mm_proof_request_dependencies (depends_on);
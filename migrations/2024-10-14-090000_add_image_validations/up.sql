@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE mm_image_validations (
+    pr_id BYTEA PRIMARY KEY,
+    images_total INT NOT NULL,
+    images_verified INT NOT NULL DEFAULT 0,
+    attempts INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    finished_at TIMESTAMP,
+    passed BOOLEAN,
+    error TEXT
+);
+
+CREATE INDEX mm_image_validations_unfinished ON mm_image_validations (created_at) WHERE finished_at IS NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_prefetch_hints;
+DROP TABLE mm_operator_images;
\ No newline at end of file
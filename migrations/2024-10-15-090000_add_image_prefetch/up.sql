@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE mm_operator_images (
+    operator_id BYTEA NOT NULL,
+    image_hash BYTEA NOT NULL,
+    reported_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (operator_id, image_hash)
+);
+
+CREATE INDEX mm_operator_images_hash ON mm_operator_images (image_hash);
+
+CREATE TABLE mm_prefetch_hints (
+    operator_id BYTEA NOT NULL,
+    image_hash BYTEA NOT NULL,
+    url TEXT NOT NULL,
+    pr_id BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (operator_id, image_hash)
+);
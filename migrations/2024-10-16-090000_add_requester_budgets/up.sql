@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE mm_requester_budgets (
+    requester BYTEA PRIMARY KEY,
+    daily_cap NUMERIC,
+    total_cap NUMERIC,
+    alert_percent INT,
+    day DATE NOT NULL,
+    spent_today NUMERIC NOT NULL DEFAULT 0,
+    spent_total NUMERIC NOT NULL DEFAULT 0,
+    daily_alerted BOOLEAN NOT NULL DEFAULT FALSE,
+    total_alerted BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE
+    mm_proof_requests DROP COLUMN protocol_fee;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE
+    mm_proof_requests
+ADD
+    protocol_fee NUMERIC;
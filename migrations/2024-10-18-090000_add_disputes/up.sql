@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE mm_disputes (
+    pr_id BYTEA PRIMARY KEY,
+    requester BYTEA NOT NULL,
+    reason TEXT NOT NULL,
+    -- open, verifying, upheld or dismissed
+    status TEXT NOT NULL,
+    verifier BYTEA,
+    created_at TIMESTAMP NOT NULL,
+    assigned_at TIMESTAMP,
+    resolved_at TIMESTAMP
+);
+
+CREATE INDEX mm_disputes_unresolved ON mm_disputes (verifier) WHERE resolved_at IS NULL;
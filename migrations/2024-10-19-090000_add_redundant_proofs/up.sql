@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE mm_redundant_proofs (
+    pr_id BYTEA NOT NULL,
+    operator_id BYTEA NOT NULL,
+    -- bincode encoded proof, and the digest copies are compared by
+    proof BYTEA,
+    proof_digest BYTEA,
+    -- Set once the copies are compared
+    agrees BOOLEAN,
+    assigned_at TIMESTAMP NOT NULL,
+    submitted_at TIMESTAMP,
+    PRIMARY KEY (pr_id, operator_id)
+);
+
+CREATE INDEX mm_redundant_proofs_outstanding ON mm_redundant_proofs (operator_id) WHERE submitted_at IS NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_shard_ranges;
+DROP TABLE mm_shard_instances;
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE mm_shard_instances (
+    instance_id TEXT PRIMARY KEY,
+    joined_at TIMESTAMP NOT NULL,
+    heartbeat_at TIMESTAMP NOT NULL
+);
+
+-- Ranges of the proof request id space each instance assigns, `range_end` is exclusive
+CREATE TABLE mm_shard_ranges (
+    range_start BYTEA PRIMARY KEY,
+    range_end BYTEA NOT NULL,
+    instance_id TEXT NOT NULL
+);
+
+CREATE INDEX mm_shard_ranges_instance ON mm_shard_ranges (instance_id);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX mm_proof_requests_payment_assigned;
+DROP INDEX mm_proof_requests_status_operator;
+DROP INDEX mm_proof_requests_status_update;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- The queries bind the statuses as parameters, which partial indexes can't be matched against in generic plans
+
+-- proof_requests_need_assignment
+CREATE INDEX mm_proof_requests_status_update ON mm_proof_requests (status, last_status_update);
+-- available_operators
+CREATE INDEX mm_proof_requests_status_operator ON mm_proof_requests (status, operator_id);
+-- get_ready_to_pay_proof_requests_for_many and get_ready_to_pay_proof_requests
+CREATE INDEX mm_proof_requests_payment_assigned ON mm_proof_requests (payment, assigned);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX mm_proof_requests_operator;
+DROP INDEX mm_proof_requests_public_key_tested;
\ No newline at end of file
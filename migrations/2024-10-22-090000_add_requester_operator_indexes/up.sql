@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- get_requester_usage
+CREATE INDEX mm_proof_requests_public_key_tested ON mm_proof_requests (public_key, tested_at);
+-- get_operator_stats
+CREATE INDEX mm_proof_requests_operator ON mm_proof_requests (operator_id);
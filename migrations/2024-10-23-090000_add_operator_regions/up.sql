@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE
+    mm_operators
+ADD
+    region TEXT;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_operator_attestations;
+
+UPDATE
+    mm_operators
+SET
+    resource = substring(resource FROM 1 FOR length(resource) - 4);
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE mm_operator_attestations (
+    operator_id BYTEA PRIMARY KEY,
+    tee TEXT NOT NULL,
+    quote BYTEA NOT NULL,
+    reported_at TIMESTAMP NOT NULL
+);
+
+-- bincode encoded resources end with their TEE now, `none` is variant 0
+UPDATE
+    mm_operators
+SET
+    resource = resource || '\x00000000'::BYTEA;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE
+    mm_operators DROP COLUMN runtime;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- bincode encoded runtime the operator advertised, docker with every feature if NULL
+ALTER TABLE
+    mm_operators
+ADD
+    runtime BYTEA;
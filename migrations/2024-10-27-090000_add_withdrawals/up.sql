@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE mm_withdrawals (
+    id BYTEA PRIMARY KEY,
+    requester BYTEA NOT NULL,
+    recipient BYTEA NOT NULL,
+    amount NUMERIC NOT NULL,
+    deadline TIMESTAMP NOT NULL,
+    status TEXT NOT NULL,
+    tx_hash BYTEA,
+    error TEXT,
+    requested_at TIMESTAMP NOT NULL,
+    resolved_at TIMESTAMP
+);
+
+-- Pending withdrawals are summed into the balance of the requester on every admission
+CREATE INDEX mm_withdrawals_requester_status_idx ON mm_withdrawals (requester, status);
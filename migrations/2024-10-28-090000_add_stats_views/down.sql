@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP MATERIALIZED VIEW mm_operator_summaries;
+DROP MATERIALIZED VIEW mm_daily_proofs;
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- Aggregates of mm_proof_requests for the stats RPCs, refreshed periodically instead of scanned on every call
+CREATE MATERIALIZED VIEW mm_daily_proofs AS
+SELECT
+    proven_at::DATE AS day,
+    COUNT(*) AS proven
+FROM mm_proof_requests
+WHERE proven_at IS NOT NULL
+GROUP BY day;
+
+-- Unique indexes let the views be refreshed concurrently, without blocking the readers
+CREATE UNIQUE INDEX mm_daily_proofs_day_idx ON mm_daily_proofs (day);
+
+CREATE MATERIALIZED VIEW mm_operator_summaries AS
+SELECT
+    operator_id,
+    COUNT(*) FILTER (WHERE status = 'Proven') AS completed,
+    COUNT(*) FILTER (WHERE status = 'Rejected') AS rejected,
+    COALESCE(SUM(amount) FILTER (WHERE payment = 'Paid'), 0) AS earned,
+    FLOOR(AVG(GREATEST(EXTRACT(EPOCH FROM tested_at - acknowledged_at) * 1000, 0))
+        FILTER (WHERE acknowledged_at IS NOT NULL AND tested_at IS NOT NULL))::BIGINT AS avg_proving_ms
+FROM mm_proof_requests
+WHERE operator_id IS NOT NULL
+GROUP BY operator_id;
+
+CREATE UNIQUE INDEX mm_operator_summaries_operator_id_idx ON mm_operator_summaries (operator_id);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE
+    mm_operators DROP COLUMN archived_at;
\ No newline at end of file
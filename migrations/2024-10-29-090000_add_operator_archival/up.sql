@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Set once the registration of the operator expired long ago, archived operators are kept but never assigned or counted
+ALTER TABLE
+    mm_operators
+ADD
+    archived_at TIMESTAMP;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Decision of the container policy on the privileged and network access a submitted request asked for
+CREATE TABLE mm_container_policies (
+    pr_id BYTEA PRIMARY KEY,
+    privileged TEXT NOT NULL,
+    network TEXT NOT NULL,
+    decided_at TIMESTAMP NOT NULL
+);
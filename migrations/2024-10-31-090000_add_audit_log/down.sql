@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER mm_audit_log_append_only ON mm_audit_log;
+
+DROP FUNCTION mm_audit_log_reject_change;
+
+DROP TABLE mm_audit_log;
\ No newline at end of file
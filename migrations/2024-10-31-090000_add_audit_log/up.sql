@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- Append-only log of the operations moving funds, every entry hashes the entry before it
+CREATE TABLE mm_audit_log (
+    seq BIGINT PRIMARY KEY,
+    actor BYTEA NOT NULL,
+    action TEXT NOT NULL,
+    amount NUMERIC,
+    tx_hash BYTEA,
+    pr_ids BYTEA[] NOT NULL,
+    details TEXT,
+    recorded_at TIMESTAMP NOT NULL,
+    prev_hash BYTEA NOT NULL,
+    hash BYTEA NOT NULL
+);
+
+CREATE INDEX mm_audit_log_actor ON mm_audit_log (actor, seq);
+
+-- Entries are never changed once written, the hash chain catches what's done bypassing the trigger
+CREATE FUNCTION mm_audit_log_reject_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'mm_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER mm_audit_log_append_only BEFORE UPDATE OR DELETE ON mm_audit_log
+FOR EACH ROW EXECUTE FUNCTION mm_audit_log_reject_change();
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+-- Every status and payment transition of a proof request, in the order they were committed
+CREATE TABLE mm_request_events (
+    seq BIGSERIAL PRIMARY KEY,
+    pr_id BYTEA NOT NULL,
+    kind TEXT NOT NULL,
+    value TEXT NOT NULL,
+    operator_id BYTEA,
+    amount NUMERIC,
+    details TEXT,
+    recorded_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX mm_request_events_pr_id ON mm_request_events (pr_id, seq);
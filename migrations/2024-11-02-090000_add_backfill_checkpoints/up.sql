@@ -0,0 +1,10 @@
+-- Your SQL goes here
+-- Progress of the backfill jobs recomputing the operator aggregates from the request history, to resume them
+CREATE TABLE mm_backfill_checkpoints (
+    job TEXT PRIMARY KEY,
+    last_operator BYTEA,
+    scanned BIGINT NOT NULL DEFAULT 0,
+    started_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    finished_at TIMESTAMP
+);
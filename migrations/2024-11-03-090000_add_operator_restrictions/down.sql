@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_operators DROP COLUMN failure_streak;
+ALTER TABLE mm_operators DROP COLUMN restricted_until;
+ALTER TABLE mm_operators DROP COLUMN restricted_at;
+ALTER TABLE mm_operators DROP COLUMN restriction_reason;
+ALTER TABLE mm_operators DROP COLUMN restriction;
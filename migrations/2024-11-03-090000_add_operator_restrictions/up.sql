@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Operators banned or greylisted from the assignments, and the proofs they failed in a row
+ALTER TABLE mm_operators ADD COLUMN restriction TEXT;
+ALTER TABLE mm_operators ADD COLUMN restriction_reason TEXT;
+ALTER TABLE mm_operators ADD COLUMN restricted_at TIMESTAMP;
+ALTER TABLE mm_operators ADD COLUMN restricted_until TIMESTAMP;
+ALTER TABLE mm_operators ADD COLUMN failure_streak INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_image_records;
+
+DROP TABLE mm_image_names;
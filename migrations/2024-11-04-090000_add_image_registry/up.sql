@@ -0,0 +1,18 @@
+-- Your SQL goes here
+-- Image names owned by their first publisher, and the signed records of their versions
+CREATE TABLE mm_image_names (
+    name TEXT PRIMARY KEY,
+    publisher BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE mm_image_records (
+    name TEXT NOT NULL REFERENCES mm_image_names (name),
+    version TEXT NOT NULL,
+    image_hash BYTEA NOT NULL,
+    record TEXT NOT NULL,
+    published_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (name, version)
+);
+
+CREATE INDEX mm_image_records_latest ON mm_image_records (name, published_at DESC);
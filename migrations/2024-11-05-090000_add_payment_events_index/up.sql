@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Payment events are streamed to accounting systems by cursor, across all the requests
+CREATE INDEX mm_request_events_payments ON mm_request_events (seq) WHERE kind = 'payment';
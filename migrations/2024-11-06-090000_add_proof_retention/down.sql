@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_proof_requests DROP COLUMN proof_deleted_at;
+ALTER TABLE mm_proof_requests DROP COLUMN retrieved_at;
+ALTER TABLE mm_proof_requests DROP COLUMN retain_until_retrieved;
+ALTER TABLE mm_proof_requests DROP COLUMN retention_days;
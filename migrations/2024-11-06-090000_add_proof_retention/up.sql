@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Retention of the proofs picked by the requester, its acknowledged retrieval and when the proof was deleted
+ALTER TABLE mm_proof_requests ADD COLUMN retention_days INTEGER;
+ALTER TABLE mm_proof_requests ADD COLUMN retain_until_retrieved BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE mm_proof_requests ADD COLUMN retrieved_at TIMESTAMP;
+ALTER TABLE mm_proof_requests ADD COLUMN proof_deleted_at TIMESTAMP;
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_proof_requests DROP COLUMN cancellation_reason;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Why the request was cancelled, NULL for the requests cancelled before the reasons were recorded
+ALTER TABLE mm_proof_requests ADD COLUMN cancellation_reason TEXT;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Approvals of the withdrawals above the approval threshold, one per approver
+CREATE TABLE mm_withdrawal_approvals (
+    withdrawal_id BYTEA NOT NULL REFERENCES mm_withdrawals (id),
+    approver BYTEA NOT NULL,
+    approved_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (withdrawal_id, approver)
+);
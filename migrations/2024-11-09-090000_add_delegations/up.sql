@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Submitter keys allowed to submit requests paid by the requester, with what they already spent
+CREATE TABLE mm_delegations (
+    requester BYTEA NOT NULL,
+    submitter BYTEA NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    spend_cap NUMERIC,
+    max_requests BIGINT,
+    spent NUMERIC NOT NULL DEFAULT 0,
+    requests BIGINT NOT NULL DEFAULT 0,
+    delegated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (requester, submitter)
+);
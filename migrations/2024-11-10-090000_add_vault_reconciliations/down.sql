@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE mm_vault_discrepancies;
+DROP TABLE mm_vault_reconciliations;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Runs comparing the books of the matchmaker with the vault balances
+CREATE TABLE mm_vault_reconciliations (
+    id BIGSERIAL PRIMARY KEY,
+    block BIGINT NOT NULL,
+    checked BIGINT NOT NULL,
+    reconciled_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE mm_vault_discrepancies (
+    reconciliation_id BIGINT NOT NULL REFERENCES mm_vault_reconciliations (id) ON DELETE CASCADE,
+    address BYTEA NOT NULL,
+    kind TEXT NOT NULL,
+    vault_balance NUMERIC NOT NULL,
+    booked NUMERIC NOT NULL,
+    PRIMARY KEY (reconciliation_id, address, kind)
+);
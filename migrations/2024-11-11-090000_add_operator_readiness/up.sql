@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Latest self-test report of each operator
+CREATE TABLE mm_operator_readiness (
+    operator_id BYTEA PRIMARY KEY,
+    ready BOOLEAN NOT NULL,
+    report TEXT NOT NULL,
+    submitted_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Requests assigned to each operator, redundant copies included
+ALTER TABLE mm_operators ADD COLUMN assignments BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Last nonce accepted from each signer for each kind of signed command, older ones are replays
+CREATE TABLE mm_signed_nonces (
+    scope TEXT NOT NULL,
+    signer BYTEA NOT NULL,
+    nonce BIGINT NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (scope, signer)
+);
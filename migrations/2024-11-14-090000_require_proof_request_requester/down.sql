@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX mm_proof_requests_requester_tested;
+CREATE INDEX mm_proof_requests_public_key_tested ON mm_proof_requests (public_key, tested_at);
+ALTER TABLE mm_proof_requests ALTER COLUMN requester DROP NOT NULL;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Requests are settled with the requester paying for them, which differs from their signer when it's delegated. The
+-- requests stored before the requester was required were signed by their requester.
+UPDATE mm_proof_requests SET requester = public_key WHERE requester IS NULL;
+ALTER TABLE mm_proof_requests ALTER COLUMN requester SET NOT NULL;
+-- get_requester_usage
+DROP INDEX mm_proof_requests_public_key_tested;
+CREATE INDEX mm_proof_requests_requester_tested ON mm_proof_requests (requester, tested_at);
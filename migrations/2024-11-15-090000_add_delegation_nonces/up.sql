@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Nonce the delegation was signed with, the older ones are refused
+ALTER TABLE mm_delegations ADD COLUMN nonce BIGINT NOT NULL DEFAULT 0;
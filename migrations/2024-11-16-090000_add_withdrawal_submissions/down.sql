@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_withdrawals DROP COLUMN submitted_at;
+ALTER TABLE mm_withdrawals DROP COLUMN tx_nonce;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- Nonce of the AVS transaction the withdrawal is sent in, recorded before it's broadcast so it's reconciled by its
+-- receipt after a failure or a restart
+ALTER TABLE mm_withdrawals ADD COLUMN tx_nonce BIGINT;
+ALTER TABLE mm_withdrawals ADD COLUMN submitted_at TIMESTAMP;
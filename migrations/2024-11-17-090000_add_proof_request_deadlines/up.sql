@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Deadline of the request's payload, so the requests proven late are counted. The requests stored before are left
+-- without it.
+ALTER TABLE mm_proof_requests ADD COLUMN deadline TIMESTAMP;
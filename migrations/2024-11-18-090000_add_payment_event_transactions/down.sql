@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX mm_request_events_payments;
+CREATE INDEX mm_request_events_payments ON mm_request_events (seq) WHERE kind = 'payment';
+ALTER TABLE mm_request_events DROP COLUMN xact;
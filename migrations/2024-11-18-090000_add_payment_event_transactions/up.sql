@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Transaction the event was recorded in. The payment events are streamed by it, so a cursor doesn't skip the events
+-- of a transaction committing after a later one.
+ALTER TABLE mm_request_events ADD COLUMN xact BIGINT NOT NULL DEFAULT txid_current();
+DROP INDEX mm_request_events_payments;
+CREATE INDEX mm_request_events_payments ON mm_request_events (xact, seq) WHERE kind = 'payment';
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_container_policies DROP COLUMN docker;
\ No newline at end of file
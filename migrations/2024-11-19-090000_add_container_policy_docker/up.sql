@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Decision on the docker socket. The requests decided on before it was part of the policy run as they were submitted.
+ALTER TABLE mm_container_policies ADD COLUMN docker TEXT NOT NULL DEFAULT 'notRequested';
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Whether the request's payload has a result key its proofs are sealed to, so the payload isn't decoded to tell.
+-- The requests stored before result keys have none.
+ALTER TABLE mm_proof_requests ADD COLUMN sealed BOOLEAN NOT NULL DEFAULT FALSE;
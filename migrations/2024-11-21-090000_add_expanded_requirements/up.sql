@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Requirement the resource class named by a submitted request stands for, the signed request keeps the class name
+CREATE TABLE mm_expanded_requirements (
+    pr_id BYTEA PRIMARY KEY,
+    requirement BYTEA NOT NULL,
+    expanded_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE mm_backfill_checkpoints RENAME COLUMN last_key TO last_operator;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Checkpoints of backfills scanning other tables than the operators, e.g. the proof requests
+ALTER TABLE mm_backfill_checkpoints RENAME COLUMN last_operator TO last_key;